@@ -0,0 +1,18 @@
+-- ============================================================
+-- Abonos: pagos parciales e independientes de un viaje
+-- ============================================================
+
+ALTER TABLE worker_payments ADD COLUMN notes TEXT;
+
+-- Un abono puede relacionarse con uno o varios viajes (o con ninguno).
+CREATE TABLE worker_payment_trips (
+    payment_id UUID NOT NULL REFERENCES worker_payments(id) ON DELETE CASCADE,
+    trip_id UUID NOT NULL REFERENCES worker_trips(id),
+    PRIMARY KEY (payment_id, trip_id)
+);
+
+CREATE INDEX idx_worker_payment_trips_trip ON worker_payment_trips(trip_id);
+
+-- Los pagos existentes quedan vinculados a su viaje
+INSERT INTO worker_payment_trips (payment_id, trip_id)
+SELECT id, trip_id FROM worker_payments WHERE trip_id IS NOT NULL;
@@ -0,0 +1,17 @@
+-- ============================================================
+-- Precios resueltos en el servidor desde price_history
+-- ============================================================
+-- price_history_id: registro de precio vigente usado para el item.
+-- price_override_reason: motivo cuando el dueño fija el precio manualmente.
+
+ALTER TABLE worker_trip_loaded_items
+    ADD COLUMN price_history_id UUID REFERENCES price_history(id),
+    ADD COLUMN price_override_reason TEXT;
+
+ALTER TABLE owner_sale_loaded_items
+    ADD COLUMN price_history_id UUID REFERENCES price_history(id),
+    ADD COLUMN price_override_reason TEXT;
+
+ALTER TABLE local_sale_items
+    ADD COLUMN price_history_id UUID REFERENCES price_history(id),
+    ADD COLUMN price_override_reason TEXT;
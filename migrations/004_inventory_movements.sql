@@ -0,0 +1,45 @@
+-- ============================================================
+-- Kardex: movimientos de inventario (append-only)
+-- ============================================================
+-- Cada cambio de stock deja un registro con su delta, la cantidad
+-- resultante y el documento que lo causó. inventory_id no tiene FK
+-- porque los deformados que llegan a 0 se eliminan de inventory;
+-- por eso se guardan también los datos de la fila.
+
+CREATE TABLE inventory_movements (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    seq BIGINT GENERATED ALWAYS AS IDENTITY UNIQUE,
+    inventory_id UUID NOT NULL,
+    freezer_id UUID NOT NULL REFERENCES freezers(id),
+    product_id UUID NOT NULL REFERENCES products(id),
+    flavor_id UUID NOT NULL REFERENCES flavors(id),
+    provider_id UUID NOT NULL REFERENCES providers(id),
+    is_deformed BOOLEAN NOT NULL,
+    assigned_worker_id UUID REFERENCES workers(id),
+    delta INTEGER NOT NULL,
+    quantity_after INTEGER NOT NULL CHECK (quantity_after >= 0),
+    movement_type VARCHAR(30) NOT NULL CHECK (movement_type IN (
+        'opening', 'manual_add', 'purchase',
+        'trip_load', 'trip_return',
+        'local_sale', 'owner_sale_load', 'owner_sale_return',
+        'transfer_out', 'transfer_in', 'adjustment'
+    )),
+    related_doc_type VARCHAR(50),
+    related_doc_id UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by UUID NOT NULL REFERENCES users(id)
+);
+
+CREATE INDEX idx_inventory_movements_inventory ON inventory_movements(inventory_id, seq);
+CREATE INDEX idx_inventory_movements_freezer ON inventory_movements(freezer_id, created_at);
+CREATE INDEX idx_inventory_movements_product ON inventory_movements(product_id, created_at);
+CREATE INDEX idx_inventory_movements_doc ON inventory_movements(related_doc_type, related_doc_id);
+
+-- Saldo inicial del stock existente
+INSERT INTO inventory_movements
+(inventory_id, freezer_id, product_id, flavor_id, provider_id, is_deformed,
+ assigned_worker_id, delta, quantity_after, movement_type, created_at, created_by)
+SELECT id, freezer_id, product_id, flavor_id, provider_id, is_deformed,
+       assigned_worker_id, quantity, quantity, 'opening', last_updated, updated_by
+FROM inventory
+WHERE quantity > 0;
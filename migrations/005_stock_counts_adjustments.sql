@@ -0,0 +1,55 @@
+-- ============================================================
+-- Conteos físicos de inventario y ajustes / mermas
+-- ============================================================
+
+-- Sesión de conteo por congelador
+CREATE TABLE stock_count_sessions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    freezer_id UUID NOT NULL REFERENCES freezers(id),
+    status VARCHAR(20) NOT NULL DEFAULT 'open' CHECK (status IN ('open', 'committed', 'cancelled')),
+    notes TEXT,
+    opened_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    opened_by UUID NOT NULL REFERENCES users(id),
+    closed_at TIMESTAMPTZ,
+    closed_by UUID REFERENCES users(id)
+);
+
+-- Solo un conteo abierto por congelador
+CREATE UNIQUE INDEX idx_stock_count_open_freezer
+    ON stock_count_sessions(freezer_id) WHERE status = 'open';
+
+-- Líneas: foto del inventario al abrir + cantidad contada
+CREATE TABLE stock_count_lines (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    session_id UUID NOT NULL REFERENCES stock_count_sessions(id) ON DELETE CASCADE,
+    inventory_id UUID NOT NULL,
+    product_id UUID NOT NULL REFERENCES products(id),
+    flavor_id UUID NOT NULL REFERENCES flavors(id),
+    provider_id UUID NOT NULL REFERENCES providers(id),
+    is_deformed BOOLEAN NOT NULL,
+    expected_quantity INTEGER NOT NULL,
+    counted_quantity INTEGER CHECK (counted_quantity >= 0),
+    reason VARCHAR(30) CHECK (reason IN ('melted', 'damaged', 'lost', 'counting_error')),
+    UNIQUE (session_id, inventory_id)
+);
+
+-- Ajustes de inventario (mermas y correcciones), valorizados a costo
+CREATE TABLE inventory_adjustments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    inventory_id UUID NOT NULL,
+    freezer_id UUID NOT NULL REFERENCES freezers(id),
+    product_id UUID NOT NULL REFERENCES products(id),
+    flavor_id UUID NOT NULL REFERENCES flavors(id),
+    provider_id UUID NOT NULL REFERENCES providers(id),
+    quantity_delta INTEGER NOT NULL CHECK (quantity_delta <> 0),
+    reason VARCHAR(30) NOT NULL CHECK (reason IN ('melted', 'damaged', 'lost', 'counting_error')),
+    unit_cost DECIMAL(10,2) NOT NULL,
+    total_cost DECIMAL(12,2) NOT NULL,
+    notes TEXT,
+    count_session_id UUID REFERENCES stock_count_sessions(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by UUID NOT NULL REFERENCES users(id)
+);
+
+CREATE INDEX idx_inventory_adjustments_created ON inventory_adjustments(created_at);
+CREATE INDEX idx_inventory_adjustments_freezer ON inventory_adjustments(freezer_id, created_at);
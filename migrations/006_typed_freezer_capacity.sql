@@ -0,0 +1,20 @@
+-- ============================================================
+-- Capacidad tipada de congeladores: { "<product_id>": unidades }
+-- ============================================================
+
+-- Las capacidades existentes usaban el nombre del tipo de producto como
+-- clave ("paletas"). Se traducen al id del producto (nombre en singular o
+-- plural, sin distinguir mayúsculas); las claves sin producto o con valores
+-- no enteros se descartan.
+UPDATE freezers f
+SET max_capacity = COALESCE((
+    SELECT jsonb_object_agg(p.id::text, c.value::int)
+    FROM jsonb_each_text(f.max_capacity) c
+    JOIN products p
+      ON p.id::text = c.key
+      OR lower(c.key) IN (lower(p.name), lower(p.name) || 's', lower(p.name) || 'es')
+    WHERE c.value ~ '^\d+$' AND c.value::int > 0
+), '{}'::jsonb);
+
+ALTER TABLE freezers
+    ADD CONSTRAINT freezers_max_capacity_object CHECK (jsonb_typeof(max_capacity) = 'object');
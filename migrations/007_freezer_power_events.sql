@@ -0,0 +1,33 @@
+-- ============================================================
+-- Historial de encendido/apagado y consumo eléctrico de congeladores
+-- ============================================================
+
+-- Consumo nominal (opcional): potencia promedio en vatios y/o kWh por día
+-- de la etiqueta energética. Si hay kWh/día se usa ese valor.
+ALTER TABLE freezers
+    ADD COLUMN power_watts INTEGER CHECK (power_watts > 0),
+    ADD COLUMN kwh_per_day DECIMAL(8,3) CHECK (kwh_per_day > 0);
+
+CREATE TABLE freezer_power_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    freezer_id UUID NOT NULL REFERENCES freezers(id),
+    is_on BOOLEAN NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by UUID NOT NULL REFERENCES users(id)
+);
+
+CREATE INDEX idx_freezer_power_events_freezer ON freezer_power_events(freezer_id, created_at DESC);
+
+-- Historial inicial: estado al crearse (el contrario al actual si ya hubo un
+-- cambio) y el último cambio registrado en last_toggle.
+INSERT INTO freezer_power_events (freezer_id, is_on, created_at, created_by)
+SELECT id,
+       CASE WHEN last_toggle IS NULL THEN is_on ELSE NOT is_on END,
+       created_at,
+       created_by
+FROM freezers;
+
+INSERT INTO freezer_power_events (freezer_id, is_on, created_at, created_by)
+SELECT id, is_on, last_toggle, created_by
+FROM freezers
+WHERE last_toggle IS NOT NULL;
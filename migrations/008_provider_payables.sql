@@ -0,0 +1,41 @@
+-- ============================================================
+-- Cuentas por pagar a proveedores
+-- ============================================================
+
+-- Monto abonado a cada compra. Una compra a crédito pasa a 'paid' cuando
+-- amount_paid alcanza el total.
+ALTER TABLE purchases
+    ADD COLUMN amount_paid DECIMAL(12,2) NOT NULL DEFAULT 0 CHECK (amount_paid >= 0);
+
+UPDATE purchases SET amount_paid = total WHERE payment_status = 'paid';
+
+ALTER TABLE purchases
+    ADD CONSTRAINT purchases_amount_paid_le_total CHECK (amount_paid <= total);
+
+CREATE TABLE provider_payments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    provider_id UUID NOT NULL REFERENCES providers(id),
+    amount DECIMAL(12,2) NOT NULL CHECK (amount > 0),
+    notes TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by UUID NOT NULL REFERENCES users(id)
+);
+
+CREATE INDEX idx_provider_payments_provider ON provider_payments(provider_id, created_at DESC);
+
+-- Distribución de cada pago entre las compras a crédito que cubre.
+CREATE TABLE provider_payment_allocations (
+    payment_id UUID NOT NULL REFERENCES provider_payments(id) ON DELETE CASCADE,
+    purchase_id UUID NOT NULL REFERENCES purchases(id),
+    amount DECIMAL(12,2) NOT NULL CHECK (amount > 0),
+    PRIMARY KEY (payment_id, purchase_id)
+);
+
+CREATE INDEX idx_provider_payment_allocations_purchase ON provider_payment_allocations(purchase_id);
+
+-- Nueva salida de caja: pago a proveedor
+ALTER TABLE cash_register DROP CONSTRAINT cash_register_type_check;
+ALTER TABLE cash_register ADD CONSTRAINT cash_register_type_check CHECK (type IN (
+    'worker_payment', 'local_sale', 'owner_sale',
+    'owner_withdrawal', 'expense', 'provider_payment'
+));
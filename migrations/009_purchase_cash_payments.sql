@@ -0,0 +1,23 @@
+-- ============================================================
+-- Compras pagadas al contado: salida de caja
+-- ============================================================
+
+-- Origen del dinero de una compra pagada al registrarla:
+-- 'cash_register' descuenta de la caja, 'external' (bolsillo del dueño,
+-- transferencia) no la toca. NULL en compras a crédito.
+ALTER TABLE purchases ADD COLUMN paid_from VARCHAR(20)
+    CHECK (paid_from IN ('cash_register', 'external'));
+
+-- Las compras pagadas existentes nunca se registraron en caja
+UPDATE purchases SET paid_from = 'external'
+WHERE payment_status = 'paid'
+  AND NOT EXISTS (
+      SELECT 1 FROM provider_payment_allocations a WHERE a.purchase_id = purchases.id
+  );
+
+-- Nueva salida de caja: compra pagada al contado
+ALTER TABLE cash_register DROP CONSTRAINT cash_register_type_check;
+ALTER TABLE cash_register ADD CONSTRAINT cash_register_type_check CHECK (type IN (
+    'worker_payment', 'local_sale', 'owner_sale',
+    'owner_withdrawal', 'expense', 'provider_payment', 'purchase_payment'
+));
@@ -0,0 +1,18 @@
+-- ============================================================
+-- Reglas de margen para precios derivados del costo de compra
+-- ============================================================
+-- Cuando una compra trae un costo distinto al vigente, se crea un nuevo
+-- registro en price_history aplicando estos porcentajes sobre el costo.
+-- product_id NULL = regla general; la regla del producto tiene prioridad.
+-- Sin regla aplicable la compra solo devuelve advertencias.
+
+CREATE TABLE price_markup_rules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    product_id UUID REFERENCES products(id),
+    base_markup_pct DECIMAL(6,2) NOT NULL CHECK (base_markup_pct >= 0),
+    route_markup_pct DECIMAL(6,2) NOT NULL CHECK (route_markup_pct >= 0),
+    local_markup_pct DECIMAL(6,2) NOT NULL CHECK (local_markup_pct >= 0),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_by UUID NOT NULL REFERENCES users(id),
+    CONSTRAINT unique_markup_rule_product UNIQUE NULLS NOT DISTINCT (product_id)
+);
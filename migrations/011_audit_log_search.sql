@@ -0,0 +1,7 @@
+-- ============================================================
+-- Índices para la búsqueda de auditoría por fecha, tabla y acción
+-- ============================================================
+-- idx_audit_log_user ya cubre (created_by, created_at).
+
+CREATE INDEX idx_audit_log_created_at ON audit_log(created_at DESC);
+CREATE INDEX idx_audit_log_table_created_at ON audit_log(table_name, created_at DESC);
@@ -0,0 +1,116 @@
+-- ============================================================
+-- Cadena de hashes en cash_register y audit_log
+-- ============================================================
+-- Cada fila nueva recibe un número de secuencia y un hash SHA-256 de su
+-- contenido encadenado al hash de la fila anterior. Modificar o borrar una
+-- fila rompe la cadena a partir de ese punto; la verificación recorre la
+-- tabla y reporta el primer eslabón roto.
+--
+-- El contenido se serializa como arreglo jsonb (sin ambigüedad de
+-- separadores) y las fechas como epoch para no depender del TimeZone de
+-- la sesión. Las tablas además son de solo inserción.
+
+-- ─── Columnas ───────────────────────────────────────────
+
+ALTER TABLE cash_register ADD COLUMN seq BIGINT, ADD COLUMN prev_hash TEXT, ADD COLUMN hash TEXT;
+ALTER TABLE audit_log ADD COLUMN seq BIGINT, ADD COLUMN prev_hash TEXT, ADD COLUMN hash TEXT;
+
+-- ─── Contenido canónico por tabla ───────────────────────
+
+CREATE FUNCTION cash_register_row_hash(r cash_register) RETURNS TEXT AS $$
+    SELECT encode(sha256(convert_to(jsonb_build_array(
+        r.seq, r.prev_hash, r.id, r.type, r.amount, r.balance,
+        r.description, r.category, r.related_doc_type, r.related_doc_id,
+        extract(epoch FROM r.created_at), r.created_by
+    )::text, 'UTF8')), 'hex')
+$$ LANGUAGE sql;
+
+CREATE FUNCTION audit_log_row_hash(r audit_log) RETURNS TEXT AS $$
+    SELECT encode(sha256(convert_to(jsonb_build_array(
+        r.seq, r.prev_hash, r.id, r.action, r.table_name, r.record_id,
+        r.changes_before, r.changes_after,
+        extract(epoch FROM r.created_at), r.created_by
+    )::text, 'UTF8')), 'hex')
+$$ LANGUAGE sql;
+
+-- ─── Backfill de filas existentes ───────────────────────
+
+DO $$
+DECLARE
+    r cash_register;
+    a audit_log;
+    n BIGINT := 0;
+    last_hash TEXT := NULL;
+BEGIN
+    FOR r IN SELECT * FROM cash_register ORDER BY created_at, id LOOP
+        n := n + 1;
+        r.seq := n;
+        r.prev_hash := last_hash;
+        last_hash := cash_register_row_hash(r);
+        UPDATE cash_register SET seq = n, prev_hash = r.prev_hash, hash = last_hash WHERE id = r.id;
+    END LOOP;
+
+    n := 0;
+    last_hash := NULL;
+    FOR a IN SELECT * FROM audit_log ORDER BY created_at, id LOOP
+        n := n + 1;
+        a.seq := n;
+        a.prev_hash := last_hash;
+        last_hash := audit_log_row_hash(a);
+        UPDATE audit_log SET seq = n, prev_hash = a.prev_hash, hash = last_hash WHERE id = a.id;
+    END LOOP;
+END $$;
+
+ALTER TABLE cash_register ALTER COLUMN seq SET NOT NULL, ALTER COLUMN hash SET NOT NULL,
+    ADD CONSTRAINT unique_cash_register_seq UNIQUE (seq);
+ALTER TABLE audit_log ALTER COLUMN seq SET NOT NULL, ALTER COLUMN hash SET NOT NULL,
+    ADD CONSTRAINT unique_audit_log_seq UNIQUE (seq);
+
+-- ─── Encadenado automático al insertar ──────────────────
+-- El advisory lock serializa las inserciones de cada tabla para que dos
+-- transacciones no tomen el mismo eslabón anterior.
+
+CREATE FUNCTION cash_register_chain() RETURNS TRIGGER AS $$
+DECLARE
+    last cash_register;
+BEGIN
+    PERFORM pg_advisory_xact_lock(hashtext('cash_register_chain'));
+    SELECT * INTO last FROM cash_register ORDER BY seq DESC LIMIT 1;
+    NEW.seq := COALESCE(last.seq, 0) + 1;
+    NEW.prev_hash := last.hash;
+    NEW.hash := cash_register_row_hash(NEW);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE FUNCTION audit_log_chain() RETURNS TRIGGER AS $$
+DECLARE
+    last audit_log;
+BEGIN
+    PERFORM pg_advisory_xact_lock(hashtext('audit_log_chain'));
+    SELECT * INTO last FROM audit_log ORDER BY seq DESC LIMIT 1;
+    NEW.seq := COALESCE(last.seq, 0) + 1;
+    NEW.prev_hash := last.hash;
+    NEW.hash := audit_log_row_hash(NEW);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER cash_register_chain BEFORE INSERT ON cash_register
+    FOR EACH ROW EXECUTE FUNCTION cash_register_chain();
+CREATE TRIGGER audit_log_chain BEFORE INSERT ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION audit_log_chain();
+
+-- ─── Solo inserción ─────────────────────────────────────
+-- Las correcciones se registran como movimientos compensatorios.
+
+CREATE FUNCTION reject_ledger_change() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION '% es de solo inserción', TG_TABLE_NAME;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER cash_register_append_only BEFORE UPDATE OR DELETE ON cash_register
+    FOR EACH ROW EXECUTE FUNCTION reject_ledger_change();
+CREATE TRIGGER audit_log_append_only BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION reject_ledger_change();
@@ -0,0 +1,38 @@
+-- ============================================================
+-- Cierre de caja diario con arqueo por denominación
+-- ============================================================
+
+-- Sobrante / faltante del arqueo: ajuste que deja el libro igual al conteo
+ALTER TABLE cash_register DROP CONSTRAINT cash_register_type_check;
+ALTER TABLE cash_register ADD CONSTRAINT cash_register_type_check CHECK (type IN (
+    'worker_payment', 'local_sale', 'owner_sale',
+    'owner_withdrawal', 'expense', 'provider_payment', 'purchase_payment',
+    'cash_surplus', 'cash_shortage'
+));
+
+-- Un cierre por día hábil. Al reabrir, la fila queda como historial
+-- ('reopened') y el día admite un nuevo cierre.
+CREATE TABLE cash_closings (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    business_date DATE NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'closed' CHECK (status IN ('closed', 'reopened')),
+    expected_balance DECIMAL(12,2) NOT NULL,
+    counted_total DECIMAL(12,2) NOT NULL CHECK (counted_total >= 0),
+    difference DECIMAL(12,2) NOT NULL,
+    -- [{ "value": "100.00", "count": 3 }, ...]
+    denominations JSONB NOT NULL,
+    -- Totales del día por tipo de movimiento y por usuario
+    report JSONB NOT NULL,
+    explanation TEXT,
+    adjustment_tx_id UUID REFERENCES cash_register(id),
+    closed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    closed_by UUID NOT NULL REFERENCES users(id),
+    reopened_at TIMESTAMPTZ,
+    reopened_by UUID REFERENCES users(id),
+    reopen_reason TEXT,
+    CHECK (difference = 0 OR explanation IS NOT NULL)
+);
+
+CREATE UNIQUE INDEX idx_cash_closings_closed_date
+    ON cash_closings(business_date) WHERE status = 'closed';
+CREATE INDEX idx_cash_closings_date ON cash_closings(business_date DESC);
@@ -0,0 +1,46 @@
+-- ============================================================
+-- Anulación de documentos con registros compensatorios
+-- ============================================================
+-- Los documentos no se borran: quedan marcados como anulados y sus
+-- efectos (caja, inventario, contadores) se revierten con movimientos
+-- nuevos. Solo el dueño puede anular y siempre con un motivo.
+
+ALTER TABLE local_sales
+    ADD COLUMN voided_at TIMESTAMPTZ,
+    ADD COLUMN voided_by UUID REFERENCES users(id),
+    ADD COLUMN void_reason TEXT;
+ALTER TABLE owner_sales
+    ADD COLUMN voided_at TIMESTAMPTZ,
+    ADD COLUMN voided_by UUID REFERENCES users(id),
+    ADD COLUMN void_reason TEXT;
+ALTER TABLE purchases
+    ADD COLUMN voided_at TIMESTAMPTZ,
+    ADD COLUMN voided_by UUID REFERENCES users(id),
+    ADD COLUMN void_reason TEXT;
+ALTER TABLE freezer_transfers
+    ADD COLUMN voided_at TIMESTAMPTZ,
+    ADD COLUMN voided_by UUID REFERENCES users(id),
+    ADD COLUMN void_reason TEXT;
+
+-- Reversa de caja: monto opuesto al original, enlazado con
+-- related_doc_type = 'cash_register' y related_doc_id = movimiento revertido
+ALTER TABLE cash_register DROP CONSTRAINT cash_register_type_check;
+ALTER TABLE cash_register ADD CONSTRAINT cash_register_type_check CHECK (type IN (
+    'worker_payment', 'local_sale', 'owner_sale',
+    'owner_withdrawal', 'expense', 'provider_payment', 'purchase_payment',
+    'cash_surplus', 'cash_shortage', 'reversal'
+));
+
+-- Un movimiento se revierte una sola vez
+CREATE UNIQUE INDEX idx_cash_register_reversal
+    ON cash_register(related_doc_id) WHERE type = 'reversal';
+
+-- Reversa de stock: enlazada al documento anulado
+ALTER TABLE inventory_movements DROP CONSTRAINT inventory_movements_movement_type_check;
+ALTER TABLE inventory_movements ADD CONSTRAINT inventory_movements_movement_type_check
+    CHECK (movement_type IN (
+        'opening', 'manual_add', 'purchase',
+        'trip_load', 'trip_return',
+        'local_sale', 'owner_sale_load', 'owner_sale_return',
+        'transfer_out', 'transfer_in', 'adjustment', 'void'
+    ));
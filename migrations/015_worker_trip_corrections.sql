@@ -0,0 +1,31 @@
+-- ============================================================
+-- Corrección y cancelación de viajes de trabajadores
+-- ============================================================
+-- Un viaje retornado puede corregirse (devoluciones mal contadas):
+-- se revierte el stock devuelto, se aplica el nuevo conteo y la deuda
+-- del trabajador se ajusta por la diferencia. Queda la última
+-- corrección en el viaje; el historial completo está en audit_log.
+-- Un viaje en progreso puede cancelarse devolviendo toda la carga.
+
+ALTER TABLE worker_trips
+    ADD COLUMN corrected_at TIMESTAMPTZ,
+    ADD COLUMN corrected_by UUID REFERENCES users(id),
+    ADD COLUMN correction_reason TEXT,
+    ADD COLUMN cancelled_at TIMESTAMPTZ,
+    ADD COLUMN cancelled_by UUID REFERENCES users(id),
+    ADD COLUMN cancel_reason TEXT;
+
+ALTER TABLE worker_trips DROP CONSTRAINT worker_trips_status_check;
+ALTER TABLE worker_trips ADD CONSTRAINT worker_trips_status_check
+    CHECK (status IN ('in_progress', 'returned', 'cancelled'));
+
+-- Reversa y reaplicación de devoluciones corregidas
+ALTER TABLE inventory_movements DROP CONSTRAINT inventory_movements_movement_type_check;
+ALTER TABLE inventory_movements ADD CONSTRAINT inventory_movements_movement_type_check
+    CHECK (movement_type IN (
+        'opening', 'manual_add', 'purchase',
+        'trip_load', 'trip_return',
+        'local_sale', 'owner_sale_load', 'owner_sale_return',
+        'transfer_out', 'transfer_in', 'adjustment', 'void',
+        'trip_correction'
+    ));
@@ -0,0 +1,25 @@
+-- ============================================================
+-- Devoluciones imputadas a la línea cargada
+-- ============================================================
+-- Un mismo sabor puede cargarse desde varios congeladores o como
+-- bueno y deformado; cada devolución apunta a la línea de la que
+-- vuelve para cobrar al precio correcto y con el proveedor exacto.
+
+ALTER TABLE worker_trip_returned_items
+    ADD COLUMN loaded_item_id UUID REFERENCES worker_trip_loaded_items(id) ON DELETE CASCADE;
+
+CREATE INDEX idx_returned_items_loaded ON worker_trip_returned_items(loaded_item_id);
+
+-- Viajes existentes: solo cuando la línea es inequívoca
+UPDATE worker_trip_returned_items ri
+SET loaded_item_id = li.id
+FROM worker_trip_loaded_items li
+WHERE li.trip_id = ri.trip_id
+  AND li.product_id = ri.product_id
+  AND li.flavor_id = ri.flavor_id
+  AND (
+      SELECT COUNT(*) FROM worker_trip_loaded_items x
+      WHERE x.trip_id = ri.trip_id
+        AND x.product_id = ri.product_id
+        AND x.flavor_id = ri.flavor_id
+  ) = 1;
@@ -0,0 +1,44 @@
+-- ============================================================
+-- Ciclo de vida de los deformados
+-- ============================================================
+-- Los deformados se acumulan en una fila por congelador, producto,
+-- sabor, proveedor y trabajador (UPSERT, como el stock normal) y se
+-- eliminan al llegar a 0. Pueden reasignarse a otro trabajador o
+-- liberarse al stock general de deformados (sin trabajador).
+
+-- Las líneas de documentos no pueden impedir que una fila de
+-- deformados en 0 se elimine; los datos de la fila ya están en la
+-- línea y en el kardex (igual que inventory_movements).
+ALTER TABLE worker_trip_loaded_items DROP CONSTRAINT worker_trip_loaded_items_inventory_id_fkey;
+ALTER TABLE owner_sale_loaded_items DROP CONSTRAINT owner_sale_loaded_items_inventory_id_fkey;
+ALTER TABLE local_sale_items DROP CONSTRAINT local_sale_items_inventory_id_fkey;
+
+CREATE TABLE deformed_transfers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    from_inventory_id UUID NOT NULL,
+    to_inventory_id UUID NOT NULL,
+    freezer_id UUID NOT NULL REFERENCES freezers(id),
+    product_id UUID NOT NULL REFERENCES products(id),
+    flavor_id UUID NOT NULL REFERENCES flavors(id),
+    -- NULL = stock general de deformados
+    from_worker_id UUID REFERENCES workers(id),
+    to_worker_id UUID REFERENCES workers(id),
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    notes TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by UUID NOT NULL REFERENCES users(id),
+    CHECK (from_worker_id IS DISTINCT FROM to_worker_id)
+);
+
+CREATE INDEX idx_deformed_transfers_from ON deformed_transfers(from_worker_id, created_at);
+CREATE INDEX idx_deformed_transfers_to ON deformed_transfers(to_worker_id, created_at);
+
+ALTER TABLE inventory_movements DROP CONSTRAINT inventory_movements_movement_type_check;
+ALTER TABLE inventory_movements ADD CONSTRAINT inventory_movements_movement_type_check
+    CHECK (movement_type IN (
+        'opening', 'manual_add', 'purchase',
+        'trip_load', 'trip_return',
+        'local_sale', 'owner_sale_load', 'owner_sale_return',
+        'transfer_out', 'transfer_in', 'adjustment', 'void',
+        'trip_correction', 'deformed_transfer'
+    ));
@@ -0,0 +1,10 @@
+-- ============================================================
+-- Ajustes de inventario sin costo
+-- ============================================================
+-- Un ajuste de un producto sin precio en price_history queda con costo
+-- nulo en vez de 0, para que el reporte de merma lo muestre como no
+-- valorizado. Los ajustes anteriores conservan su valor.
+
+ALTER TABLE inventory_adjustments
+    ALTER COLUMN unit_cost DROP NOT NULL,
+    ALTER COLUMN total_cost DROP NOT NULL;
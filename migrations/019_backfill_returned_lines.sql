@@ -0,0 +1,70 @@
+-- ============================================================
+-- Devoluciones heredadas imputadas a una línea
+-- ============================================================
+-- 016 solo enlazó las devoluciones con una única línea posible; las
+-- ambiguas quedaron sin `loaded_item_id` y los reportes por línea las
+-- contaban como vendidas. Se reparten con el mismo orden que
+-- `allocate_returns`: primero la línea del mismo estado (buena o
+-- deformada), luego la del congelador de destino, luego por id. Una
+-- devolución que abarca varias líneas se divide en varias filas.
+
+DO $$
+DECLARE
+    r RECORD;
+    l RECORD;
+    pending INTEGER;
+    take INTEGER;
+    last_line UUID;
+BEGIN
+    FOR r IN
+        SELECT * FROM worker_trip_returned_items
+        WHERE loaded_item_id IS NULL
+        ORDER BY trip_id, id
+    LOOP
+        pending := r.quantity;
+        last_line := NULL;
+
+        FOR l IN
+            SELECT li.id,
+                   li.quantity - COALESCE((
+                       SELECT SUM(x.quantity) FROM worker_trip_returned_items x
+                       WHERE x.loaded_item_id = li.id
+                   ), 0) AS remaining
+            FROM worker_trip_loaded_items li
+            WHERE li.trip_id = r.trip_id
+              AND li.product_id = r.product_id
+              AND li.flavor_id = r.flavor_id
+              AND (r.is_deformed OR NOT li.is_deformed)
+            ORDER BY li.is_deformed <> r.is_deformed,
+                     li.freezer_id <> r.destination_freezer_id,
+                     li.id
+        LOOP
+            last_line := l.id;
+            take := LEAST(pending, GREATEST(l.remaining, 0));
+            CONTINUE WHEN take = 0;
+
+            IF take = pending THEN
+                UPDATE worker_trip_returned_items
+                SET loaded_item_id = l.id, quantity = take
+                WHERE id = r.id;
+            ELSE
+                INSERT INTO worker_trip_returned_items
+                    (trip_id, loaded_item_id, product_id, flavor_id, quantity,
+                     is_deformed, destination_freezer_id)
+                VALUES
+                    (r.trip_id, l.id, r.product_id, r.flavor_id, take,
+                     r.is_deformed, r.destination_freezer_id);
+            END IF;
+            pending := pending - take;
+            EXIT WHEN pending = 0;
+        END LOOP;
+
+        -- Datos heredados que superan lo cargado: el exceso queda en la
+        -- última línea candidata para que ninguna unidad cuente como vendida.
+        IF pending > 0 AND last_line IS NOT NULL THEN
+            UPDATE worker_trip_returned_items
+            SET loaded_item_id = last_line, quantity = pending
+            WHERE id = r.id;
+        END IF;
+    END LOOP;
+END $$;
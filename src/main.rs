@@ -16,14 +16,22 @@ use shared::db::create_pool;
 
 // ─── Repositorios (adaptadores) ─────────────────────────
 use modules::audit_log::infrastructure::persistence::postgres_repo::PgAuditLogRepository;
-use modules::cash_register::infrastructure::persistence::postgres_repo::PgCashRegisterRepository;
+use modules::cash_register::infrastructure::persistence::postgres_repo::{
+    PgCashClosingRepository, PgCashRegisterRepository,
+};
 use modules::catalog::infrastructure::persistence::postgres_repo::*;
 use modules::freezer_transfers::infrastructure::persistence::postgres_repo::PgFreezerTransferRepository;
-use modules::inventory::infrastructure::persistence::postgres_repo::PgInventoryRepository;
+use modules::inventory::infrastructure::persistence::postgres_repo::{
+    PgInventoryRepository, PgStockAdjustmentRepository,
+};
 use modules::local_sales::infrastructure::persistence::postgres_repo::PgLocalSaleRepository;
 use modules::owner_sales::infrastructure::persistence::postgres_repo::PgOwnerSaleRepository;
 use modules::pricing::infrastructure::persistence::postgres_repo::PgPriceRepository;
-use modules::purchases::infrastructure::persistence::postgres_repo::PgPurchaseRepository;
+use modules::purchases::infrastructure::persistence::postgres_repo::{
+    PgProviderPayableRepository, PgPurchaseRepository,
+};
+use modules::reconciliation::infrastructure::persistence::postgres_repo::PgReconciliationRepository;
+use modules::reports::infrastructure::persistence::postgres_repo::PgReportsRepository;
 use modules::users::infrastructure::persistence::postgres_repo::PgUserRepository;
 use modules::worker_payments::infrastructure::persistence::postgres_repo::PgWorkerPaymentRepository;
 use modules::worker_trips::infrastructure::persistence::postgres_repo::PgWorkerTripRepository;
@@ -39,6 +47,8 @@ use modules::local_sales::infrastructure::controllers::http_router as local_sale
 use modules::owner_sales::infrastructure::controllers::http_router as owner_sales_router;
 use modules::pricing::infrastructure::controllers::http_router as pricing_router;
 use modules::purchases::infrastructure::controllers::http_router as purchases_router;
+use modules::reconciliation::infrastructure::controllers::http_router as reconciliation_router;
+use modules::reports::infrastructure::controllers::http_router as reports_router;
 use modules::users::infrastructure::controllers::http_router as users_router;
 use modules::worker_payments::infrastructure::controllers::http_router as payments_router;
 use modules::worker_trips::infrastructure::controllers::http_router as trips_router;
@@ -75,52 +85,101 @@ async fn main() {
     };
 
     // ─── Instanciar repositorios ────────────────────────
-    let user_repo = Arc::new(PgUserRepository::new(pool.clone()))
-        as Arc<dyn modules::users::domain::repositories::UserRepository>;
-
+    // Toda escritura se audita a través de este repositorio
     let audit_repo = Arc::new(PgAuditLogRepository::new(pool.clone()))
         as Arc<dyn modules::audit_log::domain::repositories::AuditLogRepository>;
 
-    let product_repo = Arc::new(PgProductRepository::new(pool.clone()))
+    let user_repo = Arc::new(PgUserRepository::new(pool.clone(), audit_repo.clone()))
+        as Arc<dyn modules::users::domain::repositories::UserRepository>;
+
+    let product_repo = Arc::new(PgProductRepository::new(pool.clone(), audit_repo.clone()))
         as Arc<dyn modules::catalog::domain::repositories::ProductRepository>;
-    let flavor_repo = Arc::new(PgFlavorRepository::new(pool.clone()))
+    let flavor_repo = Arc::new(PgFlavorRepository::new(pool.clone(), audit_repo.clone()))
         as Arc<dyn modules::catalog::domain::repositories::FlavorRepository>;
-    let provider_repo = Arc::new(PgProviderRepository::new(pool.clone()))
+    let provider_repo = Arc::new(PgProviderRepository::new(pool.clone(), audit_repo.clone()))
         as Arc<dyn modules::catalog::domain::repositories::ProviderRepository>;
-    let worker_repo = Arc::new(PgWorkerRepository::new(pool.clone()))
+    let worker_repo = Arc::new(PgWorkerRepository::new(pool.clone(), audit_repo.clone()))
         as Arc<dyn modules::catalog::domain::repositories::WorkerRepository>;
-    let route_repo = Arc::new(PgRouteRepository::new(pool.clone()))
+    let route_repo = Arc::new(PgRouteRepository::new(pool.clone(), audit_repo.clone()))
         as Arc<dyn modules::catalog::domain::repositories::RouteRepository>;
-    let freezer_repo = Arc::new(PgFreezerRepository::new(pool.clone()))
+    let freezer_repo = Arc::new(PgFreezerRepository::new(pool.clone(), audit_repo.clone()))
         as Arc<dyn modules::catalog::domain::repositories::FreezerRepository>;
 
-    let price_repo = Arc::new(PgPriceRepository::new(pool.clone()))
+    let price_repo = Arc::new(PgPriceRepository::new(pool.clone(), audit_repo.clone()))
         as Arc<dyn modules::pricing::domain::repositories::PriceRepository>;
 
-    let inventory_repo = Arc::new(PgInventoryRepository::new(pool.clone()))
+    let inventory_repo = Arc::new(PgInventoryRepository::new(pool.clone(), audit_repo.clone()))
         as Arc<dyn modules::inventory::domain::repositories::InventoryRepository>;
 
-    let purchase_repo = Arc::new(PgPurchaseRepository::new(pool.clone()))
+    let adjustment_repo = Arc::new(PgStockAdjustmentRepository::new(
+        pool.clone(),
+        audit_repo.clone(),
+    ))
+        as Arc<dyn modules::inventory::domain::repositories::StockAdjustmentRepository>;
+
+    let purchase_repo = Arc::new(PgPurchaseRepository::new(pool.clone(), audit_repo.clone()))
         as Arc<dyn modules::purchases::domain::repositories::PurchaseRepository>;
 
-    let trip_repo = Arc::new(PgWorkerTripRepository::new(pool.clone()))
+    let payable_repo = Arc::new(PgProviderPayableRepository::new(
+        pool.clone(),
+        audit_repo.clone(),
+    ))
+        as Arc<dyn modules::purchases::domain::repositories::ProviderPayableRepository>;
+
+    let trip_repo = Arc::new(PgWorkerTripRepository::new(
+        pool.clone(),
+        audit_repo.clone(),
+    ))
         as Arc<dyn modules::worker_trips::domain::repositories::WorkerTripRepository>;
 
-    let payment_repo = Arc::new(PgWorkerPaymentRepository::new(pool.clone()))
+    let payment_repo = Arc::new(PgWorkerPaymentRepository::new(
+        pool.clone(),
+        audit_repo.clone(),
+    ))
         as Arc<dyn modules::worker_payments::domain::repositories::WorkerPaymentRepository>;
 
-    let cash_repo = Arc::new(PgCashRegisterRepository::new(pool.clone()))
+    let cash_repo = Arc::new(PgCashRegisterRepository::new(
+        pool.clone(),
+        audit_repo.clone(),
+    ))
         as Arc<dyn modules::cash_register::domain::repositories::CashRegisterRepository>;
+    let closing_repo = Arc::new(PgCashClosingRepository::new(
+        pool.clone(),
+        audit_repo.clone(),
+    ))
+        as Arc<dyn modules::cash_register::domain::repositories::CashClosingRepository>;
 
-    let local_sale_repo = Arc::new(PgLocalSaleRepository::new(pool.clone()))
+    let local_sale_repo = Arc::new(PgLocalSaleRepository::new(pool.clone(), audit_repo.clone()))
         as Arc<dyn modules::local_sales::domain::repositories::LocalSaleRepository>;
 
-    let owner_sale_repo = Arc::new(PgOwnerSaleRepository::new(pool.clone()))
+    let owner_sale_repo = Arc::new(PgOwnerSaleRepository::new(pool.clone(), audit_repo.clone()))
         as Arc<dyn modules::owner_sales::domain::repositories::OwnerSaleRepository>;
 
-    let transfer_repo = Arc::new(PgFreezerTransferRepository::new(pool.clone()))
+    let transfer_repo = Arc::new(PgFreezerTransferRepository::new(
+        pool.clone(),
+        audit_repo.clone(),
+    ))
         as Arc<dyn modules::freezer_transfers::domain::repositories::FreezerTransferRepository>;
 
+    let reconciliation_repo = Arc::new(PgReconciliationRepository::new(
+        pool.clone(),
+        audit_repo.clone(),
+    ))
+        as Arc<dyn modules::reconciliation::domain::repositories::ReconciliationRepository>;
+
+    let reports_repo = Arc::new(PgReportsRepository::new(pool.clone()))
+        as Arc<dyn modules::reports::domain::repositories::ReportsRepository>;
+
+    // ─── Revisión periódica de contadores ───────────────
+    if config.reconcile_interval_minutes > 0 {
+        tokio::spawn(
+            modules::reconciliation::application::reconcile_counters::run_scheduled(
+                reconciliation_repo.clone(),
+                std::time::Duration::from_secs(config.reconcile_interval_minutes * 60),
+            ),
+        );
+    }
+
     // ─── Construir catálogo state ───────────────────────
     let catalog_state = catalog_router::CatalogState {
         app: app_state.clone(),
@@ -129,7 +188,7 @@ async fn main() {
         providers: provider_repo,
         workers: worker_repo,
         routes: route_repo,
-        freezers: freezer_repo,
+        freezers: freezer_repo.clone(),
     };
 
     // ─── OpenAPI / Swagger ───────────────────────────────
@@ -188,6 +247,11 @@ async fn main() {
         "/api/transfers",
         transfers_router::TransfersApiDoc::openapi(),
     );
+    doc = doc.nest(
+        "/api/reconciliation",
+        reconciliation_router::ReconciliationApiDoc::openapi(),
+    );
+    doc = doc.nest("/api/reports", reports_router::ReportsApiDoc::openapi());
 
     // ─── CORS ───────────────────────────────────────────
     let cors = CorsLayer::new()
@@ -213,19 +277,24 @@ async fn main() {
         .nest("/api/catalog", catalog_router::router(catalog_state))
         .nest(
             "/api/prices",
-            pricing_router::router(app_state.clone(), price_repo),
+            pricing_router::router(app_state.clone(), price_repo.clone()),
         )
         .nest(
             "/api/inventory",
-            inventory_router::router(app_state.clone(), inventory_repo),
+            inventory_router::router(app_state.clone(), inventory_repo.clone(), adjustment_repo),
         )
         .nest(
             "/api/purchases",
-            purchases_router::router(app_state.clone(), purchase_repo),
+            purchases_router::router(app_state.clone(), purchase_repo, payable_repo),
         )
         .nest(
             "/api/trips",
-            trips_router::router(app_state.clone(), trip_repo),
+            trips_router::router(
+                app_state.clone(),
+                trip_repo,
+                price_repo.clone(),
+                inventory_repo.clone(),
+            ),
         )
         .nest(
             "/api/payments",
@@ -233,19 +302,42 @@ async fn main() {
         )
         .nest(
             "/api/cash",
-            cash_router::router(app_state.clone(), cash_repo),
+            cash_router::router(app_state.clone(), cash_repo, closing_repo),
         )
         .nest(
             "/api/local-sales",
-            local_sales_router::router(app_state.clone(), local_sale_repo),
+            local_sales_router::router(
+                app_state.clone(),
+                local_sale_repo,
+                price_repo.clone(),
+                inventory_repo.clone(),
+            ),
         )
         .nest(
             "/api/owner-sales",
-            owner_sales_router::router(app_state.clone(), owner_sale_repo),
+            owner_sales_router::router(
+                app_state.clone(),
+                owner_sale_repo,
+                price_repo.clone(),
+                inventory_repo.clone(),
+            ),
         )
         .nest(
             "/api/transfers",
-            transfers_router::router(app_state.clone(), transfer_repo),
+            transfers_router::router(
+                app_state.clone(),
+                transfer_repo,
+                freezer_repo.clone(),
+                inventory_repo.clone(),
+            ),
+        )
+        .nest(
+            "/api/reconciliation",
+            reconciliation_router::router(app_state.clone(), reconciliation_repo),
+        )
+        .nest(
+            "/api/reports",
+            reports_router::router(app_state.clone(), reports_repo),
         )
         .layer(cors)
         .layer(TraceLayer::new_for_http());
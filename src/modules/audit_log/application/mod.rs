@@ -1 +1,2 @@
 pub mod log_action;
+pub mod search_audit;
@@ -0,0 +1,109 @@
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::modules::audit_log::domain::entities::{
+    AuditAction, AuditLogFilter, AuditLogPage, AuditLogView,
+};
+use crate::modules::audit_log::domain::repositories::AuditLogRepository;
+use crate::shared::csv::CsvWriter;
+use crate::shared::errors::AppError;
+
+/// Máximo de registros por exportación; más allá hay que acotar el filtro.
+pub const MAX_EXPORT_ROWS: i64 = 10_000;
+
+fn validate(filter: &AuditLogFilter) -> Result<(), AppError> {
+    if let (Some(from), Some(to)) = (filter.from, filter.to) {
+        if from > to {
+            return Err(AppError::BadRequest(
+                "La fecha inicial no puede ser mayor a la final".into(),
+            ));
+        }
+    }
+    if let Some(action) = &filter.action {
+        action.parse::<AuditAction>()?;
+    }
+    Ok(())
+}
+
+/// Búsqueda paginada. Página por defecto 1; 50 por página, nunca más de 200.
+pub async fn search(
+    repo: &Arc<dyn AuditLogRepository>,
+    filter: AuditLogFilter,
+) -> Result<AuditLogPage, AppError> {
+    validate(&filter)?;
+    let page = filter.page.unwrap_or(1).max(1);
+    let per_page = filter.per_page.unwrap_or(50).clamp(1, 200);
+
+    let total = repo.count(&filter).await?;
+    let entries = repo
+        .search(&filter, per_page, (page - 1) * per_page)
+        .await?;
+
+    Ok(AuditLogPage {
+        items: entries.into_iter().map(AuditLogView::from).collect(),
+        total,
+        page,
+        per_page,
+    })
+}
+
+/// Un registro con su diff por campo.
+pub async fn find_entry(
+    repo: &Arc<dyn AuditLogRepository>,
+    id: Uuid,
+) -> Result<AuditLogView, AppError> {
+    repo.find_by_id(id)
+        .await?
+        .map(AuditLogView::from)
+        .ok_or_else(|| AppError::NotFound("Registro de auditoría no encontrado".into()))
+}
+
+/// CSV del resultado filtrado (sin paginar): una fila por campo modificado.
+pub async fn export_csv(
+    repo: &Arc<dyn AuditLogRepository>,
+    filter: AuditLogFilter,
+) -> Result<String, AppError> {
+    validate(&filter)?;
+    let total = repo.count(&filter).await?;
+    if total > MAX_EXPORT_ROWS {
+        return Err(AppError::BadRequest(format!(
+            "La exportación supera {MAX_EXPORT_ROWS} registros ({total}); acote el filtro"
+        )));
+    }
+    let entries = repo.search(&filter, MAX_EXPORT_ROWS, 0).await?;
+
+    let mut csv = CsvWriter::new(&[
+        "fecha", "usuario", "accion", "tabla", "registro", "campo", "antes", "despues",
+    ]);
+    for view in entries.into_iter().map(AuditLogView::from) {
+        let e = &view.entry;
+        let prefix = [
+            e.created_at.to_rfc3339(),
+            e.created_by.to_string(),
+            e.action.clone(),
+            e.table_name.clone(),
+            e.record_id.to_string(),
+        ];
+        if view.changes.is_empty() {
+            csv.row(prefix.iter().map(String::as_str).chain(["", "", ""]));
+        }
+        for change in &view.changes {
+            let values = [
+                change.field.clone(),
+                csv_value(change.before.as_ref()),
+                csv_value(change.after.as_ref()),
+            ];
+            csv.row(prefix.iter().chain(values.iter()));
+        }
+    }
+    Ok(csv.finish())
+}
+
+/// Texto plano para celdas: sin comillas JSON en strings, vacío para null.
+fn csv_value(value: Option<&serde_json::Value>) -> String {
+    match value {
+        None | Some(serde_json::Value::Null) => String::new(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
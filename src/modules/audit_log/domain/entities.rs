@@ -1,7 +1,10 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use uuid::Uuid;
 
+use crate::shared::errors::AppError;
+
 /// Acción registrada en el log de auditoría.
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "VARCHAR")]
@@ -23,6 +26,19 @@ impl AuditAction {
     }
 }
 
+impl FromStr for AuditAction {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, AppError> {
+        match s {
+            "create" => Ok(AuditAction::Create),
+            "update" => Ok(AuditAction::Update),
+            "delete" => Ok(AuditAction::Delete),
+            _ => Err(AppError::BadRequest(format!("Acción inválida: {s}"))),
+        }
+    }
+}
+
 /// Registro de auditoría.
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
 pub struct AuditLogEntry {
@@ -34,6 +50,104 @@ pub struct AuditLogEntry {
     pub changes_after: Option<serde_json::Value>,
     pub created_by: Uuid,
     pub created_at: DateTime<Utc>,
+    /// Posición en la cadena de hashes (asignada por la BD).
+    pub seq: i64,
+    pub prev_hash: Option<String>,
+    pub hash: String,
+}
+
+/// Filtros de búsqueda de auditoría (todos opcionales).
+#[derive(Debug, Default, Clone, Deserialize, utoipa::IntoParams)]
+pub struct AuditLogFilter {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub table_name: Option<String>,
+    pub record_id: Option<Uuid>,
+    /// create, update o delete.
+    pub action: Option<String>,
+    /// Usuario que realizó la acción.
+    pub user_id: Option<Uuid>,
+    /// Página (desde 1).
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+/// Cambio de un campo entre `changes_before` y `changes_after`.
+/// Los objetos anidados se aplanan con notación de punto (`purchase.total`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+/// Registro de auditoría con su diff por campo.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct AuditLogView {
+    #[serde(flatten)]
+    pub entry: AuditLogEntry,
+    pub changes: Vec<FieldChange>,
+}
+
+impl From<AuditLogEntry> for AuditLogView {
+    fn from(entry: AuditLogEntry) -> Self {
+        let changes = field_diff(entry.changes_before.as_ref(), entry.changes_after.as_ref());
+        Self { entry, changes }
+    }
+}
+
+/// Página de resultados de auditoría.
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct AuditLogPage {
+    pub items: Vec<AuditLogView>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+}
+
+/// Diff por campo entre dos snapshots JSON. Solo devuelve los campos que cambian.
+pub fn field_diff(
+    before: Option<&serde_json::Value>,
+    after: Option<&serde_json::Value>,
+) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    diff_into(&mut changes, String::new(), before, after);
+    changes
+}
+
+fn diff_into(
+    changes: &mut Vec<FieldChange>,
+    path: String,
+    before: Option<&serde_json::Value>,
+    after: Option<&serde_json::Value>,
+) {
+    use serde_json::Value;
+
+    if before == after {
+        return;
+    }
+    if let (None | Some(Value::Object(_)), None | Some(Value::Object(_))) = (before, after) {
+        let empty = serde_json::Map::new();
+        let b = before.and_then(Value::as_object).unwrap_or(&empty);
+        let a = after.and_then(Value::as_object).unwrap_or(&empty);
+        let mut keys: Vec<&String> = b.keys().chain(a.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            let field = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{path}.{key}")
+            };
+            diff_into(changes, field, b.get(key), a.get(key));
+        }
+        return;
+    }
+    changes.push(FieldChange {
+        field: path,
+        before: before.cloned(),
+        after: after.cloned(),
+    });
 }
 
 /// DTO para crear un nuevo registro de auditoría.
@@ -45,3 +159,57 @@ pub struct CreateAuditLogDto {
     pub changes_after: Option<serde_json::Value>,
     pub created_by: Uuid,
 }
+
+impl CreateAuditLogDto {
+    /// Alta de un registro: solo el estado posterior.
+    pub fn created<T: Serialize>(
+        table_name: &str,
+        record_id: Uuid,
+        after: &T,
+        created_by: Uuid,
+    ) -> Result<Self, AppError> {
+        Ok(Self {
+            action: AuditAction::Create,
+            table_name: table_name.to_string(),
+            record_id,
+            changes_before: None,
+            changes_after: Some(serde_json::to_value(after)?),
+            created_by,
+        })
+    }
+
+    /// Modificación de un registro: estado anterior y posterior.
+    pub fn updated<B: Serialize, A: Serialize>(
+        table_name: &str,
+        record_id: Uuid,
+        before: &B,
+        after: &A,
+        created_by: Uuid,
+    ) -> Result<Self, AppError> {
+        Ok(Self {
+            action: AuditAction::Update,
+            table_name: table_name.to_string(),
+            record_id,
+            changes_before: Some(serde_json::to_value(before)?),
+            changes_after: Some(serde_json::to_value(after)?),
+            created_by,
+        })
+    }
+
+    /// Baja de un registro: solo el estado anterior.
+    pub fn deleted<T: Serialize>(
+        table_name: &str,
+        record_id: Uuid,
+        before: &T,
+        created_by: Uuid,
+    ) -> Result<Self, AppError> {
+        Ok(Self {
+            action: AuditAction::Delete,
+            table_name: table_name.to_string(),
+            record_id,
+            changes_before: Some(serde_json::to_value(before)?),
+            changes_after: None,
+            created_by,
+        })
+    }
+}
@@ -1,8 +1,9 @@
 use async_trait::async_trait;
 use uuid::Uuid;
 
-use super::entities::{AuditLogEntry, CreateAuditLogDto};
+use super::entities::{AuditLogEntry, AuditLogFilter, CreateAuditLogDto};
 use crate::shared::errors::AppError;
+use crate::shared::hash_chain::ChainVerification;
 
 /// Puerto de salida: contrato de persistencia para audit_log.
 #[async_trait]
@@ -27,4 +28,21 @@ pub trait AuditLogRepository: Send + Sync {
     /// Obtener registros de un usuario específico.
     async fn find_by_user(&self, user_id: Uuid, limit: i64)
         -> Result<Vec<AuditLogEntry>, AppError>;
+
+    /// Buscar registros filtrados (más recientes primero). La paginación del
+    /// filtro se ignora: el llamador pasa `limit`/`offset` ya validados.
+    async fn search(
+        &self,
+        filter: &AuditLogFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AuditLogEntry>, AppError>;
+
+    /// Total de registros que cumplen el filtro.
+    async fn count(&self, filter: &AuditLogFilter) -> Result<i64, AppError>;
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<AuditLogEntry>, AppError>;
+
+    /// Recorre la cadena de hashes y reporta el primer eslabón roto.
+    async fn verify_chain(&self) -> Result<ChainVerification, AppError>;
 }
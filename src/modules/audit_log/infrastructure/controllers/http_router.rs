@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use axum::{
     extract::{Path, Query, State},
+    response::Response,
     routing::get,
     Json, Router,
 };
@@ -9,15 +10,27 @@ use serde::Deserialize;
 use utoipa::OpenApi;
 use uuid::Uuid;
 
-use crate::modules::audit_log::domain::entities::AuditLogEntry;
+use crate::modules::audit_log::application::search_audit;
+use crate::modules::audit_log::domain::entities::{
+    AuditLogEntry, AuditLogFilter, AuditLogPage, AuditLogView,
+};
 use crate::modules::audit_log::domain::repositories::AuditLogRepository;
 use crate::shared::auth::{AppState, AuthUser};
+use crate::shared::csv::csv_response;
 use crate::shared::errors::AppError;
+use crate::shared::hash_chain::ChainVerification;
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(list_handler, by_user_handler),
-    components(schemas(crate::modules::audit_log::domain::entities::AuditLogEntry,))
+    paths(list_handler, export_handler, verify_chain_handler, get_handler, by_user_handler),
+    components(schemas(
+        crate::modules::audit_log::domain::entities::AuditLogEntry,
+        crate::modules::audit_log::domain::entities::AuditLogView,
+        crate::modules::audit_log::domain::entities::AuditLogPage,
+        crate::modules::audit_log::domain::entities::FieldChange,
+        crate::shared::hash_chain::ChainVerification,
+        crate::shared::hash_chain::BrokenLink,
+    ))
 )]
 pub struct AuditApiDoc;
 
@@ -35,8 +48,6 @@ impl axum::extract::FromRef<AuditState> for AppState {
 
 #[derive(Debug, Deserialize, utoipa::IntoParams)]
 pub struct AuditQuery {
-    pub table_name: Option<String>,
-    pub record_id: Option<Uuid>,
     pub limit: Option<i64>,
 }
 
@@ -48,38 +59,93 @@ pub fn router(app_state: AppState, repo: Arc<dyn AuditLogRepository>) -> Router
 
     Router::new()
         .route("/", get(list_handler))
+        .route("/export", get(export_handler))
+        .route("/verify-chain", get(verify_chain_handler))
+        .route("/{id}", get(get_handler))
         .route("/user/{user_id}", get(by_user_handler))
         .with_state(state)
 }
 
-/// GET /audit — Buscar registros de auditoría por tabla y record_id.
+/// GET /audit — Buscar registros por fecha, tabla, acción y usuario (paginado, con diff).
 #[utoipa::path(
     get,
     path = "/",
     tag = "Auditoría",
-    params(AuditQuery),
+    params(AuditLogFilter),
     responses(
-        (status = 200, description = "Registros de auditoría", body = Vec<AuditLogEntry>),
-        (status = 400, description = "Faltan parámetros requeridos")
+        (status = 200, description = "Página de registros de auditoría", body = AuditLogPage),
+        (status = 400, description = "Filtro inválido")
     ),
     security(("bearer_auth" = []))
 )]
 async fn list_handler(
     auth: AuthUser,
     State(state): State<AuditState>,
-    Query(query): Query<AuditQuery>,
-) -> Result<Json<Vec<AuditLogEntry>>, AppError> {
+    Query(filter): Query<AuditLogFilter>,
+) -> Result<Json<AuditLogPage>, AppError> {
     auth.require_owner()?;
+    Ok(Json(search_audit::search(&state.repo, filter).await?))
+}
 
-    match (query.table_name, query.record_id) {
-        (Some(table), Some(record_id)) => {
-            let entries = state.repo.find_by_record(&table, record_id).await?;
-            Ok(Json(entries))
-        }
-        _ => Err(AppError::BadRequest(
-            "Se requieren los parámetros table_name y record_id".into(),
-        )),
-    }
+/// GET /audit/export — CSV del resultado filtrado, una fila por campo modificado.
+#[utoipa::path(
+    get,
+    path = "/export",
+    tag = "Auditoría",
+    params(AuditLogFilter),
+    responses(
+        (status = 200, description = "CSV de auditoría", content_type = "text/csv", body = String),
+        (status = 400, description = "Filtro inválido o demasiados registros")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn export_handler(
+    auth: AuthUser,
+    State(state): State<AuditState>,
+    Query(filter): Query<AuditLogFilter>,
+) -> Result<Response, AppError> {
+    auth.require_owner()?;
+    let csv = search_audit::export_csv(&state.repo, filter).await?;
+    Ok(csv_response("auditoria.csv", csv))
+}
+
+/// GET /audit/verify-chain — Recorre la cadena de hashes del log de auditoría.
+#[utoipa::path(
+    get,
+    path = "/verify-chain",
+    tag = "Auditoría",
+    responses(
+        (status = 200, description = "Resultado de la verificación", body = ChainVerification)
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn verify_chain_handler(
+    auth: AuthUser,
+    State(state): State<AuditState>,
+) -> Result<Json<ChainVerification>, AppError> {
+    auth.require_owner()?;
+    Ok(Json(state.repo.verify_chain().await?))
+}
+
+/// GET /audit/:id — Un registro con su diff por campo.
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    tag = "Auditoría",
+    params(("id" = Uuid, Path, description = "ID del registro de auditoría")),
+    responses(
+        (status = 200, description = "Registro con diff", body = AuditLogView),
+        (status = 404, description = "No encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_handler(
+    auth: AuthUser,
+    State(state): State<AuditState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<AuditLogView>, AppError> {
+    auth.require_owner()?;
+    Ok(Json(search_audit::find_entry(&state.repo, id).await?))
 }
 
 /// GET /audit/user/:user_id — Auditoría de un usuario específico.
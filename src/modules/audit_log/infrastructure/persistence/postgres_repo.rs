@@ -2,9 +2,20 @@ use async_trait::async_trait;
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::modules::audit_log::domain::entities::{AuditLogEntry, CreateAuditLogDto};
+use crate::modules::audit_log::domain::entities::{AuditLogEntry, AuditLogFilter, CreateAuditLogDto};
 use crate::modules::audit_log::domain::repositories::AuditLogRepository;
 use crate::shared::errors::AppError;
+use crate::shared::hash_chain::{self, ChainVerification, ChainedTable};
+
+/// Condiciones compartidas por `search` y `count` ($1..$6).
+const FILTER_SQL: &str = r#"
+    ($1::timestamptz IS NULL OR created_at >= $1)
+    AND ($2::timestamptz IS NULL OR created_at <= $2)
+    AND ($3::varchar IS NULL OR table_name = $3)
+    AND ($4::uuid IS NULL OR record_id = $4)
+    AND ($5::varchar IS NULL OR action = $5)
+    AND ($6::uuid IS NULL OR created_by = $6)
+"#;
 
 /// Implementación PostgreSQL del repositorio de auditoría.
 pub struct PgAuditLogRepository {
@@ -99,4 +110,53 @@ impl AuditLogRepository for PgAuditLogRepository {
         .await?;
         Ok(entries)
     }
+
+    async fn search(
+        &self,
+        filter: &AuditLogFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AuditLogEntry>, AppError> {
+        let sql = format!(
+            "SELECT * FROM audit_log WHERE {FILTER_SQL} ORDER BY seq DESC LIMIT $7 OFFSET $8"
+        );
+        let entries = sqlx::query_as::<_, AuditLogEntry>(&sql)
+            .bind(filter.from)
+            .bind(filter.to)
+            .bind(&filter.table_name)
+            .bind(filter.record_id)
+            .bind(&filter.action)
+            .bind(filter.user_id)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(entries)
+    }
+
+    async fn count(&self, filter: &AuditLogFilter) -> Result<i64, AppError> {
+        let sql = format!("SELECT COUNT(*) FROM audit_log WHERE {FILTER_SQL}");
+        Ok(sqlx::query_scalar::<_, i64>(&sql)
+            .bind(filter.from)
+            .bind(filter.to)
+            .bind(&filter.table_name)
+            .bind(filter.record_id)
+            .bind(&filter.action)
+            .bind(filter.user_id)
+            .fetch_one(&self.pool)
+            .await?)
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<AuditLogEntry>, AppError> {
+        Ok(
+            sqlx::query_as::<_, AuditLogEntry>("SELECT * FROM audit_log WHERE id = $1")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?,
+        )
+    }
+
+    async fn verify_chain(&self) -> Result<ChainVerification, AppError> {
+        hash_chain::verify_chain(&self.pool, ChainedTable::AuditLog).await
+    }
 }
@@ -0,0 +1,48 @@
+use uuid::Uuid;
+
+use crate::modules::cash_register::domain::entities::*;
+use crate::modules::cash_register::domain::repositories::CashClosingRepository;
+use crate::shared::errors::AppError;
+
+/// Cierre de caja: valida el arqueo y delega el cuadre al repositorio, que lo
+/// compara con el libro dentro de la misma transacción.
+pub async fn close_day(
+    repo: &dyn CashClosingRepository,
+    dto: &CloseDayDto,
+    closed_by: Uuid,
+) -> Result<CashClosing, AppError> {
+    let counted = counted_total(&dto.denominations).map_err(AppError::BadRequest)?;
+    repo.close_day(dto, counted, closed_by).await
+}
+
+/// Reapertura (solo dueño): exige un motivo.
+pub async fn reopen_day(
+    repo: &dyn CashClosingRepository,
+    id: Uuid,
+    dto: &ReopenDayDto,
+    reopened_by: Uuid,
+) -> Result<CashClosing, AppError> {
+    let reason = dto.reason.trim();
+    if reason.is_empty() {
+        return Err(AppError::BadRequest(
+            "Se requiere el motivo de la reapertura".into(),
+        ));
+    }
+    repo.reopen(id, reason, reopened_by).await
+}
+
+pub async fn get_closing(
+    repo: &dyn CashClosingRepository,
+    id: Uuid,
+) -> Result<CashClosing, AppError> {
+    repo.find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Cierre de caja {id} no encontrado")))
+}
+
+pub async fn list_closings(
+    repo: &dyn CashClosingRepository,
+    query: &ClosingQuery,
+) -> Result<Vec<CashClosing>, AppError> {
+    repo.list(query).await
+}
@@ -5,6 +5,8 @@ use uuid::Uuid;
 use crate::modules::cash_register::domain::entities::*;
 use crate::modules::cash_register::domain::repositories::CashRegisterRepository;
 use crate::shared::errors::AppError;
+use crate::shared::hash_chain::ChainVerification;
+use crate::shared::void::VoidDto;
 
 pub async fn get_balance(repo: &dyn CashRegisterRepository) -> Result<BalanceInfo, AppError> {
     let current = repo.get_current_balance().await?;
@@ -16,6 +18,28 @@ pub async fn get_balance(repo: &dyn CashRegisterRepository) -> Result<BalanceInf
     })
 }
 
+/// Verificación completa de la cadena, con el primer eslabón roto si lo hay.
+pub async fn verify_chain(
+    repo: &dyn CashRegisterRepository,
+) -> Result<ChainVerification, AppError> {
+    repo.verify_chain().await
+}
+
+/// Balance en un punto del libro: por secuencia o por instante, no ambos.
+pub async fn balance_at(
+    repo: &dyn CashRegisterRepository,
+    query: &BalanceAtQuery,
+) -> Result<BalanceAt, AppError> {
+    match (query.seq, query.at) {
+        (Some(seq), None) if seq >= 0 => repo.balance_at_seq(seq).await,
+        (None, Some(at)) => repo.balance_at_time(at).await,
+        (Some(_), None) => Err(AppError::BadRequest("seq no puede ser negativo".into())),
+        _ => Err(AppError::BadRequest(
+            "Indicar exactamente uno de 'seq' o 'at'".into(),
+        )),
+    }
+}
+
 pub async fn add_expense(
     repo: &dyn CashRegisterRepository,
     dto: &CreateExpenseDto,
@@ -27,16 +51,15 @@ pub async fn add_expense(
         ));
     }
     // Gastos son negativos en la caja
-    repo.add_transaction(
-        CashTransactionType::Expense,
-        -dto.amount,
-        dto.description.clone(),
-        Some(dto.category.clone()),
-        None,
-        None,
-        created_by,
-    )
-    .await
+    let entry = CashEntry {
+        tx_type: CashTransactionType::Expense,
+        amount: -dto.amount,
+        description: dto.description.clone(),
+        category: Some(dto.category.clone()),
+        related_doc_type: None,
+        related_doc_id: None,
+    };
+    repo.add_transaction(entry, created_by).await
 }
 
 pub async fn add_withdrawal(
@@ -49,16 +72,25 @@ pub async fn add_withdrawal(
             "El monto del retiro debe ser positivo".into(),
         ));
     }
-    repo.add_transaction(
-        CashTransactionType::OwnerWithdrawal,
-        -dto.amount,
-        dto.description.clone(),
-        None,
-        None,
-        None,
-        created_by,
-    )
-    .await
+    let entry = CashEntry {
+        tx_type: CashTransactionType::OwnerWithdrawal,
+        amount: -dto.amount,
+        description: dto.description.clone(),
+        category: None,
+        related_doc_type: None,
+        related_doc_id: None,
+    };
+    repo.add_transaction(entry, created_by).await
+}
+
+/// Anula un gasto o retiro manual (solo dueño): asienta la reversa.
+pub async fn void_transaction(
+    repo: &dyn CashRegisterRepository,
+    id: Uuid,
+    dto: &VoidDto,
+    voided_by: Uuid,
+) -> Result<CashTransaction, AppError> {
+    repo.void_transaction(id, dto.reason()?, voided_by).await
 }
 
 pub async fn todays_transactions(
@@ -1 +1,2 @@
+pub mod close_day;
 pub mod manage_cash;
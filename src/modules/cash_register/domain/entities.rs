@@ -1,7 +1,8 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::collections::BTreeSet;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
@@ -18,6 +19,10 @@ pub struct CashTransaction {
     pub related_doc_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub created_by: Uuid,
+    /// Posición en la cadena de hashes (asignada por la BD).
+    pub seq: i64,
+    pub prev_hash: Option<String>,
+    pub hash: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +32,11 @@ pub enum CashTransactionType {
     OwnerSale,
     OwnerWithdrawal,
     Expense,
+    ProviderPayment,
+    PurchasePayment,
+    CashSurplus,
+    CashShortage,
+    Reversal,
 }
 
 impl CashTransactionType {
@@ -37,10 +47,51 @@ impl CashTransactionType {
             Self::OwnerSale => "owner_sale",
             Self::OwnerWithdrawal => "owner_withdrawal",
             Self::Expense => "expense",
+            Self::ProviderPayment => "provider_payment",
+            Self::PurchasePayment => "purchase_payment",
+            Self::CashSurplus => "cash_surplus",
+            Self::CashShortage => "cash_shortage",
+            Self::Reversal => "reversal",
         }
     }
 }
 
+/// Movimiento a asentar en el libro de caja.
+#[derive(Debug, Clone)]
+pub struct CashEntry {
+    pub tx_type: CashTransactionType,
+    /// Positivo para ingresos, negativo para salidas.
+    pub amount: Decimal,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub related_doc_type: Option<String>,
+    pub related_doc_id: Option<Uuid>,
+}
+
+impl CashEntry {
+    /// Movimiento causado por un documento (venta, pago, compra...).
+    pub fn doc(
+        tx_type: CashTransactionType,
+        amount: Decimal,
+        doc_type: &str,
+        doc_id: Uuid,
+    ) -> Self {
+        Self {
+            tx_type,
+            amount,
+            description: None,
+            category: None,
+            related_doc_type: Some(doc_type.to_string()),
+            related_doc_id: Some(doc_id),
+        }
+    }
+
+    pub fn with_description(mut self, description: Option<String>) -> Self {
+        self.description = description;
+        self
+    }
+}
+
 #[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct CreateExpenseDto {
     pub amount: Decimal,
@@ -61,8 +112,183 @@ pub struct BalanceInfo {
     pub is_consistent: bool,
 }
 
+/// Punto del libro de caja: por número de secuencia o por instante.
+/// Debe indicarse exactamente uno de los dos.
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct BalanceAtQuery {
+    pub seq: Option<i64>,
+    pub at: Option<DateTime<Utc>>,
+}
+
+/// Balance de la caja en un punto del libro.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BalanceAt {
+    /// Último movimiento incluido (0 si no hay ninguno).
+    pub seq: i64,
+    pub balance: Decimal,
+}
+
 #[derive(Debug, Deserialize, utoipa::IntoParams)]
 pub struct DateRangeQuery {
     pub from: Option<DateTime<Utc>>,
     pub to: Option<DateTime<Utc>>,
 }
+
+// ─── Cierre de caja ─────────────────────────────────────
+
+/// Cantidad contada de un billete o moneda.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct DenominationCount {
+    /// Valor nominal (p. ej. 100.00, 0.50).
+    pub value: Decimal,
+    pub count: i32,
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct CloseDayDto {
+    /// Día a cerrar; por defecto, hoy. El sobrante o faltante se fecha en
+    /// ese día, así que no puede haber días posteriores ya cerrados.
+    pub business_date: Option<NaiveDate>,
+    pub denominations: Vec<DenominationCount>,
+    /// Obligatoria si el conteo no coincide con el libro.
+    pub explanation: Option<String>,
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct ReopenDayDto {
+    pub reason: String,
+}
+
+/// Totales del día para un tipo de movimiento.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
+pub struct ClosingTypeTotal {
+    pub tx_type: String,
+    pub count: i64,
+    pub total: Decimal,
+}
+
+/// Totales del día para un usuario.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
+pub struct ClosingUserTotal {
+    pub user_id: Uuid,
+    pub count: i64,
+    pub total: Decimal,
+}
+
+/// Reporte guardado con el cierre.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ClosingReport {
+    pub income: Decimal,
+    pub outflow: Decimal,
+    pub by_type: Vec<ClosingTypeTotal>,
+    pub by_user: Vec<ClosingUserTotal>,
+}
+
+impl ClosingReport {
+    pub fn new(by_type: Vec<ClosingTypeTotal>, by_user: Vec<ClosingUserTotal>) -> Self {
+        let (income, outflow) =
+            by_type
+                .iter()
+                .fold((Decimal::ZERO, Decimal::ZERO), |(inc, out), t| {
+                    if t.total >= Decimal::ZERO {
+                        (inc + t.total, out)
+                    } else {
+                        (inc, out - t.total)
+                    }
+                });
+        Self {
+            income,
+            outflow,
+            by_type,
+            by_user,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
+pub struct CashClosing {
+    pub id: Uuid,
+    pub business_date: NaiveDate,
+    /// "closed" o "reopened".
+    pub status: String,
+    /// Balance del libro al cierre, antes del ajuste.
+    pub expected_balance: Decimal,
+    pub counted_total: Decimal,
+    /// `counted_total - expected_balance`: positivo = sobrante.
+    pub difference: Decimal,
+    #[sqlx(json)]
+    pub denominations: Vec<DenominationCount>,
+    #[sqlx(json)]
+    pub report: ClosingReport,
+    pub explanation: Option<String>,
+    pub adjustment_tx_id: Option<Uuid>,
+    pub closed_at: DateTime<Utc>,
+    pub closed_by: Uuid,
+    pub reopened_at: Option<DateTime<Utc>>,
+    pub reopened_by: Option<Uuid>,
+    pub reopen_reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct ClosingQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+/// Suma el arqueo. Valores positivos, cantidades no negativas y sin
+/// denominaciones repetidas.
+pub fn counted_total(denominations: &[DenominationCount]) -> Result<Decimal, String> {
+    if denominations.is_empty() {
+        return Err("El arqueo debe incluir al menos una denominación".into());
+    }
+    let mut seen = BTreeSet::new();
+    let mut total = Decimal::ZERO;
+    for d in denominations {
+        if d.value <= Decimal::ZERO {
+            return Err(format!("Denominación inválida: {}", d.value));
+        }
+        if d.count < 0 {
+            return Err(format!(
+                "Cantidad negativa para la denominación {}",
+                d.value
+            ));
+        }
+        if !seen.insert(d.value.normalize()) {
+            return Err(format!("Denominación repetida: {}", d.value));
+        }
+        total += d.value * Decimal::from(d.count);
+    }
+    Ok(total)
+}
+
+/// Movimiento que lleva el libro al monto contado. `None` si cuadra; una
+/// diferencia sin explicación es un error.
+pub fn closing_adjustment(
+    expected: Decimal,
+    counted: Decimal,
+    explanation: Option<&str>,
+) -> Result<Option<CashEntry>, String> {
+    let difference = counted - expected;
+    if difference.is_zero() {
+        return Ok(None);
+    }
+    let explanation = explanation
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .ok_or_else(|| {
+            format!("El arqueo difiere del libro en {difference}; se requiere una explicación")
+        })?;
+    let tx_type = if difference > Decimal::ZERO {
+        CashTransactionType::CashSurplus
+    } else {
+        CashTransactionType::CashShortage
+    };
+    Ok(Some(CashEntry {
+        tx_type,
+        amount: difference,
+        description: Some(explanation.to_string()),
+        category: None,
+        related_doc_type: None,
+        related_doc_id: None,
+    }))
+}
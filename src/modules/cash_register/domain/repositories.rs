@@ -3,23 +3,35 @@ use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use uuid::Uuid;
 
-use super::entities::{CashTransaction, CashTransactionType};
+use super::entities::{
+    BalanceAt, CashClosing, CashEntry, CashTransaction, CloseDayDto, ClosingQuery,
+};
 use crate::shared::errors::AppError;
+use crate::shared::hash_chain::ChainVerification;
 
 #[async_trait]
 pub trait CashRegisterRepository: Send + Sync {
     async fn get_current_balance(&self) -> Result<Decimal, AppError>;
     async fn calculate_balance_from_scratch(&self) -> Result<Decimal, AppError>;
+    /// Recorre la cadena de hashes y reporta el primer eslabón roto.
+    async fn verify_chain(&self) -> Result<ChainVerification, AppError>;
+    /// Balance tras el movimiento `seq` (o el último anterior).
+    async fn balance_at_seq(&self, seq: i64) -> Result<BalanceAt, AppError>;
+    /// Balance con los movimientos registrados hasta `at`.
+    async fn balance_at_time(&self, at: DateTime<Utc>) -> Result<BalanceAt, AppError>;
     async fn add_transaction(
         &self,
-        tx_type: CashTransactionType,
-        amount: Decimal,
-        description: Option<String>,
-        category: Option<String>,
-        related_doc_type: Option<String>,
-        related_doc_id: Option<Uuid>,
+        entry: CashEntry,
         created_by: Uuid,
     ) -> Result<CashTransaction, AppError>;
+    /// Revierte un gasto o retiro manual con un movimiento compensatorio.
+    /// Los movimientos de un documento se anulan desde el documento.
+    async fn void_transaction(
+        &self,
+        id: Uuid,
+        reason: &str,
+        voided_by: Uuid,
+    ) -> Result<CashTransaction, AppError>;
     async fn get_todays_transactions(&self) -> Result<Vec<CashTransaction>, AppError>;
     async fn get_transactions_by_range(
         &self,
@@ -32,3 +44,25 @@ pub trait CashRegisterRepository: Send + Sync {
         month: u32,
     ) -> Result<Vec<CashTransaction>, AppError>;
 }
+
+#[async_trait]
+pub trait CashClosingRepository: Send + Sync {
+    /// Cierra el día: compara el arqueo con el libro, asienta el sobrante o
+    /// faltante, guarda el reporte y bloquea nuevos movimientos ese día.
+    /// Todo en una transacción.
+    async fn close_day(
+        &self,
+        dto: &CloseDayDto,
+        counted_total: Decimal,
+        closed_by: Uuid,
+    ) -> Result<CashClosing, AppError>;
+    /// Reabre un día cerrado; el cierre queda como historial.
+    async fn reopen(
+        &self,
+        id: Uuid,
+        reason: &str,
+        reopened_by: Uuid,
+    ) -> Result<CashClosing, AppError>;
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<CashClosing>, AppError>;
+    async fn list(&self, query: &ClosingQuery) -> Result<Vec<CashClosing>, AppError>;
+}
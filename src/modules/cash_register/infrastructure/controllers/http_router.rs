@@ -1,31 +1,54 @@
 use axum::{
-    extract::{Query, State},
+    extract::{Path, Query, State},
     routing::{get, post},
     Json, Router,
 };
 use std::sync::Arc;
 use utoipa::OpenApi;
+use uuid::Uuid;
 
-use crate::modules::cash_register::application::manage_cash;
+use crate::modules::cash_register::application::{close_day, manage_cash};
 use crate::modules::cash_register::domain::entities::*;
-use crate::modules::cash_register::domain::repositories::CashRegisterRepository;
+use crate::modules::cash_register::domain::repositories::{
+    CashClosingRepository, CashRegisterRepository,
+};
 use crate::shared::auth::{AppState, AuthUser, Role};
 use crate::shared::errors::AppError;
+use crate::shared::hash_chain::ChainVerification;
+use crate::shared::void::VoidDto;
 
 #[derive(OpenApi)]
 #[openapi(
     paths(
         get_balance,
+        balance_at,
+        verify_chain,
         todays_transactions,
         transactions_by_range,
         add_expense,
-        add_withdrawal
+        add_withdrawal,
+        void_transaction,
+        close_cash_day,
+        list_closings,
+        get_closing,
+        reopen_cash_day
     ),
     components(schemas(
         crate::modules::cash_register::domain::entities::CashTransaction,
         crate::modules::cash_register::domain::entities::BalanceInfo,
+        crate::modules::cash_register::domain::entities::BalanceAt,
+        crate::shared::hash_chain::ChainVerification,
+        crate::shared::hash_chain::BrokenLink,
         crate::modules::cash_register::domain::entities::CreateExpenseDto,
         crate::modules::cash_register::domain::entities::CreateWithdrawalDto,
+        crate::shared::void::VoidDto,
+        crate::modules::cash_register::domain::entities::CashClosing,
+        crate::modules::cash_register::domain::entities::CloseDayDto,
+        crate::modules::cash_register::domain::entities::ReopenDayDto,
+        crate::modules::cash_register::domain::entities::DenominationCount,
+        crate::modules::cash_register::domain::entities::ClosingReport,
+        crate::modules::cash_register::domain::entities::ClosingTypeTotal,
+        crate::modules::cash_register::domain::entities::ClosingUserTotal,
     ))
 )]
 pub struct CashApiDoc;
@@ -34,6 +57,7 @@ pub struct CashApiDoc;
 pub struct CashState {
     pub app: AppState,
     pub repo: Arc<dyn CashRegisterRepository>,
+    pub closings: Arc<dyn CashClosingRepository>,
 }
 
 impl axum::extract::FromRef<CashState> for AppState {
@@ -42,14 +66,28 @@ impl axum::extract::FromRef<CashState> for AppState {
     }
 }
 
-pub fn router(app: AppState, repo: Arc<dyn CashRegisterRepository>) -> Router {
-    let state = CashState { app, repo };
+pub fn router(
+    app: AppState,
+    repo: Arc<dyn CashRegisterRepository>,
+    closings: Arc<dyn CashClosingRepository>,
+) -> Router {
+    let state = CashState {
+        app,
+        repo,
+        closings,
+    };
     Router::new()
         .route("/balance", get(get_balance))
+        .route("/balance-at", get(balance_at))
+        .route("/verify-chain", get(verify_chain))
         .route("/today", get(todays_transactions))
         .route("/range", get(transactions_by_range))
         .route("/expense", post(add_expense))
         .route("/withdrawal", post(add_withdrawal))
+        .route("/transactions/{id}/void", post(void_transaction))
+        .route("/closings", get(list_closings).post(close_cash_day))
+        .route("/closings/{id}", get(get_closing))
+        .route("/closings/{id}/reopen", post(reopen_cash_day))
         .with_state(state)
 }
 
@@ -67,6 +105,36 @@ async fn get_balance(
     Ok(Json(info))
 }
 
+#[utoipa::path(
+    get, path = "/balance-at", tag = "Caja",
+    params(BalanceAtQuery),
+    responses((status = 200, description = "Balance en ese punto del libro", body = BalanceAt)),
+    security(("bearer_auth" = []))
+)]
+async fn balance_at(
+    State(state): State<CashState>,
+    auth: AuthUser,
+    Query(q): Query<BalanceAtQuery>,
+) -> Result<Json<BalanceAt>, AppError> {
+    auth.require_role(Role::Admin)?;
+    let result = manage_cash::balance_at(state.repo.as_ref(), &q).await?;
+    Ok(Json(result))
+}
+
+#[utoipa::path(
+    get, path = "/verify-chain", tag = "Caja",
+    responses((status = 200, description = "Resultado de la verificación", body = ChainVerification)),
+    security(("bearer_auth" = []))
+)]
+async fn verify_chain(
+    State(state): State<CashState>,
+    auth: AuthUser,
+) -> Result<Json<ChainVerification>, AppError> {
+    auth.require_owner()?;
+    let result = manage_cash::verify_chain(state.repo.as_ref()).await?;
+    Ok(Json(result))
+}
+
 #[utoipa::path(
     get, path = "/today", tag = "Caja",
     responses((status = 200, description = "Transacciones de hoy", body = Vec<CashTransaction>)),
@@ -132,3 +200,104 @@ async fn add_withdrawal(
     let tx = manage_cash::add_withdrawal(state.repo.as_ref(), &dto, auth.user_id()).await?;
     Ok(Json(tx))
 }
+
+#[utoipa::path(
+    post, path = "/transactions/{id}/void", tag = "Caja",
+    params(("id" = Uuid, Path, description = "ID del movimiento")),
+    request_body = VoidDto,
+    responses(
+        (status = 200, description = "Reversa asentada", body = CashTransaction),
+        (status = 400, description = "Sin motivo o movimiento de un documento"),
+        (status = 409, description = "El movimiento ya fue revertido")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn void_transaction(
+    State(state): State<CashState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(dto): Json<VoidDto>,
+) -> Result<Json<CashTransaction>, AppError> {
+    auth.require_owner()?;
+    let tx = manage_cash::void_transaction(state.repo.as_ref(), id, &dto, auth.user_id()).await?;
+    Ok(Json(tx))
+}
+
+// ─── Cierre de caja ─────────────────────────────────────
+
+#[utoipa::path(
+    post, path = "/closings", tag = "Caja",
+    request_body = CloseDayDto,
+    responses(
+        (status = 200, description = "Día cerrado", body = CashClosing),
+        (status = 400, description = "Arqueo inválido o diferencia sin explicación"),
+        (status = 409, description = "El día ya está cerrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn close_cash_day(
+    State(state): State<CashState>,
+    auth: AuthUser,
+    Json(dto): Json<CloseDayDto>,
+) -> Result<Json<CashClosing>, AppError> {
+    auth.require_role(Role::Admin)?;
+    let closing = close_day::close_day(state.closings.as_ref(), &dto, auth.user_id()).await?;
+    Ok(Json(closing))
+}
+
+#[utoipa::path(
+    get, path = "/closings", tag = "Caja",
+    params(ClosingQuery),
+    responses((status = 200, description = "Cierres de caja", body = Vec<CashClosing>)),
+    security(("bearer_auth" = []))
+)]
+async fn list_closings(
+    State(state): State<CashState>,
+    auth: AuthUser,
+    Query(q): Query<ClosingQuery>,
+) -> Result<Json<Vec<CashClosing>>, AppError> {
+    auth.require_role(Role::Admin)?;
+    let closings = close_day::list_closings(state.closings.as_ref(), &q).await?;
+    Ok(Json(closings))
+}
+
+#[utoipa::path(
+    get, path = "/closings/{id}", tag = "Caja",
+    params(("id" = Uuid, Path, description = "ID del cierre")),
+    responses(
+        (status = 200, description = "Cierre con su reporte", body = CashClosing),
+        (status = 404, description = "No encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_closing(
+    State(state): State<CashState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<CashClosing>, AppError> {
+    auth.require_role(Role::Admin)?;
+    let closing = close_day::get_closing(state.closings.as_ref(), id).await?;
+    Ok(Json(closing))
+}
+
+#[utoipa::path(
+    post, path = "/closings/{id}/reopen", tag = "Caja",
+    params(("id" = Uuid, Path, description = "ID del cierre")),
+    request_body = ReopenDayDto,
+    responses(
+        (status = 200, description = "Día reabierto", body = CashClosing),
+        (status = 409, description = "El cierre ya fue reabierto")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn reopen_cash_day(
+    State(state): State<CashState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(dto): Json<ReopenDayDto>,
+) -> Result<Json<CashClosing>, AppError> {
+    auth.require_owner()?;
+    let closing =
+        close_day::reopen_day(state.closings.as_ref(), id, &dto, auth.user_id()).await?;
+    Ok(Json(closing))
+}
@@ -0,0 +1,173 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::modules::cash_register::domain::entities::{
+    CashEntry, CashTransaction, CashTransactionType,
+};
+use crate::shared::errors::AppError;
+
+/// Asienta un movimiento en el libro de caja: única vía de escritura en
+/// `cash_register` para todos los módulos.
+///
+/// Toma el mismo advisory lock que el trigger de la cadena (migración 012)
+/// antes de leer el último balance, de modo que dos transacciones nunca
+/// parten del mismo saldo, aun con la tabla vacía. El lock se libera al
+/// terminar la transacción del llamador, que es quien audita el documento.
+/// Si el día ya tiene cierre de caja, el movimiento se rechaza.
+pub async fn append_cash_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    entry: CashEntry,
+    created_by: Uuid,
+) -> Result<CashTransaction, AppError> {
+    insert_cash_tx(tx, entry, created_by, None).await
+}
+
+/// Como [`append_cash_tx`], pero fechado al final de `business_date` (o
+/// ahora, si es hoy). Lo usa el cierre de un día anterior para que su ajuste
+/// quede en ese día.
+pub async fn append_cash_tx_on(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    entry: CashEntry,
+    created_by: Uuid,
+    business_date: NaiveDate,
+) -> Result<CashTransaction, AppError> {
+    insert_cash_tx(tx, entry, created_by, Some(business_date)).await
+}
+
+async fn insert_cash_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    entry: CashEntry,
+    created_by: Uuid,
+    business_date: Option<NaiveDate>,
+) -> Result<CashTransaction, AppError> {
+    lock_ledger_tx(tx).await?;
+
+    // Un movimiento fechado en un día cambia el saldo final de ese día y de
+    // todos los siguientes: ninguno de ellos puede estar cerrado.
+    let closed_day = sqlx::query_scalar::<_, NaiveDate>(
+        r#"
+        SELECT business_date FROM cash_closings
+        WHERE status = 'closed' AND business_date >= COALESCE($1::date, CURRENT_DATE)
+        ORDER BY business_date
+        LIMIT 1
+        "#,
+    )
+    .bind(business_date)
+    .fetch_optional(&mut **tx)
+    .await?;
+    if let Some(day) = closed_day {
+        return Err(AppError::Conflict(format!(
+            "La caja del {day} ya está cerrada; el dueño debe reabrirla"
+        )));
+    }
+
+    let current_balance = sqlx::query_scalar::<_, Decimal>(
+        "SELECT balance FROM cash_register ORDER BY seq DESC LIMIT 1",
+    )
+    .fetch_optional(&mut **tx)
+    .await?
+    .unwrap_or(Decimal::ZERO);
+
+    let transaction = sqlx::query_as::<_, CashTransaction>(
+        r#"
+        INSERT INTO cash_register
+        (type, amount, balance, description, category, related_doc_type, related_doc_id,
+         created_by, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8,
+                CASE WHEN $9::date IS NULL THEN NOW()
+                     ELSE LEAST(NOW(), ($9::date + 1)::timestamptz - INTERVAL '1 microsecond')
+                END)
+        RETURNING *
+        "#,
+    )
+    .bind(entry.tx_type.as_str())
+    .bind(entry.amount)
+    .bind(current_balance + entry.amount)
+    .bind(entry.description)
+    .bind(entry.category)
+    .bind(entry.related_doc_type)
+    .bind(entry.related_doc_id)
+    .bind(created_by)
+    .bind(business_date)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    Ok(transaction)
+}
+
+/// Serializa el acceso al libro hasta el fin de la transacción. Es reentrante:
+/// el cierre de caja lo toma antes de leer el balance y asentar el ajuste.
+pub async fn lock_ledger_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<(), AppError> {
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext('cash_register_chain'))")
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+/// Asienta la reversa de un movimiento: mismo monto con signo opuesto,
+/// enlazado al original con `related_doc_type = 'cash_register'`.
+pub async fn reverse_cash_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    original: &CashTransaction,
+    reason: &str,
+    created_by: Uuid,
+) -> Result<CashTransaction, AppError> {
+    if original.tx_type == CashTransactionType::Reversal.as_str() {
+        return Err(AppError::BadRequest(
+            "Una reversa no se puede revertir".into(),
+        ));
+    }
+
+    lock_ledger_tx(tx).await?;
+    let already_reversed = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM cash_register WHERE type = 'reversal' AND related_doc_id = $1)",
+    )
+    .bind(original.id)
+    .fetch_one(&mut **tx)
+    .await?;
+    if already_reversed {
+        return Err(AppError::Conflict(format!(
+            "El movimiento de caja {} ya fue revertido",
+            original.id
+        )));
+    }
+
+    append_cash_tx(
+        tx,
+        CashEntry::doc(
+            CashTransactionType::Reversal,
+            -original.amount,
+            "cash_register",
+            original.id,
+        )
+        .with_description(Some(reason.to_string())),
+        created_by,
+    )
+    .await
+}
+
+/// Revierte todos los movimientos de caja de un documento anulado.
+pub async fn reverse_doc_cash_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    doc_type: &str,
+    doc_id: Uuid,
+    reason: &str,
+    created_by: Uuid,
+) -> Result<Vec<CashTransaction>, AppError> {
+    let originals = sqlx::query_as::<_, CashTransaction>(
+        "SELECT * FROM cash_register WHERE related_doc_type = $1 AND related_doc_id = $2 ORDER BY seq",
+    )
+    .bind(doc_type)
+    .bind(doc_id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    let mut reversals = Vec::with_capacity(originals.len());
+    for original in &originals {
+        reversals.push(reverse_cash_tx(tx, original, reason, created_by).await?);
+    }
+    Ok(reversals)
+}
@@ -2,19 +2,32 @@ use async_trait::async_trait;
 use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use sqlx::PgPool;
+use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::modules::cash_register::domain::entities::{CashTransaction, CashTransactionType};
-use crate::modules::cash_register::domain::repositories::CashRegisterRepository;
+use crate::modules::audit_log::domain::entities::CreateAuditLogDto;
+use crate::modules::audit_log::domain::repositories::AuditLogRepository;
+use crate::modules::cash_register::domain::entities::{
+    closing_adjustment, BalanceAt, CashClosing, CashEntry, CashTransaction, CloseDayDto,
+    ClosingQuery, ClosingReport, ClosingTypeTotal, ClosingUserTotal,
+};
+use crate::modules::cash_register::domain::repositories::{
+    CashClosingRepository, CashRegisterRepository,
+};
+use crate::modules::cash_register::infrastructure::persistence::ledger::{
+    append_cash_tx, append_cash_tx_on, lock_ledger_tx, reverse_cash_tx,
+};
 use crate::shared::errors::AppError;
+use crate::shared::hash_chain::{self, ChainVerification, ChainedTable};
 
 pub struct PgCashRegisterRepository {
     pool: PgPool,
+    audit: Arc<dyn AuditLogRepository>,
 }
 
 impl PgCashRegisterRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, audit: Arc<dyn AuditLogRepository>) -> Self {
+        Self { pool, audit }
     }
 }
 
@@ -22,7 +35,7 @@ impl PgCashRegisterRepository {
 impl CashRegisterRepository for PgCashRegisterRepository {
     async fn get_current_balance(&self) -> Result<Decimal, AppError> {
         let balance = sqlx::query_scalar::<_, Option<Decimal>>(
-            "SELECT balance FROM cash_register ORDER BY created_at DESC LIMIT 1",
+            "SELECT balance FROM cash_register ORDER BY seq DESC LIMIT 1",
         )
         .fetch_optional(&self.pool)
         .await?
@@ -41,55 +54,90 @@ impl CashRegisterRepository for PgCashRegisterRepository {
         Ok(balance)
     }
 
+    async fn verify_chain(&self) -> Result<ChainVerification, AppError> {
+        hash_chain::verify_chain(&self.pool, ChainedTable::CashRegister).await
+    }
+
+    async fn balance_at_seq(&self, seq: i64) -> Result<BalanceAt, AppError> {
+        let row = sqlx::query_as::<_, (i64, Decimal)>(
+            "SELECT seq, balance FROM cash_register WHERE seq <= $1 ORDER BY seq DESC LIMIT 1",
+        )
+        .bind(seq)
+        .fetch_optional(&self.pool)
+        .await?;
+        let (seq, balance) = row.unwrap_or((0, Decimal::ZERO));
+        Ok(BalanceAt { seq, balance })
+    }
+
+    async fn balance_at_time(&self, at: DateTime<Utc>) -> Result<BalanceAt, AppError> {
+        // created_at es el inicio de la transacción y no sigue el orden de seq
+        // entre transacciones concurrentes: se suma en vez de leer `balance`.
+        let (seq, balance) = sqlx::query_as::<_, (Option<i64>, Option<Decimal>)>(
+            "SELECT MAX(seq), SUM(amount) FROM cash_register WHERE created_at <= $1",
+        )
+        .bind(at)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(BalanceAt {
+            seq: seq.unwrap_or(0),
+            balance: balance.unwrap_or(Decimal::ZERO),
+        })
+    }
+
     async fn add_transaction(
         &self,
-        tx_type: CashTransactionType,
-        amount: Decimal,
-        description: Option<String>,
-        category: Option<String>,
-        related_doc_type: Option<String>,
-        related_doc_id: Option<Uuid>,
+        entry: CashEntry,
         created_by: Uuid,
     ) -> Result<CashTransaction, AppError> {
         let mut tx = self.pool.begin().await?;
 
-        // Obtener balance actual CON LOCK para evitar race conditions
-        let current_balance = sqlx::query_scalar::<_, Option<Decimal>>(
-            "SELECT balance FROM cash_register ORDER BY created_at DESC LIMIT 1 FOR UPDATE",
-        )
-        .fetch_optional(&mut *tx)
-        .await?
-        .flatten()
-        .unwrap_or(Decimal::ZERO);
+        let transaction = append_cash_tx(&mut tx, entry, created_by).await?;
 
-        let new_balance = current_balance + amount;
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::created("cash_register", transaction.id, &transaction, created_by)?,
+            )
+            .await?;
+        tx.commit().await?;
+        Ok(transaction)
+    }
 
-        let transaction = sqlx::query_as::<_, CashTransaction>(
-            r#"
-            INSERT INTO cash_register 
-            (type, amount, balance, description, category, related_doc_type, related_doc_id, created_by)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            RETURNING *
-            "#,
-        )
-        .bind(tx_type.as_str())
-        .bind(amount)
-        .bind(new_balance)
-        .bind(description)
-        .bind(category)
-        .bind(related_doc_type)
-        .bind(related_doc_id)
-        .bind(created_by)
-        .fetch_one(&mut *tx)
-        .await?;
+    async fn void_transaction(
+        &self,
+        id: Uuid,
+        reason: &str,
+        voided_by: Uuid,
+    ) -> Result<CashTransaction, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let original =
+            sqlx::query_as::<_, CashTransaction>("SELECT * FROM cash_register WHERE id = $1")
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or_else(|| AppError::NotFound("Movimiento de caja no encontrado".into()))?;
+        if let Some(doc_type) = &original.related_doc_type {
+            return Err(AppError::BadRequest(format!(
+                "El movimiento pertenece a un documento ({doc_type}); anule el documento"
+            )));
+        }
+
+        let reversal = reverse_cash_tx(&mut tx, &original, reason, voided_by).await?;
 
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::created("cash_register", reversal.id, &reversal, voided_by)?,
+            )
+            .await?;
         tx.commit().await?;
-        Ok(transaction)
+        Ok(reversal)
     }
 
     async fn get_todays_transactions(&self) -> Result<Vec<CashTransaction>, AppError> {
         Ok(sqlx::query_as::<_, CashTransaction>(
-            "SELECT * FROM cash_register WHERE created_at >= CURRENT_DATE ORDER BY created_at DESC",
+            "SELECT * FROM cash_register WHERE created_at >= CURRENT_DATE ORDER BY seq DESC",
         )
         .fetch_all(&self.pool)
         .await?)
@@ -101,7 +149,7 @@ impl CashRegisterRepository for PgCashRegisterRepository {
         to: DateTime<Utc>,
     ) -> Result<Vec<CashTransaction>, AppError> {
         Ok(sqlx::query_as::<_, CashTransaction>(
-            "SELECT * FROM cash_register WHERE created_at >= $1 AND created_at < $2 ORDER BY created_at DESC",
+            "SELECT * FROM cash_register WHERE created_at >= $1 AND created_at < $2 ORDER BY seq DESC",
         )
         .bind(from)
         .bind(to)
@@ -123,7 +171,7 @@ impl CashRegisterRepository for PgCashRegisterRepository {
         };
 
         Ok(sqlx::query_as::<_, CashTransaction>(
-            "SELECT * FROM cash_register WHERE created_at >= $1 AND created_at < $2 ORDER BY created_at DESC",
+            "SELECT * FROM cash_register WHERE created_at >= $1 AND created_at < $2 ORDER BY seq DESC",
         )
         .bind(start.and_hms_opt(0, 0, 0).unwrap().and_utc())
         .bind(end.and_hms_opt(0, 0, 0).unwrap().and_utc())
@@ -131,3 +179,208 @@ impl CashRegisterRepository for PgCashRegisterRepository {
         .await?)
     }
 }
+
+pub struct PgCashClosingRepository {
+    pool: PgPool,
+    audit: Arc<dyn AuditLogRepository>,
+}
+
+impl PgCashClosingRepository {
+    pub fn new(pool: PgPool, audit: Arc<dyn AuditLogRepository>) -> Self {
+        Self { pool, audit }
+    }
+}
+
+#[async_trait]
+impl CashClosingRepository for PgCashClosingRepository {
+    async fn close_day(
+        &self,
+        dto: &CloseDayDto,
+        counted_total: Decimal,
+        closed_by: Uuid,
+    ) -> Result<CashClosing, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // 1. Bloquear el libro: nadie asienta mientras se cuadra
+        lock_ledger_tx(&mut tx).await?;
+
+        let (business_date, today) = sqlx::query_as::<_, (NaiveDate, NaiveDate)>(
+            "SELECT COALESCE($1::date, CURRENT_DATE), CURRENT_DATE",
+        )
+        .bind(dto.business_date)
+        .fetch_one(&mut *tx)
+        .await?;
+        if business_date > today {
+            return Err(AppError::BadRequest(
+                "No se puede cerrar un día futuro".into(),
+            ));
+        }
+
+        let already_closed = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM cash_closings WHERE business_date = $1 AND status = 'closed')",
+        )
+        .bind(business_date)
+        .fetch_one(&mut *tx)
+        .await?;
+        if already_closed {
+            return Err(AppError::Conflict(format!(
+                "La caja del {business_date} ya está cerrada"
+            )));
+        }
+
+        // 2. Balance del libro al final del día
+        let expected_balance = sqlx::query_scalar::<_, Decimal>(
+            "SELECT COALESCE(SUM(amount), 0) FROM cash_register WHERE created_at < $1::date + 1",
+        )
+        .bind(business_date)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // 3. Sobrante / faltante: el libro queda igual al conteo
+        let closing_id = Uuid::new_v4();
+        let adjustment =
+            closing_adjustment(expected_balance, counted_total, dto.explanation.as_deref())
+                .map_err(AppError::BadRequest)?;
+        let adjustment_tx_id = match adjustment {
+            Some(mut entry) => {
+                entry.related_doc_type = Some("cash_closings".into());
+                entry.related_doc_id = Some(closing_id);
+                Some(
+                    append_cash_tx_on(&mut tx, entry, closed_by, business_date)
+                        .await?
+                        .id,
+                )
+            }
+            None => None,
+        };
+
+        // 4. Reporte del día
+        let by_type = sqlx::query_as::<_, ClosingTypeTotal>(
+            r#"
+            SELECT type AS tx_type, COUNT(*) AS count, SUM(amount) AS total
+            FROM cash_register
+            WHERE created_at >= $1::date AND created_at < $1::date + 1
+            GROUP BY type
+            ORDER BY type
+            "#,
+        )
+        .bind(business_date)
+        .fetch_all(&mut *tx)
+        .await?;
+        let by_user = sqlx::query_as::<_, ClosingUserTotal>(
+            r#"
+            SELECT created_by AS user_id, COUNT(*) AS count, SUM(amount) AS total
+            FROM cash_register
+            WHERE created_at >= $1::date AND created_at < $1::date + 1
+            GROUP BY created_by
+            ORDER BY created_by
+            "#,
+        )
+        .bind(business_date)
+        .fetch_all(&mut *tx)
+        .await?;
+        let report = ClosingReport::new(by_type, by_user);
+
+        // 5. Cierre (bloquea el día)
+        let closing = sqlx::query_as::<_, CashClosing>(
+            r#"
+            INSERT INTO cash_closings
+            (id, business_date, expected_balance, counted_total, difference,
+             denominations, report, explanation, adjustment_tx_id, closed_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING *
+            "#,
+        )
+        .bind(closing_id)
+        .bind(business_date)
+        .bind(expected_balance)
+        .bind(counted_total)
+        .bind(counted_total - expected_balance)
+        .bind(sqlx::types::Json(&dto.denominations))
+        .bind(sqlx::types::Json(&report))
+        .bind(dto.explanation.as_deref().map(str::trim))
+        .bind(adjustment_tx_id)
+        .bind(closed_by)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // 6. Auditoría
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::created("cash_closings", closing.id, &closing, closed_by)?,
+            )
+            .await?;
+
+        tx.commit().await?;
+        Ok(closing)
+    }
+
+    async fn reopen(
+        &self,
+        id: Uuid,
+        reason: &str,
+        reopened_by: Uuid,
+    ) -> Result<CashClosing, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let existing = sqlx::query_as::<_, CashClosing>(
+            "SELECT * FROM cash_closings WHERE id = $1 FOR UPDATE",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Cierre de caja {id} no encontrado")))?;
+        if existing.status != "closed" {
+            return Err(AppError::Conflict("El cierre ya fue reabierto".into()));
+        }
+
+        let closing = sqlx::query_as::<_, CashClosing>(
+            r#"
+            UPDATE cash_closings
+            SET status = 'reopened', reopened_at = NOW(), reopened_by = $1, reopen_reason = $2
+            WHERE id = $3
+            RETURNING *
+            "#,
+        )
+        .bind(reopened_by)
+        .bind(reason)
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::updated("cash_closings", id, &existing, &closing, reopened_by)?,
+            )
+            .await?;
+
+        tx.commit().await?;
+        Ok(closing)
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<CashClosing>, AppError> {
+        Ok(
+            sqlx::query_as::<_, CashClosing>("SELECT * FROM cash_closings WHERE id = $1")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?,
+        )
+    }
+
+    async fn list(&self, query: &ClosingQuery) -> Result<Vec<CashClosing>, AppError> {
+        Ok(sqlx::query_as::<_, CashClosing>(
+            r#"
+            SELECT * FROM cash_closings
+            WHERE ($1::date IS NULL OR business_date >= $1)
+              AND ($2::date IS NULL OR business_date <= $2)
+            ORDER BY business_date DESC, closed_at DESC
+            "#,
+        )
+        .bind(query.from)
+        .bind(query.to)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+}
@@ -63,12 +63,13 @@ pub async fn update_flavor(
     repo: &Arc<dyn FlavorRepository>,
     id: Uuid,
     dto: UpdateFlavorDto,
+    updated_by: Uuid,
 ) -> Result<Flavor, AppError> {
     let _ = repo
         .find_by_id(id)
         .await?
         .ok_or_else(|| AppError::NotFound(format!("Sabor {id} no encontrado")))?;
-    repo.update(id, &dto).await
+    repo.update(id, &dto, updated_by).await
 }
 
 // ─── Providers ──────────────────────────────────────────
@@ -89,12 +90,13 @@ pub async fn update_provider(
     repo: &Arc<dyn ProviderRepository>,
     id: Uuid,
     dto: UpdateProviderDto,
+    updated_by: Uuid,
 ) -> Result<Provider, AppError> {
     let _ = repo
         .find_by_id(id)
         .await?
         .ok_or_else(|| AppError::NotFound(format!("Proveedor {id} no encontrado")))?;
-    repo.update(id, &dto).await
+    repo.update(id, &dto, updated_by).await
 }
 
 // ─── Workers ────────────────────────────────────────────
@@ -121,12 +123,13 @@ pub async fn update_worker(
     repo: &Arc<dyn WorkerRepository>,
     id: Uuid,
     dto: UpdateWorkerDto,
+    updated_by: Uuid,
 ) -> Result<Worker, AppError> {
     let _ = repo
         .find_by_id(id)
         .await?
         .ok_or_else(|| AppError::NotFound(format!("Trabajador {id} no encontrado")))?;
-    repo.update(id, &dto).await
+    repo.update(id, &dto, updated_by).await
 }
 
 // ─── Routes ─────────────────────────────────────────────
@@ -145,6 +148,18 @@ pub async fn create_route(
 
 // ─── Freezers ───────────────────────────────────────────
 
+fn validate_power_rating(
+    power_watts: Option<i32>,
+    kwh_per_day: Option<rust_decimal::Decimal>,
+) -> Result<(), AppError> {
+    if power_watts.is_some_and(|w| w <= 0) || kwh_per_day.is_some_and(|k| k <= rust_decimal::Decimal::ZERO) {
+        return Err(AppError::BadRequest(
+            "El consumo nominal del congelador debe ser positivo".into(),
+        ));
+    }
+    Ok(())
+}
+
 pub async fn list_freezers(repo: &Arc<dyn FreezerRepository>) -> Result<Vec<Freezer>, AppError> {
     repo.find_all().await
 }
@@ -160,6 +175,10 @@ pub async fn create_freezer(
     dto: CreateFreezerDto,
     created_by: Uuid,
 ) -> Result<Freezer, AppError> {
+    if let Some(cap) = &dto.max_capacity {
+        cap.validate().map_err(AppError::BadRequest)?;
+    }
+    validate_power_rating(dto.power_watts, dto.kwh_per_day)?;
     repo.create(&dto, created_by).await
 }
 
@@ -167,21 +186,48 @@ pub async fn update_freezer(
     repo: &Arc<dyn FreezerRepository>,
     id: Uuid,
     dto: UpdateFreezerDto,
+    updated_by: Uuid,
 ) -> Result<Freezer, AppError> {
+    if let Some(cap) = &dto.max_capacity {
+        cap.validate().map_err(AppError::BadRequest)?;
+    }
+    validate_power_rating(dto.power_watts, dto.kwh_per_day)?;
     let _ = repo
         .find_by_id(id)
         .await?
         .ok_or_else(|| AppError::NotFound(format!("Congelador {id} no encontrado")))?;
-    repo.update(id, &dto).await
+    repo.update(id, &dto, updated_by).await
 }
 
 pub async fn toggle_freezer(
     repo: &Arc<dyn FreezerRepository>,
     id: Uuid,
+    toggled_by: Uuid,
 ) -> Result<Freezer, AppError> {
     let _ = repo
         .find_by_id(id)
         .await?
         .ok_or_else(|| AppError::NotFound(format!("Congelador {id} no encontrado")))?;
-    repo.toggle_power(id).await
+    repo.toggle_power(id, toggled_by).await
+}
+
+/// Ocupación de un congelador contra su capacidad por producto.
+pub async fn freezer_occupancy(
+    repo: &Arc<dyn FreezerRepository>,
+    id: Uuid,
+) -> Result<FreezerOccupancy, AppError> {
+    let freezer = get_freezer(repo, id).await?;
+    let stock = repo.stock_by_product(Some(id)).await?;
+    Ok(FreezerOccupancy::compute(&freezer, &stock))
+}
+
+pub async fn list_freezer_occupancy(
+    repo: &Arc<dyn FreezerRepository>,
+) -> Result<Vec<FreezerOccupancy>, AppError> {
+    let freezers = repo.find_all().await?;
+    let stock = repo.stock_by_product(None).await?;
+    Ok(freezers
+        .iter()
+        .map(|f| FreezerOccupancy::compute(f, &stock))
+        .collect())
 }
@@ -0,0 +1,130 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::modules::catalog::domain::entities::{
+    Freezer, FreezerConsumption, FreezerPowerEvent, PowerConsumptionReport, PowerReportQuery,
+};
+use crate::modules::catalog::domain::repositories::FreezerRepository;
+use crate::shared::errors::AppError;
+
+pub async fn list_power_events(
+    repo: &Arc<dyn FreezerRepository>,
+    freezer_id: Uuid,
+    limit: Option<i64>,
+) -> Result<Vec<FreezerPowerEvent>, AppError> {
+    repo.find_power_events(freezer_id, limit.unwrap_or(100).clamp(1, 1000))
+        .await
+}
+
+/// Horas encendido, kWh y costo estimados por congelador en el período.
+pub async fn power_report(
+    repo: &Arc<dyn FreezerRepository>,
+    query: PowerReportQuery,
+) -> Result<PowerConsumptionReport, AppError> {
+    let now = Utc::now();
+    let to = query.to.unwrap_or(now).min(now);
+    if query.from >= to {
+        return Err(AppError::BadRequest(
+            "La fecha inicial debe ser anterior a la final".into(),
+        ));
+    }
+    if query.price_per_kwh.is_some_and(|p| p < Decimal::ZERO) {
+        return Err(AppError::BadRequest(
+            "La tarifa por kWh no puede ser negativa".into(),
+        ));
+    }
+
+    let freezers = repo.find_all().await?;
+    let events = repo.power_events_between(query.from, to).await?;
+
+    let rows: Vec<FreezerConsumption> = freezers
+        .iter()
+        .map(|f| {
+            let own: Vec<&FreezerPowerEvent> =
+                events.iter().filter(|e| e.freezer_id == f.id).collect();
+            compute_consumption(f, &own, query.from, to, query.price_per_kwh)
+        })
+        .collect();
+
+    let total_cost = query
+        .price_per_kwh
+        .map(|_| rows.iter().filter_map(|r| r.cost).sum());
+    let total_cost_saved = query
+        .price_per_kwh
+        .map(|_| rows.iter().filter_map(|r| r.cost_saved).sum());
+
+    Ok(PowerConsumptionReport {
+        from: query.from,
+        to,
+        price_per_kwh: query.price_per_kwh,
+        total_hours_on: rows.iter().map(|r| r.hours_on).sum(),
+        total_kwh: rows.iter().filter_map(|r| r.kwh).sum(),
+        total_kwh_saved: rows.iter().filter_map(|r| r.kwh_saved).sum(),
+        total_cost,
+        total_cost_saved,
+        freezers: rows,
+    })
+}
+
+/// Recorre los eventos (ordenados por fecha) recortando los intervalos a `[from, to]`.
+/// Antes del primer evento el congelador no existía y no suma horas.
+pub fn compute_consumption(
+    freezer: &Freezer,
+    events: &[&FreezerPowerEvent],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    price_per_kwh: Option<Decimal>,
+) -> FreezerConsumption {
+    let mut state: Option<bool> = None;
+    let mut cursor = from;
+    let mut seconds_on = 0i64;
+    let mut seconds_off = 0i64;
+    let mut power_events = 0i64;
+
+    let mut add = |state: Option<bool>, start: DateTime<Utc>, end: DateTime<Utc>| {
+        let secs = (end - start).num_seconds().max(0);
+        match state {
+            Some(true) => seconds_on += secs,
+            Some(false) => seconds_off += secs,
+            None => {}
+        }
+    };
+
+    for event in events {
+        if event.created_at <= from {
+            state = Some(event.is_on);
+            continue;
+        }
+        if event.created_at > to {
+            break;
+        }
+        add(state, cursor, event.created_at);
+        cursor = event.created_at;
+        state = Some(event.is_on);
+        power_events += 1;
+    }
+    add(state, cursor, to);
+
+    let hours = |secs: i64| Decimal::from(secs) / Decimal::from(3600);
+    let hours_on = hours(seconds_on);
+    let hours_off = hours(seconds_off);
+
+    let rate = freezer.kwh_per_hour();
+    let kwh = rate.map(|r| (r * hours_on).round_dp(3));
+    let kwh_saved = rate.map(|r| (r * hours_off).round_dp(3));
+    let cost = |k: Option<Decimal>| price_per_kwh.zip(k).map(|(p, k)| (p * k).round_dp(2));
+
+    FreezerConsumption {
+        freezer_id: freezer.id,
+        number: freezer.number,
+        hours_on: hours_on.round_dp(2),
+        hours_off: hours_off.round_dp(2),
+        power_events,
+        kwh,
+        kwh_saved,
+        cost: cost(kwh),
+        cost_saved: cost(kwh_saved),
+    }
+}
@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 // ─── Products ───────────────────────────────────────────
@@ -125,28 +126,209 @@ pub struct CreateRouteDto {
 
 // ─── Freezers ───────────────────────────────────────────
 
+/// Capacidad máxima de un congelador: unidades por tipo de producto
+/// (`product_id` → unidades). Se guarda en `freezers.max_capacity` (JSONB).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(transparent)]
+#[schema(value_type = HashMap<Uuid, i32>)]
+pub struct FreezerCapacity(pub BTreeMap<Uuid, i32>);
+
+impl FreezerCapacity {
+    /// Capacidad para un tipo de producto. `None` si el congelador no lo admite.
+    pub fn for_product(&self, product_id: Uuid) -> Option<i32> {
+        self.0.get(&product_id).copied()
+    }
+
+    pub fn total(&self) -> i64 {
+        self.0.values().map(|&v| v as i64).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn product_ids(&self) -> Vec<Uuid> {
+        self.0.keys().copied().collect()
+    }
+
+    /// Cada capacidad debe ser positiva.
+    pub fn validate(&self) -> Result<(), String> {
+        match self.0.iter().find(|(_, &units)| units <= 0) {
+            Some((product_id, units)) => Err(format!(
+                "La capacidad para el producto {product_id} debe ser positiva ({units})"
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
 pub struct Freezer {
     pub id: Uuid,
     pub number: i32,
-    #[schema(value_type = Object)]
-    pub max_capacity: serde_json::Value,
+    #[sqlx(json)]
+    pub max_capacity: FreezerCapacity,
     pub is_on: bool,
     pub last_toggle: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub created_by: Uuid,
+    /// Potencia promedio en vatios.
+    pub power_watts: Option<i32>,
+    /// Consumo nominal en kWh por día (tiene prioridad sobre `power_watts`).
+    pub kwh_per_day: Option<Decimal>,
+}
+
+impl Freezer {
+    /// kWh consumidos por hora encendido, según el consumo nominal configurado.
+    pub fn kwh_per_hour(&self) -> Option<Decimal> {
+        match (self.kwh_per_day, self.power_watts) {
+            (Some(kwh_day), _) => Some(kwh_day / Decimal::from(24)),
+            (None, Some(watts)) => Some(Decimal::from(watts) / Decimal::from(1000)),
+            (None, None) => None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct CreateFreezerDto {
     pub number: i32,
-    #[schema(value_type = Option<Object>)]
-    pub max_capacity: Option<serde_json::Value>,
+    pub max_capacity: Option<FreezerCapacity>,
+    pub power_watts: Option<i32>,
+    pub kwh_per_day: Option<Decimal>,
 }
 
 #[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct UpdateFreezerDto {
-    #[schema(value_type = Option<Object>)]
-    pub max_capacity: Option<serde_json::Value>,
+    pub max_capacity: Option<FreezerCapacity>,
     pub is_on: Option<bool>,
+    pub power_watts: Option<i32>,
+    pub kwh_per_day: Option<Decimal>,
+}
+
+/// Unidades almacenadas de un tipo de producto en un congelador
+/// (incluye deformados, que también ocupan espacio).
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct FreezerProductStock {
+    pub freezer_id: Uuid,
+    pub product_id: Uuid,
+    pub quantity: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ProductOccupancy {
+    pub product_id: Uuid,
+    /// `None` si el producto está en el congelador sin capacidad configurada.
+    pub capacity: Option<i32>,
+    pub quantity: i64,
+    /// Porcentaje ocupado (0-100). `None` sin capacidad configurada.
+    pub occupancy_pct: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct FreezerOccupancy {
+    pub freezer_id: Uuid,
+    pub number: i32,
+    pub is_on: bool,
+    pub total_capacity: i64,
+    pub total_quantity: i64,
+    /// Porcentaje ocupado sobre la capacidad total. `None` sin capacidad configurada.
+    pub occupancy_pct: Option<Decimal>,
+    pub products: Vec<ProductOccupancy>,
+}
+
+fn pct(quantity: i64, capacity: i64) -> Option<Decimal> {
+    (capacity > 0).then(|| {
+        (Decimal::from(quantity) * Decimal::ONE_HUNDRED / Decimal::from(capacity)).round_dp(2)
+    })
+}
+
+impl FreezerOccupancy {
+    /// Ocupación del congelador a partir del stock agrupado por producto.
+    pub fn compute(freezer: &Freezer, stock: &[FreezerProductStock]) -> Self {
+        let mut by_product: BTreeMap<Uuid, i64> = freezer
+            .max_capacity
+            .product_ids()
+            .into_iter()
+            .map(|id| (id, 0))
+            .collect();
+        for row in stock.iter().filter(|r| r.freezer_id == freezer.id) {
+            *by_product.entry(row.product_id).or_insert(0) += row.quantity;
+        }
+
+        let products = by_product
+            .into_iter()
+            .map(|(product_id, quantity)| {
+                let capacity = freezer.max_capacity.for_product(product_id);
+                ProductOccupancy {
+                    product_id,
+                    capacity,
+                    quantity,
+                    occupancy_pct: capacity.and_then(|c| pct(quantity, c as i64)),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let total_capacity = freezer.max_capacity.total();
+        let total_quantity = products.iter().map(|p| p.quantity).sum();
+
+        Self {
+            freezer_id: freezer.id,
+            number: freezer.number,
+            is_on: freezer.is_on,
+            total_capacity,
+            total_quantity,
+            occupancy_pct: pct(total_quantity, total_capacity),
+            products,
+        }
+    }
+}
+
+// ─── Consumo eléctrico ──────────────────────────────────
+
+/// Cambio de estado (encendido/apagado) de un congelador.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct FreezerPowerEvent {
+    pub id: Uuid,
+    pub freezer_id: Uuid,
+    pub is_on: bool,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Uuid,
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct PowerReportQuery {
+    pub from: DateTime<Utc>,
+    /// Por defecto, ahora. Nunca se cuentan horas futuras.
+    pub to: Option<DateTime<Utc>>,
+    /// Tarifa por kWh para estimar el costo.
+    pub price_per_kwh: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct FreezerConsumption {
+    pub freezer_id: Uuid,
+    pub number: i32,
+    pub hours_on: Decimal,
+    /// Horas apagado dentro del período (desde que existe el congelador).
+    pub hours_off: Decimal,
+    pub power_events: i64,
+    /// `None` si el congelador no tiene consumo nominal configurado.
+    pub kwh: Option<Decimal>,
+    /// kWh que no se consumieron por estar apagado.
+    pub kwh_saved: Option<Decimal>,
+    pub cost: Option<Decimal>,
+    pub cost_saved: Option<Decimal>,
+}
+
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct PowerConsumptionReport {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub price_per_kwh: Option<Decimal>,
+    pub freezers: Vec<FreezerConsumption>,
+    pub total_hours_on: Decimal,
+    pub total_kwh: Decimal,
+    pub total_kwh_saved: Decimal,
+    pub total_cost: Option<Decimal>,
+    pub total_cost_saved: Option<Decimal>,
 }
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use super::entities::*;
@@ -28,7 +29,12 @@ pub trait FlavorRepository: Send + Sync {
     async fn find_by_product(&self, product_id: Uuid) -> Result<Vec<Flavor>, AppError>;
     async fn find_by_id(&self, id: Uuid) -> Result<Option<Flavor>, AppError>;
     async fn create(&self, dto: &CreateFlavorDto, created_by: Uuid) -> Result<Flavor, AppError>;
-    async fn update(&self, id: Uuid, dto: &UpdateFlavorDto) -> Result<Flavor, AppError>;
+    async fn update(
+        &self,
+        id: Uuid,
+        dto: &UpdateFlavorDto,
+        updated_by: Uuid,
+    ) -> Result<Flavor, AppError>;
 }
 
 // ─── ProviderRepository ─────────────────────────────────
@@ -40,7 +46,12 @@ pub trait ProviderRepository: Send + Sync {
     async fn find_by_id(&self, id: Uuid) -> Result<Option<Provider>, AppError>;
     async fn create(&self, dto: &CreateProviderDto, created_by: Uuid)
         -> Result<Provider, AppError>;
-    async fn update(&self, id: Uuid, dto: &UpdateProviderDto) -> Result<Provider, AppError>;
+    async fn update(
+        &self,
+        id: Uuid,
+        dto: &UpdateProviderDto,
+        updated_by: Uuid,
+    ) -> Result<Provider, AppError>;
 }
 
 // ─── WorkerRepository ───────────────────────────────────
@@ -51,7 +62,12 @@ pub trait WorkerRepository: Send + Sync {
     async fn find_active(&self) -> Result<Vec<Worker>, AppError>;
     async fn find_by_id(&self, id: Uuid) -> Result<Option<Worker>, AppError>;
     async fn create(&self, dto: &CreateWorkerDto, created_by: Uuid) -> Result<Worker, AppError>;
-    async fn update(&self, id: Uuid, dto: &UpdateWorkerDto) -> Result<Worker, AppError>;
+    async fn update(
+        &self,
+        id: Uuid,
+        dto: &UpdateWorkerDto,
+        updated_by: Uuid,
+    ) -> Result<Worker, AppError>;
 }
 
 // ─── RouteRepository ────────────────────────────────────
@@ -70,6 +86,30 @@ pub trait FreezerRepository: Send + Sync {
     async fn find_all(&self) -> Result<Vec<Freezer>, AppError>;
     async fn find_by_id(&self, id: Uuid) -> Result<Option<Freezer>, AppError>;
     async fn create(&self, dto: &CreateFreezerDto, created_by: Uuid) -> Result<Freezer, AppError>;
-    async fn update(&self, id: Uuid, dto: &UpdateFreezerDto) -> Result<Freezer, AppError>;
-    async fn toggle_power(&self, id: Uuid) -> Result<Freezer, AppError>;
+    /// Si `is_on` cambia, registra el evento en `freezer_power_events`.
+    async fn update(
+        &self,
+        id: Uuid,
+        dto: &UpdateFreezerDto,
+        updated_by: Uuid,
+    ) -> Result<Freezer, AppError>;
+    /// Invierte `is_on` y registra el evento en `freezer_power_events`.
+    async fn toggle_power(&self, id: Uuid, toggled_by: Uuid) -> Result<Freezer, AppError>;
+    async fn find_power_events(
+        &self,
+        freezer_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<FreezerPowerEvent>, AppError>;
+    /// Último evento de cada congelador hasta `from` más los eventos en `(from, to]`,
+    /// ordenados por congelador y fecha.
+    async fn power_events_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<FreezerPowerEvent>, AppError>;
+    /// Stock agrupado por congelador y producto. `None` = todos los congeladores.
+    async fn stock_by_product(
+        &self,
+        freezer_id: Option<Uuid>,
+    ) -> Result<Vec<FreezerProductStock>, AppError>;
 }
@@ -1,14 +1,15 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     routing::{get, post, put},
     Json, Router,
 };
+use serde::Deserialize;
 use utoipa::OpenApi;
 use uuid::Uuid;
 
-use crate::modules::catalog::application::crud;
+use crate::modules::catalog::application::{crud, freezer_power};
 use crate::modules::catalog::domain::entities::*;
 use crate::modules::catalog::domain::repositories::*;
 use crate::shared::auth::{AppState, AuthUser, Role};
@@ -39,6 +40,10 @@ use crate::shared::errors::AppError;
         create_freezer,
         update_freezer,
         toggle_freezer,
+        list_freezer_occupancy,
+        freezer_occupancy,
+        freezer_power_events,
+        freezer_power_report,
     ),
     components(schemas(
         crate::modules::catalog::domain::entities::Product,
@@ -58,6 +63,12 @@ use crate::shared::errors::AppError;
         crate::modules::catalog::domain::entities::Freezer,
         crate::modules::catalog::domain::entities::CreateFreezerDto,
         crate::modules::catalog::domain::entities::UpdateFreezerDto,
+        crate::modules::catalog::domain::entities::FreezerCapacity,
+        crate::modules::catalog::domain::entities::FreezerOccupancy,
+        crate::modules::catalog::domain::entities::ProductOccupancy,
+        crate::modules::catalog::domain::entities::FreezerPowerEvent,
+        crate::modules::catalog::domain::entities::FreezerConsumption,
+        crate::modules::catalog::domain::entities::PowerConsumptionReport,
     ))
 )]
 pub struct CatalogApiDoc;
@@ -101,7 +112,11 @@ pub fn router(state: CatalogState) -> Router {
         // Freezers
         .route("/freezers", get(list_freezers).post(create_freezer))
         .route("/freezers/{id}", get(get_freezer).put(update_freezer))
+        .route("/freezers/occupancy", get(list_freezer_occupancy))
+        .route("/freezers/power-report", get(freezer_power_report))
         .route("/freezers/{id}/toggle", post(toggle_freezer))
+        .route("/freezers/{id}/occupancy", get(freezer_occupancy))
+        .route("/freezers/{id}/power-events", get(freezer_power_events))
         .with_state(state)
 }
 
@@ -235,7 +250,7 @@ async fn update_flavor(
     Json(dto): Json<UpdateFlavorDto>,
 ) -> Result<Json<Flavor>, AppError> {
     auth.require_owner()?;
-    Ok(Json(crud::update_flavor(&state.flavors, id, dto).await?))
+    Ok(Json(crud::update_flavor(&state.flavors, id, dto, auth.user_id()).await?))
 }
 
 // ─── Providers ──────────────────────────────────────────
@@ -284,7 +299,7 @@ async fn update_provider(
 ) -> Result<Json<Provider>, AppError> {
     auth.require_owner()?;
     Ok(Json(
-        crud::update_provider(&state.providers, id, dto).await?,
+        crud::update_provider(&state.providers, id, dto, auth.user_id()).await?,
     ))
 }
 
@@ -348,7 +363,7 @@ async fn update_worker(
     Json(dto): Json<UpdateWorkerDto>,
 ) -> Result<Json<Worker>, AppError> {
     auth.require_owner()?;
-    Ok(Json(crud::update_worker(&state.workers, id, dto).await?))
+    Ok(Json(crud::update_worker(&state.workers, id, dto, auth.user_id()).await?))
 }
 
 // ─── Routes ─────────────────────────────────────────────
@@ -442,7 +457,9 @@ async fn update_freezer(
     Json(dto): Json<UpdateFreezerDto>,
 ) -> Result<Json<Freezer>, AppError> {
     auth.require_owner()?;
-    Ok(Json(crud::update_freezer(&state.freezers, id, dto).await?))
+    Ok(Json(
+        crud::update_freezer(&state.freezers, id, dto, auth.user_id()).await?,
+    ))
 }
 
 #[utoipa::path(
@@ -457,5 +474,73 @@ async fn toggle_freezer(
     Path(id): Path<Uuid>,
 ) -> Result<Json<Freezer>, AppError> {
     auth.require_role(Role::Admin)?;
-    Ok(Json(crud::toggle_freezer(&state.freezers, id).await?))
+    Ok(Json(
+        crud::toggle_freezer(&state.freezers, id, auth.user_id()).await?,
+    ))
+}
+
+#[utoipa::path(
+    get, path = "/freezers/occupancy", tag = "Catálogo - Congeladores",
+    responses((status = 200, description = "Ocupación de todos los congeladores", body = Vec<FreezerOccupancy>)),
+    security(("bearer_auth" = []))
+)]
+async fn list_freezer_occupancy(
+    auth: AuthUser,
+    State(state): State<CatalogState>,
+) -> Result<Json<Vec<FreezerOccupancy>>, AppError> {
+    auth.require_role(Role::Admin)?;
+    Ok(Json(crud::list_freezer_occupancy(&state.freezers).await?))
+}
+
+#[utoipa::path(
+    get, path = "/freezers/{id}/occupancy", tag = "Catálogo - Congeladores",
+    params(("id" = Uuid, Path, description = "ID del congelador")),
+    responses((status = 200, description = "Ocupación por producto", body = FreezerOccupancy)),
+    security(("bearer_auth" = []))
+)]
+async fn freezer_occupancy(
+    auth: AuthUser,
+    State(state): State<CatalogState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<FreezerOccupancy>, AppError> {
+    auth.require_role(Role::Admin)?;
+    Ok(Json(crud::freezer_occupancy(&state.freezers, id).await?))
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct PowerEventsQuery {
+    pub limit: Option<i64>,
+}
+
+#[utoipa::path(
+    get, path = "/freezers/{id}/power-events", tag = "Catálogo - Congeladores",
+    params(("id" = Uuid, Path, description = "ID del congelador"), PowerEventsQuery),
+    responses((status = 200, description = "Historial de encendido/apagado", body = Vec<FreezerPowerEvent>)),
+    security(("bearer_auth" = []))
+)]
+async fn freezer_power_events(
+    auth: AuthUser,
+    State(state): State<CatalogState>,
+    Path(id): Path<Uuid>,
+    Query(q): Query<PowerEventsQuery>,
+) -> Result<Json<Vec<FreezerPowerEvent>>, AppError> {
+    auth.require_role(Role::Admin)?;
+    Ok(Json(
+        freezer_power::list_power_events(&state.freezers, id, q.limit).await?,
+    ))
+}
+
+#[utoipa::path(
+    get, path = "/freezers/power-report", tag = "Catálogo - Congeladores",
+    params(PowerReportQuery),
+    responses((status = 200, description = "Horas encendido, kWh y costo estimado por congelador", body = PowerConsumptionReport)),
+    security(("bearer_auth" = []))
+)]
+async fn freezer_power_report(
+    auth: AuthUser,
+    State(state): State<CatalogState>,
+    Query(query): Query<PowerReportQuery>,
+) -> Result<Json<PowerConsumptionReport>, AppError> {
+    auth.require_owner()?;
+    Ok(Json(freezer_power::power_report(&state.freezers, query).await?))
 }
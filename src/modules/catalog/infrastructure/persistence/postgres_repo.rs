@@ -1,7 +1,11 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
+use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::modules::audit_log::domain::entities::CreateAuditLogDto;
+use crate::modules::audit_log::domain::repositories::AuditLogRepository;
 use crate::modules::catalog::domain::entities::*;
 use crate::modules::catalog::domain::repositories::*;
 use crate::shared::errors::AppError;
@@ -12,11 +16,12 @@ use crate::shared::errors::AppError;
 
 pub struct PgProductRepository {
     pool: PgPool,
+    audit: Arc<dyn AuditLogRepository>,
 }
 
 impl PgProductRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, audit: Arc<dyn AuditLogRepository>) -> Self {
+        Self { pool, audit }
     }
 }
 
@@ -50,13 +55,23 @@ impl ProductRepository for PgProductRepository {
     }
 
     async fn create(&self, dto: &CreateProductDto, created_by: Uuid) -> Result<Product, AppError> {
-        Ok(sqlx::query_as::<_, Product>(
+        let mut tx = self.pool.begin().await?;
+        let product = sqlx::query_as::<_, Product>(
             "INSERT INTO products (name, created_by) VALUES ($1, $2) RETURNING *",
         )
         .bind(&dto.name)
         .bind(created_by)
-        .fetch_one(&self.pool)
-        .await?)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::created("products", product.id, &product, created_by)?,
+            )
+            .await?;
+        tx.commit().await?;
+        Ok(product)
     }
 
     async fn update(
@@ -65,7 +80,14 @@ impl ProductRepository for PgProductRepository {
         dto: &UpdateProductDto,
         modified_by: Uuid,
     ) -> Result<Product, AppError> {
-        Ok(sqlx::query_as::<_, Product>(
+        let mut tx = self.pool.begin().await?;
+        let before =
+            sqlx::query_as::<_, Product>("SELECT * FROM products WHERE id = $1 FOR UPDATE")
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await?;
+
+        let product = sqlx::query_as::<_, Product>(
             r#"
             UPDATE products SET
                 name = COALESCE($1, name),
@@ -80,8 +102,17 @@ impl ProductRepository for PgProductRepository {
         .bind(dto.active)
         .bind(modified_by)
         .bind(id)
-        .fetch_one(&self.pool)
-        .await?)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::updated("products", id, &before, &product, modified_by)?,
+            )
+            .await?;
+        tx.commit().await?;
+        Ok(product)
     }
 }
 
@@ -91,11 +122,12 @@ impl ProductRepository for PgProductRepository {
 
 pub struct PgFlavorRepository {
     pool: PgPool,
+    audit: Arc<dyn AuditLogRepository>,
 }
 
 impl PgFlavorRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, audit: Arc<dyn AuditLogRepository>) -> Self {
+        Self { pool, audit }
     }
 }
 
@@ -128,18 +160,39 @@ impl FlavorRepository for PgFlavorRepository {
     }
 
     async fn create(&self, dto: &CreateFlavorDto, created_by: Uuid) -> Result<Flavor, AppError> {
-        Ok(sqlx::query_as::<_, Flavor>(
+        let mut tx = self.pool.begin().await?;
+        let flavor = sqlx::query_as::<_, Flavor>(
             "INSERT INTO flavors (name, product_id, created_by) VALUES ($1, $2, $3) RETURNING *",
         )
         .bind(&dto.name)
         .bind(dto.product_id)
         .bind(created_by)
-        .fetch_one(&self.pool)
-        .await?)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::created("flavors", flavor.id, &flavor, created_by)?,
+            )
+            .await?;
+        tx.commit().await?;
+        Ok(flavor)
     }
 
-    async fn update(&self, id: Uuid, dto: &UpdateFlavorDto) -> Result<Flavor, AppError> {
-        Ok(sqlx::query_as::<_, Flavor>(
+    async fn update(
+        &self,
+        id: Uuid,
+        dto: &UpdateFlavorDto,
+        updated_by: Uuid,
+    ) -> Result<Flavor, AppError> {
+        let mut tx = self.pool.begin().await?;
+        let before = sqlx::query_as::<_, Flavor>("SELECT * FROM flavors WHERE id = $1 FOR UPDATE")
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let flavor = sqlx::query_as::<_, Flavor>(
             r#"
             UPDATE flavors SET
                 name = COALESCE($1, name),
@@ -151,8 +204,17 @@ impl FlavorRepository for PgFlavorRepository {
         .bind(&dto.name)
         .bind(dto.active)
         .bind(id)
-        .fetch_one(&self.pool)
-        .await?)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::updated("flavors", id, &before, &flavor, updated_by)?,
+            )
+            .await?;
+        tx.commit().await?;
+        Ok(flavor)
     }
 }
 
@@ -162,11 +224,12 @@ impl FlavorRepository for PgFlavorRepository {
 
 pub struct PgProviderRepository {
     pool: PgPool,
+    audit: Arc<dyn AuditLogRepository>,
 }
 
 impl PgProviderRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, audit: Arc<dyn AuditLogRepository>) -> Self {
+        Self { pool, audit }
     }
 }
 
@@ -202,7 +265,8 @@ impl ProviderRepository for PgProviderRepository {
         dto: &CreateProviderDto,
         created_by: Uuid,
     ) -> Result<Provider, AppError> {
-        Ok(sqlx::query_as::<_, Provider>(
+        let mut tx = self.pool.begin().await?;
+        let provider = sqlx::query_as::<_, Provider>(
             r#"
             INSERT INTO providers (name, contact_info, created_by) 
             VALUES ($1, $2, $3) RETURNING *
@@ -211,12 +275,33 @@ impl ProviderRepository for PgProviderRepository {
         .bind(&dto.name)
         .bind(&dto.contact_info)
         .bind(created_by)
-        .fetch_one(&self.pool)
-        .await?)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::created("providers", provider.id, &provider, created_by)?,
+            )
+            .await?;
+        tx.commit().await?;
+        Ok(provider)
     }
 
-    async fn update(&self, id: Uuid, dto: &UpdateProviderDto) -> Result<Provider, AppError> {
-        Ok(sqlx::query_as::<_, Provider>(
+    async fn update(
+        &self,
+        id: Uuid,
+        dto: &UpdateProviderDto,
+        updated_by: Uuid,
+    ) -> Result<Provider, AppError> {
+        let mut tx = self.pool.begin().await?;
+        let before =
+            sqlx::query_as::<_, Provider>("SELECT * FROM providers WHERE id = $1 FOR UPDATE")
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await?;
+
+        let provider = sqlx::query_as::<_, Provider>(
             r#"
             UPDATE providers SET
                 name = COALESCE($1, name),
@@ -230,8 +315,17 @@ impl ProviderRepository for PgProviderRepository {
         .bind(&dto.contact_info)
         .bind(dto.active)
         .bind(id)
-        .fetch_one(&self.pool)
-        .await?)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::updated("providers", id, &before, &provider, updated_by)?,
+            )
+            .await?;
+        tx.commit().await?;
+        Ok(provider)
     }
 }
 
@@ -241,11 +335,12 @@ impl ProviderRepository for PgProviderRepository {
 
 pub struct PgWorkerRepository {
     pool: PgPool,
+    audit: Arc<dyn AuditLogRepository>,
 }
 
 impl PgWorkerRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, audit: Arc<dyn AuditLogRepository>) -> Self {
+        Self { pool, audit }
     }
 }
 
@@ -277,7 +372,8 @@ impl WorkerRepository for PgWorkerRepository {
     }
 
     async fn create(&self, dto: &CreateWorkerDto, created_by: Uuid) -> Result<Worker, AppError> {
-        Ok(sqlx::query_as::<_, Worker>(
+        let mut tx = self.pool.begin().await?;
+        let worker = sqlx::query_as::<_, Worker>(
             r#"
             INSERT INTO workers (name, phone, address, created_by) 
             VALUES ($1, $2, $3, $4) RETURNING *
@@ -287,12 +383,32 @@ impl WorkerRepository for PgWorkerRepository {
         .bind(&dto.phone)
         .bind(&dto.address)
         .bind(created_by)
-        .fetch_one(&self.pool)
-        .await?)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::created("workers", worker.id, &worker, created_by)?,
+            )
+            .await?;
+        tx.commit().await?;
+        Ok(worker)
     }
 
-    async fn update(&self, id: Uuid, dto: &UpdateWorkerDto) -> Result<Worker, AppError> {
-        Ok(sqlx::query_as::<_, Worker>(
+    async fn update(
+        &self,
+        id: Uuid,
+        dto: &UpdateWorkerDto,
+        updated_by: Uuid,
+    ) -> Result<Worker, AppError> {
+        let mut tx = self.pool.begin().await?;
+        let before = sqlx::query_as::<_, Worker>("SELECT * FROM workers WHERE id = $1 FOR UPDATE")
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let worker = sqlx::query_as::<_, Worker>(
             r#"
             UPDATE workers SET
                 name = COALESCE($1, name),
@@ -308,8 +424,17 @@ impl WorkerRepository for PgWorkerRepository {
         .bind(&dto.address)
         .bind(dto.active)
         .bind(id)
-        .fetch_one(&self.pool)
-        .await?)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::updated("workers", id, &before, &worker, updated_by)?,
+            )
+            .await?;
+        tx.commit().await?;
+        Ok(worker)
     }
 }
 
@@ -319,11 +444,12 @@ impl WorkerRepository for PgWorkerRepository {
 
 pub struct PgRouteRepository {
     pool: PgPool,
+    audit: Arc<dyn AuditLogRepository>,
 }
 
 impl PgRouteRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, audit: Arc<dyn AuditLogRepository>) -> Self {
+        Self { pool, audit }
     }
 }
 
@@ -347,13 +473,23 @@ impl RouteRepository for PgRouteRepository {
     }
 
     async fn create(&self, dto: &CreateRouteDto, created_by: Uuid) -> Result<Route, AppError> {
-        Ok(sqlx::query_as::<_, Route>(
+        let mut tx = self.pool.begin().await?;
+        let route = sqlx::query_as::<_, Route>(
             "INSERT INTO routes (name, created_by) VALUES ($1, $2) RETURNING *",
         )
         .bind(&dto.name)
         .bind(created_by)
-        .fetch_one(&self.pool)
-        .await?)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::created("routes", route.id, &route, created_by)?,
+            )
+            .await?;
+        tx.commit().await?;
+        Ok(route)
     }
 }
 
@@ -361,13 +497,51 @@ impl RouteRepository for PgRouteRepository {
 // Freezers
 // ═══════════════════════════════════════════════════════════
 
+/// Registra un cambio de encendido/apagado en el historial.
+async fn insert_power_event_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    freezer_id: Uuid,
+    is_on: bool,
+    created_by: Uuid,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "INSERT INTO freezer_power_events (freezer_id, is_on, created_by) VALUES ($1, $2, $3)",
+    )
+    .bind(freezer_id)
+    .bind(is_on)
+    .bind(created_by)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
 pub struct PgFreezerRepository {
     pool: PgPool,
+    audit: Arc<dyn AuditLogRepository>,
 }
 
 impl PgFreezerRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, audit: Arc<dyn AuditLogRepository>) -> Self {
+        Self { pool, audit }
+    }
+
+    /// La capacidad solo puede referirse a productos existentes.
+    async fn ensure_products_exist(&self, cap: &FreezerCapacity) -> Result<(), AppError> {
+        if cap.is_empty() {
+            return Ok(());
+        }
+        let ids = cap.product_ids();
+        let found =
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM products WHERE id = ANY($1)")
+                .bind(&ids)
+                .fetch_one(&self.pool)
+                .await?;
+        if found != ids.len() as i64 {
+            return Err(AppError::BadRequest(
+                "La capacidad contiene productos inexistentes".into(),
+            ));
+        }
+        Ok(())
     }
 }
 
@@ -391,39 +565,94 @@ impl FreezerRepository for PgFreezerRepository {
     }
 
     async fn create(&self, dto: &CreateFreezerDto, created_by: Uuid) -> Result<Freezer, AppError> {
-        let cap = dto.max_capacity.clone().unwrap_or(serde_json::json!({}));
-        Ok(sqlx::query_as::<_, Freezer>(
+        let cap = dto.max_capacity.clone().unwrap_or_default();
+        self.ensure_products_exist(&cap).await?;
+        let mut tx = self.pool.begin().await?;
+        let freezer = sqlx::query_as::<_, Freezer>(
             r#"
-            INSERT INTO freezers (number, max_capacity, created_by) 
-            VALUES ($1, $2, $3) RETURNING *
+            INSERT INTO freezers (number, max_capacity, power_watts, kwh_per_day, created_by) 
+            VALUES ($1, $2, $3, $4, $5) RETURNING *
             "#,
         )
         .bind(dto.number)
-        .bind(&cap)
+        .bind(sqlx::types::Json(&cap))
+        .bind(dto.power_watts)
+        .bind(dto.kwh_per_day)
         .bind(created_by)
-        .fetch_one(&self.pool)
-        .await?)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        insert_power_event_tx(&mut tx, freezer.id, freezer.is_on, created_by).await?;
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::created("freezers", freezer.id, &freezer, created_by)?,
+            )
+            .await?;
+        tx.commit().await?;
+        Ok(freezer)
     }
 
-    async fn update(&self, id: Uuid, dto: &UpdateFreezerDto) -> Result<Freezer, AppError> {
-        Ok(sqlx::query_as::<_, Freezer>(
+    async fn update(
+        &self,
+        id: Uuid,
+        dto: &UpdateFreezerDto,
+        updated_by: Uuid,
+    ) -> Result<Freezer, AppError> {
+        if let Some(cap) = &dto.max_capacity {
+            self.ensure_products_exist(cap).await?;
+        }
+        let mut tx = self.pool.begin().await?;
+        let before =
+            sqlx::query_as::<_, Freezer>("SELECT * FROM freezers WHERE id = $1 FOR UPDATE")
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await?;
+        let toggled = dto.is_on.is_some_and(|on| on != before.is_on);
+
+        let freezer = sqlx::query_as::<_, Freezer>(
             r#"
             UPDATE freezers SET
                 max_capacity = COALESCE($1, max_capacity),
-                is_on = COALESCE($2, is_on)
-            WHERE id = $3
+                is_on = COALESCE($2, is_on),
+                power_watts = COALESCE($3, power_watts),
+                kwh_per_day = COALESCE($4, kwh_per_day),
+                last_toggle = CASE WHEN $5 THEN NOW() ELSE last_toggle END
+            WHERE id = $6
             RETURNING *
             "#,
         )
-        .bind(&dto.max_capacity)
+        .bind(dto.max_capacity.as_ref().map(sqlx::types::Json))
         .bind(dto.is_on)
+        .bind(dto.power_watts)
+        .bind(dto.kwh_per_day)
+        .bind(toggled)
         .bind(id)
-        .fetch_one(&self.pool)
-        .await?)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if toggled {
+            insert_power_event_tx(&mut tx, id, freezer.is_on, updated_by).await?;
+        }
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::updated("freezers", id, &before, &freezer, updated_by)?,
+            )
+            .await?;
+        tx.commit().await?;
+        Ok(freezer)
     }
 
-    async fn toggle_power(&self, id: Uuid) -> Result<Freezer, AppError> {
-        Ok(sqlx::query_as::<_, Freezer>(
+    async fn toggle_power(&self, id: Uuid, toggled_by: Uuid) -> Result<Freezer, AppError> {
+        let mut tx = self.pool.begin().await?;
+        let before =
+            sqlx::query_as::<_, Freezer>("SELECT * FROM freezers WHERE id = $1 FOR UPDATE")
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await?;
+
+        let freezer = sqlx::query_as::<_, Freezer>(
             r#"
             UPDATE freezers SET 
                 is_on = NOT is_on,
@@ -433,7 +662,79 @@ impl FreezerRepository for PgFreezerRepository {
             "#,
         )
         .bind(id)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        insert_power_event_tx(&mut tx, id, freezer.is_on, toggled_by).await?;
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::updated("freezers", id, &before, &freezer, toggled_by)?,
+            )
+            .await?;
+        tx.commit().await?;
+        Ok(freezer)
+    }
+
+    async fn find_power_events(
+        &self,
+        freezer_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<FreezerPowerEvent>, AppError> {
+        Ok(sqlx::query_as::<_, FreezerPowerEvent>(
+            r#"
+            SELECT * FROM freezer_power_events
+            WHERE freezer_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(freezer_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn power_events_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<FreezerPowerEvent>, AppError> {
+        // Último estado antes del período + cambios dentro del período
+        Ok(sqlx::query_as::<_, FreezerPowerEvent>(
+            r#"
+            SELECT * FROM (
+                SELECT DISTINCT ON (freezer_id) *
+                FROM freezer_power_events
+                WHERE created_at <= $1
+                ORDER BY freezer_id, created_at DESC
+            ) before_range
+            UNION ALL
+            SELECT * FROM freezer_power_events
+            WHERE created_at > $1 AND created_at <= $2
+            ORDER BY freezer_id, created_at
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn stock_by_product(
+        &self,
+        freezer_id: Option<Uuid>,
+    ) -> Result<Vec<FreezerProductStock>, AppError> {
+        Ok(sqlx::query_as::<_, FreezerProductStock>(
+            r#"
+            SELECT freezer_id, product_id, SUM(quantity)::BIGINT AS quantity
+            FROM inventory
+            WHERE ($1::uuid IS NULL OR freezer_id = $1) AND quantity > 0
+            GROUP BY freezer_id, product_id
+            "#,
+        )
+        .bind(freezer_id)
+        .fetch_all(&self.pool)
         .await?)
     }
 }
@@ -0,0 +1,186 @@
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashSet};
+use uuid::Uuid;
+
+use crate::modules::catalog::domain::entities::{Freezer, FreezerOccupancy, FreezerProductStock};
+use crate::modules::catalog::domain::repositories::FreezerRepository;
+use crate::modules::freezer_transfers::domain::entities::{
+    ConsolidationPlan, ConsolidationQuery, CreateTransferDto, TransferItemDto,
+};
+use crate::modules::inventory::domain::entities::InventoryItem;
+use crate::modules::inventory::domain::repositories::InventoryRepository;
+use crate::shared::errors::AppError;
+
+pub const DEFAULT_MAX_OCCUPANCY_PCT: u8 = 50;
+
+/// Sugerencias de consolidación con el estado actual de congeladores e inventario.
+pub async fn suggest_consolidation(
+    freezers: &dyn FreezerRepository,
+    inventory: &dyn InventoryRepository,
+    query: ConsolidationQuery,
+) -> Result<Vec<ConsolidationPlan>, AppError> {
+    let max_pct = query.max_occupancy_pct.unwrap_or(DEFAULT_MAX_OCCUPANCY_PCT);
+    if max_pct == 0 || max_pct > 100 {
+        return Err(AppError::BadRequest(
+            "La ocupación máxima debe estar entre 1 y 100".into(),
+        ));
+    }
+    let freezers = freezers.find_all().await?;
+    let stock = inventory.find_all().await?;
+    Ok(plan_consolidation(&freezers, &stock, max_pct))
+}
+
+/// Motor de consolidación.
+///
+/// Un congelador encendido es semi-vacío si su ocupación no supera `max_pct`.
+/// Para cada destino (de mayor a menor capacidad y, a igual capacidad, el más
+/// lleno primero para mover menos unidades) se acumulan semi-vacíos,
+/// del menos al más lleno, mientras su contenido quepa producto por producto
+/// en el espacio libre del destino. Solo se sugiere si participan al menos dos
+/// congeladores semi-vacíos (contando el destino). Cada congelador aparece en
+/// una sola sugerencia.
+///
+/// No se vacían congeladores con deformados (las transferencias solo mueven
+/// stock normal) ni congeladores ya vacíos (basta con apagarlos).
+pub fn plan_consolidation(
+    freezers: &[Freezer],
+    stock: &[InventoryItem],
+    max_pct: u8,
+) -> Vec<ConsolidationPlan> {
+    let grouped = group_by_product(stock);
+    let occupancy: BTreeMap<Uuid, FreezerOccupancy> = freezers
+        .iter()
+        .map(|f| (f.id, FreezerOccupancy::compute(f, &grouped)))
+        .collect();
+
+    let threshold = Decimal::from(max_pct);
+    let is_semi_empty = |id: &Uuid| {
+        occupancy[id]
+            .occupancy_pct
+            .is_some_and(|pct| pct <= threshold)
+    };
+
+    let mut targets: Vec<&Freezer> = freezers
+        .iter()
+        .filter(|f| f.is_on && !f.max_capacity.is_empty())
+        .collect();
+    targets.sort_by_key(|f| {
+        (
+            std::cmp::Reverse(f.max_capacity.total()),
+            std::cmp::Reverse(occupancy[&f.id].total_quantity),
+            f.number,
+        )
+    });
+
+    let mut sources: Vec<&Freezer> = targets
+        .iter()
+        .copied()
+        .filter(|f| is_semi_empty(&f.id))
+        .filter(|f| occupancy[&f.id].total_quantity > 0)
+        .filter(|f| {
+            !stock
+                .iter()
+                .any(|i| i.freezer_id == f.id && i.is_deformed && i.quantity > 0)
+        })
+        .collect();
+    sources.sort_by_key(|f| (occupancy[&f.id].total_quantity, f.number));
+
+    let mut used: HashSet<Uuid> = HashSet::new();
+    let mut plans = Vec::new();
+
+    for target in targets {
+        if used.contains(&target.id) {
+            continue;
+        }
+        let target_occ = &occupancy[&target.id];
+        let mut free: BTreeMap<Uuid, i64> = target_occ
+            .products
+            .iter()
+            .filter_map(|p| p.capacity.map(|c| (p.product_id, c as i64 - p.quantity)))
+            .collect();
+
+        let mut chosen: Vec<&Freezer> = Vec::new();
+        for source in sources.iter().copied() {
+            if source.id == target.id || used.contains(&source.id) {
+                continue;
+            }
+            let fits = occupancy[&source.id]
+                .products
+                .iter()
+                .filter(|p| p.quantity > 0)
+                .all(|p| free.get(&p.product_id).is_some_and(|&f| p.quantity <= f));
+            if !fits {
+                continue;
+            }
+            for p in &occupancy[&source.id].products {
+                if let Some(f) = free.get_mut(&p.product_id) {
+                    *f -= p.quantity;
+                }
+            }
+            chosen.push(source);
+        }
+
+        let semi_empty_involved = chosen.len() + usize::from(is_semi_empty(&target.id));
+        if chosen.is_empty() || semi_empty_involved < 2 {
+            continue;
+        }
+
+        let transfers: Vec<CreateTransferDto> = chosen
+            .iter()
+            .map(|source| CreateTransferDto {
+                from_freezer_id: source.id,
+                to_freezer_id: target.id,
+                reason: Some(format!(
+                    "Consolidación: apagar congelador {}",
+                    source.number
+                )),
+                items: stock
+                    .iter()
+                    .filter(|i| i.freezer_id == source.id && !i.is_deformed && i.quantity > 0)
+                    .map(|i| TransferItemDto {
+                        product_id: i.product_id,
+                        flavor_id: i.flavor_id,
+                        provider_id: Some(i.provider_id),
+                        quantity: i.quantity,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        let units_moved: i64 = chosen.iter().map(|s| occupancy[&s.id].total_quantity).sum();
+        let after = target_occ.total_quantity + units_moved;
+
+        used.insert(target.id);
+        used.extend(chosen.iter().map(|s| s.id));
+
+        plans.push(ConsolidationPlan {
+            target_freezer_id: target.id,
+            target_number: target.number,
+            freezers_to_turn_off: chosen.iter().map(|s| s.id).collect(),
+            units_moved,
+            target_occupancy_after_pct: (Decimal::from(after) * Decimal::ONE_HUNDRED
+                / Decimal::from(target_occ.total_capacity))
+            .round_dp(2),
+            transfers,
+        });
+    }
+
+    plans
+}
+
+fn group_by_product(stock: &[InventoryItem]) -> Vec<FreezerProductStock> {
+    let mut totals: BTreeMap<(Uuid, Uuid), i64> = BTreeMap::new();
+    for item in stock.iter().filter(|i| i.quantity > 0) {
+        *totals
+            .entry((item.freezer_id, item.product_id))
+            .or_insert(0) += item.quantity as i64;
+    }
+    totals
+        .into_iter()
+        .map(|((freezer_id, product_id), quantity)| FreezerProductStock {
+            freezer_id,
+            product_id,
+            quantity,
+        })
+        .collect()
+}
@@ -1,6 +1,7 @@
 use crate::modules::freezer_transfers::domain::entities::*;
 use crate::modules::freezer_transfers::domain::repositories::FreezerTransferRepository;
 use crate::shared::errors::AppError;
+use crate::shared::void::VoidDto;
 use uuid::Uuid;
 
 pub async fn list_transfers(
@@ -43,3 +44,12 @@ pub async fn create_transfer(
     }
     repo.create_transfer(dto, created_by).await
 }
+
+pub async fn void_transfer(
+    repo: &dyn FreezerTransferRepository,
+    id: Uuid,
+    dto: &VoidDto,
+    voided_by: Uuid,
+) -> Result<FreezerTransfer, AppError> {
+    repo.void_transfer(id, dto.reason()?, voided_by).await
+}
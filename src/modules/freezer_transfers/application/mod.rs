@@ -1 +1,2 @@
 pub mod manage_transfers;
+pub mod consolidation;
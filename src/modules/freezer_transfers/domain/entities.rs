@@ -11,6 +11,10 @@ pub struct FreezerTransfer {
     pub reason: Option<String>,
     pub created_at: DateTime<Utc>,
     pub created_by: Uuid,
+    /// Anulada por el dueño: el stock volvió al congelador origen.
+    pub voided_at: Option<DateTime<Utc>>,
+    pub voided_by: Option<Uuid>,
+    pub void_reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
@@ -22,7 +26,7 @@ pub struct FreezerTransferItem {
     pub quantity: i32,
 }
 
-#[derive(Debug, Deserialize, utoipa::ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct CreateTransferDto {
     pub from_freezer_id: Uuid,
     pub to_freezer_id: Uuid,
@@ -30,10 +34,13 @@ pub struct CreateTransferDto {
     pub items: Vec<TransferItemDto>,
 }
 
-#[derive(Debug, Deserialize, utoipa::ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct TransferItemDto {
     pub product_id: Uuid,
     pub flavor_id: Uuid,
+    /// Proveedor del stock a mover. Sin él se toma cualquier fila del producto/sabor.
+    #[serde(default)]
+    pub provider_id: Option<Uuid>,
     pub quantity: i32,
 }
 
@@ -43,3 +50,22 @@ pub struct TransferWithItems {
     pub transfer: FreezerTransfer,
     pub items: Vec<FreezerTransferItem>,
 }
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct ConsolidationQuery {
+    /// Ocupación máxima (%) para considerar un congelador semi-vacío. Por defecto 50.
+    pub max_occupancy_pct: Option<u8>,
+}
+
+/// Sugerencia de consolidación: vaciar `freezers_to_turn_off` en `target_freezer_id`.
+/// `transfers` se puede enviar tal cual a `POST /api/transfers`.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ConsolidationPlan {
+    pub target_freezer_id: Uuid,
+    pub target_number: i32,
+    pub freezers_to_turn_off: Vec<Uuid>,
+    pub units_moved: i64,
+    /// Ocupación (%) del destino después de consolidar.
+    pub target_occupancy_after_pct: rust_decimal::Decimal,
+    pub transfers: Vec<CreateTransferDto>,
+}
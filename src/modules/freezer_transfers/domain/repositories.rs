@@ -15,4 +15,11 @@ pub trait FreezerTransferRepository: Send + Sync {
         dto: &CreateTransferDto,
         created_by: Uuid,
     ) -> Result<FreezerTransfer, AppError>;
+    /// Anula la transferencia: devuelve el stock del destino al origen.
+    async fn void_transfer(
+        &self,
+        id: Uuid,
+        reason: &str,
+        voided_by: Uuid,
+    ) -> Result<FreezerTransfer, AppError>;
 }
@@ -1,6 +1,6 @@
 use axum::{
     extract::{Path, Query, State},
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
 use serde::Deserialize;
@@ -8,21 +8,33 @@ use std::sync::Arc;
 use utoipa::OpenApi;
 use uuid::Uuid;
 
-use crate::modules::freezer_transfers::application::manage_transfers;
+use crate::modules::catalog::domain::repositories::FreezerRepository;
+use crate::modules::freezer_transfers::application::{consolidation, manage_transfers};
 use crate::modules::freezer_transfers::domain::entities::*;
 use crate::modules::freezer_transfers::domain::repositories::FreezerTransferRepository;
+use crate::modules::inventory::domain::repositories::InventoryRepository;
 use crate::shared::auth::{AppState, AuthUser, Role};
 use crate::shared::errors::AppError;
+use crate::shared::void::VoidDto;
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(list_transfers, get_transfer, list_by_freezer, create_transfer),
+    paths(
+        list_transfers,
+        get_transfer,
+        list_by_freezer,
+        create_transfer,
+        void_transfer,
+        consolidation_suggestions
+    ),
     components(schemas(
         crate::modules::freezer_transfers::domain::entities::FreezerTransfer,
         crate::modules::freezer_transfers::domain::entities::FreezerTransferItem,
         crate::modules::freezer_transfers::domain::entities::CreateTransferDto,
         crate::modules::freezer_transfers::domain::entities::TransferItemDto,
         crate::modules::freezer_transfers::domain::entities::TransferWithItems,
+        crate::modules::freezer_transfers::domain::entities::ConsolidationPlan,
+        crate::shared::void::VoidDto,
     ))
 )]
 pub struct TransfersApiDoc;
@@ -31,6 +43,8 @@ pub struct TransfersApiDoc;
 pub struct TransfersState {
     pub app: AppState,
     pub repo: Arc<dyn FreezerTransferRepository>,
+    pub freezers: Arc<dyn FreezerRepository>,
+    pub inventory: Arc<dyn InventoryRepository>,
 }
 
 impl axum::extract::FromRef<TransfersState> for AppState {
@@ -44,11 +58,23 @@ pub struct LimitQuery {
     pub limit: Option<i64>,
 }
 
-pub fn router(app: AppState, repo: Arc<dyn FreezerTransferRepository>) -> Router {
-    let state = TransfersState { app, repo };
+pub fn router(
+    app: AppState,
+    repo: Arc<dyn FreezerTransferRepository>,
+    freezers: Arc<dyn FreezerRepository>,
+    inventory: Arc<dyn InventoryRepository>,
+) -> Router {
+    let state = TransfersState {
+        app,
+        repo,
+        freezers,
+        inventory,
+    };
     Router::new()
         .route("/", get(list_transfers).post(create_transfer))
+        .route("/consolidation", get(consolidation_suggestions))
         .route("/{id}", get(get_transfer))
+        .route("/{id}/void", post(void_transfer))
         .route("/freezer/{freezer_id}", get(list_by_freezer))
         .with_state(state)
 }
@@ -121,3 +147,46 @@ async fn create_transfer(
         manage_transfers::create_transfer(state.repo.as_ref(), &dto, auth.user_id()).await?;
     Ok(Json(transfer))
 }
+
+#[utoipa::path(
+    post, path = "/{id}/void", tag = "Transferencias entre Congeladores",
+    params(("id" = Uuid, Path, description = "ID de la transferencia")),
+    request_body = VoidDto,
+    responses(
+        (status = 200, description = "Transferencia anulada", body = FreezerTransfer),
+        (status = 409, description = "Ya anulada o el stock ya salió del destino")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn void_transfer(
+    State(state): State<TransfersState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(dto): Json<VoidDto>,
+) -> Result<Json<FreezerTransfer>, AppError> {
+    auth.require_owner()?;
+    let transfer =
+        manage_transfers::void_transfer(state.repo.as_ref(), id, &dto, auth.user_id()).await?;
+    Ok(Json(transfer))
+}
+
+#[utoipa::path(
+    get, path = "/consolidation", tag = "Transferencias entre Congeladores",
+    params(ConsolidationQuery),
+    responses((status = 200, description = "Sugerencias de consolidación para apagar congeladores", body = Vec<ConsolidationPlan>)),
+    security(("bearer_auth" = []))
+)]
+async fn consolidation_suggestions(
+    State(state): State<TransfersState>,
+    auth: AuthUser,
+    Query(q): Query<ConsolidationQuery>,
+) -> Result<Json<Vec<ConsolidationPlan>>, AppError> {
+    auth.require_owner()?;
+    let plans = consolidation::suggest_consolidation(
+        state.freezers.as_ref(),
+        state.inventory.as_ref(),
+        q,
+    )
+    .await?;
+    Ok(Json(plans))
+}
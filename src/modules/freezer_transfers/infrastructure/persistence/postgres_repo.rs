@@ -1,18 +1,26 @@
 use async_trait::async_trait;
 use sqlx::PgPool;
+use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::modules::audit_log::domain::entities::CreateAuditLogDto;
+use crate::modules::audit_log::domain::repositories::AuditLogRepository;
 use crate::modules::freezer_transfers::domain::entities::*;
 use crate::modules::freezer_transfers::domain::repositories::FreezerTransferRepository;
+use crate::modules::inventory::domain::entities::{MovementCause, MovementType};
+use crate::modules::inventory::infrastructure::persistence::movements::{
+    record_movement_tx, reverse_doc_movements_tx,
+};
 use crate::shared::errors::AppError;
 
 pub struct PgFreezerTransferRepository {
     pool: PgPool,
+    audit: Arc<dyn AuditLogRepository>,
 }
 
 impl PgFreezerTransferRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, audit: Arc<dyn AuditLogRepository>) -> Self {
+        Self { pool, audit }
     }
 }
 
@@ -106,12 +114,14 @@ impl FreezerTransferRepository for PgFreezerTransferRepository {
                 r#"
                 SELECT id FROM inventory 
                 WHERE freezer_id = $1 AND product_id = $2 AND flavor_id = $3 AND is_deformed = FALSE
+                  AND ($4::uuid IS NULL OR provider_id = $4)
                 LIMIT 1
                 "#,
             )
             .bind(dto.from_freezer_id)
             .bind(item.product_id)
             .bind(item.flavor_id)
+            .bind(item.provider_id)
             .fetch_optional(&mut *tx)
             .await?
             .ok_or_else(|| {
@@ -135,6 +145,15 @@ impl FreezerTransferRepository for PgFreezerTransferRepository {
                 return Err(AppError::InsufficientStock(source_inv));
             }
 
+            record_movement_tx(
+                &mut tx,
+                source_inv,
+                -item.quantity,
+                MovementCause::doc(MovementType::TransferOut, "freezer_transfers", transfer.id),
+                created_by,
+            )
+            .await?;
+
             // Sumar al congelador destino (UPSERT)
             // Necesitamos el provider_id del item origen
             let provider_id =
@@ -143,7 +162,7 @@ impl FreezerTransferRepository for PgFreezerTransferRepository {
                     .fetch_one(&mut *tx)
                     .await?;
 
-            sqlx::query(
+            let dest_inv = sqlx::query_scalar::<_, Uuid>(
                 r#"
                 INSERT INTO inventory 
                 (freezer_id, product_id, flavor_id, provider_id, quantity, is_deformed, min_stock_alert, updated_by)
@@ -153,6 +172,7 @@ impl FreezerTransferRepository for PgFreezerTransferRepository {
                     quantity = inventory.quantity + EXCLUDED.quantity,
                     last_updated = NOW(),
                     updated_by = EXCLUDED.updated_by
+                RETURNING id
                 "#,
             )
             .bind(dto.to_freezer_id)
@@ -161,23 +181,88 @@ impl FreezerTransferRepository for PgFreezerTransferRepository {
             .bind(provider_id)
             .bind(item.quantity)
             .bind(created_by)
-            .execute(&mut *tx)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            record_movement_tx(
+                &mut tx,
+                dest_inv,
+                item.quantity,
+                MovementCause::doc(MovementType::TransferIn, "freezer_transfers", transfer.id),
+                created_by,
+            )
             .await?;
         }
 
         // 3. Auditoría
-        sqlx::query(
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::created(
+                    "freezer_transfers",
+                    transfer.id,
+                    &transfer,
+                    created_by,
+                )?,
+            )
+            .await?;
+
+        tx.commit().await?;
+        Ok(transfer)
+    }
+
+    async fn void_transfer(
+        &self,
+        id: Uuid,
+        reason: &str,
+        voided_by: Uuid,
+    ) -> Result<FreezerTransfer, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // 1. Transferencia (con lock)
+        let existing = sqlx::query_as::<_, FreezerTransfer>(
+            "SELECT * FROM freezer_transfers WHERE id = $1 FOR UPDATE",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Transferencia no encontrada".into()))?;
+        if existing.voided_at.is_some() {
+            return Err(AppError::Conflict("La transferencia ya fue anulada".into()));
+        }
+
+        // 2. Sacar del destino y devolver al origen
+        reverse_doc_movements_tx(&mut tx, "freezer_transfers", id, voided_by).await?;
+
+        // 3. Marcar como anulada
+        let transfer = sqlx::query_as::<_, FreezerTransfer>(
             r#"
-            INSERT INTO audit_log (action, table_name, record_id, changes_after, created_by)
-            VALUES ('create', 'freezer_transfers', $1, $2, $3)
+            UPDATE freezer_transfers
+            SET voided_at = NOW(), voided_by = $1, void_reason = $2
+            WHERE id = $3
+            RETURNING *
             "#,
         )
-        .bind(transfer.id)
-        .bind(serde_json::to_value(&transfer)?)
-        .bind(created_by)
-        .execute(&mut *tx)
+        .bind(voided_by)
+        .bind(reason)
+        .bind(id)
+        .fetch_one(&mut *tx)
         .await?;
 
+        // 4. Auditoría
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::updated(
+                    "freezer_transfers",
+                    id,
+                    &existing,
+                    &transfer,
+                    voided_by,
+                )?,
+            )
+            .await?;
+
         tx.commit().await?;
         Ok(transfer)
     }
@@ -0,0 +1,76 @@
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::modules::inventory::domain::entities::{
+    AdjustmentReason, CreateAdjustmentDto, DeformedTransfer, InventoryAdjustment,
+    ReassignDeformedDto, ReleaseDeformedDto, WriteOffDeformedDto,
+};
+use crate::modules::inventory::domain::repositories::{
+    InventoryRepository, StockAdjustmentRepository,
+};
+use crate::shared::errors::AppError;
+
+/// Pasa deformados de un trabajador (o del stock general) a otro trabajador,
+/// p. ej. cuando el dueño de la fila deja de trabajar.
+pub async fn reassign(
+    repo: &Arc<dyn InventoryRepository>,
+    inventory_id: Uuid,
+    dto: ReassignDeformedDto,
+    created_by: Uuid,
+) -> Result<DeformedTransfer, AppError> {
+    repo.transfer_deformed(
+        inventory_id,
+        Some(dto.worker_id),
+        dto.quantity,
+        dto.notes.as_deref(),
+        created_by,
+    )
+    .await
+}
+
+/// Libera deformados al stock general, sin trabajador asignado.
+pub async fn release(
+    repo: &Arc<dyn InventoryRepository>,
+    inventory_id: Uuid,
+    dto: ReleaseDeformedDto,
+    created_by: Uuid,
+) -> Result<DeformedTransfer, AppError> {
+    repo.transfer_deformed(
+        inventory_id,
+        None,
+        dto.quantity,
+        dto.notes.as_deref(),
+        created_by,
+    )
+    .await
+}
+
+/// Baja de deformados como ajuste negativo, para que cuente como merma.
+pub async fn write_off(
+    inventory: &Arc<dyn InventoryRepository>,
+    adjustments: &Arc<dyn StockAdjustmentRepository>,
+    inventory_id: Uuid,
+    dto: WriteOffDeformedDto,
+    created_by: Uuid,
+) -> Result<InventoryAdjustment, AppError> {
+    let item = inventory
+        .find_by_id(inventory_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Inventario {inventory_id} no encontrado")))?;
+    if !item.is_deformed {
+        return Err(AppError::BadRequest(
+            "Esta baja es solo para deformados; use un ajuste".into(),
+        ));
+    }
+    let quantity = dto.quantity.unwrap_or(item.quantity);
+    if quantity <= 0 {
+        return Err(AppError::BadRequest("La cantidad debe ser positiva".into()));
+    }
+    let adjustment = CreateAdjustmentDto {
+        inventory_id,
+        quantity_delta: -quantity,
+        reason: dto.reason.unwrap_or(AdjustmentReason::Damaged),
+        notes: dto.notes,
+    };
+    adjustments.create_adjustment(&adjustment, created_by).await
+}
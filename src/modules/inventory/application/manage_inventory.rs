@@ -1,7 +1,9 @@
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::modules::inventory::domain::entities::{AddStockDto, InventoryItem};
+use crate::modules::inventory::domain::entities::{
+    AddStockDto, InventoryItem, InventoryMovement, MovementFilter, StockAtQuery, StockAtTime,
+};
 use crate::modules::inventory::domain::repositories::InventoryRepository;
 use crate::shared::errors::AppError;
 
@@ -55,6 +57,32 @@ pub async fn update_alert(
     repo: &Arc<dyn InventoryRepository>,
     id: Uuid,
     min_stock: i32,
+    updated_by: Uuid,
 ) -> Result<InventoryItem, AppError> {
-    repo.update_alert(id, min_stock).await
+    repo.update_alert(id, min_stock, updated_by).await
+}
+
+/// Kardex filtrado. El límite por defecto es 200 y nunca supera 1000.
+pub async fn list_movements(
+    repo: &Arc<dyn InventoryRepository>,
+    mut filter: MovementFilter,
+) -> Result<Vec<InventoryMovement>, AppError> {
+    if let (Some(from), Some(to)) = (filter.from, filter.to) {
+        if from > to {
+            return Err(AppError::BadRequest(
+                "La fecha inicial no puede ser mayor a la final".into(),
+            ));
+        }
+    }
+    filter.limit = Some(filter.limit.unwrap_or(200).clamp(1, 1000));
+    repo.find_movements(&filter).await
+}
+
+/// Stock reconstruido desde el kardex en una fecha pasada.
+pub async fn stock_at(
+    repo: &Arc<dyn InventoryRepository>,
+    query: StockAtQuery,
+) -> Result<Vec<StockAtTime>, AppError> {
+    repo.stock_at(query.at, query.freezer_id, query.product_id)
+        .await
 }
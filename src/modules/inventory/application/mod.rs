@@ -1 +1,3 @@
+pub mod deformed_stock;
 pub mod manage_inventory;
+pub mod stock_adjustments;
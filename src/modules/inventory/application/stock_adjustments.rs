@@ -0,0 +1,128 @@
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::modules::inventory::domain::entities::{
+    AdjustmentQuery, CreateAdjustmentDto, InventoryAdjustment, OpenStockCountDto, ShrinkageReport,
+    StockCountSession, StockCountWithLines, SubmitCountsDto,
+};
+use crate::modules::inventory::domain::repositories::StockAdjustmentRepository;
+use crate::shared::errors::AppError;
+
+fn validate_range(query: &AdjustmentQuery) -> Result<(), AppError> {
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from > to {
+            return Err(AppError::BadRequest(
+                "La fecha inicial no puede ser mayor a la final".into(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Ajuste o baja manual, valorizado a `cost_price`.
+pub async fn create_adjustment(
+    repo: &Arc<dyn StockAdjustmentRepository>,
+    dto: CreateAdjustmentDto,
+    created_by: Uuid,
+) -> Result<InventoryAdjustment, AppError> {
+    if dto.quantity_delta == 0 {
+        return Err(AppError::BadRequest(
+            "La cantidad del ajuste no puede ser cero".into(),
+        ));
+    }
+    repo.create_adjustment(&dto, created_by).await
+}
+
+pub async fn list_adjustments(
+    repo: &Arc<dyn StockAdjustmentRepository>,
+    query: AdjustmentQuery,
+) -> Result<Vec<InventoryAdjustment>, AppError> {
+    validate_range(&query)?;
+    repo.find_adjustments(&query).await
+}
+
+/// Reporte de merma por motivo y producto, con totales del período.
+pub async fn shrinkage_report(
+    repo: &Arc<dyn StockAdjustmentRepository>,
+    query: AdjustmentQuery,
+) -> Result<ShrinkageReport, AppError> {
+    validate_range(&query)?;
+    let rows = repo.shrinkage(&query).await?;
+
+    let total_units_lost = rows.iter().map(|r| r.units_lost).sum();
+    let total_value_lost: Decimal = rows.iter().map(|r| r.value_lost).sum();
+    let total_value_found: Decimal = rows.iter().map(|r| r.value_found).sum();
+    let total_uncosted_units = rows.iter().map(|r| r.uncosted_units).sum();
+
+    Ok(ShrinkageReport {
+        from: query.from,
+        to: query.to,
+        rows,
+        total_units_lost,
+        total_value_lost,
+        total_value_found,
+        total_uncosted_units,
+        net_value: total_value_found - total_value_lost,
+    })
+}
+
+pub async fn open_count(
+    repo: &Arc<dyn StockAdjustmentRepository>,
+    dto: OpenStockCountDto,
+    opened_by: Uuid,
+) -> Result<StockCountWithLines, AppError> {
+    repo.open_count(&dto, opened_by).await
+}
+
+pub async fn get_count(
+    repo: &Arc<dyn StockAdjustmentRepository>,
+    id: Uuid,
+) -> Result<StockCountWithLines, AppError> {
+    repo.find_count(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Conteo no encontrado".into()))
+}
+
+pub async fn list_counts(
+    repo: &Arc<dyn StockAdjustmentRepository>,
+    freezer_id: Option<Uuid>,
+) -> Result<Vec<StockCountSession>, AppError> {
+    repo.find_counts(freezer_id).await
+}
+
+pub async fn submit_counts(
+    repo: &Arc<dyn StockAdjustmentRepository>,
+    session_id: Uuid,
+    dto: SubmitCountsDto,
+    submitted_by: Uuid,
+) -> Result<StockCountWithLines, AppError> {
+    if dto.lines.is_empty() {
+        return Err(AppError::BadRequest(
+            "Debe enviar al menos una línea".into(),
+        ));
+    }
+    if dto.lines.iter().any(|l| l.counted_quantity < 0) {
+        return Err(AppError::BadRequest(
+            "La cantidad contada no puede ser negativa".into(),
+        ));
+    }
+    repo.submit_counts(session_id, &dto, submitted_by).await
+}
+
+/// Confirma el conteo: cada diferencia se convierte en un ajuste.
+pub async fn commit_count(
+    repo: &Arc<dyn StockAdjustmentRepository>,
+    session_id: Uuid,
+    committed_by: Uuid,
+) -> Result<StockCountWithLines, AppError> {
+    repo.commit_count(session_id, committed_by).await
+}
+
+pub async fn cancel_count(
+    repo: &Arc<dyn StockAdjustmentRepository>,
+    session_id: Uuid,
+    cancelled_by: Uuid,
+) -> Result<StockCountSession, AppError> {
+    repo.cancel_count(session_id, cancelled_by).await
+}
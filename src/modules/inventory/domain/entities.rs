@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -33,3 +34,326 @@ pub struct AddStockDto {
 pub struct UpdateAlertDto {
     pub min_stock_alert: i32,
 }
+
+// ─── Deformados ─────────────────────────────────────────
+
+/// Movimiento de deformados entre trabajadores o hacia/desde el stock
+/// general de deformados (`*_worker_id` nulo).
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct DeformedTransfer {
+    pub id: Uuid,
+    pub from_inventory_id: Uuid,
+    pub to_inventory_id: Uuid,
+    pub freezer_id: Uuid,
+    pub product_id: Uuid,
+    pub flavor_id: Uuid,
+    pub from_worker_id: Option<Uuid>,
+    pub to_worker_id: Option<Uuid>,
+    pub quantity: i32,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Uuid,
+}
+
+/// DTO para reasignar deformados a otro trabajador.
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct ReassignDeformedDto {
+    pub worker_id: Uuid,
+    /// Si se omite, se reasigna toda la fila.
+    pub quantity: Option<i32>,
+    pub notes: Option<String>,
+}
+
+/// DTO para liberar deformados al stock general (venta con descuento).
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct ReleaseDeformedDto {
+    /// Si se omite, se libera toda la fila.
+    pub quantity: Option<i32>,
+    pub notes: Option<String>,
+}
+
+/// DTO para dar de baja deformados (queda como ajuste valorizado).
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct WriteOffDeformedDto {
+    /// Si se omite, se da de baja toda la fila.
+    pub quantity: Option<i32>,
+    /// Por defecto `damaged`.
+    pub reason: Option<AdjustmentReason>,
+    pub notes: Option<String>,
+}
+
+// ─── Kardex ─────────────────────────────────────────────
+
+/// Movimiento de inventario (append-only). Registra el delta, la cantidad
+/// resultante y el documento que causó el cambio.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct InventoryMovement {
+    pub id: Uuid,
+    pub seq: i64,
+    pub inventory_id: Uuid,
+    pub freezer_id: Uuid,
+    pub product_id: Uuid,
+    pub flavor_id: Uuid,
+    pub provider_id: Uuid,
+    pub is_deformed: bool,
+    pub assigned_worker_id: Option<Uuid>,
+    pub delta: i32,
+    pub quantity_after: i32,
+    pub movement_type: String,
+    pub related_doc_type: Option<String>,
+    pub related_doc_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Uuid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MovementType {
+    Opening,
+    ManualAdd,
+    Purchase,
+    TripLoad,
+    TripReturn,
+    LocalSale,
+    OwnerSaleLoad,
+    OwnerSaleReturn,
+    TransferOut,
+    TransferIn,
+    Adjustment,
+    Void,
+    TripCorrection,
+    DeformedTransfer,
+}
+
+impl MovementType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Opening => "opening",
+            Self::ManualAdd => "manual_add",
+            Self::Purchase => "purchase",
+            Self::TripLoad => "trip_load",
+            Self::TripReturn => "trip_return",
+            Self::LocalSale => "local_sale",
+            Self::OwnerSaleLoad => "owner_sale_load",
+            Self::OwnerSaleReturn => "owner_sale_return",
+            Self::TransferOut => "transfer_out",
+            Self::TransferIn => "transfer_in",
+            Self::Adjustment => "adjustment",
+            Self::Void => "void",
+            Self::TripCorrection => "trip_correction",
+            Self::DeformedTransfer => "deformed_transfer",
+        }
+    }
+}
+
+/// Causa de un movimiento: tipo y documento relacionado (si hay).
+#[derive(Debug, Clone, Copy)]
+pub struct MovementCause {
+    pub movement_type: MovementType,
+    pub related_doc_type: Option<&'static str>,
+    pub related_doc_id: Option<Uuid>,
+}
+
+impl MovementCause {
+    /// Movimiento causado por un documento (viaje, venta, compra, traspaso...).
+    pub fn doc(movement_type: MovementType, doc_type: &'static str, doc_id: Uuid) -> Self {
+        Self {
+            movement_type,
+            related_doc_type: Some(doc_type),
+            related_doc_id: Some(doc_id),
+        }
+    }
+
+    /// Movimiento sin documento (p. ej. ingreso manual de stock).
+    pub fn manual(movement_type: MovementType) -> Self {
+        Self {
+            movement_type,
+            related_doc_type: None,
+            related_doc_id: None,
+        }
+    }
+}
+
+/// Filtros para listar movimientos.
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct MovementFilter {
+    pub inventory_id: Option<Uuid>,
+    pub freezer_id: Option<Uuid>,
+    pub product_id: Option<Uuid>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+}
+
+/// Consulta de stock reconstruido en una fecha.
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct StockAtQuery {
+    pub at: DateTime<Utc>,
+    pub freezer_id: Option<Uuid>,
+    pub product_id: Option<Uuid>,
+}
+
+/// Stock de una fila de inventario reconstruido desde el kardex.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct StockAtTime {
+    pub inventory_id: Uuid,
+    pub freezer_id: Uuid,
+    pub product_id: Uuid,
+    pub flavor_id: Uuid,
+    pub provider_id: Uuid,
+    pub is_deformed: bool,
+    pub assigned_worker_id: Option<Uuid>,
+    pub quantity: i32,
+    pub last_movement_at: DateTime<Utc>,
+}
+
+// ─── Ajustes y conteos físicos ──────────────────────────
+
+/// Motivo de un ajuste de inventario.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AdjustmentReason {
+    Melted,
+    Damaged,
+    Lost,
+    CountingError,
+}
+
+impl AdjustmentReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Melted => "melted",
+            Self::Damaged => "damaged",
+            Self::Lost => "lost",
+            Self::CountingError => "counting_error",
+        }
+    }
+}
+
+/// Ajuste de inventario valorizado a `cost_price`. `total_cost` lleva el signo
+/// del ajuste: negativo es merma, positivo es sobrante. Ambos costos son nulos
+/// si el producto no tenía precio vigente.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct InventoryAdjustment {
+    pub id: Uuid,
+    pub inventory_id: Uuid,
+    pub freezer_id: Uuid,
+    pub product_id: Uuid,
+    pub flavor_id: Uuid,
+    pub provider_id: Uuid,
+    pub quantity_delta: i32,
+    pub reason: String,
+    pub unit_cost: Option<Decimal>,
+    pub total_cost: Option<Decimal>,
+    pub notes: Option<String>,
+    pub count_session_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Uuid,
+}
+
+/// DTO para un ajuste/baja manual.
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct CreateAdjustmentDto {
+    pub inventory_id: Uuid,
+    /// Negativo para bajas (merma), positivo para sobrantes.
+    pub quantity_delta: i32,
+    pub reason: AdjustmentReason,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct StockCountSession {
+    pub id: Uuid,
+    pub freezer_id: Uuid,
+    pub status: String,
+    pub notes: Option<String>,
+    pub opened_at: DateTime<Utc>,
+    pub opened_by: Uuid,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub closed_by: Option<Uuid>,
+}
+
+/// Línea de conteo con la cantidad actual del sistema y la diferencia.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct StockCountLine {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub inventory_id: Uuid,
+    pub product_id: Uuid,
+    pub flavor_id: Uuid,
+    pub provider_id: Uuid,
+    pub is_deformed: bool,
+    /// Cantidad del sistema al abrir el conteo.
+    pub expected_quantity: i32,
+    pub counted_quantity: Option<i32>,
+    pub reason: Option<String>,
+    /// Cantidad actual del sistema (puede cambiar mientras el conteo está abierto).
+    pub system_quantity: i32,
+    /// `counted_quantity - expected_quantity`: lo que se ajusta al confirmar,
+    /// sin contar los movimientos hechos mientras el conteo está abierto.
+    /// `None` si no se ha contado.
+    pub variance: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct StockCountWithLines {
+    #[serde(flatten)]
+    pub session: StockCountSession,
+    pub lines: Vec<StockCountLine>,
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct OpenStockCountDto {
+    pub freezer_id: Uuid,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct SubmitCountsDto {
+    pub lines: Vec<CountedLineDto>,
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct CountedLineDto {
+    pub inventory_id: Uuid,
+    pub counted_quantity: i32,
+    /// Motivo de la diferencia. Por defecto `counting_error`.
+    pub reason: Option<AdjustmentReason>,
+}
+
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct StockCountQuery {
+    pub freezer_id: Option<Uuid>,
+}
+
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct AdjustmentQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub freezer_id: Option<Uuid>,
+}
+
+/// Merma/sobrante agregado por motivo y producto.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct ShrinkageRow {
+    pub reason: String,
+    pub product_id: Uuid,
+    pub units_lost: i64,
+    pub value_lost: Decimal,
+    pub units_found: i64,
+    pub value_found: Decimal,
+    /// Unidades ajustadas (en cualquier sentido) sin costo; no suman valor.
+    pub uncosted_units: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ShrinkageReport {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub rows: Vec<ShrinkageRow>,
+    pub total_units_lost: i64,
+    pub total_value_lost: Decimal,
+    pub total_value_found: Decimal,
+    pub total_uncosted_units: i64,
+    /// `total_value_found - total_value_lost`.
+    pub net_value: Decimal,
+}
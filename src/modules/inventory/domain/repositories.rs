@@ -1,7 +1,8 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
-use super::entities::InventoryItem;
+use super::entities::*;
 use crate::shared::errors::AppError;
 
 /// Puerto de salida: persistencia de inventario.
@@ -42,10 +43,11 @@ pub trait InventoryRepository: Send + Sync {
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
         inventory_id: Uuid,
         quantity: i32,
+        cause: MovementCause,
         updated_by: Uuid,
     ) -> Result<(), AppError>;
 
-    /// Agregar stock deformado asignado a trabajador.
+    /// Agregar stock deformado asignado a trabajador (UPSERT).
     async fn add_deformed_tx(
         &self,
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
@@ -55,6 +57,7 @@ pub trait InventoryRepository: Send + Sync {
         provider_id: Uuid,
         quantity: i32,
         worker_id: Uuid,
+        cause: MovementCause,
         updated_by: Uuid,
     ) -> Result<InventoryItem, AppError>;
 
@@ -67,9 +70,91 @@ pub trait InventoryRepository: Send + Sync {
         flavor_id: Uuid,
         provider_id: Uuid,
         quantity: i32,
+        cause: MovementCause,
         updated_by: Uuid,
     ) -> Result<(), AppError>;
 
+    /// Mover deformados de una fila a otro trabajador o, con `to_worker_id`
+    /// nulo, al stock general de deformados. Sin `quantity` se mueve todo.
+    async fn transfer_deformed(
+        &self,
+        inventory_id: Uuid,
+        to_worker_id: Option<Uuid>,
+        quantity: Option<i32>,
+        notes: Option<&str>,
+        created_by: Uuid,
+    ) -> Result<DeformedTransfer, AppError>;
+
     /// Actualizar alerta de stock mínimo.
-    async fn update_alert(&self, id: Uuid, min_stock: i32) -> Result<InventoryItem, AppError>;
+    async fn update_alert(
+        &self,
+        id: Uuid,
+        min_stock: i32,
+        updated_by: Uuid,
+    ) -> Result<InventoryItem, AppError>;
+
+    /// Movimientos del kardex (más recientes primero).
+    async fn find_movements(
+        &self,
+        filter: &MovementFilter,
+    ) -> Result<Vec<InventoryMovement>, AppError>;
+
+    /// Stock de cada fila reconstruido desde el kardex en una fecha.
+    async fn stock_at(
+        &self,
+        at: DateTime<Utc>,
+        freezer_id: Option<Uuid>,
+        product_id: Option<Uuid>,
+    ) -> Result<Vec<StockAtTime>, AppError>;
+}
+
+/// Puerto de salida: conteos físicos y ajustes de inventario.
+#[async_trait]
+pub trait StockAdjustmentRepository: Send + Sync {
+    /// Ajuste/baja manual (TRANSACCIÓN: inventario, kardex, auditoría).
+    async fn create_adjustment(
+        &self,
+        dto: &CreateAdjustmentDto,
+        created_by: Uuid,
+    ) -> Result<InventoryAdjustment, AppError>;
+
+    async fn find_adjustments(
+        &self,
+        query: &AdjustmentQuery,
+    ) -> Result<Vec<InventoryAdjustment>, AppError>;
+
+    /// Merma y sobrantes agregados por motivo y producto.
+    async fn shrinkage(&self, query: &AdjustmentQuery) -> Result<Vec<ShrinkageRow>, AppError>;
+
+    /// Abre un conteo tomando una foto del inventario del congelador.
+    async fn open_count(
+        &self,
+        dto: &OpenStockCountDto,
+        opened_by: Uuid,
+    ) -> Result<StockCountWithLines, AppError>;
+
+    async fn find_count(&self, id: Uuid) -> Result<Option<StockCountWithLines>, AppError>;
+
+    async fn find_counts(&self, freezer_id: Option<Uuid>) -> Result<Vec<StockCountSession>, AppError>;
+
+    /// Registra cantidades contadas (solo conteos abiertos).
+    async fn submit_counts(
+        &self,
+        session_id: Uuid,
+        dto: &SubmitCountsDto,
+        submitted_by: Uuid,
+    ) -> Result<StockCountWithLines, AppError>;
+
+    /// Aplica las diferencias como ajustes y cierra el conteo.
+    async fn commit_count(
+        &self,
+        session_id: Uuid,
+        committed_by: Uuid,
+    ) -> Result<StockCountWithLines, AppError>;
+
+    async fn cancel_count(
+        &self,
+        session_id: Uuid,
+        cancelled_by: Uuid,
+    ) -> Result<StockCountSession, AppError>;
 }
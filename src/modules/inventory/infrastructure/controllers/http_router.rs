@@ -1,16 +1,23 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path, State},
-    routing::{get, put},
+    extract::{Path, Query, State},
+    routing::{get, post, put},
     Json, Router,
 };
 use utoipa::OpenApi;
 use uuid::Uuid;
 
-use crate::modules::inventory::application::manage_inventory;
-use crate::modules::inventory::domain::entities::{AddStockDto, InventoryItem, UpdateAlertDto};
-use crate::modules::inventory::domain::repositories::InventoryRepository;
+use crate::modules::inventory::application::{deformed_stock, manage_inventory, stock_adjustments};
+use crate::modules::inventory::domain::entities::{
+    AddStockDto, AdjustmentQuery, CreateAdjustmentDto, DeformedTransfer, InventoryAdjustment,
+    InventoryItem, InventoryMovement, MovementFilter, OpenStockCountDto, ReassignDeformedDto,
+    ReleaseDeformedDto, ShrinkageReport, StockAtQuery, StockAtTime, StockCountQuery,
+    StockCountSession, StockCountWithLines, SubmitCountsDto, UpdateAlertDto, WriteOffDeformedDto,
+};
+use crate::modules::inventory::domain::repositories::{
+    InventoryRepository, StockAdjustmentRepository,
+};
 use crate::shared::auth::{AppState, AuthUser, Role};
 use crate::shared::errors::AppError;
 
@@ -23,12 +30,43 @@ use crate::shared::errors::AppError;
         low_stock_handler,
         by_freezer_handler,
         worker_deformed_handler,
+        reassign_deformed_handler,
+        release_deformed_handler,
+        write_off_deformed_handler,
         update_alert_handler,
+        movements_handler,
+        stock_at_handler,
+        create_adjustment_handler,
+        list_adjustments_handler,
+        shrinkage_handler,
+        open_count_handler,
+        list_counts_handler,
+        get_count_handler,
+        submit_counts_handler,
+        commit_count_handler,
+        cancel_count_handler,
     ),
     components(schemas(
         crate::modules::inventory::domain::entities::InventoryItem,
         crate::modules::inventory::domain::entities::AddStockDto,
         crate::modules::inventory::domain::entities::UpdateAlertDto,
+        crate::modules::inventory::domain::entities::InventoryMovement,
+        crate::modules::inventory::domain::entities::StockAtTime,
+        crate::modules::inventory::domain::entities::AdjustmentReason,
+        crate::modules::inventory::domain::entities::InventoryAdjustment,
+        crate::modules::inventory::domain::entities::CreateAdjustmentDto,
+        crate::modules::inventory::domain::entities::StockCountSession,
+        crate::modules::inventory::domain::entities::StockCountLine,
+        crate::modules::inventory::domain::entities::StockCountWithLines,
+        crate::modules::inventory::domain::entities::OpenStockCountDto,
+        crate::modules::inventory::domain::entities::SubmitCountsDto,
+        crate::modules::inventory::domain::entities::CountedLineDto,
+        crate::modules::inventory::domain::entities::ShrinkageRow,
+        crate::modules::inventory::domain::entities::ShrinkageReport,
+        crate::modules::inventory::domain::entities::DeformedTransfer,
+        crate::modules::inventory::domain::entities::ReassignDeformedDto,
+        crate::modules::inventory::domain::entities::ReleaseDeformedDto,
+        crate::modules::inventory::domain::entities::WriteOffDeformedDto,
     ))
 )]
 pub struct InventoryApiDoc;
@@ -37,6 +75,7 @@ pub struct InventoryApiDoc;
 pub struct InventoryState {
     pub app: AppState,
     pub repo: Arc<dyn InventoryRepository>,
+    pub adjustments: Arc<dyn StockAdjustmentRepository>,
 }
 
 impl axum::extract::FromRef<InventoryState> for AppState {
@@ -45,10 +84,15 @@ impl axum::extract::FromRef<InventoryState> for AppState {
     }
 }
 
-pub fn router(app_state: AppState, repo: Arc<dyn InventoryRepository>) -> Router {
+pub fn router(
+    app_state: AppState,
+    repo: Arc<dyn InventoryRepository>,
+    adjustments: Arc<dyn StockAdjustmentRepository>,
+) -> Router {
     let state = InventoryState {
         app: app_state,
         repo,
+        adjustments,
     };
 
     Router::new()
@@ -57,6 +101,21 @@ pub fn router(app_state: AppState, repo: Arc<dyn InventoryRepository>) -> Router
         .route("/low-stock", get(low_stock_handler))
         .route("/freezer/{freezer_id}", get(by_freezer_handler))
         .route("/worker/{worker_id}/deformed", get(worker_deformed_handler))
+        .route("/deformed/{id}/reassign", post(reassign_deformed_handler))
+        .route("/deformed/{id}/release", post(release_deformed_handler))
+        .route("/deformed/{id}/write-off", post(write_off_deformed_handler))
+        .route("/movements", get(movements_handler))
+        .route("/stock-at", get(stock_at_handler))
+        .route(
+            "/adjustments",
+            get(list_adjustments_handler).post(create_adjustment_handler),
+        )
+        .route("/shrinkage", get(shrinkage_handler))
+        .route("/counts", get(list_counts_handler).post(open_count_handler))
+        .route("/counts/{id}", get(get_count_handler))
+        .route("/counts/{id}/lines", put(submit_counts_handler))
+        .route("/counts/{id}/commit", post(commit_count_handler))
+        .route("/counts/{id}/cancel", post(cancel_count_handler))
         .route("/{id}/alert", put(update_alert_handler))
         .with_state(state)
 }
@@ -134,6 +193,74 @@ async fn worker_deformed_handler(
     ))
 }
 
+#[utoipa::path(
+    post, path = "/deformed/{id}/reassign", tag = "Inventario",
+    params(("id" = Uuid, Path, description = "ID de la fila de deformados")),
+    request_body = ReassignDeformedDto,
+    responses(
+        (status = 200, description = "Deformados reasignados", body = DeformedTransfer),
+        (status = 400, description = "No es deformado, mismo trabajador o trabajador inactivo"),
+        (status = 409, description = "Cantidad mayor a la disponible")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn reassign_deformed_handler(
+    auth: AuthUser,
+    State(state): State<InventoryState>,
+    Path(id): Path<Uuid>,
+    Json(dto): Json<ReassignDeformedDto>,
+) -> Result<Json<DeformedTransfer>, AppError> {
+    auth.require_role(Role::Admin)?;
+    Ok(Json(
+        deformed_stock::reassign(&state.repo, id, dto, auth.user_id()).await?,
+    ))
+}
+
+#[utoipa::path(
+    post, path = "/deformed/{id}/release", tag = "Inventario",
+    params(("id" = Uuid, Path, description = "ID de la fila de deformados")),
+    request_body = ReleaseDeformedDto,
+    responses(
+        (status = 200, description = "Deformados liberados al stock general", body = DeformedTransfer),
+        (status = 409, description = "Cantidad mayor a la disponible")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn release_deformed_handler(
+    auth: AuthUser,
+    State(state): State<InventoryState>,
+    Path(id): Path<Uuid>,
+    Json(dto): Json<ReleaseDeformedDto>,
+) -> Result<Json<DeformedTransfer>, AppError> {
+    auth.require_role(Role::Admin)?;
+    Ok(Json(
+        deformed_stock::release(&state.repo, id, dto, auth.user_id()).await?,
+    ))
+}
+
+#[utoipa::path(
+    post, path = "/deformed/{id}/write-off", tag = "Inventario",
+    params(("id" = Uuid, Path, description = "ID de la fila de deformados")),
+    request_body = WriteOffDeformedDto,
+    responses(
+        (status = 200, description = "Baja registrada como ajuste", body = InventoryAdjustment),
+        (status = 400, description = "La fila no es de deformados")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn write_off_deformed_handler(
+    auth: AuthUser,
+    State(state): State<InventoryState>,
+    Path(id): Path<Uuid>,
+    Json(dto): Json<WriteOffDeformedDto>,
+) -> Result<Json<InventoryAdjustment>, AppError> {
+    auth.require_role(Role::Admin)?;
+    Ok(Json(
+        deformed_stock::write_off(&state.repo, &state.adjustments, id, dto, auth.user_id())
+            .await?,
+    ))
+}
+
 #[utoipa::path(
     post, path = "/", tag = "Inventario",
     request_body = AddStockDto,
@@ -166,6 +293,191 @@ async fn update_alert_handler(
 ) -> Result<Json<InventoryItem>, AppError> {
     auth.require_role(Role::Admin)?;
     Ok(Json(
-        manage_inventory::update_alert(&state.repo, id, dto.min_stock_alert).await?,
+        manage_inventory::update_alert(&state.repo, id, dto.min_stock_alert, auth.user_id()).await?,
+    ))
+}
+
+#[utoipa::path(
+    get, path = "/movements", tag = "Inventario",
+    params(MovementFilter),
+    responses((status = 200, description = "Kardex de movimientos", body = Vec<InventoryMovement>)),
+    security(("bearer_auth" = []))
+)]
+async fn movements_handler(
+    auth: AuthUser,
+    State(state): State<InventoryState>,
+    Query(filter): Query<MovementFilter>,
+) -> Result<Json<Vec<InventoryMovement>>, AppError> {
+    auth.require_role(Role::Admin)?;
+    Ok(Json(
+        manage_inventory::list_movements(&state.repo, filter).await?,
+    ))
+}
+
+#[utoipa::path(
+    get, path = "/stock-at", tag = "Inventario",
+    params(StockAtQuery),
+    responses((status = 200, description = "Stock reconstruido en la fecha", body = Vec<StockAtTime>)),
+    security(("bearer_auth" = []))
+)]
+async fn stock_at_handler(
+    auth: AuthUser,
+    State(state): State<InventoryState>,
+    Query(query): Query<StockAtQuery>,
+) -> Result<Json<Vec<StockAtTime>>, AppError> {
+    auth.require_role(Role::Admin)?;
+    Ok(Json(manage_inventory::stock_at(&state.repo, query).await?))
+}
+
+#[utoipa::path(
+    post, path = "/adjustments", tag = "Inventario",
+    request_body = CreateAdjustmentDto,
+    responses((status = 200, description = "Ajuste registrado", body = InventoryAdjustment)),
+    security(("bearer_auth" = []))
+)]
+async fn create_adjustment_handler(
+    auth: AuthUser,
+    State(state): State<InventoryState>,
+    Json(dto): Json<CreateAdjustmentDto>,
+) -> Result<Json<InventoryAdjustment>, AppError> {
+    auth.require_role(Role::Admin)?;
+    Ok(Json(
+        stock_adjustments::create_adjustment(&state.adjustments, dto, auth.user_id()).await?,
+    ))
+}
+
+#[utoipa::path(
+    get, path = "/adjustments", tag = "Inventario",
+    params(AdjustmentQuery),
+    responses((status = 200, description = "Ajustes de inventario", body = Vec<InventoryAdjustment>)),
+    security(("bearer_auth" = []))
+)]
+async fn list_adjustments_handler(
+    auth: AuthUser,
+    State(state): State<InventoryState>,
+    Query(query): Query<AdjustmentQuery>,
+) -> Result<Json<Vec<InventoryAdjustment>>, AppError> {
+    auth.require_role(Role::Admin)?;
+    Ok(Json(
+        stock_adjustments::list_adjustments(&state.adjustments, query).await?,
+    ))
+}
+
+#[utoipa::path(
+    get, path = "/shrinkage", tag = "Inventario",
+    params(AdjustmentQuery),
+    responses((status = 200, description = "Reporte de merma", body = ShrinkageReport)),
+    security(("bearer_auth" = []))
+)]
+async fn shrinkage_handler(
+    auth: AuthUser,
+    State(state): State<InventoryState>,
+    Query(query): Query<AdjustmentQuery>,
+) -> Result<Json<ShrinkageReport>, AppError> {
+    auth.require_role(Role::Admin)?;
+    Ok(Json(
+        stock_adjustments::shrinkage_report(&state.adjustments, query).await?,
+    ))
+}
+
+#[utoipa::path(
+    post, path = "/counts", tag = "Inventario",
+    request_body = OpenStockCountDto,
+    responses((status = 200, description = "Conteo abierto", body = StockCountWithLines)),
+    security(("bearer_auth" = []))
+)]
+async fn open_count_handler(
+    auth: AuthUser,
+    State(state): State<InventoryState>,
+    Json(dto): Json<OpenStockCountDto>,
+) -> Result<Json<StockCountWithLines>, AppError> {
+    auth.require_role(Role::Admin)?;
+    Ok(Json(
+        stock_adjustments::open_count(&state.adjustments, dto, auth.user_id()).await?,
+    ))
+}
+
+#[utoipa::path(
+    get, path = "/counts", tag = "Inventario",
+    params(StockCountQuery),
+    responses((status = 200, description = "Conteos físicos", body = Vec<StockCountSession>)),
+    security(("bearer_auth" = []))
+)]
+async fn list_counts_handler(
+    auth: AuthUser,
+    State(state): State<InventoryState>,
+    Query(query): Query<StockCountQuery>,
+) -> Result<Json<Vec<StockCountSession>>, AppError> {
+    auth.require_role(Role::Admin)?;
+    Ok(Json(
+        stock_adjustments::list_counts(&state.adjustments, query.freezer_id).await?,
+    ))
+}
+
+#[utoipa::path(
+    get, path = "/counts/{id}", tag = "Inventario",
+    params(("id" = Uuid, Path, description = "ID del conteo")),
+    responses((status = 200, description = "Conteo con sus líneas", body = StockCountWithLines)),
+    security(("bearer_auth" = []))
+)]
+async fn get_count_handler(
+    auth: AuthUser,
+    State(state): State<InventoryState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<StockCountWithLines>, AppError> {
+    auth.require_role(Role::Admin)?;
+    Ok(Json(stock_adjustments::get_count(&state.adjustments, id).await?))
+}
+
+#[utoipa::path(
+    put, path = "/counts/{id}/lines", tag = "Inventario",
+    params(("id" = Uuid, Path, description = "ID del conteo")),
+    request_body = SubmitCountsDto,
+    responses((status = 200, description = "Cantidades registradas", body = StockCountWithLines)),
+    security(("bearer_auth" = []))
+)]
+async fn submit_counts_handler(
+    auth: AuthUser,
+    State(state): State<InventoryState>,
+    Path(id): Path<Uuid>,
+    Json(dto): Json<SubmitCountsDto>,
+) -> Result<Json<StockCountWithLines>, AppError> {
+    auth.require_role(Role::Admin)?;
+    Ok(Json(
+        stock_adjustments::submit_counts(&state.adjustments, id, dto, auth.user_id()).await?,
+    ))
+}
+
+#[utoipa::path(
+    post, path = "/counts/{id}/commit", tag = "Inventario",
+    params(("id" = Uuid, Path, description = "ID del conteo")),
+    responses((status = 200, description = "Conteo confirmado y ajustes aplicados", body = StockCountWithLines)),
+    security(("bearer_auth" = []))
+)]
+async fn commit_count_handler(
+    auth: AuthUser,
+    State(state): State<InventoryState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<StockCountWithLines>, AppError> {
+    auth.require_role(Role::Admin)?;
+    Ok(Json(
+        stock_adjustments::commit_count(&state.adjustments, id, auth.user_id()).await?,
+    ))
+}
+
+#[utoipa::path(
+    post, path = "/counts/{id}/cancel", tag = "Inventario",
+    params(("id" = Uuid, Path, description = "ID del conteo")),
+    responses((status = 200, description = "Conteo cancelado", body = StockCountSession)),
+    security(("bearer_auth" = []))
+)]
+async fn cancel_count_handler(
+    auth: AuthUser,
+    State(state): State<InventoryState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<StockCountSession>, AppError> {
+    auth.require_role(Role::Admin)?;
+    Ok(Json(
+        stock_adjustments::cancel_count(&state.adjustments, id, auth.user_id()).await?,
     ))
 }
@@ -1 +1,2 @@
+pub mod movements;
 pub mod postgres_repo;
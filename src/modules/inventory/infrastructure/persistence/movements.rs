@@ -0,0 +1,250 @@
+use uuid::Uuid;
+
+use crate::modules::inventory::domain::entities::{MovementCause, MovementType};
+use crate::shared::errors::AppError;
+
+/// Registra en el kardex un cambio de stock ya aplicado a `inventory`.
+///
+/// Debe llamarse dentro de la misma transacción, después del UPDATE/UPSERT y
+/// antes de eliminar la fila (deformados en 0): la cantidad resultante y los
+/// datos de la fila se leen de `inventory`.
+pub async fn record_movement_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    inventory_id: Uuid,
+    delta: i32,
+    cause: MovementCause,
+    created_by: Uuid,
+) -> Result<(), AppError> {
+    let rows = sqlx::query(
+        r#"
+        INSERT INTO inventory_movements
+        (inventory_id, freezer_id, product_id, flavor_id, provider_id, is_deformed,
+         assigned_worker_id, delta, quantity_after, movement_type,
+         related_doc_type, related_doc_id, created_by)
+        SELECT id, freezer_id, product_id, flavor_id, provider_id, is_deformed,
+               assigned_worker_id, $2, quantity, $3, $4, $5, $6
+        FROM inventory
+        WHERE id = $1
+        "#,
+    )
+    .bind(inventory_id)
+    .bind(delta)
+    .bind(cause.movement_type.as_str())
+    .bind(cause.related_doc_type)
+    .bind(cause.related_doc_id)
+    .bind(created_by)
+    .execute(&mut **tx)
+    .await?
+    .rows_affected();
+
+    if rows == 0 {
+        return Err(AppError::NotFound(format!(
+            "Inventario {inventory_id} no encontrado al registrar movimiento"
+        )));
+    }
+    Ok(())
+}
+
+/// Bloquea (`FOR UPDATE`) la fila de inventario de la que sale una línea y
+/// verifica que la línea describa esa fila: producto, sabor, congelador y,
+/// si el canal lo registra, la condición de deformado. Debe llamarse en la
+/// transacción del documento, antes de insertar la línea.
+pub async fn lock_stock_line_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    inventory_id: Uuid,
+    product_id: Uuid,
+    flavor_id: Uuid,
+    freezer_id: Uuid,
+    is_deformed: Option<bool>,
+) -> Result<(), AppError> {
+    let (row_product, row_flavor, row_freezer, row_deformed) =
+        sqlx::query_as::<_, (Uuid, Uuid, Uuid, bool)>(
+            "SELECT product_id, flavor_id, freezer_id, is_deformed FROM inventory WHERE id = $1 FOR UPDATE",
+        )
+        .bind(inventory_id)
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Inventario {inventory_id} no encontrado")))?;
+
+    if row_product != product_id
+        || row_flavor != flavor_id
+        || row_freezer != freezer_id
+        || is_deformed.is_some_and(|d| d != row_deformed)
+    {
+        return Err(AppError::BadRequest(format!(
+            "La línea no coincide con el inventario {inventory_id} (producto, sabor, congelador o deformado)"
+        )));
+    }
+    Ok(())
+}
+
+/// Devuelve el stock de un documento anulado a las filas que afectó.
+///
+/// Suma los movimientos del documento por fila y aplica el delta opuesto,
+/// registrado como `void` con el mismo documento. Falla si el stock a
+/// retirar ya no está (p. ej. lo comprado ya se vendió), y con `Conflict` si
+/// el documento tiene items pero no movimientos (anterior al kardex): no se
+/// sabe qué filas tocó y anularlo dejaría el stock sin devolver.
+pub async fn reverse_doc_movements_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    doc_type: &'static str,
+    doc_id: Uuid,
+    created_by: Uuid,
+) -> Result<(), AppError> {
+    if let Some(items_sql) = doc_items_exist_sql(doc_type) {
+        let (has_items, has_movements) = sqlx::query_as::<_, (bool, bool)>(&format!(
+            r#"
+            SELECT {items_sql},
+                   EXISTS (SELECT 1 FROM inventory_movements
+                           WHERE related_doc_type = $2 AND related_doc_id = $1)
+            "#
+        ))
+        .bind(doc_id)
+        .bind(doc_type)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        if has_items && !has_movements {
+            return Err(AppError::Conflict(
+                "El documento es anterior al kardex y no tiene movimientos de inventario; no se puede anular".into(),
+            ));
+        }
+    }
+
+    reverse_doc_movement_types_tx(tx, doc_type, doc_id, &[], MovementType::Void, created_by).await
+}
+
+/// `EXISTS` de las líneas de items del documento (`$1` = id).
+fn doc_items_exist_sql(doc_type: &str) -> Option<&'static str> {
+    Some(match doc_type {
+        "purchases" => "EXISTS (SELECT 1 FROM purchase_items WHERE purchase_id = $1)",
+        "worker_trips" => "EXISTS (SELECT 1 FROM worker_trip_loaded_items WHERE trip_id = $1)",
+        "local_sales" => "EXISTS (SELECT 1 FROM local_sale_items WHERE sale_id = $1)",
+        "owner_sales" => "EXISTS (SELECT 1 FROM owner_sale_loaded_items WHERE sale_id = $1)",
+        "freezer_transfers" => {
+            "EXISTS (SELECT 1 FROM freezer_transfer_items WHERE transfer_id = $1)"
+        }
+        _ => return None,
+    })
+}
+
+/// Como [`reverse_doc_movements_tx`], pero solo para los movimientos del
+/// documento de los tipos indicados (todos si `types` está vacío). El
+/// contramovimiento se registra como `recorded_as`; si ese tipo también está
+/// en `types`, una segunda reversa parte del neto ya corregido.
+pub async fn reverse_doc_movement_types_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    doc_type: &'static str,
+    doc_id: Uuid,
+    types: &[MovementType],
+    recorded_as: MovementType,
+    created_by: Uuid,
+) -> Result<(), AppError> {
+    let types: Vec<&str> = types.iter().map(MovementType::as_str).collect();
+    let net = sqlx::query_as::<_, (Uuid, i64)>(
+        r#"
+        SELECT inventory_id, SUM(delta)
+        FROM inventory_movements
+        WHERE related_doc_type = $1 AND related_doc_id = $2
+          AND (cardinality($3::text[]) = 0 OR movement_type = ANY($3))
+        GROUP BY inventory_id
+        HAVING SUM(delta) <> 0
+        ORDER BY inventory_id
+        "#,
+    )
+    .bind(doc_type)
+    .bind(doc_id)
+    .bind(&types)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    for (inventory_id, delta) in net {
+        let reverse = i32::try_from(-delta)
+            .map_err(|_| AppError::Internal(format!("Delta fuera de rango en {inventory_id}")))?;
+
+        let rows = sqlx::query(
+            "UPDATE inventory SET quantity = quantity + $1, last_updated = NOW(), updated_by = $2 WHERE id = $3 AND quantity + $1 >= 0",
+        )
+        .bind(reverse)
+        .bind(created_by)
+        .bind(inventory_id)
+        .execute(&mut **tx)
+        .await?
+        .rows_affected();
+
+        let mut target_id = inventory_id;
+        if rows == 0 {
+            let exists = sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS(SELECT 1 FROM inventory WHERE id = $1)",
+            )
+            .bind(inventory_id)
+            .fetch_one(&mut **tx)
+            .await?;
+            if exists {
+                return Err(AppError::InsufficientStock(inventory_id));
+            }
+            if reverse < 0 {
+                return Err(AppError::Conflict(format!(
+                    "La fila de inventario {inventory_id} ya no existe; no se puede revertir"
+                )));
+            }
+            target_id = restore_row_tx(tx, inventory_id, reverse, created_by).await?;
+        }
+
+        record_movement_tx(
+            tx,
+            target_id,
+            reverse,
+            MovementCause::doc(recorded_as, doc_type, doc_id),
+            created_by,
+        )
+        .await?;
+
+        // Deformados en 0 se eliminan, como en el resto del inventario
+        sqlx::query("DELETE FROM inventory WHERE id = $1 AND quantity = 0 AND is_deformed = TRUE")
+            .bind(target_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Vuelve a crear (UPSERT) una fila eliminada al llegar a 0, como los
+/// deformados que una salida cargó completos, con los datos que guardó el
+/// kardex. Devuelve el id de la fila resultante.
+async fn restore_row_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    deleted_id: Uuid,
+    quantity: i32,
+    created_by: Uuid,
+) -> Result<Uuid, AppError> {
+    sqlx::query_scalar::<_, Uuid>(
+        r#"
+        INSERT INTO inventory
+        (freezer_id, product_id, flavor_id, provider_id, quantity,
+         is_deformed, assigned_worker_id, min_stock_alert, updated_by)
+        SELECT freezer_id, product_id, flavor_id, provider_id, $2,
+               is_deformed, assigned_worker_id, CASE WHEN is_deformed THEN 0 ELSE 20 END, $3
+        FROM inventory_movements
+        WHERE inventory_id = $1
+        ORDER BY seq DESC
+        LIMIT 1
+        ON CONFLICT (freezer_id, product_id, flavor_id, provider_id, is_deformed, assigned_worker_id)
+        DO UPDATE SET
+            quantity = inventory.quantity + EXCLUDED.quantity,
+            last_updated = NOW(),
+            updated_by = EXCLUDED.updated_by
+        RETURNING id
+        "#,
+    )
+    .bind(deleted_id)
+    .bind(quantity)
+    .bind(created_by)
+    .fetch_optional(&mut **tx)
+    .await?
+    .ok_or_else(|| {
+        AppError::Conflict(format!(
+            "La fila de inventario {deleted_id} ya no existe; no se puede revertir"
+        ))
+    })
+}
@@ -1,18 +1,27 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use sqlx::PgPool;
+use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::modules::inventory::domain::entities::InventoryItem;
-use crate::modules::inventory::domain::repositories::InventoryRepository;
+use crate::modules::audit_log::domain::entities::CreateAuditLogDto;
+use crate::modules::audit_log::domain::repositories::AuditLogRepository;
+use crate::modules::inventory::domain::entities::*;
+use crate::modules::inventory::domain::repositories::{
+    InventoryRepository, StockAdjustmentRepository,
+};
+use crate::modules::inventory::infrastructure::persistence::movements::record_movement_tx;
 use crate::shared::errors::AppError;
 
 pub struct PgInventoryRepository {
     pool: PgPool,
+    audit: Arc<dyn AuditLogRepository>,
 }
 
 impl PgInventoryRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, audit: Arc<dyn AuditLogRepository>) -> Self {
+        Self { pool, audit }
     }
 }
 
@@ -78,7 +87,24 @@ impl InventoryRepository for PgInventoryRepository {
         quantity: i32,
         updated_by: Uuid,
     ) -> Result<InventoryItem, AppError> {
-        Ok(sqlx::query_as::<_, InventoryItem>(
+        let mut tx = self.pool.begin().await?;
+
+        let before = sqlx::query_as::<_, InventoryItem>(
+            r#"
+            SELECT * FROM inventory
+            WHERE freezer_id = $1 AND product_id = $2 AND flavor_id = $3 AND provider_id = $4
+              AND is_deformed = FALSE AND assigned_worker_id IS NULL
+            FOR UPDATE
+            "#,
+        )
+        .bind(freezer_id)
+        .bind(product_id)
+        .bind(flavor_id)
+        .bind(provider_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let item = sqlx::query_as::<_, InventoryItem>(
             r#"
             INSERT INTO inventory 
             (freezer_id, product_id, flavor_id, provider_id, quantity, is_deformed, min_stock_alert, updated_by)
@@ -97,8 +123,27 @@ impl InventoryRepository for PgInventoryRepository {
         .bind(provider_id)
         .bind(quantity)
         .bind(updated_by)
-        .fetch_one(&self.pool)
-        .await?)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_movement_tx(
+            &mut tx,
+            item.id,
+            quantity,
+            MovementCause::manual(MovementType::ManualAdd),
+            updated_by,
+        )
+        .await?;
+
+        let entry = match &before {
+            Some(before) => {
+                CreateAuditLogDto::updated("inventory", item.id, before, &item, updated_by)?
+            }
+            None => CreateAuditLogDto::created("inventory", item.id, &item, updated_by)?,
+        };
+        self.audit.create_with_tx(&mut tx, entry).await?;
+        tx.commit().await?;
+        Ok(item)
     }
 
     async fn subtract_stock_tx(
@@ -106,6 +151,7 @@ impl InventoryRepository for PgInventoryRepository {
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
         inventory_id: Uuid,
         quantity: i32,
+        cause: MovementCause,
         updated_by: Uuid,
     ) -> Result<(), AppError> {
         let rows = sqlx::query(
@@ -126,6 +172,8 @@ impl InventoryRepository for PgInventoryRepository {
             return Err(AppError::InsufficientStock(inventory_id));
         }
 
+        record_movement_tx(tx, inventory_id, -quantity, cause, updated_by).await?;
+
         // Eliminar registro si quedó en 0 y es deformado
         sqlx::query("DELETE FROM inventory WHERE id = $1 AND quantity = 0 AND is_deformed = TRUE")
             .bind(inventory_id)
@@ -144,14 +192,20 @@ impl InventoryRepository for PgInventoryRepository {
         provider_id: Uuid,
         quantity: i32,
         worker_id: Uuid,
+        cause: MovementCause,
         updated_by: Uuid,
     ) -> Result<InventoryItem, AppError> {
-        Ok(sqlx::query_as::<_, InventoryItem>(
+        let item = sqlx::query_as::<_, InventoryItem>(
             r#"
             INSERT INTO inventory 
             (freezer_id, product_id, flavor_id, provider_id, quantity, 
              is_deformed, assigned_worker_id, min_stock_alert, updated_by)
             VALUES ($1, $2, $3, $4, $5, TRUE, $6, 0, $7)
+            ON CONFLICT (freezer_id, product_id, flavor_id, provider_id, is_deformed, assigned_worker_id)
+            DO UPDATE SET 
+                quantity = inventory.quantity + EXCLUDED.quantity,
+                last_updated = NOW(),
+                updated_by = EXCLUDED.updated_by
             RETURNING *
             "#,
         )
@@ -163,7 +217,10 @@ impl InventoryRepository for PgInventoryRepository {
         .bind(worker_id)
         .bind(updated_by)
         .fetch_one(&mut **tx)
-        .await?)
+        .await?;
+
+        record_movement_tx(tx, item.id, quantity, cause, updated_by).await?;
+        Ok(item)
     }
 
     async fn return_stock_tx(
@@ -174,9 +231,10 @@ impl InventoryRepository for PgInventoryRepository {
         flavor_id: Uuid,
         provider_id: Uuid,
         quantity: i32,
+        cause: MovementCause,
         updated_by: Uuid,
     ) -> Result<(), AppError> {
-        sqlx::query(
+        let inventory_id = sqlx::query_scalar::<_, Uuid>(
             r#"
             INSERT INTO inventory 
             (freezer_id, product_id, flavor_id, provider_id, quantity, is_deformed, min_stock_alert, updated_by)
@@ -186,6 +244,7 @@ impl InventoryRepository for PgInventoryRepository {
                 quantity = inventory.quantity + EXCLUDED.quantity,
                 last_updated = NOW(),
                 updated_by = EXCLUDED.updated_by
+            RETURNING id
             "#,
         )
         .bind(freezer_id)
@@ -194,18 +253,760 @@ impl InventoryRepository for PgInventoryRepository {
         .bind(provider_id)
         .bind(quantity)
         .bind(updated_by)
-        .execute(&mut **tx)
+        .fetch_one(&mut **tx)
         .await?;
+
+        record_movement_tx(tx, inventory_id, quantity, cause, updated_by).await?;
         Ok(())
     }
 
-    async fn update_alert(&self, id: Uuid, min_stock: i32) -> Result<InventoryItem, AppError> {
-        Ok(sqlx::query_as::<_, InventoryItem>(
+    async fn transfer_deformed(
+        &self,
+        inventory_id: Uuid,
+        to_worker_id: Option<Uuid>,
+        quantity: Option<i32>,
+        notes: Option<&str>,
+        created_by: Uuid,
+    ) -> Result<DeformedTransfer, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // 1. Fila origen (con lock)
+        let source =
+            sqlx::query_as::<_, InventoryItem>("SELECT * FROM inventory WHERE id = $1 FOR UPDATE")
+                .bind(inventory_id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or_else(|| {
+                    AppError::NotFound(format!("Inventario {inventory_id} no encontrado"))
+                })?;
+
+        if !source.is_deformed {
+            return Err(AppError::BadRequest(
+                "Solo se pueden reasignar o liberar deformados".into(),
+            ));
+        }
+        if source.assigned_worker_id == to_worker_id {
+            return Err(AppError::BadRequest(
+                "El destino es el mismo que el origen".into(),
+            ));
+        }
+        let quantity = quantity.unwrap_or(source.quantity);
+        if quantity <= 0 {
+            return Err(AppError::BadRequest("La cantidad debe ser positiva".into()));
+        }
+        if quantity > source.quantity {
+            return Err(AppError::InsufficientStock(inventory_id));
+        }
+        if let Some(worker_id) = to_worker_id {
+            let active = sqlx::query_scalar::<_, bool>("SELECT active FROM workers WHERE id = $1")
+                .bind(worker_id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or_else(|| AppError::NotFound("Trabajador no encontrado".into()))?;
+            if !active {
+                return Err(AppError::BadRequest(
+                    "No se pueden asignar deformados a un trabajador inactivo".into(),
+                ));
+            }
+        }
+
+        // 2. Restar del origen y acumular en el destino
+        sqlx::query(
+            "UPDATE inventory SET quantity = quantity - $1, last_updated = NOW(), updated_by = $2 WHERE id = $3",
+        )
+        .bind(quantity)
+        .bind(created_by)
+        .bind(inventory_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let target_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO inventory
+            (freezer_id, product_id, flavor_id, provider_id, quantity,
+             is_deformed, assigned_worker_id, min_stock_alert, updated_by)
+            VALUES ($1, $2, $3, $4, $5, TRUE, $6, 0, $7)
+            ON CONFLICT (freezer_id, product_id, flavor_id, provider_id, is_deformed, assigned_worker_id)
+            DO UPDATE SET
+                quantity = inventory.quantity + EXCLUDED.quantity,
+                last_updated = NOW(),
+                updated_by = EXCLUDED.updated_by
+            RETURNING id
+            "#,
+        )
+        .bind(source.freezer_id)
+        .bind(source.product_id)
+        .bind(source.flavor_id)
+        .bind(source.provider_id)
+        .bind(quantity)
+        .bind(to_worker_id)
+        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // 3. Documento y kardex de ambos lados
+        let transfer = sqlx::query_as::<_, DeformedTransfer>(
+            r#"
+            INSERT INTO deformed_transfers
+            (from_inventory_id, to_inventory_id, freezer_id, product_id, flavor_id,
+             from_worker_id, to_worker_id, quantity, notes, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING *
+            "#,
+        )
+        .bind(inventory_id)
+        .bind(target_id)
+        .bind(source.freezer_id)
+        .bind(source.product_id)
+        .bind(source.flavor_id)
+        .bind(source.assigned_worker_id)
+        .bind(to_worker_id)
+        .bind(quantity)
+        .bind(notes)
+        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let cause = MovementCause::doc(
+            MovementType::DeformedTransfer,
+            "deformed_transfers",
+            transfer.id,
+        );
+        record_movement_tx(&mut tx, inventory_id, -quantity, cause, created_by).await?;
+        record_movement_tx(&mut tx, target_id, quantity, cause, created_by).await?;
+
+        sqlx::query("DELETE FROM inventory WHERE id = $1 AND quantity = 0 AND is_deformed = TRUE")
+            .bind(inventory_id)
+            .execute(&mut *tx)
+            .await?;
+
+        // 4. Auditoría
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::created(
+                    "deformed_transfers",
+                    transfer.id,
+                    &transfer,
+                    created_by,
+                )?,
+            )
+            .await?;
+
+        tx.commit().await?;
+        Ok(transfer)
+    }
+
+    async fn update_alert(
+        &self,
+        id: Uuid,
+        min_stock: i32,
+        updated_by: Uuid,
+    ) -> Result<InventoryItem, AppError> {
+        let mut tx = self.pool.begin().await?;
+        let before =
+            sqlx::query_as::<_, InventoryItem>("SELECT * FROM inventory WHERE id = $1 FOR UPDATE")
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or_else(|| AppError::NotFound("Item de inventario no encontrado".into()))?;
+
+        let item = sqlx::query_as::<_, InventoryItem>(
             "UPDATE inventory SET min_stock_alert = $1 WHERE id = $2 RETURNING *",
         )
         .bind(min_stock)
         .bind(id)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::updated("inventory", id, &before, &item, updated_by)?,
+            )
+            .await?;
+        tx.commit().await?;
+        Ok(item)
+    }
+
+    async fn find_movements(
+        &self,
+        filter: &MovementFilter,
+    ) -> Result<Vec<InventoryMovement>, AppError> {
+        Ok(sqlx::query_as::<_, InventoryMovement>(
+            r#"
+            SELECT * FROM inventory_movements
+            WHERE ($1::uuid IS NULL OR inventory_id = $1)
+              AND ($2::uuid IS NULL OR freezer_id = $2)
+              AND ($3::uuid IS NULL OR product_id = $3)
+              AND ($4::timestamptz IS NULL OR created_at >= $4)
+              AND ($5::timestamptz IS NULL OR created_at <= $5)
+            ORDER BY seq DESC
+            LIMIT $6
+            "#,
+        )
+        .bind(filter.inventory_id)
+        .bind(filter.freezer_id)
+        .bind(filter.product_id)
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(filter.limit.unwrap_or(200))
+        .fetch_all(&self.pool)
         .await?)
     }
+
+    async fn stock_at(
+        &self,
+        at: DateTime<Utc>,
+        freezer_id: Option<Uuid>,
+        product_id: Option<Uuid>,
+    ) -> Result<Vec<StockAtTime>, AppError> {
+        // Último movimiento de cada fila hasta la fecha → cantidad en ese momento
+        Ok(sqlx::query_as::<_, StockAtTime>(
+            r#"
+            SELECT * FROM (
+                SELECT DISTINCT ON (inventory_id)
+                    inventory_id, freezer_id, product_id, flavor_id, provider_id,
+                    is_deformed, assigned_worker_id,
+                    quantity_after AS quantity, created_at AS last_movement_at
+                FROM inventory_movements
+                WHERE created_at <= $1
+                  AND ($2::uuid IS NULL OR freezer_id = $2)
+                  AND ($3::uuid IS NULL OR product_id = $3)
+                ORDER BY inventory_id, seq DESC
+            ) s
+            WHERE quantity > 0
+            ORDER BY freezer_id, product_id, flavor_id
+            "#,
+        )
+        .bind(at)
+        .bind(freezer_id)
+        .bind(product_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+}
+
+// ═══════════════════════════════════════════════════════════
+// Conteos físicos y ajustes
+// ═══════════════════════════════════════════════════════════
+
+pub struct PgStockAdjustmentRepository {
+    pool: PgPool,
+    audit: Arc<dyn AuditLogRepository>,
+}
+
+impl PgStockAdjustmentRepository {
+    pub fn new(pool: PgPool, audit: Arc<dyn AuditLogRepository>) -> Self {
+        Self { pool, audit }
+    }
+}
+
+const COUNT_LINES_SQL: &str = r#"
+    SELECT l.id, l.session_id, l.inventory_id, l.product_id, l.flavor_id, l.provider_id,
+           l.is_deformed, l.expected_quantity, l.counted_quantity, l.reason,
+           COALESCE(i.quantity, 0) AS system_quantity,
+           l.counted_quantity - l.expected_quantity AS variance
+    FROM stock_count_lines l
+    LEFT JOIN inventory i ON i.id = l.inventory_id
+    WHERE l.session_id = $1
+    ORDER BY l.product_id, l.flavor_id
+"#;
+
+/// Ajuste dentro de una transacción abierta.
+struct NewAdjustment<'a> {
+    inventory_id: Uuid,
+    quantity_delta: i32,
+    reason: &'a str,
+    notes: Option<&'a str>,
+    count_session_id: Option<Uuid>,
+    created_by: Uuid,
+}
+
+/// Aplica el ajuste al inventario, lo valoriza a `cost_price` vigente,
+/// lo registra en el kardex y en auditoría.
+async fn apply_adjustment_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    audit: &dyn AuditLogRepository,
+    a: NewAdjustment<'_>,
+) -> Result<InventoryAdjustment, AppError> {
+    let item =
+        sqlx::query_as::<_, InventoryItem>("SELECT * FROM inventory WHERE id = $1 FOR UPDATE")
+            .bind(a.inventory_id)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Inventario {} no encontrado", a.inventory_id))
+            })?;
+
+    if item.quantity + a.quantity_delta < 0 {
+        return Err(AppError::InsufficientStock(a.inventory_id));
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE inventory
+        SET quantity = quantity + $1, last_updated = NOW(), updated_by = $2
+        WHERE id = $3
+        "#,
+    )
+    .bind(a.quantity_delta)
+    .bind(a.created_by)
+    .bind(a.inventory_id)
+    .execute(&mut **tx)
+    .await?;
+
+    // Costo vigente; sin precio registrado el ajuste queda sin valorizar
+    let unit_cost = sqlx::query_scalar::<_, Decimal>(
+        r#"
+        SELECT cost_price FROM price_history
+        WHERE product_id = $1 AND flavor_id = $2 AND provider_id = $3
+          AND effective_date <= NOW()
+        ORDER BY effective_date DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(item.product_id)
+    .bind(item.flavor_id)
+    .bind(item.provider_id)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    let adjustment = sqlx::query_as::<_, InventoryAdjustment>(
+        r#"
+        INSERT INTO inventory_adjustments
+        (inventory_id, freezer_id, product_id, flavor_id, provider_id, quantity_delta,
+         reason, unit_cost, total_cost, notes, count_session_id, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        RETURNING *
+        "#,
+    )
+    .bind(item.id)
+    .bind(item.freezer_id)
+    .bind(item.product_id)
+    .bind(item.flavor_id)
+    .bind(item.provider_id)
+    .bind(a.quantity_delta)
+    .bind(a.reason)
+    .bind(unit_cost)
+    .bind(unit_cost.map(|cost| cost * Decimal::from(a.quantity_delta)))
+    .bind(a.notes)
+    .bind(a.count_session_id)
+    .bind(a.created_by)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    record_movement_tx(
+        tx,
+        item.id,
+        a.quantity_delta,
+        MovementCause::doc(
+            MovementType::Adjustment,
+            "inventory_adjustments",
+            adjustment.id,
+        ),
+        a.created_by,
+    )
+    .await?;
+
+    sqlx::query("DELETE FROM inventory WHERE id = $1 AND quantity = 0 AND is_deformed = TRUE")
+        .bind(item.id)
+        .execute(&mut **tx)
+        .await?;
+
+    audit
+        .create_with_tx(
+            tx,
+            CreateAuditLogDto::created(
+                "inventory_adjustments",
+                adjustment.id,
+                &adjustment,
+                a.created_by,
+            )?,
+        )
+        .await?;
+
+    Ok(adjustment)
+}
+
+impl PgStockAdjustmentRepository {
+    async fn load_count<'e, E>(
+        executor: E,
+        session: StockCountSession,
+    ) -> Result<StockCountWithLines, AppError>
+    where
+        E: sqlx::PgExecutor<'e>,
+    {
+        let lines = sqlx::query_as::<_, StockCountLine>(COUNT_LINES_SQL)
+            .bind(session.id)
+            .fetch_all(executor)
+            .await?;
+        Ok(StockCountWithLines { session, lines })
+    }
+}
+
+/// Bloquea un conteo y verifica que siga abierto.
+async fn lock_open_count(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    session_id: Uuid,
+) -> Result<StockCountSession, AppError> {
+    let session = sqlx::query_as::<_, StockCountSession>(
+        "SELECT * FROM stock_count_sessions WHERE id = $1 FOR UPDATE",
+    )
+    .bind(session_id)
+    .fetch_optional(&mut **tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Conteo no encontrado".into()))?;
+
+    if session.status != "open" {
+        return Err(AppError::Conflict(format!(
+            "El conteo ya está cerrado ({})",
+            session.status
+        )));
+    }
+    Ok(session)
+}
+
+#[async_trait]
+impl StockAdjustmentRepository for PgStockAdjustmentRepository {
+    async fn create_adjustment(
+        &self,
+        dto: &CreateAdjustmentDto,
+        created_by: Uuid,
+    ) -> Result<InventoryAdjustment, AppError> {
+        let mut tx = self.pool.begin().await?;
+        let adjustment = apply_adjustment_tx(
+            &mut tx,
+            self.audit.as_ref(),
+            NewAdjustment {
+                inventory_id: dto.inventory_id,
+                quantity_delta: dto.quantity_delta,
+                reason: dto.reason.as_str(),
+                notes: dto.notes.as_deref(),
+                count_session_id: None,
+                created_by,
+            },
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(adjustment)
+    }
+
+    async fn find_adjustments(
+        &self,
+        query: &AdjustmentQuery,
+    ) -> Result<Vec<InventoryAdjustment>, AppError> {
+        Ok(sqlx::query_as::<_, InventoryAdjustment>(
+            r#"
+            SELECT * FROM inventory_adjustments
+            WHERE ($1::timestamptz IS NULL OR created_at >= $1)
+              AND ($2::timestamptz IS NULL OR created_at <= $2)
+              AND ($3::uuid IS NULL OR freezer_id = $3)
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(query.from)
+        .bind(query.to)
+        .bind(query.freezer_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn shrinkage(&self, query: &AdjustmentQuery) -> Result<Vec<ShrinkageRow>, AppError> {
+        Ok(sqlx::query_as::<_, ShrinkageRow>(
+            r#"
+            SELECT reason, product_id,
+                   COALESCE(SUM(-quantity_delta) FILTER (WHERE quantity_delta < 0), 0)::BIGINT AS units_lost,
+                   COALESCE(SUM(-total_cost) FILTER (WHERE quantity_delta < 0), 0) AS value_lost,
+                   COALESCE(SUM(quantity_delta) FILTER (WHERE quantity_delta > 0), 0)::BIGINT AS units_found,
+                   COALESCE(SUM(total_cost) FILTER (WHERE quantity_delta > 0), 0) AS value_found,
+                   COALESCE(SUM(ABS(quantity_delta)) FILTER (WHERE total_cost IS NULL), 0)::BIGINT
+                       AS uncosted_units
+            FROM inventory_adjustments
+            WHERE ($1::timestamptz IS NULL OR created_at >= $1)
+              AND ($2::timestamptz IS NULL OR created_at <= $2)
+              AND ($3::uuid IS NULL OR freezer_id = $3)
+            GROUP BY reason, product_id
+            ORDER BY value_lost DESC
+            "#,
+        )
+        .bind(query.from)
+        .bind(query.to)
+        .bind(query.freezer_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn open_count(
+        &self,
+        dto: &OpenStockCountDto,
+        opened_by: Uuid,
+    ) -> Result<StockCountWithLines, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let already_open = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM stock_count_sessions WHERE freezer_id = $1 AND status = 'open')",
+        )
+        .bind(dto.freezer_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if already_open {
+            return Err(AppError::Conflict(
+                "Ya hay un conteo abierto para este congelador".into(),
+            ));
+        }
+
+        let session = sqlx::query_as::<_, StockCountSession>(
+            r#"
+            INSERT INTO stock_count_sessions (freezer_id, notes, opened_by)
+            VALUES ($1, $2, $3)
+            RETURNING *
+            "#,
+        )
+        .bind(dto.freezer_id)
+        .bind(&dto.notes)
+        .bind(opened_by)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // Foto del inventario del congelador
+        sqlx::query(
+            r#"
+            INSERT INTO stock_count_lines
+            (session_id, inventory_id, product_id, flavor_id, provider_id, is_deformed, expected_quantity)
+            SELECT $1, id, product_id, flavor_id, provider_id, is_deformed, quantity
+            FROM inventory
+            WHERE freezer_id = $2
+            "#,
+        )
+        .bind(session.id)
+        .bind(dto.freezer_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let count = Self::load_count(&mut *tx, session).await?;
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::created(
+                    "stock_count_sessions",
+                    count.session.id,
+                    &count,
+                    opened_by,
+                )?,
+            )
+            .await?;
+        tx.commit().await?;
+        Ok(count)
+    }
+
+    async fn find_count(&self, id: Uuid) -> Result<Option<StockCountWithLines>, AppError> {
+        let session = sqlx::query_as::<_, StockCountSession>(
+            "SELECT * FROM stock_count_sessions WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match session {
+            Some(s) => Ok(Some(Self::load_count(&self.pool, s).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn find_counts(
+        &self,
+        freezer_id: Option<Uuid>,
+    ) -> Result<Vec<StockCountSession>, AppError> {
+        Ok(sqlx::query_as::<_, StockCountSession>(
+            r#"
+            SELECT * FROM stock_count_sessions
+            WHERE ($1::uuid IS NULL OR freezer_id = $1)
+            ORDER BY opened_at DESC
+            "#,
+        )
+        .bind(freezer_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn submit_counts(
+        &self,
+        session_id: Uuid,
+        dto: &SubmitCountsDto,
+        submitted_by: Uuid,
+    ) -> Result<StockCountWithLines, AppError> {
+        let mut tx = self.pool.begin().await?;
+        let session = lock_open_count(&mut tx, session_id).await?;
+        let before = Self::load_count(&mut *tx, session.clone()).await?;
+
+        for line in &dto.lines {
+            let rows = sqlx::query(
+                r#"
+                UPDATE stock_count_lines
+                SET counted_quantity = $1, reason = $2
+                WHERE session_id = $3 AND inventory_id = $4
+                "#,
+            )
+            .bind(line.counted_quantity)
+            .bind(line.reason.map(|r| r.as_str()))
+            .bind(session_id)
+            .bind(line.inventory_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+            if rows == 0 {
+                return Err(AppError::BadRequest(format!(
+                    "El inventario {} no pertenece a este conteo",
+                    line.inventory_id
+                )));
+            }
+        }
+
+        let count = Self::load_count(&mut *tx, session).await?;
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::updated(
+                    "stock_count_sessions",
+                    session_id,
+                    &before,
+                    &count,
+                    submitted_by,
+                )?,
+            )
+            .await?;
+        tx.commit().await?;
+        Ok(count)
+    }
+
+    async fn commit_count(
+        &self,
+        session_id: Uuid,
+        committed_by: Uuid,
+    ) -> Result<StockCountWithLines, AppError> {
+        let mut tx = self.pool.begin().await?;
+        let before = lock_open_count(&mut tx, session_id).await?;
+
+        let lines = sqlx::query_as::<_, StockCountLine>(COUNT_LINES_SQL)
+            .bind(session_id)
+            .fetch_all(&mut *tx)
+            .await?;
+
+        if lines.iter().any(|l| l.counted_quantity.is_none()) {
+            return Err(AppError::BadRequest(
+                "Todas las líneas deben estar contadas antes de confirmar".into(),
+            ));
+        }
+
+        // La diferencia es contra lo esperado al abrir: las ventas, cargas y
+        // devoluciones hechas mientras el conteo estaba abierto ya movieron el
+        // stock y no deben revertirse con el ajuste.
+        for line in &lines {
+            let counted = line.counted_quantity.unwrap_or_default();
+            let delta = counted - line.expected_quantity;
+            if delta == 0 {
+                continue;
+            }
+            // Una fila de deformados borrada al quedar en 0 ya no tiene qué ajustar
+            let exists = sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS(SELECT 1 FROM inventory WHERE id = $1)",
+            )
+            .bind(line.inventory_id)
+            .fetch_one(&mut *tx)
+            .await?;
+            match (exists, counted) {
+                (true, _) => {}
+                (false, 0) => continue,
+                (false, _) => {
+                    return Err(AppError::Conflict(format!(
+                        "El inventario {} ya no existe; no se puede ajustar",
+                        line.inventory_id
+                    )))
+                }
+            }
+
+            apply_adjustment_tx(
+                &mut tx,
+                self.audit.as_ref(),
+                NewAdjustment {
+                    inventory_id: line.inventory_id,
+                    quantity_delta: delta,
+                    reason: line.reason.as_deref().unwrap_or("counting_error"),
+                    notes: Some("Ajuste por conteo físico"),
+                    count_session_id: Some(session_id),
+                    created_by: committed_by,
+                },
+            )
+            .await?;
+        }
+
+        let session = sqlx::query_as::<_, StockCountSession>(
+            r#"
+            UPDATE stock_count_sessions
+            SET status = 'committed', closed_at = NOW(), closed_by = $1
+            WHERE id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(committed_by)
+        .bind(session_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::updated(
+                    "stock_count_sessions",
+                    session_id,
+                    &before,
+                    &session,
+                    committed_by,
+                )?,
+            )
+            .await?;
+
+        let count = Self::load_count(&mut *tx, session).await?;
+        tx.commit().await?;
+        Ok(count)
+    }
+
+    async fn cancel_count(
+        &self,
+        session_id: Uuid,
+        cancelled_by: Uuid,
+    ) -> Result<StockCountSession, AppError> {
+        let mut tx = self.pool.begin().await?;
+        let before = lock_open_count(&mut tx, session_id).await?;
+
+        let session = sqlx::query_as::<_, StockCountSession>(
+            r#"
+            UPDATE stock_count_sessions
+            SET status = 'cancelled', closed_at = NOW(), closed_by = $1
+            WHERE id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(cancelled_by)
+        .bind(session_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::updated(
+                    "stock_count_sessions",
+                    session_id,
+                    &before,
+                    &session,
+                    cancelled_by,
+                )?,
+            )
+            .await?;
+        tx.commit().await?;
+        Ok(session)
+    }
 }
@@ -1,6 +1,12 @@
+use crate::modules::inventory::domain::repositories::InventoryRepository;
 use crate::modules::local_sales::domain::entities::*;
 use crate::modules::local_sales::domain::repositories::LocalSaleRepository;
+use crate::modules::pricing::application::resolve_prices;
+use crate::modules::pricing::domain::entities::PriceTier;
+use crate::modules::pricing::domain::repositories::PriceRepository;
 use crate::shared::errors::AppError;
+use crate::shared::void::VoidDto;
+use chrono::Utc;
 use uuid::Uuid;
 
 pub async fn list_sales(
@@ -23,8 +29,11 @@ pub async fn todays_sales(repo: &dyn LocalSaleRepository) -> Result<Vec<LocalSal
     repo.find_todays().await
 }
 
+/// Crea la venta local con el `price_local` vigente al momento de la venta.
 pub async fn create_sale(
     repo: &dyn LocalSaleRepository,
+    prices: &dyn PriceRepository,
+    inventory: &dyn InventoryRepository,
     dto: &CreateLocalSaleDto,
     created_by: Uuid,
 ) -> Result<LocalSale, AppError> {
@@ -40,5 +49,24 @@ pub async fn create_sale(
             valid_types
         )));
     }
-    repo.create_sale(dto, created_by).await
+    let items: Vec<_> = dto.items.iter().map(LocalSaleItemDto::priced).collect();
+    let resolved = resolve_prices::resolve_item_prices(
+        prices,
+        inventory,
+        &items,
+        Utc::now(),
+        PriceTier::Local,
+    )
+    .await?;
+    repo.create_sale(dto, &resolved, created_by).await
+}
+
+/// Anula la venta (solo dueño) con registros compensatorios.
+pub async fn void_sale(
+    repo: &dyn LocalSaleRepository,
+    id: Uuid,
+    dto: &VoidDto,
+    voided_by: Uuid,
+) -> Result<LocalSale, AppError> {
+    repo.void_sale(id, dto.reason()?, voided_by).await
 }
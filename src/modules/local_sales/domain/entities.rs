@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::modules::pricing::domain::entities::PricedItem;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
 pub struct LocalSale {
     pub id: Uuid,
@@ -12,6 +14,10 @@ pub struct LocalSale {
     pub notes: Option<String>,
     pub created_at: DateTime<Utc>,
     pub created_by: Uuid,
+    /// Anulada por el dueño: stock devuelto y cobro revertido en caja.
+    pub voided_at: Option<DateTime<Utc>>,
+    pub voided_by: Option<Uuid>,
+    pub void_reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
@@ -24,6 +30,8 @@ pub struct LocalSaleItem {
     pub freezer_id: Uuid,
     pub quantity: i32,
     pub unit_price: Decimal,
+    pub price_history_id: Option<Uuid>,
+    pub price_override_reason: Option<String>,
 }
 
 #[derive(Debug, Deserialize, utoipa::ToSchema)]
@@ -40,7 +48,25 @@ pub struct LocalSaleItemDto {
     pub flavor_id: Uuid,
     pub freezer_id: Uuid,
     pub quantity: i32,
-    pub unit_price: Decimal,
+    /// Precio manual (solo dueño). Si se omite, se usa `price_local` vigente.
+    #[serde(default)]
+    pub unit_price: Option<Decimal>,
+    /// Motivo obligatorio cuando se envía `unit_price`.
+    pub price_override_reason: Option<String>,
+}
+
+impl LocalSaleItemDto {
+    pub fn priced(&self) -> PricedItem<'_> {
+        PricedItem {
+            inventory_id: self.inventory_id,
+            product_id: self.product_id,
+            flavor_id: self.flavor_id,
+            freezer_id: self.freezer_id,
+            is_deformed: None,
+            unit_price: self.unit_price,
+            override_reason: self.price_override_reason.as_deref(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, utoipa::ToSchema)]
@@ -2,6 +2,7 @@ use async_trait::async_trait;
 use uuid::Uuid;
 
 use super::entities::{CreateLocalSaleDto, LocalSale, LocalSaleWithItems};
+use crate::modules::pricing::domain::entities::ResolvedPrice;
 use crate::shared::errors::AppError;
 
 #[async_trait]
@@ -11,9 +12,19 @@ pub trait LocalSaleRepository: Send + Sync {
         -> Result<Option<LocalSaleWithItems>, AppError>;
     async fn find_todays(&self) -> Result<Vec<LocalSale>, AppError>;
     /// Crea venta, resta inventario, registra en caja. Todo transaccional.
+    /// `prices` va alineado con `dto.items`.
     async fn create_sale(
         &self,
         dto: &CreateLocalSaleDto,
+        prices: &[ResolvedPrice],
         created_by: Uuid,
     ) -> Result<LocalSale, AppError>;
+    /// Anula la venta: devuelve el stock a sus filas, revierte el cobro en
+    /// caja y la marca como anulada. Todo transaccional.
+    async fn void_sale(
+        &self,
+        id: Uuid,
+        reason: &str,
+        voided_by: Uuid,
+    ) -> Result<LocalSale, AppError>;
 }
@@ -1,6 +1,6 @@
 use axum::{
     extract::{Path, Query, State},
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
 use serde::Deserialize;
@@ -8,21 +8,25 @@ use std::sync::Arc;
 use utoipa::OpenApi;
 use uuid::Uuid;
 
+use crate::modules::inventory::domain::repositories::InventoryRepository;
 use crate::modules::local_sales::application::manage_local_sales;
 use crate::modules::local_sales::domain::entities::*;
 use crate::modules::local_sales::domain::repositories::LocalSaleRepository;
+use crate::modules::pricing::domain::repositories::PriceRepository;
 use crate::shared::auth::{AppState, AuthUser, Role};
 use crate::shared::errors::AppError;
+use crate::shared::void::VoidDto;
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(list_sales, create_sale, get_sale, todays_sales),
+    paths(list_sales, create_sale, get_sale, todays_sales, void_sale),
     components(schemas(
         crate::modules::local_sales::domain::entities::LocalSale,
         crate::modules::local_sales::domain::entities::LocalSaleItem,
         crate::modules::local_sales::domain::entities::CreateLocalSaleDto,
         crate::modules::local_sales::domain::entities::LocalSaleItemDto,
         crate::modules::local_sales::domain::entities::LocalSaleWithItems,
+        crate::shared::void::VoidDto,
     ))
 )]
 pub struct LocalSalesApiDoc;
@@ -31,6 +35,8 @@ pub struct LocalSalesApiDoc;
 pub struct LocalSalesState {
     pub app: AppState,
     pub repo: Arc<dyn LocalSaleRepository>,
+    pub prices: Arc<dyn PriceRepository>,
+    pub inventory: Arc<dyn InventoryRepository>,
 }
 
 impl axum::extract::FromRef<LocalSalesState> for AppState {
@@ -44,12 +50,23 @@ pub struct LimitQuery {
     pub limit: Option<i64>,
 }
 
-pub fn router(app: AppState, repo: Arc<dyn LocalSaleRepository>) -> Router {
-    let state = LocalSalesState { app, repo };
+pub fn router(
+    app: AppState,
+    repo: Arc<dyn LocalSaleRepository>,
+    prices: Arc<dyn PriceRepository>,
+    inventory: Arc<dyn InventoryRepository>,
+) -> Router {
+    let state = LocalSalesState {
+        app,
+        repo,
+        prices,
+        inventory,
+    };
     Router::new()
         .route("/", get(list_sales).post(create_sale))
         .route("/today", get(todays_sales))
         .route("/{id}", get(get_sale))
+        .route("/{id}/void", post(void_sale))
         .with_state(state)
 }
 
@@ -106,7 +123,11 @@ async fn todays_sales(
 #[utoipa::path(
     post, path = "/", tag = "Ventas Locales",
     request_body = CreateLocalSaleDto,
-    responses((status = 200, description = "Venta creada", body = LocalSale)),
+    responses(
+        (status = 200, description = "Venta creada", body = LocalSale),
+        (status = 400, description = "Sin precio vigente o precio manual sin motivo"),
+        (status = 403, description = "Precio manual sin ser dueño")
+    ),
     security(("bearer_auth" = []))
 )]
 async fn create_sale(
@@ -115,6 +136,39 @@ async fn create_sale(
     Json(dto): Json<CreateLocalSaleDto>,
 ) -> Result<Json<LocalSale>, AppError> {
     auth.require_role(Role::Admin)?;
-    let sale = manage_local_sales::create_sale(state.repo.as_ref(), &dto, auth.user_id()).await?;
+    // Solo el dueño puede fijar precios manualmente
+    if dto.items.iter().any(|i| i.unit_price.is_some()) {
+        auth.require_owner()?;
+    }
+    let sale = manage_local_sales::create_sale(
+        state.repo.as_ref(),
+        state.prices.as_ref(),
+        state.inventory.as_ref(),
+        &dto,
+        auth.user_id(),
+    )
+    .await?;
+    Ok(Json(sale))
+}
+
+#[utoipa::path(
+    post, path = "/{id}/void", tag = "Ventas Locales",
+    params(("id" = Uuid, Path, description = "ID de la venta")),
+    request_body = VoidDto,
+    responses(
+        (status = 200, description = "Venta anulada", body = LocalSale),
+        (status = 400, description = "Sin motivo"),
+        (status = 409, description = "Ya anulada o la caja del día está cerrada")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn void_sale(
+    State(state): State<LocalSalesState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(dto): Json<VoidDto>,
+) -> Result<Json<LocalSale>, AppError> {
+    auth.require_owner()?;
+    let sale = manage_local_sales::void_sale(state.repo.as_ref(), id, &dto, auth.user_id()).await?;
     Ok(Json(sale))
 }
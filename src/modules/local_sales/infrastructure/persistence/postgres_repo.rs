@@ -1,19 +1,32 @@
 use async_trait::async_trait;
 use rust_decimal::Decimal;
 use sqlx::PgPool;
+use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::modules::audit_log::domain::entities::CreateAuditLogDto;
+use crate::modules::audit_log::domain::repositories::AuditLogRepository;
+use crate::modules::cash_register::domain::entities::{CashEntry, CashTransactionType};
+use crate::modules::cash_register::infrastructure::persistence::ledger::{
+    append_cash_tx, reverse_doc_cash_tx,
+};
+use crate::modules::inventory::domain::entities::{MovementCause, MovementType};
+use crate::modules::inventory::infrastructure::persistence::movements::{
+    lock_stock_line_tx, record_movement_tx, reverse_doc_movements_tx,
+};
 use crate::modules::local_sales::domain::entities::*;
 use crate::modules::local_sales::domain::repositories::LocalSaleRepository;
+use crate::modules::pricing::domain::entities::ResolvedPrice;
 use crate::shared::errors::AppError;
 
 pub struct PgLocalSaleRepository {
     pool: PgPool,
+    audit: Arc<dyn AuditLogRepository>,
 }
 
 impl PgLocalSaleRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, audit: Arc<dyn AuditLogRepository>) -> Self {
+        Self { pool, audit }
     }
 }
 
@@ -67,15 +80,23 @@ impl LocalSaleRepository for PgLocalSaleRepository {
     async fn create_sale(
         &self,
         dto: &CreateLocalSaleDto,
+        prices: &[ResolvedPrice],
         created_by: Uuid,
     ) -> Result<LocalSale, AppError> {
+        if prices.len() != dto.items.len() {
+            return Err(AppError::Internal(
+                "Precios resueltos no coinciden con los items de la venta".into(),
+            ));
+        }
+
         let mut tx = self.pool.begin().await?;
 
-        // Calcular total
+        // Calcular total con los precios resueltos
         let total: Decimal = dto
             .items
             .iter()
-            .map(|i| i.unit_price * Decimal::from(i.quantity))
+            .zip(prices)
+            .map(|(i, p)| p.unit_price * Decimal::from(i.quantity))
             .sum();
 
         // 1. Crear venta
@@ -94,12 +115,23 @@ impl LocalSaleRepository for PgLocalSaleRepository {
         .await?;
 
         // 2. Insertar items y restar inventario
-        for item in &dto.items {
+        for (item, price) in dto.items.iter().zip(prices) {
+            lock_stock_line_tx(
+                &mut tx,
+                item.inventory_id,
+                item.product_id,
+                item.flavor_id,
+                item.freezer_id,
+                None,
+            )
+            .await?;
+
             sqlx::query(
                 r#"
                 INSERT INTO local_sale_items 
-                (sale_id, inventory_id, product_id, flavor_id, freezer_id, quantity, unit_price)
-                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                (sale_id, inventory_id, product_id, flavor_id, freezer_id, quantity, unit_price,
+                 price_history_id, price_override_reason)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
                 "#,
             )
             .bind(sale.id)
@@ -108,7 +140,9 @@ impl LocalSaleRepository for PgLocalSaleRepository {
             .bind(item.flavor_id)
             .bind(item.freezer_id)
             .bind(item.quantity)
-            .bind(item.unit_price)
+            .bind(price.unit_price)
+            .bind(price.price_history_id)
+            .bind(&price.override_reason)
             .execute(&mut *tx)
             .await?;
 
@@ -126,48 +160,92 @@ impl LocalSaleRepository for PgLocalSaleRepository {
             if rows == 0 {
                 return Err(AppError::InsufficientStock(item.inventory_id));
             }
+
+            record_movement_tx(
+                &mut tx,
+                item.inventory_id,
+                -item.quantity,
+                MovementCause::doc(MovementType::LocalSale, "local_sales", sale.id),
+                created_by,
+            )
+            .await?;
         }
 
         // 3. Registrar en caja (solo si es venta "local" o "custom", no para regalos)
         if dto.sale_type != "gift" {
-            let current_balance = sqlx::query_scalar::<_, Option<Decimal>>(
-                "SELECT balance FROM cash_register ORDER BY created_at DESC LIMIT 1 FOR UPDATE",
+            append_cash_tx(
+                &mut tx,
+                CashEntry::doc(
+                    CashTransactionType::LocalSale,
+                    total,
+                    "local_sales",
+                    sale.id,
+                ),
+                created_by,
             )
-            .fetch_optional(&mut *tx)
-            .await?
-            .flatten()
-            .unwrap_or(Decimal::ZERO);
-
-            let new_balance = current_balance + total;
+            .await?;
+        }
 
-            sqlx::query(
-                r#"
-                INSERT INTO cash_register 
-                (type, amount, balance, related_doc_type, related_doc_id, created_by)
-                VALUES ('local_sale', $1, $2, 'local_sales', $3, $4)
-                "#,
+        // 4. Auditoría
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::created("local_sales", sale.id, &sale, created_by)?,
             )
-            .bind(total)
-            .bind(new_balance)
-            .bind(sale.id)
-            .bind(created_by)
-            .execute(&mut *tx)
             .await?;
+
+        tx.commit().await?;
+        Ok(sale)
+    }
+
+    async fn void_sale(
+        &self,
+        id: Uuid,
+        reason: &str,
+        voided_by: Uuid,
+    ) -> Result<LocalSale, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // 1. Venta (con lock)
+        let existing =
+            sqlx::query_as::<_, LocalSale>("SELECT * FROM local_sales WHERE id = $1 FOR UPDATE")
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or_else(|| AppError::NotFound("Venta local no encontrada".into()))?;
+        if existing.voided_at.is_some() {
+            return Err(AppError::Conflict("La venta ya fue anulada".into()));
         }
 
-        // 4. Auditoría
-        sqlx::query(
+        // 2. Devolver el stock a las filas de donde salió
+        reverse_doc_movements_tx(&mut tx, "local_sales", id, voided_by).await?;
+
+        // 3. Revertir el cobro (las ventas de regalo no pasaron por caja)
+        reverse_doc_cash_tx(&mut tx, "local_sales", id, reason, voided_by).await?;
+
+        // 4. Marcar como anulada
+        let sale = sqlx::query_as::<_, LocalSale>(
             r#"
-            INSERT INTO audit_log (action, table_name, record_id, changes_after, created_by)
-            VALUES ('create', 'local_sales', $1, $2, $3)
+            UPDATE local_sales
+            SET voided_at = NOW(), voided_by = $1, void_reason = $2
+            WHERE id = $3
+            RETURNING *
             "#,
         )
-        .bind(sale.id)
-        .bind(serde_json::to_value(&sale)?)
-        .bind(created_by)
-        .execute(&mut *tx)
+        .bind(voided_by)
+        .bind(reason)
+        .bind(id)
+        .fetch_one(&mut *tx)
         .await?;
 
+        // 5. Auditoría
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::updated("local_sales", id, &existing, &sale, voided_by)?,
+            )
+            .await?;
+
         tx.commit().await?;
         Ok(sale)
     }
@@ -8,6 +8,8 @@ pub mod local_sales;
 pub mod owner_sales;
 pub mod pricing;
 pub mod purchases;
+pub mod reconciliation;
+pub mod reports;
 pub mod users;
 pub mod worker_payments;
 pub mod worker_trips;
@@ -1,6 +1,11 @@
+use crate::modules::inventory::domain::repositories::InventoryRepository;
 use crate::modules::owner_sales::domain::entities::*;
 use crate::modules::owner_sales::domain::repositories::OwnerSaleRepository;
+use crate::modules::pricing::application::resolve_prices;
+use crate::modules::pricing::domain::entities::PriceTier;
+use crate::modules::pricing::domain::repositories::PriceRepository;
 use crate::shared::errors::AppError;
+use crate::shared::void::VoidDto;
 use uuid::Uuid;
 
 pub async fn list_sales(
@@ -19,15 +24,31 @@ pub async fn get_sale(
         .ok_or_else(|| AppError::NotFound("Venta del dueño no encontrada".into()))
 }
 
+/// Crea la venta del dueño con el `price_route` vigente a `departure_time`.
 pub async fn create_sale(
     repo: &dyn OwnerSaleRepository,
+    prices: &dyn PriceRepository,
+    inventory: &dyn InventoryRepository,
     dto: &CreateOwnerSaleDto,
     owner_id: Uuid,
 ) -> Result<OwnerSale, AppError> {
     if dto.loaded_items.is_empty() {
         return Err(AppError::BadRequest("Debe cargar al menos un item".into()));
     }
-    repo.create_sale(dto, owner_id).await
+    let items: Vec<_> = dto
+        .loaded_items
+        .iter()
+        .map(OwnerLoadedItemDto::priced)
+        .collect();
+    let resolved = resolve_prices::resolve_item_prices(
+        prices,
+        inventory,
+        &items,
+        dto.departure_time,
+        PriceTier::Route,
+    )
+    .await?;
+    repo.create_sale(dto, &resolved, owner_id).await
 }
 
 pub async fn complete_sale(
@@ -38,3 +59,12 @@ pub async fn complete_sale(
 ) -> Result<OwnerSale, AppError> {
     repo.complete_sale(sale_id, dto, owner_id).await
 }
+
+pub async fn void_sale(
+    repo: &dyn OwnerSaleRepository,
+    sale_id: Uuid,
+    dto: &VoidDto,
+    owner_id: Uuid,
+) -> Result<OwnerSale, AppError> {
+    repo.void_sale(sale_id, dto.reason()?, owner_id).await
+}
@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::modules::pricing::domain::entities::PricedItem;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
 pub struct OwnerSale {
     pub id: Uuid,
@@ -16,6 +18,10 @@ pub struct OwnerSale {
     pub auto_withdrawal: Decimal,
     pub created_at: DateTime<Utc>,
     pub created_by: Uuid,
+    /// Anulada por el dueño: carga, devoluciones y caja revertidas.
+    pub voided_at: Option<DateTime<Utc>>,
+    pub voided_by: Option<Uuid>,
+    pub void_reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
@@ -29,6 +35,8 @@ pub struct OwnerSaleLoadedItem {
     pub quantity: i32,
     pub unit_price: Decimal,
     pub is_deformed: bool,
+    pub price_history_id: Option<Uuid>,
+    pub price_override_reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
@@ -56,10 +64,28 @@ pub struct OwnerLoadedItemDto {
     pub flavor_id: Uuid,
     pub freezer_id: Uuid,
     pub quantity: i32,
-    pub unit_price: Decimal,
+    /// Precio manual. Si se omite, se usa `price_route` vigente a `departure_time`.
+    #[serde(default)]
+    pub unit_price: Option<Decimal>,
+    /// Motivo obligatorio cuando se envía `unit_price`.
+    pub price_override_reason: Option<String>,
     pub is_deformed: bool,
 }
 
+impl OwnerLoadedItemDto {
+    pub fn priced(&self) -> PricedItem<'_> {
+        PricedItem {
+            inventory_id: self.inventory_id,
+            product_id: self.product_id,
+            flavor_id: self.flavor_id,
+            freezer_id: self.freezer_id,
+            is_deformed: Some(self.is_deformed),
+            unit_price: self.unit_price,
+            override_reason: self.price_override_reason.as_deref(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct CompleteOwnerSaleDto {
     pub returned_items: Vec<OwnerReturnedItemDto>,
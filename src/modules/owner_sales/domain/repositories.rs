@@ -2,6 +2,7 @@ use async_trait::async_trait;
 use uuid::Uuid;
 
 use super::entities::*;
+use crate::modules::pricing::domain::entities::ResolvedPrice;
 use crate::shared::errors::AppError;
 
 #[async_trait]
@@ -9,9 +10,11 @@ pub trait OwnerSaleRepository: Send + Sync {
     async fn find_all(&self, limit: i64) -> Result<Vec<OwnerSale>, AppError>;
     async fn find_by_id_with_items(&self, id: Uuid)
         -> Result<Option<OwnerSaleWithItems>, AppError>;
+    /// `prices` va alineado con `dto.loaded_items`.
     async fn create_sale(
         &self,
         dto: &CreateOwnerSaleDto,
+        prices: &[ResolvedPrice],
         owner_id: Uuid,
     ) -> Result<OwnerSale, AppError>;
     /// Completa venta, procesa devoluciones, registra 2 eventos en caja (ingreso + retiro).
@@ -21,4 +24,12 @@ pub trait OwnerSaleRepository: Send + Sync {
         dto: &CompleteOwnerSaleDto,
         owner_id: Uuid,
     ) -> Result<OwnerSale, AppError>;
+    /// Anula la venta, completada o no: revierte carga, devoluciones, caja y
+    /// el uso de la ruta.
+    async fn void_sale(
+        &self,
+        sale_id: Uuid,
+        reason: &str,
+        owner_id: Uuid,
+    ) -> Result<OwnerSale, AppError>;
 }
@@ -8,15 +8,18 @@ use std::sync::Arc;
 use utoipa::OpenApi;
 use uuid::Uuid;
 
+use crate::modules::inventory::domain::repositories::InventoryRepository;
 use crate::modules::owner_sales::application::manage_owner_sales;
 use crate::modules::owner_sales::domain::entities::*;
 use crate::modules::owner_sales::domain::repositories::OwnerSaleRepository;
+use crate::modules::pricing::domain::repositories::PriceRepository;
 use crate::shared::auth::{AppState, AuthUser};
 use crate::shared::errors::AppError;
+use crate::shared::void::VoidDto;
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(list_sales, get_sale, create_sale, complete_sale),
+    paths(list_sales, get_sale, create_sale, complete_sale, void_sale),
     components(schemas(
         crate::modules::owner_sales::domain::entities::OwnerSale,
         crate::modules::owner_sales::domain::entities::OwnerSaleLoadedItem,
@@ -26,6 +29,7 @@ use crate::shared::errors::AppError;
         crate::modules::owner_sales::domain::entities::CompleteOwnerSaleDto,
         crate::modules::owner_sales::domain::entities::OwnerReturnedItemDto,
         crate::modules::owner_sales::domain::entities::OwnerSaleWithItems,
+        crate::shared::void::VoidDto,
     ))
 )]
 pub struct OwnerSalesApiDoc;
@@ -34,6 +38,8 @@ pub struct OwnerSalesApiDoc;
 pub struct OwnerSalesState {
     pub app: AppState,
     pub repo: Arc<dyn OwnerSaleRepository>,
+    pub prices: Arc<dyn PriceRepository>,
+    pub inventory: Arc<dyn InventoryRepository>,
 }
 
 impl axum::extract::FromRef<OwnerSalesState> for AppState {
@@ -47,12 +53,23 @@ pub struct LimitQuery {
     pub limit: Option<i64>,
 }
 
-pub fn router(app: AppState, repo: Arc<dyn OwnerSaleRepository>) -> Router {
-    let state = OwnerSalesState { app, repo };
+pub fn router(
+    app: AppState,
+    repo: Arc<dyn OwnerSaleRepository>,
+    prices: Arc<dyn PriceRepository>,
+    inventory: Arc<dyn InventoryRepository>,
+) -> Router {
+    let state = OwnerSalesState {
+        app,
+        repo,
+        prices,
+        inventory,
+    };
     Router::new()
         .route("/", get(list_sales).post(create_sale))
         .route("/{id}", get(get_sale))
         .route("/{id}/complete", post(complete_sale))
+        .route("/{id}/void", post(void_sale))
         .with_state(state)
 }
 
@@ -104,7 +121,14 @@ async fn create_sale(
     Json(dto): Json<CreateOwnerSaleDto>,
 ) -> Result<Json<OwnerSale>, AppError> {
     auth.require_owner()?;
-    let sale = manage_owner_sales::create_sale(state.repo.as_ref(), &dto, auth.user_id()).await?;
+    let sale = manage_owner_sales::create_sale(
+        state.repo.as_ref(),
+        state.prices.as_ref(),
+        state.inventory.as_ref(),
+        &dto,
+        auth.user_id(),
+    )
+    .await?;
     Ok(Json(sale))
 }
 
@@ -126,3 +150,24 @@ async fn complete_sale(
         manage_owner_sales::complete_sale(state.repo.as_ref(), id, &dto, auth.user_id()).await?;
     Ok(Json(sale))
 }
+
+#[utoipa::path(
+    post, path = "/{id}/void", tag = "Ventas del Dueño",
+    params(("id" = Uuid, Path, description = "ID de la venta")),
+    request_body = VoidDto,
+    responses(
+        (status = 200, description = "Venta anulada", body = OwnerSale),
+        (status = 409, description = "Ya anulada o stock devuelto ya consumido")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn void_sale(
+    State(state): State<OwnerSalesState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(dto): Json<VoidDto>,
+) -> Result<Json<OwnerSale>, AppError> {
+    auth.require_owner()?;
+    let sale = manage_owner_sales::void_sale(state.repo.as_ref(), id, &dto, auth.user_id()).await?;
+    Ok(Json(sale))
+}
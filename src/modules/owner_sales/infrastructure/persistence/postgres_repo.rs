@@ -2,19 +2,32 @@ use async_trait::async_trait;
 use rust_decimal::Decimal;
 use sqlx::PgPool;
 use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::modules::audit_log::domain::entities::CreateAuditLogDto;
+use crate::modules::audit_log::domain::repositories::AuditLogRepository;
+use crate::modules::cash_register::domain::entities::{CashEntry, CashTransactionType};
+use crate::modules::cash_register::infrastructure::persistence::ledger::{
+    append_cash_tx, reverse_doc_cash_tx,
+};
+use crate::modules::inventory::domain::entities::{MovementCause, MovementType};
+use crate::modules::inventory::infrastructure::persistence::movements::{
+    lock_stock_line_tx, record_movement_tx, reverse_doc_movements_tx,
+};
 use crate::modules::owner_sales::domain::entities::*;
 use crate::modules::owner_sales::domain::repositories::OwnerSaleRepository;
+use crate::modules::pricing::domain::entities::ResolvedPrice;
 use crate::shared::errors::AppError;
 
 pub struct PgOwnerSaleRepository {
     pool: PgPool,
+    audit: Arc<dyn AuditLogRepository>,
 }
 
 impl PgOwnerSaleRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, audit: Arc<dyn AuditLogRepository>) -> Self {
+        Self { pool, audit }
     }
 }
 
@@ -67,8 +80,15 @@ impl OwnerSaleRepository for PgOwnerSaleRepository {
     async fn create_sale(
         &self,
         dto: &CreateOwnerSaleDto,
+        prices: &[ResolvedPrice],
         owner_id: Uuid,
     ) -> Result<OwnerSale, AppError> {
+        if prices.len() != dto.loaded_items.len() {
+            return Err(AppError::Internal(
+                "Precios resueltos no coinciden con los items cargados".into(),
+            ));
+        }
+
         let mut tx = self.pool.begin().await?;
 
         // 1. Crear sale
@@ -86,12 +106,23 @@ impl OwnerSaleRepository for PgOwnerSaleRepository {
         .await?;
 
         // 2. Insertar loaded_items y restar inventario
-        for item in &dto.loaded_items {
+        for (item, price) in dto.loaded_items.iter().zip(prices) {
+            lock_stock_line_tx(
+                &mut tx,
+                item.inventory_id,
+                item.product_id,
+                item.flavor_id,
+                item.freezer_id,
+                Some(item.is_deformed),
+            )
+            .await?;
+
             sqlx::query(
                 r#"
                 INSERT INTO owner_sale_loaded_items 
-                (sale_id, inventory_id, product_id, flavor_id, freezer_id, quantity, unit_price, is_deformed)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                (sale_id, inventory_id, product_id, flavor_id, freezer_id, quantity, unit_price, is_deformed,
+                 price_history_id, price_override_reason)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
                 "#,
             )
             .bind(sale.id)
@@ -100,8 +131,10 @@ impl OwnerSaleRepository for PgOwnerSaleRepository {
             .bind(item.flavor_id)
             .bind(item.freezer_id)
             .bind(item.quantity)
-            .bind(item.unit_price)
+            .bind(price.unit_price)
             .bind(item.is_deformed)
+            .bind(price.price_history_id)
+            .bind(&price.override_reason)
             .execute(&mut *tx)
             .await?;
 
@@ -118,6 +151,15 @@ impl OwnerSaleRepository for PgOwnerSaleRepository {
             if rows == 0 {
                 return Err(AppError::InsufficientStock(item.inventory_id));
             }
+
+            record_movement_tx(
+                &mut tx,
+                item.inventory_id,
+                -item.quantity,
+                MovementCause::doc(MovementType::OwnerSaleLoad, "owner_sales", sale.id),
+                owner_id,
+            )
+            .await?;
         }
 
         // 3. Ruta usage
@@ -128,6 +170,14 @@ impl OwnerSaleRepository for PgOwnerSaleRepository {
                 .await?;
         }
 
+        // 4. Auditoría
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::created("owner_sales", sale.id, &sale, owner_id)?,
+            )
+            .await?;
+
         tx.commit().await?;
         Ok(sale)
     }
@@ -141,8 +191,8 @@ impl OwnerSaleRepository for PgOwnerSaleRepository {
         let mut tx = self.pool.begin().await?;
 
         // 1. Verificar que la venta existe y no está completada
-        let _existing = sqlx::query_as::<_, OwnerSale>(
-            "SELECT * FROM owner_sales WHERE id = $1 AND return_time IS NULL",
+        let existing = sqlx::query_as::<_, OwnerSale>(
+            "SELECT * FROM owner_sales WHERE id = $1 AND return_time IS NULL AND voided_at IS NULL FOR UPDATE",
         )
         .bind(sale_id)
         .fetch_optional(&mut *tx)
@@ -203,13 +253,19 @@ impl OwnerSaleRepository for PgOwnerSaleRepository {
                 }
             };
 
-            if returned.is_deformed {
-                // Deformados: nuevo item asignado al dueño (owner como worker)
-                sqlx::query(
+            let inventory_id = if returned.is_deformed {
+                // Deformados: stock general de deformados (sin trabajador)
+                sqlx::query_scalar::<_, Uuid>(
                     r#"
                     INSERT INTO inventory 
                     (freezer_id, product_id, flavor_id, provider_id, quantity, is_deformed, min_stock_alert, updated_by)
                     VALUES ($1, $2, $3, $4, $5, TRUE, 0, $6)
+                    ON CONFLICT (freezer_id, product_id, flavor_id, provider_id, is_deformed, assigned_worker_id)
+                    DO UPDATE SET 
+                        quantity = inventory.quantity + EXCLUDED.quantity,
+                        last_updated = NOW(),
+                        updated_by = EXCLUDED.updated_by
+                    RETURNING id
                     "#,
                 )
                 .bind(returned.destination_freezer_id)
@@ -218,11 +274,11 @@ impl OwnerSaleRepository for PgOwnerSaleRepository {
                 .bind(provider_id)
                 .bind(returned.quantity)
                 .bind(owner_id)
-                .execute(&mut *tx)
-                .await?;
+                .fetch_one(&mut *tx)
+                .await?
             } else {
                 // Buenos: UPSERT
-                sqlx::query(
+                sqlx::query_scalar::<_, Uuid>(
                     r#"
                     INSERT INTO inventory 
                     (freezer_id, product_id, flavor_id, provider_id, quantity, is_deformed, min_stock_alert, updated_by)
@@ -232,6 +288,7 @@ impl OwnerSaleRepository for PgOwnerSaleRepository {
                         quantity = inventory.quantity + EXCLUDED.quantity,
                         last_updated = NOW(),
                         updated_by = EXCLUDED.updated_by
+                    RETURNING id
                     "#,
                 )
                 .bind(returned.destination_freezer_id)
@@ -240,9 +297,18 @@ impl OwnerSaleRepository for PgOwnerSaleRepository {
                 .bind(provider_id)
                 .bind(returned.quantity)
                 .bind(owner_id)
-                .execute(&mut *tx)
-                .await?;
-            }
+                .fetch_one(&mut *tx)
+                .await?
+            };
+
+            record_movement_tx(
+                &mut tx,
+                inventory_id,
+                returned.quantity,
+                MovementCause::doc(MovementType::OwnerSaleReturn, "owner_sales", sale_id),
+                owner_id,
+            )
+            .await?;
         }
 
         // 4. Calcular ventas
@@ -268,44 +334,104 @@ impl OwnerSaleRepository for PgOwnerSaleRepository {
         .await?;
 
         // 6. CAJA: 2 registros (ingreso + retiro automático)
-        let current_balance = sqlx::query_scalar::<_, Option<Decimal>>(
-            "SELECT balance FROM cash_register ORDER BY created_at DESC LIMIT 1 FOR UPDATE",
+        // Evento 1: Ingreso
+        append_cash_tx(
+            &mut tx,
+            CashEntry::doc(
+                CashTransactionType::OwnerSale,
+                total_amount,
+                "owner_sales",
+                sale.id,
+            )
+            .with_description(Some("Venta del dueño".into())),
+            owner_id,
         )
-        .fetch_optional(&mut *tx)
-        .await?
-        .flatten()
-        .unwrap_or(Decimal::ZERO);
+        .await?;
 
-        // Evento 1: Ingreso
-        sqlx::query(
-            r#"
-            INSERT INTO cash_register 
-            (type, amount, balance, description, related_doc_type, related_doc_id, created_by)
-            VALUES ('owner_sale', $1, $2, 'Venta del dueño', 'owner_sales', $3, $4)
-            "#,
+        // Evento 2: Retiro inmediato (vuelve al balance original)
+        append_cash_tx(
+            &mut tx,
+            CashEntry::doc(
+                CashTransactionType::OwnerWithdrawal,
+                -total_amount,
+                "owner_sales",
+                sale.id,
+            )
+            .with_description(Some("Retiro automático por venta del dueño".into())),
+            owner_id,
         )
-        .bind(total_amount)
-        .bind(current_balance + total_amount)
-        .bind(sale.id)
-        .bind(owner_id)
-        .execute(&mut *tx)
         .await?;
 
-        // Evento 2: Retiro inmediato
-        sqlx::query(
+        // 7. Auditoría
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::updated("owner_sales", sale.id, &existing, &sale, owner_id)?,
+            )
+            .await?;
+
+        tx.commit().await?;
+        Ok(sale)
+    }
+
+    async fn void_sale(
+        &self,
+        sale_id: Uuid,
+        reason: &str,
+        owner_id: Uuid,
+    ) -> Result<OwnerSale, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // 1. Venta (con lock)
+        let existing =
+            sqlx::query_as::<_, OwnerSale>("SELECT * FROM owner_sales WHERE id = $1 FOR UPDATE")
+                .bind(sale_id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or_else(|| AppError::NotFound("Venta no encontrada".into()))?;
+        if existing.voided_at.is_some() {
+            return Err(AppError::Conflict("La venta ya fue anulada".into()));
+        }
+
+        // 2. Inventario: la carga vuelve y las devoluciones salen
+        reverse_doc_movements_tx(&mut tx, "owner_sales", sale_id, owner_id).await?;
+
+        // 3. Caja: ingreso y retiro automático (solo si ya se completó)
+        reverse_doc_cash_tx(&mut tx, "owner_sales", sale_id, reason, owner_id).await?;
+
+        // 4. Ruta usage
+        if let Some(route_id) = existing.route_id {
+            sqlx::query(
+                "UPDATE routes SET usage_count = GREATEST(usage_count - 1, 0) WHERE id = $1",
+            )
+            .bind(route_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // 5. Marcar como anulada
+        let sale = sqlx::query_as::<_, OwnerSale>(
             r#"
-            INSERT INTO cash_register 
-            (type, amount, balance, description, related_doc_type, related_doc_id, created_by)
-            VALUES ('owner_withdrawal', $1, $2, 'Retiro automático por venta del dueño', 'owner_sales', $3, $4)
+            UPDATE owner_sales
+            SET voided_at = NOW(), voided_by = $1, void_reason = $2
+            WHERE id = $3
+            RETURNING *
             "#,
         )
-        .bind(-total_amount)
-        .bind(current_balance) // Vuelve al balance original
-        .bind(sale.id)
         .bind(owner_id)
-        .execute(&mut *tx)
+        .bind(reason)
+        .bind(sale_id)
+        .fetch_one(&mut *tx)
         .await?;
 
+        // 6. Auditoría
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::updated("owner_sales", sale_id, &existing, &sale, owner_id)?,
+            )
+            .await?;
+
         tx.commit().await?;
         Ok(sale)
     }
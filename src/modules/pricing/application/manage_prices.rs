@@ -1,7 +1,10 @@
+use rust_decimal::Decimal;
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::modules::pricing::domain::entities::{CreatePriceDto, PriceHistory};
+use crate::modules::pricing::domain::entities::{
+    CreatePriceDto, MarkupRule, PriceHistory, UpsertMarkupRuleDto,
+};
 use crate::modules::pricing::domain::repositories::PriceRepository;
 use crate::shared::errors::AppError;
 
@@ -46,3 +49,28 @@ pub async fn get_price_history(
 ) -> Result<Vec<PriceHistory>, AppError> {
     repo.get_history(product_id, flavor_id, provider_id).await
 }
+
+/// Caso de uso: Listar reglas de margen usadas al recibir compras.
+pub async fn list_markup_rules(
+    repo: &Arc<dyn PriceRepository>,
+) -> Result<Vec<MarkupRule>, AppError> {
+    repo.list_markup_rules().await
+}
+
+/// Caso de uso: Crear o reemplazar una regla de margen.
+/// Los porcentajes se aplican sobre el costo, así que no pueden ser negativos.
+pub async fn upsert_markup_rule(
+    repo: &Arc<dyn PriceRepository>,
+    dto: UpsertMarkupRuleDto,
+    updated_by: Uuid,
+) -> Result<MarkupRule, AppError> {
+    if [dto.base_markup_pct, dto.route_markup_pct, dto.local_markup_pct]
+        .iter()
+        .any(|pct| *pct < Decimal::ZERO)
+    {
+        return Err(AppError::BadRequest(
+            "Los porcentajes de margen no pueden ser negativos".into(),
+        ));
+    }
+    repo.upsert_markup_rule(&dto, updated_by).await
+}
@@ -1 +1,2 @@
 pub mod manage_prices;
+pub mod resolve_prices;
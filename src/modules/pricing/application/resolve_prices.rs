@@ -0,0 +1,93 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+use crate::modules::inventory::domain::repositories::InventoryRepository;
+use crate::modules::pricing::domain::entities::{PriceTier, PricedItem, ResolvedPrice};
+use crate::modules::pricing::domain::repositories::PriceRepository;
+use crate::shared::errors::AppError;
+
+/// Caso de uso: Resolver el precio unitario de un item desde `price_history`.
+///
+/// La línea debe describir la fila de inventario (producto, sabor, congelador y
+/// condición de deformado). El proveedor se toma de la fila, y el precio es el vigente en `at`
+/// según el `tier` del canal. Si el item trae `unit_price`, se usa como override
+/// (el control de rol lo hace el controlador) y se exige un motivo.
+pub async fn resolve_item_price(
+    prices: &dyn PriceRepository,
+    inventory: &dyn InventoryRepository,
+    item: PricedItem<'_>,
+    at: DateTime<Utc>,
+    tier: PriceTier,
+) -> Result<ResolvedPrice, AppError> {
+    let row = inventory
+        .find_by_id(item.inventory_id)
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(format!("Inventario {} no encontrado", item.inventory_id))
+        })?;
+
+    if row.product_id != item.product_id
+        || row.flavor_id != item.flavor_id
+        || row.freezer_id != item.freezer_id
+        || item.is_deformed.is_some_and(|d| d != row.is_deformed)
+    {
+        return Err(AppError::BadRequest(format!(
+            "La línea no coincide con el inventario {} (producto, sabor, congelador o deformado)",
+            item.inventory_id
+        )));
+    }
+
+    let current = prices
+        .get_price_at(row.product_id, row.flavor_id, row.provider_id, at)
+        .await?;
+
+    match item.unit_price {
+        Some(unit_price) => {
+            let reason = item
+                .override_reason
+                .map(str::trim)
+                .filter(|r| !r.is_empty())
+                .ok_or_else(|| {
+                    AppError::BadRequest("El precio manual requiere un motivo".into())
+                })?;
+            if unit_price < Decimal::ZERO {
+                return Err(AppError::BadRequest(
+                    "El precio manual no puede ser negativo".into(),
+                ));
+            }
+            Ok(ResolvedPrice {
+                unit_price,
+                price_history_id: current.map(|p| p.id),
+                override_reason: Some(reason.to_string()),
+            })
+        }
+        None => {
+            let current = current.ok_or_else(|| {
+                AppError::BadRequest(format!(
+                    "No hay precio vigente para el inventario {} en {}",
+                    item.inventory_id, at
+                ))
+            })?;
+            Ok(ResolvedPrice {
+                unit_price: tier.price_of(&current),
+                price_history_id: Some(current.id),
+                override_reason: None,
+            })
+        }
+    }
+}
+
+/// Resuelve los precios de varios items, en el mismo orden.
+pub async fn resolve_item_prices(
+    prices: &dyn PriceRepository,
+    inventory: &dyn InventoryRepository,
+    items: &[PricedItem<'_>],
+    at: DateTime<Utc>,
+    tier: PriceTier,
+) -> Result<Vec<ResolvedPrice>, AppError> {
+    let mut resolved = Vec::with_capacity(items.len());
+    for item in items {
+        resolved.push(resolve_item_price(prices, inventory, *item, at, tier).await?);
+    }
+    Ok(resolved)
+}
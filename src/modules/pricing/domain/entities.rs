@@ -31,3 +31,108 @@ pub struct CreatePriceDto {
     pub price_route: Decimal,
     pub price_local: Decimal,
 }
+
+/// Nivel de precio aplicado según el canal de venta.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriceTier {
+    /// Viajes de trabajadores: lo que el trabajador le debe al negocio.
+    Base,
+    /// Ventas del dueño en ruta.
+    Route,
+    /// Ventas en el local.
+    Local,
+}
+
+impl PriceTier {
+    pub fn price_of(&self, price: &PriceHistory) -> Decimal {
+        match self {
+            Self::Base => price.price_base,
+            Self::Route => price.price_route,
+            Self::Local => price.price_local,
+        }
+    }
+}
+
+/// Item a cotizar: fila de inventario y precio manual opcional (solo dueño).
+#[derive(Debug, Clone, Copy)]
+pub struct PricedItem<'a> {
+    pub inventory_id: Uuid,
+    pub product_id: Uuid,
+    pub flavor_id: Uuid,
+    pub freezer_id: Uuid,
+    /// Condición declarada de la línea; `None` si el canal no la registra.
+    pub is_deformed: Option<bool>,
+    pub unit_price: Option<Decimal>,
+    pub override_reason: Option<&'a str>,
+}
+
+/// Precio unitario resuelto en el servidor para un item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedPrice {
+    pub unit_price: Decimal,
+    /// Precio vigente usado (también se guarda cuando hay override, si existe).
+    pub price_history_id: Option<Uuid>,
+    /// Motivo del precio manual. `None` si se usó el precio vigente.
+    pub override_reason: Option<String>,
+}
+
+/// Regla de margen: porcentajes sobre el costo para cada nivel de precio.
+/// `product_id = None` es la regla general.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct MarkupRule {
+    pub id: Uuid,
+    pub product_id: Option<Uuid>,
+    pub base_markup_pct: Decimal,
+    pub route_markup_pct: Decimal,
+    pub local_markup_pct: Decimal,
+    pub updated_at: DateTime<Utc>,
+    pub updated_by: Uuid,
+}
+
+impl MarkupRule {
+    /// Precios (base, ruta, local) para un costo, redondeados a centavos.
+    pub fn prices_for(&self, cost: Decimal) -> (Decimal, Decimal, Decimal) {
+        let apply = |pct: Decimal| {
+            (cost * (Decimal::ONE_HUNDRED + pct) / Decimal::ONE_HUNDRED).round_dp(2)
+        };
+        (
+            apply(self.base_markup_pct),
+            apply(self.route_markup_pct),
+            apply(self.local_markup_pct),
+        )
+    }
+}
+
+/// Crea o reemplaza la regla de margen de un producto (o la general).
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct UpsertMarkupRuleDto {
+    pub product_id: Option<Uuid>,
+    pub base_markup_pct: Decimal,
+    pub route_markup_pct: Decimal,
+    pub local_markup_pct: Decimal,
+}
+
+/// Combo cuyo costo cambió con una compra y no tiene regla de margen:
+/// los precios de venta vigentes no se tocaron.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct PriceWarning {
+    pub product_id: Uuid,
+    pub flavor_id: Uuid,
+    pub provider_id: Uuid,
+    /// Costo vigente antes de la compra. `None` si el combo no tenía precio.
+    pub previous_cost: Option<Decimal>,
+    pub new_cost: Decimal,
+    pub price_base: Option<Decimal>,
+    pub price_route: Option<Decimal>,
+    pub price_local: Option<Decimal>,
+    /// Algún precio de venta vigente queda por debajo del nuevo costo (o no hay precio).
+    pub below_cost: bool,
+}
+
+/// Resultado de contrastar el costo de compra con el precio vigente.
+#[derive(Debug, Clone)]
+pub enum CostSync {
+    Unchanged,
+    Updated(PriceHistory),
+    Warning(PriceWarning),
+}
@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
-use super::entities::{CreatePriceDto, PriceHistory};
+use super::entities::{CreatePriceDto, MarkupRule, PriceHistory, UpsertMarkupRuleDto};
 use crate::shared::errors::AppError;
 
 /// Puerto de salida: persistencia de precios.
@@ -42,4 +42,14 @@ pub trait PriceRepository: Send + Sync {
 
     /// Listar todos los precios actuales (último de cada combo).
     async fn list_current_prices(&self) -> Result<Vec<PriceHistory>, AppError>;
+
+    /// Reglas de margen configuradas (la general primero).
+    async fn list_markup_rules(&self) -> Result<Vec<MarkupRule>, AppError>;
+
+    /// Crear o reemplazar la regla de margen de un producto (o la general).
+    async fn upsert_markup_rule(
+        &self,
+        dto: &UpsertMarkupRuleDto,
+        updated_by: Uuid,
+    ) -> Result<MarkupRule, AppError>;
 }
@@ -10,7 +10,9 @@ use utoipa::OpenApi;
 use uuid::Uuid;
 
 use crate::modules::pricing::application::manage_prices;
-use crate::modules::pricing::domain::entities::{CreatePriceDto, PriceHistory};
+use crate::modules::pricing::domain::entities::{
+    CreatePriceDto, MarkupRule, PriceHistory, UpsertMarkupRuleDto,
+};
 use crate::modules::pricing::domain::repositories::PriceRepository;
 use crate::shared::auth::{AppState, AuthUser, Role};
 use crate::shared::errors::AppError;
@@ -21,11 +23,16 @@ use crate::shared::errors::AppError;
         list_current_handler,
         create_handler,
         current_price_handler,
-        history_handler
+        history_handler,
+        list_markup_rules_handler,
+        upsert_markup_rule_handler
     ),
     components(schemas(
         crate::modules::pricing::domain::entities::PriceHistory,
         crate::modules::pricing::domain::entities::CreatePriceDto,
+        crate::modules::pricing::domain::entities::MarkupRule,
+        crate::modules::pricing::domain::entities::UpsertMarkupRuleDto,
+        crate::modules::pricing::domain::entities::PriceWarning,
     ))
 )]
 pub struct PricingApiDoc;
@@ -59,6 +66,10 @@ pub fn router(app_state: AppState, repo: Arc<dyn PriceRepository>) -> Router {
         .route("/", get(list_current_handler).post(create_handler))
         .route("/current", get(current_price_handler))
         .route("/history", get(history_handler))
+        .route(
+            "/markup-rules",
+            get(list_markup_rules_handler).put(upsert_markup_rule_handler),
+        )
         .with_state(state)
 }
 
@@ -134,3 +145,35 @@ async fn history_handler(
             .await?,
     ))
 }
+
+/// GET /prices/markup-rules — Reglas de margen sobre el costo de compra.
+#[utoipa::path(
+    get, path = "/markup-rules", tag = "Precios",
+    responses((status = 200, description = "Reglas de margen", body = Vec<MarkupRule>)),
+    security(("bearer_auth" = []))
+)]
+async fn list_markup_rules_handler(
+    auth: AuthUser,
+    State(state): State<PricingState>,
+) -> Result<Json<Vec<MarkupRule>>, AppError> {
+    auth.require_role(Role::Admin)?;
+    Ok(Json(manage_prices::list_markup_rules(&state.repo).await?))
+}
+
+/// PUT /prices/markup-rules — Crear o reemplazar la regla de un producto (o la general).
+#[utoipa::path(
+    put, path = "/markup-rules", tag = "Precios",
+    request_body = UpsertMarkupRuleDto,
+    responses((status = 200, description = "Regla guardada", body = MarkupRule)),
+    security(("bearer_auth" = []))
+)]
+async fn upsert_markup_rule_handler(
+    auth: AuthUser,
+    State(state): State<PricingState>,
+    Json(dto): Json<UpsertMarkupRuleDto>,
+) -> Result<Json<MarkupRule>, AppError> {
+    auth.require_owner()?;
+    Ok(Json(
+        manage_prices::upsert_markup_rule(&state.repo, dto, auth.user_id()).await?,
+    ))
+}
@@ -0,0 +1,100 @@
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::modules::audit_log::domain::entities::CreateAuditLogDto;
+use crate::modules::audit_log::domain::repositories::AuditLogRepository;
+use crate::modules::pricing::domain::entities::{CostSync, MarkupRule, PriceHistory, PriceWarning};
+use crate::shared::errors::AppError;
+
+/// Contrasta el costo de una compra con el `cost_price` vigente del combo.
+///
+/// Si cambió y hay regla de margen (la del producto o la general), crea un
+/// nuevo registro de precio con los precios de venta derivados del costo.
+/// Sin regla, no toca los precios y devuelve una advertencia.
+/// Debe llamarse dentro de la transacción de la compra.
+pub async fn sync_cost_price_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    audit: &dyn AuditLogRepository,
+    product_id: Uuid,
+    flavor_id: Uuid,
+    provider_id: Uuid,
+    unit_cost: Decimal,
+    created_by: Uuid,
+) -> Result<CostSync, AppError> {
+    let current = sqlx::query_as::<_, PriceHistory>(
+        r#"
+        SELECT * FROM price_history
+        WHERE product_id = $1 AND flavor_id = $2 AND provider_id = $3
+          AND effective_date <= NOW()
+        ORDER BY effective_date DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(product_id)
+    .bind(flavor_id)
+    .bind(provider_id)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    if current.as_ref().is_some_and(|p| p.cost_price == unit_cost) {
+        return Ok(CostSync::Unchanged);
+    }
+
+    let rule = sqlx::query_as::<_, MarkupRule>(
+        r#"
+        SELECT * FROM price_markup_rules
+        WHERE product_id = $1 OR product_id IS NULL
+        ORDER BY product_id NULLS LAST
+        LIMIT 1
+        "#,
+    )
+    .bind(product_id)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    let Some(rule) = rule else {
+        return Ok(CostSync::Warning(PriceWarning {
+            product_id,
+            flavor_id,
+            provider_id,
+            previous_cost: current.as_ref().map(|p| p.cost_price),
+            new_cost: unit_cost,
+            price_base: current.as_ref().map(|p| p.price_base),
+            price_route: current.as_ref().map(|p| p.price_route),
+            price_local: current.as_ref().map(|p| p.price_local),
+            below_cost: current
+                .as_ref()
+                .is_none_or(|p| p.price_base.min(p.price_route).min(p.price_local) < unit_cost),
+        }));
+    };
+
+    let (price_base, price_route, price_local) = rule.prices_for(unit_cost);
+    let price = sqlx::query_as::<_, PriceHistory>(
+        r#"
+        INSERT INTO price_history
+        (product_id, flavor_id, provider_id, cost_price, price_base,
+         price_route, price_local, commission, effective_date, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW(), $9)
+        RETURNING *
+        "#,
+    )
+    .bind(product_id)
+    .bind(flavor_id)
+    .bind(provider_id)
+    .bind(unit_cost)
+    .bind(price_base)
+    .bind(price_route)
+    .bind(price_local)
+    .bind(price_route - price_base)
+    .bind(created_by)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    audit
+        .create_with_tx(
+            tx,
+            CreateAuditLogDto::created("price_history", price.id, &price, created_by)?,
+        )
+        .await?;
+    Ok(CostSync::Updated(price))
+}
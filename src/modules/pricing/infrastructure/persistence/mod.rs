@@ -1 +1,2 @@
+pub mod cost_updates;
 pub mod postgres_repo;
@@ -1,19 +1,25 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use sqlx::PgPool;
+use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::modules::pricing::domain::entities::{CreatePriceDto, PriceHistory};
+use crate::modules::audit_log::domain::entities::CreateAuditLogDto;
+use crate::modules::audit_log::domain::repositories::AuditLogRepository;
+use crate::modules::pricing::domain::entities::{
+    CreatePriceDto, MarkupRule, PriceHistory, UpsertMarkupRuleDto,
+};
 use crate::modules::pricing::domain::repositories::PriceRepository;
 use crate::shared::errors::AppError;
 
 pub struct PgPriceRepository {
     pool: PgPool,
+    audit: Arc<dyn AuditLogRepository>,
 }
 
 impl PgPriceRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, audit: Arc<dyn AuditLogRepository>) -> Self {
+        Self { pool, audit }
     }
 }
 
@@ -70,7 +76,8 @@ impl PriceRepository for PgPriceRepository {
         created_by: Uuid,
     ) -> Result<PriceHistory, AppError> {
         let commission = dto.price_route - dto.price_base;
-        Ok(sqlx::query_as::<_, PriceHistory>(
+        let mut tx = self.pool.begin().await?;
+        let price = sqlx::query_as::<_, PriceHistory>(
             r#"
             INSERT INTO price_history 
             (product_id, flavor_id, provider_id, cost_price, price_base, 
@@ -88,8 +95,17 @@ impl PriceRepository for PgPriceRepository {
         .bind(dto.price_local)
         .bind(commission)
         .bind(created_by)
-        .fetch_one(&self.pool)
-        .await?)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::created("price_history", price.id, &price, created_by)?,
+            )
+            .await?;
+        tx.commit().await?;
+        Ok(price)
     }
 
     async fn get_history(
@@ -123,4 +139,62 @@ impl PriceRepository for PgPriceRepository {
         .fetch_all(&self.pool)
         .await?)
     }
+
+    async fn list_markup_rules(&self) -> Result<Vec<MarkupRule>, AppError> {
+        Ok(sqlx::query_as::<_, MarkupRule>(
+            "SELECT * FROM price_markup_rules ORDER BY product_id NULLS FIRST",
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn upsert_markup_rule(
+        &self,
+        dto: &UpsertMarkupRuleDto,
+        updated_by: Uuid,
+    ) -> Result<MarkupRule, AppError> {
+        let mut tx = self.pool.begin().await?;
+        let before = sqlx::query_as::<_, MarkupRule>(
+            "SELECT * FROM price_markup_rules WHERE product_id IS NOT DISTINCT FROM $1 FOR UPDATE",
+        )
+        .bind(dto.product_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let rule = sqlx::query_as::<_, MarkupRule>(
+            r#"
+            INSERT INTO price_markup_rules
+            (product_id, base_markup_pct, route_markup_pct, local_markup_pct, updated_by)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT ON CONSTRAINT unique_markup_rule_product DO UPDATE SET
+                base_markup_pct = EXCLUDED.base_markup_pct,
+                route_markup_pct = EXCLUDED.route_markup_pct,
+                local_markup_pct = EXCLUDED.local_markup_pct,
+                updated_at = NOW(),
+                updated_by = EXCLUDED.updated_by
+            RETURNING *
+            "#,
+        )
+        .bind(dto.product_id)
+        .bind(dto.base_markup_pct)
+        .bind(dto.route_markup_pct)
+        .bind(dto.local_markup_pct)
+        .bind(updated_by)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let entry = match &before {
+            Some(before) => CreateAuditLogDto::updated(
+                "price_markup_rules",
+                rule.id,
+                before,
+                &rule,
+                updated_by,
+            )?,
+            None => CreateAuditLogDto::created("price_markup_rules", rule.id, &rule, updated_by)?,
+        };
+        self.audit.create_with_tx(&mut tx, entry).await?;
+        tx.commit().await?;
+        Ok(rule)
+    }
 }
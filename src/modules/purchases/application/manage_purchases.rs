@@ -1,9 +1,12 @@
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::modules::purchases::domain::entities::{CreatePurchaseDto, Purchase, PurchaseWithItems};
+use crate::modules::purchases::domain::entities::{
+    CreatePurchaseDto, Purchase, PurchaseCreated, PurchaseWithItems,
+};
 use crate::modules::purchases::domain::repositories::PurchaseRepository;
 use crate::shared::errors::AppError;
+use crate::shared::void::VoidDto;
 
 pub async fn list_purchases(repo: &Arc<dyn PurchaseRepository>) -> Result<Vec<Purchase>, AppError> {
     repo.find_all().await
@@ -19,16 +22,42 @@ pub async fn get_purchase(
 }
 
 /// Crear compra e insertar stock en inventario automáticamente.
+/// Los costos distintos al vigente generan precios nuevos o advertencias.
 pub async fn create_purchase(
     repo: &Arc<dyn PurchaseRepository>,
-    dto: CreatePurchaseDto,
+    mut dto: CreatePurchaseDto,
     created_by: Uuid,
-) -> Result<PurchaseWithItems, AppError> {
+) -> Result<PurchaseCreated, AppError> {
     // Validar payment_status
     if dto.payment_status != "paid" && dto.payment_status != "credit" {
         return Err(AppError::BadRequest(
             "payment_status debe ser 'paid' o 'credit'".into(),
         ));
     }
+    // Origen del pago: solo aplica a compras pagadas, por defecto sale de caja
+    match (dto.payment_status.as_str(), dto.paid_from.as_deref()) {
+        ("paid", None) => dto.paid_from = Some("cash_register".into()),
+        ("paid", Some("cash_register" | "external")) | ("credit", None) => {}
+        ("paid", Some(_)) => {
+            return Err(AppError::BadRequest(
+                "paid_from debe ser 'cash_register' o 'external'".into(),
+            ))
+        }
+        _ => {
+            return Err(AppError::BadRequest(
+                "paid_from solo aplica a compras pagadas".into(),
+            ))
+        }
+    }
     repo.create(&dto, created_by).await
 }
+
+/// Anular compra (solo dueño). Los precios derivados de su costo se conservan.
+pub async fn void_purchase(
+    repo: &Arc<dyn PurchaseRepository>,
+    id: Uuid,
+    dto: &VoidDto,
+    voided_by: Uuid,
+) -> Result<Purchase, AppError> {
+    repo.void_purchase(id, dto.reason()?, voided_by).await
+}
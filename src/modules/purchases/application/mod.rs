@@ -1 +1,2 @@
 pub mod manage_purchases;
+pub mod provider_payables;
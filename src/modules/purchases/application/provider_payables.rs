@@ -0,0 +1,57 @@
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::modules::purchases::domain::entities::{
+    CreateProviderPaymentDto, PayablesSummary, ProviderPayables, ProviderPaymentWithAllocations,
+};
+use crate::modules::purchases::domain::repositories::ProviderPayableRepository;
+use crate::shared::errors::AppError;
+
+/// Deuda total con proveedores y saldo de cada uno.
+pub async fn payables_summary(
+    repo: &Arc<dyn ProviderPayableRepository>,
+) -> Result<PayablesSummary, AppError> {
+    let providers = repo.balances().await?;
+    Ok(PayablesSummary {
+        total_debt: providers.iter().map(|p| p.balance).sum(),
+        providers,
+    })
+}
+
+pub async fn provider_payables(
+    repo: &Arc<dyn ProviderPayableRepository>,
+    provider_id: Uuid,
+) -> Result<ProviderPayables, AppError> {
+    let open_purchases = repo.open_purchases(provider_id).await?;
+    Ok(ProviderPayables {
+        provider_id,
+        balance: open_purchases.iter().map(|p| p.balance()).sum(),
+        open_purchases,
+    })
+}
+
+pub async fn list_payments(
+    repo: &Arc<dyn ProviderPayableRepository>,
+    provider_id: Uuid,
+) -> Result<Vec<ProviderPaymentWithAllocations>, AppError> {
+    repo.find_payments(provider_id).await
+}
+
+/// Pago total o parcial de compras a crédito.
+/// El tope contra el saldo pendiente se valida en el repositorio, con las compras bloqueadas.
+pub async fn pay_provider(
+    repo: &Arc<dyn ProviderPayableRepository>,
+    provider_id: Uuid,
+    mut dto: CreateProviderPaymentDto,
+    created_by: Uuid,
+) -> Result<ProviderPaymentWithAllocations, AppError> {
+    if dto.amount <= Decimal::ZERO {
+        return Err(AppError::BadRequest(
+            "El monto del pago debe ser positivo".into(),
+        ));
+    }
+    dto.purchase_ids.sort();
+    dto.purchase_ids.dedup();
+    repo.pay(provider_id, &dto, created_by).await
+}
@@ -3,6 +3,8 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::modules::pricing::domain::entities::{PriceHistory, PriceWarning};
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
 pub struct Purchase {
     pub id: Uuid,
@@ -12,6 +14,21 @@ pub struct Purchase {
     pub paid_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub created_by: Uuid,
+    /// Abonado hasta ahora. Igual a `total` cuando la compra está pagada.
+    pub amount_paid: Decimal,
+    /// Origen del pago al contado: "cash_register" o "external". `None` en compras a crédito.
+    pub paid_from: Option<String>,
+    /// Anulada por el dueño: stock retirado y pago de caja revertido.
+    pub voided_at: Option<DateTime<Utc>>,
+    pub voided_by: Option<Uuid>,
+    pub void_reason: Option<String>,
+}
+
+impl Purchase {
+    /// Saldo pendiente con el proveedor.
+    pub fn balance(&self) -> Decimal {
+        self.total - self.amount_paid
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
@@ -38,6 +55,9 @@ pub struct CreatePurchaseItemDto {
 pub struct CreatePurchaseDto {
     pub provider_id: Uuid,
     pub payment_status: String, // "paid" o "credit"
+    /// Solo para compras pagadas: "cash_register" (por defecto, sale de caja)
+    /// o "external" (pagada fuera de caja, no afecta el saldo).
+    pub paid_from: Option<String>,
     pub items: Vec<CreatePurchaseItemDto>,
 }
 
@@ -47,3 +67,77 @@ pub struct PurchaseWithItems {
     pub purchase: Purchase,
     pub items: Vec<PurchaseItem>,
 }
+
+/// Compra registrada y efecto de sus costos sobre los precios vigentes.
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct PurchaseCreated {
+    #[serde(flatten)]
+    pub purchase: PurchaseWithItems,
+    /// Precios nuevos creados con las reglas de margen porque el costo cambió.
+    pub price_updates: Vec<PriceHistory>,
+    /// Combos con costo distinto al vigente y sin regla de margen.
+    pub price_warnings: Vec<PriceWarning>,
+}
+
+// ─── Cuentas por pagar ──────────────────────────────────
+
+/// Saldo pendiente con un proveedor.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct ProviderBalance {
+    pub provider_id: Uuid,
+    pub provider_name: String,
+    pub open_purchases: i64,
+    pub total_credit: Decimal,
+    pub total_paid: Decimal,
+    pub balance: Decimal,
+    pub oldest_unpaid_at: Option<DateTime<Utc>>,
+}
+
+/// Vista del dueño: deuda total con proveedores.
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct PayablesSummary {
+    pub total_debt: Decimal,
+    pub providers: Vec<ProviderBalance>,
+}
+
+/// Estado de cuenta de un proveedor con sus compras a crédito pendientes.
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ProviderPayables {
+    pub provider_id: Uuid,
+    pub balance: Decimal,
+    pub open_purchases: Vec<Purchase>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct ProviderPayment {
+    pub id: Uuid,
+    pub provider_id: Uuid,
+    pub amount: Decimal,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct PaymentAllocation {
+    pub payment_id: Uuid,
+    pub purchase_id: Uuid,
+    pub amount: Decimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ProviderPaymentWithAllocations {
+    #[serde(flatten)]
+    pub payment: ProviderPayment,
+    pub allocations: Vec<PaymentAllocation>,
+}
+
+/// Pago total o parcial a un proveedor. Sin `purchase_ids` se abona a las
+/// compras a crédito más antiguas primero.
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct CreateProviderPaymentDto {
+    pub amount: Decimal,
+    #[serde(default)]
+    pub purchase_ids: Vec<Uuid>,
+    pub notes: Option<String>,
+}
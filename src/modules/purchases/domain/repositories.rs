@@ -1,16 +1,46 @@
 use async_trait::async_trait;
 use uuid::Uuid;
 
-use super::entities::{CreatePurchaseDto, Purchase, PurchaseWithItems};
+use super::entities::*;
 use crate::shared::errors::AppError;
 
 #[async_trait]
 pub trait PurchaseRepository: Send + Sync {
     async fn find_all(&self) -> Result<Vec<Purchase>, AppError>;
     async fn find_by_id_with_items(&self, id: Uuid) -> Result<Option<PurchaseWithItems>, AppError>;
+    /// Registra la compra, suma inventario, sale de caja si se pagó con ella y
+    /// actualiza los precios cuyo costo cambió. Todo en una transacción.
     async fn create(
         &self,
         dto: &CreatePurchaseDto,
         created_by: Uuid,
-    ) -> Result<PurchaseWithItems, AppError>;
+    ) -> Result<PurchaseCreated, AppError>;
+    /// Anula la compra: retira el stock ingresado y revierte el pago de caja.
+    /// Las compras con abonos a proveedor no se pueden anular.
+    async fn void_purchase(
+        &self,
+        id: Uuid,
+        reason: &str,
+        voided_by: Uuid,
+    ) -> Result<Purchase, AppError>;
+}
+
+#[async_trait]
+pub trait ProviderPayableRepository: Send + Sync {
+    /// Proveedores con saldo pendiente, mayor deuda primero.
+    async fn balances(&self) -> Result<Vec<ProviderBalance>, AppError>;
+    /// Compras a crédito con saldo, más antiguas primero.
+    async fn open_purchases(&self, provider_id: Uuid) -> Result<Vec<Purchase>, AppError>;
+    async fn find_payments(
+        &self,
+        provider_id: Uuid,
+    ) -> Result<Vec<ProviderPaymentWithAllocations>, AppError>;
+    /// Distribuye el pago entre compras, actualiza saldos, registra la salida
+    /// de caja y la auditoría. Todo en una transacción.
+    async fn pay(
+        &self,
+        provider_id: Uuid,
+        dto: &CreateProviderPaymentDto,
+        created_by: Uuid,
+    ) -> Result<ProviderPaymentWithAllocations, AppError>;
 }
@@ -2,27 +2,50 @@ use std::sync::Arc;
 
 use axum::{
     extract::{Path, State},
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
 use utoipa::OpenApi;
 use uuid::Uuid;
 
-use crate::modules::purchases::application::manage_purchases;
+use crate::modules::purchases::application::{manage_purchases, provider_payables};
 use crate::modules::purchases::domain::entities::*;
-use crate::modules::purchases::domain::repositories::PurchaseRepository;
+use crate::modules::purchases::domain::repositories::{
+    ProviderPayableRepository, PurchaseRepository,
+};
 use crate::shared::auth::{AppState, AuthUser, Role};
 use crate::shared::errors::AppError;
+use crate::shared::void::VoidDto;
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(list_handler, get_handler, create_handler),
+    paths(
+        list_handler,
+        get_handler,
+        create_handler,
+        void_handler,
+        payables_summary_handler,
+        provider_payables_handler,
+        provider_payments_handler,
+        pay_provider_handler
+    ),
     components(schemas(
         crate::modules::purchases::domain::entities::Purchase,
         crate::modules::purchases::domain::entities::PurchaseItem,
         crate::modules::purchases::domain::entities::CreatePurchaseDto,
         crate::modules::purchases::domain::entities::CreatePurchaseItemDto,
         crate::modules::purchases::domain::entities::PurchaseWithItems,
+        crate::modules::purchases::domain::entities::PurchaseCreated,
+        crate::modules::pricing::domain::entities::PriceHistory,
+        crate::modules::pricing::domain::entities::PriceWarning,
+        crate::modules::purchases::domain::entities::ProviderBalance,
+        crate::modules::purchases::domain::entities::PayablesSummary,
+        crate::modules::purchases::domain::entities::ProviderPayables,
+        crate::modules::purchases::domain::entities::ProviderPayment,
+        crate::modules::purchases::domain::entities::PaymentAllocation,
+        crate::modules::purchases::domain::entities::ProviderPaymentWithAllocations,
+        crate::modules::purchases::domain::entities::CreateProviderPaymentDto,
+        crate::shared::void::VoidDto,
     ))
 )]
 pub struct PurchasesApiDoc;
@@ -31,6 +54,7 @@ pub struct PurchasesApiDoc;
 pub struct PurchasesState {
     pub app: AppState,
     pub repo: Arc<dyn PurchaseRepository>,
+    pub payables: Arc<dyn ProviderPayableRepository>,
 }
 
 impl axum::extract::FromRef<PurchasesState> for AppState {
@@ -39,15 +63,27 @@ impl axum::extract::FromRef<PurchasesState> for AppState {
     }
 }
 
-pub fn router(app_state: AppState, repo: Arc<dyn PurchaseRepository>) -> Router {
+pub fn router(
+    app_state: AppState,
+    repo: Arc<dyn PurchaseRepository>,
+    payables: Arc<dyn ProviderPayableRepository>,
+) -> Router {
     let state = PurchasesState {
         app: app_state,
         repo,
+        payables,
     };
 
     Router::new()
         .route("/", get(list_handler).post(create_handler))
+        .route("/payables", get(payables_summary_handler))
+        .route("/payables/{provider_id}", get(provider_payables_handler))
+        .route(
+            "/payables/{provider_id}/payments",
+            get(provider_payments_handler).post(pay_provider_handler),
+        )
         .route("/{id}", get(get_handler))
+        .route("/{id}/void", post(void_handler))
         .with_state(state)
 }
 
@@ -85,16 +121,106 @@ async fn get_handler(
 #[utoipa::path(
     post, path = "/", tag = "Compras",
     request_body = CreatePurchaseDto,
-    responses((status = 200, description = "Compra creada", body = PurchaseWithItems)),
+    responses((status = 200, description = "Compra creada con precios actualizados o advertencias", body = PurchaseCreated)),
     security(("bearer_auth" = []))
 )]
 async fn create_handler(
     auth: AuthUser,
     State(state): State<PurchasesState>,
     Json(dto): Json<CreatePurchaseDto>,
-) -> Result<Json<PurchaseWithItems>, AppError> {
+) -> Result<Json<PurchaseCreated>, AppError> {
     auth.require_role(Role::Admin)?;
     Ok(Json(
         manage_purchases::create_purchase(&state.repo, dto, auth.user_id()).await?,
     ))
 }
+
+#[utoipa::path(
+    post, path = "/{id}/void", tag = "Compras",
+    params(("id" = Uuid, Path, description = "ID de la compra")),
+    request_body = VoidDto,
+    responses(
+        (status = 200, description = "Compra anulada, stock retirado y pago devuelto a caja", body = Purchase),
+        (status = 409, description = "Ya anulada, con abonos a proveedor o stock ya consumido")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn void_handler(
+    auth: AuthUser,
+    State(state): State<PurchasesState>,
+    Path(id): Path<Uuid>,
+    Json(dto): Json<VoidDto>,
+) -> Result<Json<Purchase>, AppError> {
+    auth.require_owner()?;
+    Ok(Json(
+        manage_purchases::void_purchase(&state.repo, id, &dto, auth.user_id()).await?,
+    ))
+}
+
+#[utoipa::path(
+    get, path = "/payables", tag = "Compras",
+    responses((status = 200, description = "Deuda total y saldo por proveedor", body = PayablesSummary)),
+    security(("bearer_auth" = []))
+)]
+async fn payables_summary_handler(
+    auth: AuthUser,
+    State(state): State<PurchasesState>,
+) -> Result<Json<PayablesSummary>, AppError> {
+    auth.require_owner()?;
+    Ok(Json(
+        provider_payables::payables_summary(&state.payables).await?,
+    ))
+}
+
+#[utoipa::path(
+    get, path = "/payables/{provider_id}", tag = "Compras",
+    params(("provider_id" = Uuid, Path, description = "ID del proveedor")),
+    responses((status = 200, description = "Compras a crédito pendientes del proveedor", body = ProviderPayables)),
+    security(("bearer_auth" = []))
+)]
+async fn provider_payables_handler(
+    auth: AuthUser,
+    State(state): State<PurchasesState>,
+    Path(provider_id): Path<Uuid>,
+) -> Result<Json<ProviderPayables>, AppError> {
+    auth.require_role(Role::Admin)?;
+    Ok(Json(
+        provider_payables::provider_payables(&state.payables, provider_id).await?,
+    ))
+}
+
+#[utoipa::path(
+    get, path = "/payables/{provider_id}/payments", tag = "Compras",
+    params(("provider_id" = Uuid, Path, description = "ID del proveedor")),
+    responses((status = 200, description = "Historial de pagos al proveedor", body = Vec<ProviderPaymentWithAllocations>)),
+    security(("bearer_auth" = []))
+)]
+async fn provider_payments_handler(
+    auth: AuthUser,
+    State(state): State<PurchasesState>,
+    Path(provider_id): Path<Uuid>,
+) -> Result<Json<Vec<ProviderPaymentWithAllocations>>, AppError> {
+    auth.require_role(Role::Admin)?;
+    Ok(Json(
+        provider_payables::list_payments(&state.payables, provider_id).await?,
+    ))
+}
+
+#[utoipa::path(
+    post, path = "/payables/{provider_id}/payments", tag = "Compras",
+    params(("provider_id" = Uuid, Path, description = "ID del proveedor")),
+    request_body = CreateProviderPaymentDto,
+    responses((status = 200, description = "Pago registrado y descontado de caja", body = ProviderPaymentWithAllocations)),
+    security(("bearer_auth" = []))
+)]
+async fn pay_provider_handler(
+    auth: AuthUser,
+    State(state): State<PurchasesState>,
+    Path(provider_id): Path<Uuid>,
+    Json(dto): Json<CreateProviderPaymentDto>,
+) -> Result<Json<ProviderPaymentWithAllocations>, AppError> {
+    auth.require_owner()?;
+    Ok(Json(
+        provider_payables::pay_provider(&state.payables, provider_id, dto, auth.user_id()).await?,
+    ))
+}
@@ -1,19 +1,36 @@
 use async_trait::async_trait;
 use rust_decimal::Decimal;
 use sqlx::PgPool;
+use std::collections::BTreeMap;
+use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::modules::audit_log::domain::entities::CreateAuditLogDto;
+use crate::modules::audit_log::domain::repositories::AuditLogRepository;
+use crate::modules::cash_register::domain::entities::{CashEntry, CashTransactionType};
+use crate::modules::cash_register::infrastructure::persistence::ledger::{
+    append_cash_tx, reverse_doc_cash_tx,
+};
+use crate::modules::inventory::domain::entities::{MovementCause, MovementType};
+use crate::modules::inventory::infrastructure::persistence::movements::{
+    record_movement_tx, reverse_doc_movements_tx,
+};
+use crate::modules::pricing::domain::entities::CostSync;
+use crate::modules::pricing::infrastructure::persistence::cost_updates::sync_cost_price_tx;
 use crate::modules::purchases::domain::entities::*;
-use crate::modules::purchases::domain::repositories::PurchaseRepository;
+use crate::modules::purchases::domain::repositories::{
+    ProviderPayableRepository, PurchaseRepository,
+};
 use crate::shared::errors::AppError;
 
 pub struct PgPurchaseRepository {
     pool: PgPool,
+    audit: Arc<dyn AuditLogRepository>,
 }
 
 impl PgPurchaseRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, audit: Arc<dyn AuditLogRepository>) -> Self {
+        Self { pool, audit }
     }
 }
 
@@ -52,7 +69,7 @@ impl PurchaseRepository for PgPurchaseRepository {
         &self,
         dto: &CreatePurchaseDto,
         created_by: Uuid,
-    ) -> Result<PurchaseWithItems, AppError> {
+    ) -> Result<PurchaseCreated, AppError> {
         let mut tx = self.pool.begin().await?;
 
         // Calcular total
@@ -62,17 +79,18 @@ impl PurchaseRepository for PgPurchaseRepository {
             .map(|i| i.unit_price * Decimal::from(i.quantity))
             .sum();
 
-        let paid_at = if dto.payment_status == "paid" {
-            Some(chrono::Utc::now())
+        let (paid_at, amount_paid) = if dto.payment_status == "paid" {
+            (Some(chrono::Utc::now()), total)
         } else {
-            None
+            (None, Decimal::ZERO)
         };
 
         // Crear la compra
         let purchase = sqlx::query_as::<_, Purchase>(
             r#"
-            INSERT INTO purchases (provider_id, total, payment_status, paid_at, created_by)
-            VALUES ($1, $2, $3, $4, $5)
+            INSERT INTO purchases
+            (provider_id, total, payment_status, paid_at, amount_paid, paid_from, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             RETURNING *
             "#,
         )
@@ -80,6 +98,8 @@ impl PurchaseRepository for PgPurchaseRepository {
         .bind(total)
         .bind(&dto.payment_status)
         .bind(paid_at)
+        .bind(amount_paid)
+        .bind(&dto.paid_from)
         .bind(created_by)
         .fetch_one(&mut *tx)
         .await?;
@@ -105,7 +125,7 @@ impl PurchaseRepository for PgPurchaseRepository {
             .await?;
 
             // UPSERT inventario — agregar stock comprado
-            sqlx::query(
+            let inventory_id = sqlx::query_scalar::<_, Uuid>(
                 r#"
                 INSERT INTO inventory 
                 (freezer_id, product_id, flavor_id, provider_id, quantity, is_deformed, min_stock_alert, updated_by)
@@ -115,6 +135,7 @@ impl PurchaseRepository for PgPurchaseRepository {
                     quantity = inventory.quantity + EXCLUDED.quantity,
                     last_updated = NOW(),
                     updated_by = EXCLUDED.updated_by
+                RETURNING id
                 "#,
             )
             .bind(item_dto.freezer_id)
@@ -123,14 +144,362 @@ impl PurchaseRepository for PgPurchaseRepository {
             .bind(dto.provider_id)
             .bind(item_dto.quantity)
             .bind(created_by)
-            .execute(&mut *tx)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            record_movement_tx(
+                &mut tx,
+                inventory_id,
+                item_dto.quantity,
+                MovementCause::doc(MovementType::Purchase, "purchases", purchase.id),
+                created_by,
+            )
             .await?;
 
             items.push(item);
         }
 
+        // Compra pagada con dinero de la caja: salida de caja (Event Sourcing)
+        if purchase.paid_from.as_deref() == Some("cash_register") {
+            append_cash_tx(
+                &mut tx,
+                CashEntry::doc(
+                    CashTransactionType::PurchasePayment,
+                    -total,
+                    "purchases",
+                    purchase.id,
+                )
+                .with_description(Some("Compra a proveedor".into())),
+                created_by,
+            )
+            .await?;
+        }
+
+        // Costos de compra contra price_history. Si un combo llega a precios
+        // distintos en la misma compra se toma el mayor, para no vender bajo costo.
+        let mut costs: BTreeMap<(Uuid, Uuid), Decimal> = BTreeMap::new();
+        for item in &dto.items {
+            let cost = costs.entry((item.product_id, item.flavor_id)).or_default();
+            *cost = (*cost).max(item.unit_price);
+        }
+
+        let mut price_updates = Vec::new();
+        let mut price_warnings = Vec::new();
+        for ((product_id, flavor_id), unit_cost) in costs {
+            match sync_cost_price_tx(
+                &mut tx,
+                self.audit.as_ref(),
+                product_id,
+                flavor_id,
+                dto.provider_id,
+                unit_cost,
+                created_by,
+            )
+            .await?
+            {
+                CostSync::Unchanged => {}
+                CostSync::Updated(price) => price_updates.push(price),
+                CostSync::Warning(warning) => price_warnings.push(warning),
+            }
+        }
+
+        let purchase = PurchaseWithItems { purchase, items };
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::created(
+                    "purchases",
+                    purchase.purchase.id,
+                    &purchase,
+                    created_by,
+                )?,
+            )
+            .await?;
         tx.commit().await?;
 
-        Ok(PurchaseWithItems { purchase, items })
+        Ok(PurchaseCreated {
+            purchase,
+            price_updates,
+            price_warnings,
+        })
+    }
+
+    async fn void_purchase(
+        &self,
+        id: Uuid,
+        reason: &str,
+        voided_by: Uuid,
+    ) -> Result<Purchase, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // 1. Compra (con lock)
+        let existing =
+            sqlx::query_as::<_, Purchase>("SELECT * FROM purchases WHERE id = $1 FOR UPDATE")
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or_else(|| AppError::NotFound(format!("Compra {id} no encontrada")))?;
+        if existing.voided_at.is_some() {
+            return Err(AppError::Conflict("La compra ya fue anulada".into()));
+        }
+
+        // Abonos ya entregados al proveedor: la anulación no los devuelve
+        let has_payments = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM provider_payment_allocations WHERE purchase_id = $1)",
+        )
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if has_payments {
+            return Err(AppError::Conflict(
+                "La compra tiene abonos a proveedor; no se puede anular".into(),
+            ));
+        }
+
+        // 2. Retirar el stock de las filas donde ingresó
+        reverse_doc_movements_tx(&mut tx, "purchases", id, voided_by).await?;
+
+        // 3. Devolver a caja el pago al contado (si salió de caja)
+        reverse_doc_cash_tx(&mut tx, "purchases", id, reason, voided_by).await?;
+
+        // 4. Marcar como anulada
+        let purchase = sqlx::query_as::<_, Purchase>(
+            r#"
+            UPDATE purchases
+            SET voided_at = NOW(), voided_by = $1, void_reason = $2
+            WHERE id = $3
+            RETURNING *
+            "#,
+        )
+        .bind(voided_by)
+        .bind(reason)
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // 5. Auditoría
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::updated("purchases", id, &existing, &purchase, voided_by)?,
+            )
+            .await?;
+
+        tx.commit().await?;
+        Ok(purchase)
+    }
+}
+
+// ═══════════════════════════════════════════════════════════
+// Cuentas por pagar a proveedores
+// ═══════════════════════════════════════════════════════════
+
+pub struct PgProviderPayableRepository {
+    pool: PgPool,
+    audit: Arc<dyn AuditLogRepository>,
+}
+
+impl PgProviderPayableRepository {
+    pub fn new(pool: PgPool, audit: Arc<dyn AuditLogRepository>) -> Self {
+        Self { pool, audit }
+    }
+}
+
+#[async_trait]
+impl ProviderPayableRepository for PgProviderPayableRepository {
+    async fn balances(&self) -> Result<Vec<ProviderBalance>, AppError> {
+        Ok(sqlx::query_as::<_, ProviderBalance>(
+            r#"
+            SELECT p.provider_id, pr.name AS provider_name,
+                   COUNT(*) AS open_purchases,
+                   SUM(p.total) AS total_credit,
+                   SUM(p.amount_paid) AS total_paid,
+                   SUM(p.total - p.amount_paid) AS balance,
+                   MIN(p.created_at) AS oldest_unpaid_at
+            FROM purchases p
+            JOIN providers pr ON pr.id = p.provider_id
+            WHERE p.payment_status = 'credit' AND p.amount_paid < p.total AND p.voided_at IS NULL
+            GROUP BY p.provider_id, pr.name
+            ORDER BY balance DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn open_purchases(&self, provider_id: Uuid) -> Result<Vec<Purchase>, AppError> {
+        Ok(sqlx::query_as::<_, Purchase>(
+            r#"
+            SELECT * FROM purchases
+            WHERE provider_id = $1 AND payment_status = 'credit' AND amount_paid < total
+              AND voided_at IS NULL
+            ORDER BY created_at
+            "#,
+        )
+        .bind(provider_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn find_payments(
+        &self,
+        provider_id: Uuid,
+    ) -> Result<Vec<ProviderPaymentWithAllocations>, AppError> {
+        let payments = sqlx::query_as::<_, ProviderPayment>(
+            "SELECT * FROM provider_payments WHERE provider_id = $1 ORDER BY created_at DESC",
+        )
+        .bind(provider_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let ids: Vec<Uuid> = payments.iter().map(|p| p.id).collect();
+        let allocations = sqlx::query_as::<_, PaymentAllocation>(
+            "SELECT * FROM provider_payment_allocations WHERE payment_id = ANY($1)",
+        )
+        .bind(&ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(payments
+            .into_iter()
+            .map(|payment| {
+                let allocations = allocations
+                    .iter()
+                    .filter(|a| a.payment_id == payment.id)
+                    .cloned()
+                    .collect();
+                ProviderPaymentWithAllocations {
+                    payment,
+                    allocations,
+                }
+            })
+            .collect())
+    }
+
+    async fn pay(
+        &self,
+        provider_id: Uuid,
+        dto: &CreateProviderPaymentDto,
+        created_by: Uuid,
+    ) -> Result<ProviderPaymentWithAllocations, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // 1. Compras pendientes del proveedor (con lock), más antiguas primero
+        let purchases = sqlx::query_as::<_, Purchase>(
+            r#"
+            SELECT * FROM purchases
+            WHERE provider_id = $1 AND payment_status = 'credit' AND amount_paid < total
+              AND voided_at IS NULL
+              AND (cardinality($2::uuid[]) = 0 OR id = ANY($2))
+            ORDER BY created_at
+            FOR UPDATE
+            "#,
+        )
+        .bind(provider_id)
+        .bind(&dto.purchase_ids)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        if !dto.purchase_ids.is_empty() && purchases.len() != dto.purchase_ids.len() {
+            return Err(AppError::BadRequest(
+                "Las compras deben ser del proveedor y tener saldo pendiente".into(),
+            ));
+        }
+
+        let outstanding: Decimal = purchases.iter().map(|p| p.balance()).sum();
+        if dto.amount > outstanding {
+            return Err(AppError::BadRequest(format!(
+                "El pago ({}) supera el saldo pendiente ({})",
+                dto.amount, outstanding
+            )));
+        }
+
+        // 2. Registrar pago
+        let payment = sqlx::query_as::<_, ProviderPayment>(
+            r#"
+            INSERT INTO provider_payments (provider_id, amount, notes, created_by)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+        )
+        .bind(provider_id)
+        .bind(dto.amount)
+        .bind(&dto.notes)
+        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // 3. Distribuir entre compras; las saldadas pasan a 'paid'
+        let mut remaining = dto.amount;
+        let mut allocations = Vec::new();
+        for purchase in &purchases {
+            if remaining <= Decimal::ZERO {
+                break;
+            }
+            let amount = remaining.min(purchase.balance());
+            remaining -= amount;
+
+            sqlx::query(
+                r#"
+                UPDATE purchases SET
+                    amount_paid = amount_paid + $1,
+                    payment_status = CASE WHEN amount_paid + $1 >= total THEN 'paid' ELSE payment_status END,
+                    paid_at = CASE WHEN amount_paid + $1 >= total THEN NOW() ELSE paid_at END
+                WHERE id = $2
+                "#,
+            )
+            .bind(amount)
+            .bind(purchase.id)
+            .execute(&mut *tx)
+            .await?;
+
+            let allocation = sqlx::query_as::<_, PaymentAllocation>(
+                r#"
+                INSERT INTO provider_payment_allocations (payment_id, purchase_id, amount)
+                VALUES ($1, $2, $3)
+                RETURNING *
+                "#,
+            )
+            .bind(payment.id)
+            .bind(purchase.id)
+            .bind(amount)
+            .fetch_one(&mut *tx)
+            .await?;
+            allocations.push(allocation);
+        }
+
+        // 4. Salida de caja (Event Sourcing)
+        append_cash_tx(
+            &mut tx,
+            CashEntry::doc(
+                CashTransactionType::ProviderPayment,
+                -dto.amount,
+                "provider_payments",
+                payment.id,
+            )
+            .with_description(dto.notes.clone()),
+            created_by,
+        )
+        .await?;
+
+        // 5. Auditoría
+        let result = ProviderPaymentWithAllocations {
+            payment,
+            allocations,
+        };
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::created(
+                    "provider_payments",
+                    result.payment.id,
+                    &result,
+                    created_by,
+                )?,
+            )
+            .await?;
+
+        tx.commit().await?;
+        Ok(result)
     }
 }
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use uuid::Uuid;
+
+use crate::modules::reconciliation::domain::entities::ReconciliationReport;
+use crate::modules::reconciliation::domain::repositories::ReconciliationRepository;
+use crate::shared::errors::AppError;
+
+pub async fn check(repo: &dyn ReconciliationRepository) -> Result<ReconciliationReport, AppError> {
+    let check = repo.check_counters().await?;
+    Ok(ReconciliationReport::new(check, false))
+}
+
+pub async fn repair(
+    repo: &dyn ReconciliationRepository,
+    repaired_by: Uuid,
+) -> Result<ReconciliationReport, AppError> {
+    let check = repo.repair_counters(repaired_by).await?;
+    let repaired = !(check.workers.is_empty() && check.routes.is_empty());
+    Ok(ReconciliationReport::new(check, repaired))
+}
+
+/// Revisión periódica: solo informa en el log. La reparación queda en
+/// manos del dueño, que decide si la diferencia es un error de datos.
+pub async fn run_scheduled(repo: Arc<dyn ReconciliationRepository>, every: Duration) {
+    let mut interval = tokio::time::interval(every);
+    loop {
+        interval.tick().await;
+        match check(repo.as_ref()).await {
+            Ok(report) if report.is_consistent => {
+                tracing::debug!("Contadores consistentes");
+            }
+            Ok(report) => {
+                for w in &report.workers {
+                    tracing::warn!(
+                        worker_id = %w.worker_id,
+                        current_debt = %w.current_debt,
+                        expected_current_debt = %w.expected_current_debt,
+                        total_sales = w.total_sales,
+                        expected_total_sales = w.expected_total_sales,
+                        "Contadores del trabajador descuadrados"
+                    );
+                }
+                for r in &report.routes {
+                    tracing::warn!(
+                        route_id = %r.route_id,
+                        usage_count = r.usage_count,
+                        expected_usage_count = r.expected_usage_count,
+                        "Uso de la ruta descuadrado"
+                    );
+                }
+            }
+            Err(e) => tracing::error!("Error revisando contadores: {e:?}"),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+// ─── Contadores desnormalizados ─────────────────────────
+
+/// Contadores de un trabajador: guardados en `workers` frente a los
+/// recalculados desde viajes retornados y pagos.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
+pub struct WorkerCounterDrift {
+    pub worker_id: Uuid,
+    pub name: String,
+    pub current_debt: Decimal,
+    /// Σ `amount_due` de viajes retornados − Σ pagos.
+    pub expected_current_debt: Decimal,
+    pub total_sales: i32,
+    /// Σ `sold_quantity` de viajes retornados.
+    pub expected_total_sales: i32,
+    pub last_sale: Option<DateTime<Utc>>,
+    /// Último `return_time` de un viaje retornado.
+    pub expected_last_sale: Option<DateTime<Utc>>,
+}
+
+/// Valores que se corrigen en `workers` (antes/después en la auditoría).
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerCounters {
+    pub current_debt: Decimal,
+    pub total_sales: i32,
+    pub last_sale: Option<DateTime<Utc>>,
+}
+
+impl WorkerCounterDrift {
+    pub fn stored(&self) -> WorkerCounters {
+        WorkerCounters {
+            current_debt: self.current_debt,
+            total_sales: self.total_sales,
+            last_sale: self.last_sale,
+        }
+    }
+
+    pub fn expected(&self) -> WorkerCounters {
+        WorkerCounters {
+            current_debt: self.expected_current_debt,
+            total_sales: self.expected_total_sales,
+            last_sale: self.expected_last_sale,
+        }
+    }
+}
+
+/// Usos de una ruta: guardado en `routes` frente a viajes no cancelados
+/// más ventas del dueño no anuladas.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
+pub struct RouteCounterDrift {
+    pub route_id: Uuid,
+    pub name: String,
+    pub usage_count: i32,
+    pub expected_usage_count: i32,
+}
+
+/// Resultado de recorrer todos los contadores. Solo trae los registros
+/// con diferencias.
+#[derive(Debug, Clone, Default)]
+pub struct CounterCheck {
+    pub workers_checked: i64,
+    pub routes_checked: i64,
+    pub workers: Vec<WorkerCounterDrift>,
+    pub routes: Vec<RouteCounterDrift>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ReconciliationReport {
+    pub checked_at: DateTime<Utc>,
+    pub workers_checked: i64,
+    pub routes_checked: i64,
+    pub workers: Vec<WorkerCounterDrift>,
+    pub routes: Vec<RouteCounterDrift>,
+    /// Ningún contador difiere de su recálculo.
+    pub is_consistent: bool,
+    /// Las diferencias listadas ya se corrigieron (con auditoría).
+    pub repaired: bool,
+}
+
+impl ReconciliationReport {
+    pub fn new(check: CounterCheck, repaired: bool) -> Self {
+        Self {
+            checked_at: Utc::now(),
+            workers_checked: check.workers_checked,
+            routes_checked: check.routes_checked,
+            is_consistent: check.workers.is_empty() && check.routes.is_empty(),
+            workers: check.workers,
+            routes: check.routes,
+            repaired,
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use super::entities::CounterCheck;
+use crate::shared::errors::AppError;
+
+#[async_trait]
+pub trait ReconciliationRepository: Send + Sync {
+    /// Recalcula los contadores de trabajadores y rutas y devuelve los que difieren.
+    async fn check_counters(&self) -> Result<CounterCheck, AppError>;
+    /// Igual que `check_counters`, pero con las filas bloqueadas y escribiendo
+    /// el valor recalculado (auditado) en cada registro con diferencias.
+    async fn repair_counters(&self, repaired_by: Uuid) -> Result<CounterCheck, AppError>;
+}
@@ -0,0 +1,75 @@
+use axum::{
+    extract::State,
+    routing::{get, post},
+    Json, Router,
+};
+use std::sync::Arc;
+use utoipa::OpenApi;
+
+use crate::modules::reconciliation::application::reconcile_counters;
+use crate::modules::reconciliation::domain::entities::*;
+use crate::modules::reconciliation::domain::repositories::ReconciliationRepository;
+use crate::shared::auth::{AppState, AuthUser, Role};
+use crate::shared::errors::AppError;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(check_counters, repair_counters),
+    components(schemas(
+        crate::modules::reconciliation::domain::entities::ReconciliationReport,
+        crate::modules::reconciliation::domain::entities::WorkerCounterDrift,
+        crate::modules::reconciliation::domain::entities::RouteCounterDrift,
+    ))
+)]
+pub struct ReconciliationApiDoc;
+
+#[derive(Clone)]
+pub struct ReconciliationState {
+    pub app: AppState,
+    pub repo: Arc<dyn ReconciliationRepository>,
+}
+
+impl axum::extract::FromRef<ReconciliationState> for AppState {
+    fn from_ref(s: &ReconciliationState) -> AppState {
+        s.app.clone()
+    }
+}
+
+pub fn router(app: AppState, repo: Arc<dyn ReconciliationRepository>) -> Router {
+    let state = ReconciliationState { app, repo };
+    Router::new()
+        .route("/counters", get(check_counters))
+        .route("/counters/repair", post(repair_counters))
+        .with_state(state)
+}
+
+#[utoipa::path(
+    get, path = "/counters", tag = "Conciliación",
+    responses((status = 200, description = "Contadores con diferencias", body = ReconciliationReport)),
+    security(("bearer_auth" = []))
+)]
+async fn check_counters(
+    State(state): State<ReconciliationState>,
+    auth: AuthUser,
+) -> Result<Json<ReconciliationReport>, AppError> {
+    auth.require_role(Role::Admin)?;
+    let report = reconcile_counters::check(state.repo.as_ref()).await?;
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    post, path = "/counters/repair", tag = "Conciliación",
+    responses(
+        (status = 200, description = "Contadores corregidos y auditados", body = ReconciliationReport),
+        (status = 403, description = "Solo el dueño")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn repair_counters(
+    State(state): State<ReconciliationState>,
+    auth: AuthUser,
+) -> Result<Json<ReconciliationReport>, AppError> {
+    auth.require_owner()?;
+    let report = reconcile_counters::repair(state.repo.as_ref(), auth.user_id()).await?;
+    Ok(Json(report))
+}
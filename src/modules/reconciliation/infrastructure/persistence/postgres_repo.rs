@@ -0,0 +1,175 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::modules::audit_log::domain::entities::CreateAuditLogDto;
+use crate::modules::audit_log::domain::repositories::AuditLogRepository;
+use crate::modules::reconciliation::domain::entities::{
+    CounterCheck, RouteCounterDrift, WorkerCounterDrift,
+};
+use crate::modules::reconciliation::domain::repositories::ReconciliationRepository;
+use crate::shared::errors::AppError;
+
+pub struct PgReconciliationRepository {
+    pool: PgPool,
+    audit: Arc<dyn AuditLogRepository>,
+}
+
+impl PgReconciliationRepository {
+    pub fn new(pool: PgPool, audit: Arc<dyn AuditLogRepository>) -> Self {
+        Self { pool, audit }
+    }
+}
+
+/// Recalcula cada contador desde los documentos y devuelve solo los que
+/// difieren. Las mismas reglas que aplican los repositorios al escribir:
+/// - deuda: + `amount_due` al retornar (y la diferencia al corregir), − cada pago;
+/// - ventas y última venta: al retornar un viaje;
+/// - uso de ruta: + al salir (viaje o venta del dueño), − al cancelar o anular.
+async fn find_drift_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<CounterCheck, AppError> {
+    let workers = sqlx::query_as::<_, WorkerCounterDrift>(
+        r#"
+        SELECT * FROM (
+            SELECT w.id AS worker_id, w.name::text AS name,
+                   w.current_debt,
+                   COALESCE(t.amount_due, 0) - COALESCE(p.amount, 0) AS expected_current_debt,
+                   w.total_sales,
+                   COALESCE(t.sold_quantity, 0)::int AS expected_total_sales,
+                   w.last_sale,
+                   t.last_sale AS expected_last_sale
+            FROM workers w
+            LEFT JOIN (
+                SELECT worker_id, SUM(amount_due) AS amount_due,
+                       SUM(sold_quantity) AS sold_quantity, MAX(return_time) AS last_sale
+                FROM worker_trips
+                WHERE status = 'returned'
+                GROUP BY worker_id
+            ) t ON t.worker_id = w.id
+            LEFT JOIN (
+                SELECT worker_id, SUM(amount) AS amount
+                FROM worker_payments
+                GROUP BY worker_id
+            ) p ON p.worker_id = w.id
+        ) c
+        WHERE c.current_debt <> c.expected_current_debt
+           OR c.total_sales <> c.expected_total_sales
+           OR c.last_sale IS DISTINCT FROM c.expected_last_sale
+        ORDER BY c.name, c.worker_id
+        "#,
+    )
+    .fetch_all(&mut **tx)
+    .await?;
+
+    let routes = sqlx::query_as::<_, RouteCounterDrift>(
+        r#"
+        SELECT * FROM (
+            SELECT r.id AS route_id, r.name::text AS name, r.usage_count,
+                   (
+                       (SELECT COUNT(*) FROM worker_trips t
+                        WHERE t.route_id = r.id AND t.status <> 'cancelled')
+                     + (SELECT COUNT(*) FROM owner_sales s
+                        WHERE s.route_id = r.id AND s.voided_at IS NULL)
+                   )::int AS expected_usage_count
+            FROM routes r
+        ) c
+        WHERE c.usage_count <> c.expected_usage_count
+        ORDER BY c.name, c.route_id
+        "#,
+    )
+    .fetch_all(&mut **tx)
+    .await?;
+
+    let (workers_checked, routes_checked) = sqlx::query_as::<_, (i64, i64)>(
+        "SELECT (SELECT COUNT(*) FROM workers), (SELECT COUNT(*) FROM routes)",
+    )
+    .fetch_one(&mut **tx)
+    .await?;
+
+    Ok(CounterCheck {
+        workers_checked,
+        routes_checked,
+        workers,
+        routes,
+    })
+}
+
+#[async_trait]
+impl ReconciliationRepository for PgReconciliationRepository {
+    async fn check_counters(&self) -> Result<CounterCheck, AppError> {
+        // Una sola instantánea para trabajadores y rutas
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ READ ONLY")
+            .execute(&mut *tx)
+            .await?;
+        let check = find_drift_tx(&mut tx).await?;
+        tx.commit().await?;
+        Ok(check)
+    }
+
+    async fn repair_counters(&self, repaired_by: Uuid) -> Result<CounterCheck, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // 1. Bloquear contadores: quien escribe en ellos espera a la reparación
+        sqlx::query("SELECT id FROM workers ORDER BY id FOR UPDATE")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("SELECT id FROM routes ORDER BY id FOR UPDATE")
+            .execute(&mut *tx)
+            .await?;
+
+        // 2. Recalcular y escribir lo que difiere
+        let check = find_drift_tx(&mut tx).await?;
+
+        for w in &check.workers {
+            sqlx::query(
+                "UPDATE workers SET current_debt = $1, total_sales = $2, last_sale = $3 WHERE id = $4",
+            )
+            .bind(w.expected_current_debt)
+            .bind(w.expected_total_sales)
+            .bind(w.expected_last_sale)
+            .bind(w.worker_id)
+            .execute(&mut *tx)
+            .await?;
+
+            self.audit
+                .create_with_tx(
+                    &mut tx,
+                    CreateAuditLogDto::updated(
+                        "workers",
+                        w.worker_id,
+                        &w.stored(),
+                        &w.expected(),
+                        repaired_by,
+                    )?,
+                )
+                .await?;
+        }
+
+        for r in &check.routes {
+            sqlx::query("UPDATE routes SET usage_count = $1 WHERE id = $2")
+                .bind(r.expected_usage_count)
+                .bind(r.route_id)
+                .execute(&mut *tx)
+                .await?;
+
+            self.audit
+                .create_with_tx(
+                    &mut tx,
+                    CreateAuditLogDto::updated(
+                        "routes",
+                        r.route_id,
+                        &serde_json::json!({ "usage_count": r.usage_count }),
+                        &serde_json::json!({ "usage_count": r.expected_usage_count }),
+                        repaired_by,
+                    )?,
+                )
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(check)
+    }
+}
@@ -0,0 +1,58 @@
+use chrono::{Duration, NaiveDate, Utc};
+
+use crate::modules::reports::domain::entities::{sales_totals, Dashboard, DashboardQuery};
+use crate::modules::reports::domain::repositories::ReportsRepository;
+use crate::shared::errors::AppError;
+
+const DEFAULT_DAYS: i64 = 30;
+const MAX_DAYS: i64 = 366;
+const DEFAULT_TOP: i64 = 5;
+const MAX_TOP: i64 = 50;
+
+pub async fn get_dashboard(
+    repo: &dyn ReportsRepository,
+    query: &DashboardQuery,
+) -> Result<Dashboard, AppError> {
+    get_dashboard_at(repo, query, Utc::now().date_naive()).await
+}
+
+/// Tablero con la ventana terminando en `today` (inclusive).
+pub async fn get_dashboard_at(
+    repo: &dyn ReportsRepository,
+    query: &DashboardQuery,
+    today: NaiveDate,
+) -> Result<Dashboard, AppError> {
+    let days = query.days.unwrap_or(DEFAULT_DAYS);
+    if !(1..=MAX_DAYS).contains(&days) {
+        return Err(AppError::BadRequest(format!(
+            "La ventana debe ser de 1 a {MAX_DAYS} días"
+        )));
+    }
+    let top = query.top.unwrap_or(DEFAULT_TOP);
+    if !(1..=MAX_TOP).contains(&top) {
+        return Err(AppError::BadRequest(format!(
+            "El ranking debe ser de 1 a {MAX_TOP} elementos"
+        )));
+    }
+    let from = today - Duration::days(days - 1);
+
+    let (cash_available, sales, top_workers, top_products, alerts) = tokio::try_join!(
+        repo.cash_available(),
+        repo.daily_sales(from, today),
+        repo.top_workers(from, today, top),
+        repo.top_products(from, today, top),
+        repo.alerts(from, today),
+    )?;
+
+    Ok(Dashboard {
+        generated_at: Utc::now(),
+        from,
+        to: today,
+        cash_available,
+        totals: sales_totals(&sales),
+        sales,
+        top_workers,
+        top_products,
+        alerts,
+    })
+}
@@ -0,0 +1,12 @@
+use chrono::Utc;
+
+use crate::modules::reports::domain::entities::{value_inventory, InventoryValuation};
+use crate::modules::reports::domain::repositories::ReportsRepository;
+use crate::shared::errors::AppError;
+
+pub async fn get_inventory_valuation(
+    repo: &dyn ReportsRepository,
+) -> Result<InventoryValuation, AppError> {
+    let rows = repo.inventory_valuation().await?;
+    Ok(value_inventory(&rows, Utc::now()))
+}
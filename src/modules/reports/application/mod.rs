@@ -0,0 +1,5 @@
+pub mod dashboard;
+pub mod inventory_valuation;
+pub mod profit_loss;
+pub mod route_profitability;
+pub mod sales_report;
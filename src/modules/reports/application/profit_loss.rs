@@ -0,0 +1,136 @@
+use chrono::{Months, NaiveDate, Utc};
+
+use crate::modules::reports::domain::entities::{
+    month_start, next_month, profit_and_loss, ProfitLossQuery, ProfitLossReport,
+};
+use crate::modules::reports::domain::repositories::ReportsRepository;
+use crate::shared::csv::CsvWriter;
+use crate::shared::errors::AppError;
+
+const DEFAULT_MONTHS: u32 = 6;
+const MAX_MONTHS: usize = 24;
+
+pub async fn get_profit_loss(
+    repo: &dyn ReportsRepository,
+    query: &ProfitLossQuery,
+) -> Result<ProfitLossReport, AppError> {
+    get_profit_loss_at(repo, query, Utc::now().date_naive()).await
+}
+
+/// Estado de resultados de los meses completos entre `from` y `to`; el mes
+/// de `today` se incluye con lo registrado hasta ahora.
+pub async fn get_profit_loss_at(
+    repo: &dyn ReportsRepository,
+    query: &ProfitLossQuery,
+    today: NaiveDate,
+) -> Result<ProfitLossReport, AppError> {
+    let out_of_range = || AppError::BadRequest("Fecha fuera de rango".into());
+    let to = month_start(query.to.unwrap_or(today));
+    let from = match query.from {
+        Some(from) => month_start(from),
+        None => to
+            .checked_sub_months(Months::new(DEFAULT_MONTHS - 1))
+            .ok_or_else(out_of_range)?,
+    };
+    if from > to {
+        return Err(AppError::BadRequest(
+            "El mes inicial no puede ser posterior al final".into(),
+        ));
+    }
+    let months: Vec<NaiveDate> =
+        std::iter::successors(Some(from), |m| next_month(*m).filter(|n| *n <= to))
+            .take(MAX_MONTHS + 1)
+            .collect();
+    if months.len() > MAX_MONTHS {
+        return Err(AppError::BadRequest(format!(
+            "El reporte no puede superar {MAX_MONTHS} meses"
+        )));
+    }
+
+    let last_day = next_month(to)
+        .and_then(|m| m.pred_opt())
+        .ok_or_else(out_of_range)?;
+    let (daily, costs, outflows) = tokio::try_join!(
+        repo.daily_sales(from, last_day),
+        repo.monthly_cost_of_goods(from, last_day),
+        repo.monthly_outflows(from, last_day),
+    )?;
+
+    Ok(ProfitLossReport {
+        from,
+        to,
+        months: profit_and_loss(&months, &daily, &costs, &outflows),
+    })
+}
+
+/// Un mes por fila y una columna por categoría de gasto del periodo, para
+/// comparar meses lado a lado en una hoja de cálculo.
+pub fn render_csv(report: &ProfitLossReport) -> String {
+    let mut categories: Vec<&str> = report
+        .months
+        .iter()
+        .flat_map(|m| m.expenses.iter().map(|e| e.category.as_str()))
+        .collect();
+    categories.sort_unstable();
+    categories.dedup();
+
+    let mut header: Vec<String> = [
+        "mes",
+        "ventas_viajes",
+        "ventas_dueno",
+        "ventas_local",
+        "ventas_totales",
+        "unidades",
+        "costo_ventas",
+        "unidades_sin_costo",
+        "margen_bruto",
+        "margen_bruto_pct",
+    ]
+    .iter()
+    .map(|h| h.to_string())
+    .collect();
+    header.extend(categories.iter().map(|c| format!("gasto_{c}")));
+    header.extend(
+        [
+            "gastos_operativos",
+            "retiros_dueno",
+            "utilidad_neta",
+            "variacion_utilidad_pct",
+        ]
+        .iter()
+        .map(|h| h.to_string()),
+    );
+    let header: Vec<&str> = header.iter().map(String::as_str).collect();
+    let mut csv = CsvWriter::new(&header);
+
+    let opt = |v: Option<rust_decimal::Decimal>| v.map(|d| d.to_string()).unwrap_or_default();
+    for m in &report.months {
+        let mut row = vec![
+            m.month.format("%Y-%m").to_string(),
+            m.revenue.worker_trips.to_string(),
+            m.revenue.owner_sales.to_string(),
+            m.revenue.local_sales.to_string(),
+            m.revenue.total.to_string(),
+            m.revenue.units.to_string(),
+            m.cost_of_goods_sold.to_string(),
+            m.uncosted_units.to_string(),
+            m.gross_margin.to_string(),
+            opt(m.gross_margin_pct),
+        ];
+        row.extend(categories.iter().map(|c| {
+            m.expenses
+                .iter()
+                .find(|e| e.category == *c)
+                .map(|e| e.amount.to_string())
+                .unwrap_or_else(|| "0".into())
+        }));
+        row.extend([
+            m.operating_expenses.to_string(),
+            m.owner_withdrawals.to_string(),
+            m.net_profit.to_string(),
+            opt(m.net_profit_change_pct),
+        ]);
+        csv.row(row);
+    }
+    csv.finish()
+}
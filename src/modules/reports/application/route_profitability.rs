@@ -0,0 +1,49 @@
+use chrono::{Duration, NaiveDate, Utc};
+
+use crate::modules::reports::domain::entities::{
+    route_profitability, route_totals, RouteProfitabilityQuery, RouteProfitabilityReport,
+};
+use crate::modules::reports::domain::repositories::ReportsRepository;
+use crate::shared::errors::AppError;
+
+const DEFAULT_DAYS: i64 = 30;
+const MAX_DAYS: i64 = 731;
+
+pub async fn get_route_profitability(
+    repo: &dyn ReportsRepository,
+    query: &RouteProfitabilityQuery,
+) -> Result<RouteProfitabilityReport, AppError> {
+    get_route_profitability_at(repo, query, Utc::now().date_naive()).await
+}
+
+pub async fn get_route_profitability_at(
+    repo: &dyn ReportsRepository,
+    query: &RouteProfitabilityQuery,
+    today: NaiveDate,
+) -> Result<RouteProfitabilityReport, AppError> {
+    let to = query.to.unwrap_or(today);
+    let from = query
+        .from
+        .unwrap_or_else(|| to - Duration::days(DEFAULT_DAYS - 1));
+    if from > to {
+        return Err(AppError::BadRequest(
+            "La fecha inicial no puede ser posterior a la final".into(),
+        ));
+    }
+    if (to - from).num_days() + 1 > MAX_DAYS {
+        return Err(AppError::BadRequest(format!(
+            "El rango no puede superar {MAX_DAYS} días"
+        )));
+    }
+
+    let rows = repo
+        .route_profitability(from, to, query.route_id, query.worker_id)
+        .await?;
+
+    Ok(RouteProfitabilityReport {
+        from,
+        to,
+        routes: route_profitability(&rows),
+        totals: route_totals(&rows),
+    })
+}
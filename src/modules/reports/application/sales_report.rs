@@ -0,0 +1,90 @@
+use chrono::{Duration, NaiveDate, Utc};
+
+use crate::modules::reports::domain::entities::{
+    change_pct, sales_figures, SalesComparison, SalesFilter, SalesReport, SalesReportQuery,
+};
+use crate::modules::reports::domain::repositories::ReportsRepository;
+use crate::shared::errors::AppError;
+
+const DEFAULT_DAYS: i64 = 7;
+const MAX_DAYS: i64 = 731;
+
+pub async fn get_sales_report(
+    repo: &dyn ReportsRepository,
+    query: &SalesReportQuery,
+) -> Result<SalesReport, AppError> {
+    get_sales_report_at(repo, query, Utc::now().date_naive()).await
+}
+
+/// Reporte con `today` como fin por defecto del rango.
+pub async fn get_sales_report_at(
+    repo: &dyn ReportsRepository,
+    query: &SalesReportQuery,
+    today: NaiveDate,
+) -> Result<SalesReport, AppError> {
+    let to = query.to.unwrap_or(today);
+    let from = query
+        .from
+        .unwrap_or_else(|| to - Duration::days(DEFAULT_DAYS - 1));
+    if from > to {
+        return Err(AppError::BadRequest(
+            "La fecha inicial no puede ser posterior a la final".into(),
+        ));
+    }
+    let days = (to - from).num_days() + 1;
+    if days > MAX_DAYS {
+        return Err(AppError::BadRequest(format!(
+            "El rango no puede superar {MAX_DAYS} días"
+        )));
+    }
+
+    let filter = SalesFilter {
+        from,
+        to,
+        period: query.period,
+        breakdown: query.breakdown,
+        product_id: query.product_id,
+        flavor_id: query.flavor_id,
+        provider_id: query.provider_id,
+        worker_id: query.worker_id,
+        route_id: query.route_id,
+        channel: query.channel,
+    };
+    let rows = repo.sales_report(&filter).await?;
+    let totals = sales_figures(&rows);
+
+    // El periodo anterior tiene la misma duración y termina el día antes de `from`.
+    let comparison = if query.compare {
+        let previous = SalesFilter {
+            from: from - Duration::days(days),
+            to: from - Duration::days(1),
+            ..filter
+        };
+        let rows = repo.sales_report(&previous).await?;
+        let previous_totals = sales_figures(&rows);
+        Some(SalesComparison {
+            from: previous.from,
+            to: previous.to,
+            units_change_pct: change_pct(
+                totals.units.into(),
+                previous_totals.units.into(),
+            ),
+            revenue_change_pct: change_pct(totals.revenue, previous_totals.revenue),
+            income_change_pct: change_pct(totals.income, previous_totals.income),
+            rows,
+            totals: previous_totals,
+        })
+    } else {
+        None
+    };
+
+    Ok(SalesReport {
+        from,
+        to,
+        period: query.period,
+        breakdown: query.breakdown,
+        rows,
+        totals,
+        comparison,
+    })
+}
@@ -0,0 +1,746 @@
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+// ─── Tablero del dueño ──────────────────────────────────
+
+/// Ventas de un día por canal. Los días sin ventas vienen en cero para
+/// que la serie se grafique sin huecos.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
+pub struct DailySales {
+    pub date: NaiveDate,
+    /// `amount_due` de los viajes retornados ese día.
+    pub worker_trips: Decimal,
+    /// Ventas del dueño completadas y no anuladas.
+    pub owner_sales: Decimal,
+    /// Ventas en local no anuladas.
+    pub local_sales: Decimal,
+    pub total: Decimal,
+    pub units: i32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SalesTotals {
+    pub worker_trips: Decimal,
+    pub owner_sales: Decimal,
+    pub local_sales: Decimal,
+    pub total: Decimal,
+    pub units: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
+pub struct TopWorker {
+    pub worker_id: Uuid,
+    pub name: String,
+    pub trips: i32,
+    pub sold_quantity: i32,
+    pub amount: Decimal,
+    pub current_debt: Decimal,
+}
+
+/// Sabor más vendido sumando viajes, ventas del dueño y ventas en local.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
+pub struct TopProduct {
+    pub product_id: Uuid,
+    pub product_name: String,
+    pub flavor_id: Uuid,
+    pub flavor_name: String,
+    pub units: i32,
+    pub amount: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
+pub struct LowStockAlert {
+    pub inventory_id: Uuid,
+    pub freezer_id: Uuid,
+    pub freezer_number: i32,
+    pub product_name: String,
+    pub flavor_name: String,
+    pub quantity: i32,
+    pub min_stock_alert: i32,
+}
+
+/// Viaje que salió antes de hoy y sigue sin retornar.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
+pub struct OpenTripAlert {
+    pub trip_id: Uuid,
+    pub worker_id: Uuid,
+    pub worker_name: String,
+    pub departure_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct DashboardAlerts {
+    pub low_stock: Vec<LowStockAlert>,
+    pub open_trips: Vec<OpenTripAlert>,
+    /// Días del periodo, anteriores a hoy, con movimientos de caja y sin cierre.
+    pub unclosed_cash_days: Vec<NaiveDate>,
+    pub workers_with_debt: i64,
+    pub worker_debt_total: Decimal,
+    /// Saldo pendiente de compras a crédito no anuladas.
+    pub provider_payables_total: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct Dashboard {
+    pub generated_at: DateTime<Utc>,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub cash_available: Decimal,
+    pub sales: Vec<DailySales>,
+    pub totals: SalesTotals,
+    pub top_workers: Vec<TopWorker>,
+    pub top_products: Vec<TopProduct>,
+    pub alerts: DashboardAlerts,
+}
+
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct DashboardQuery {
+    /// Días hacia atrás, incluyendo hoy (30 por defecto, máximo 366).
+    pub days: Option<i64>,
+    /// Cantidad de trabajadores y productos en los rankings (5 por defecto, máximo 50).
+    pub top: Option<i64>,
+}
+
+/// Suma la serie diaria por canal.
+pub fn sales_totals(series: &[DailySales]) -> SalesTotals {
+    series.iter().fold(SalesTotals::default(), |mut t, d| {
+        t.worker_trips += d.worker_trips;
+        t.owner_sales += d.owner_sales;
+        t.local_sales += d.local_sales;
+        t.total += d.total;
+        t.units += d.units;
+        t
+    })
+}
+
+// ─── Análisis de ventas ─────────────────────────────────
+
+/// Canal de venta de un hecho de venta.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SalesChannel {
+    WorkerTrip,
+    OwnerSale,
+    LocalSale,
+}
+
+impl SalesChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::WorkerTrip => "worker_trip",
+            Self::OwnerSale => "owner_sale",
+            Self::LocalSale => "local_sale",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SalesPeriod {
+    #[default]
+    Day,
+    /// Semanas de lunes a domingo.
+    Week,
+    Month,
+}
+
+impl SalesPeriod {
+    /// Unidad para `date_trunc`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+        }
+    }
+}
+
+/// Dimensión por la que se desglosa cada periodo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SalesDimension {
+    Product,
+    Flavor,
+    Provider,
+    Worker,
+    Route,
+    Channel,
+}
+
+impl SalesDimension {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Product => "product",
+            Self::Flavor => "flavor",
+            Self::Provider => "provider",
+            Self::Worker => "worker",
+            Self::Route => "route",
+            Self::Channel => "channel",
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct SalesReportQuery {
+    /// Por defecto, 6 días antes de `to` (una semana).
+    pub from: Option<NaiveDate>,
+    /// Por defecto, hoy.
+    pub to: Option<NaiveDate>,
+    #[serde(default)]
+    pub period: SalesPeriod,
+    pub breakdown: Option<SalesDimension>,
+    pub product_id: Option<Uuid>,
+    pub flavor_id: Option<Uuid>,
+    pub provider_id: Option<Uuid>,
+    pub worker_id: Option<Uuid>,
+    pub route_id: Option<Uuid>,
+    pub channel: Option<SalesChannel>,
+    /// Compara con el periodo inmediatamente anterior de la misma duración.
+    #[serde(default)]
+    pub compare: bool,
+}
+
+/// Filtros ya resueltos que recibe el repositorio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SalesFilter {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub period: SalesPeriod,
+    pub breakdown: Option<SalesDimension>,
+    pub product_id: Option<Uuid>,
+    pub flavor_id: Option<Uuid>,
+    pub provider_id: Option<Uuid>,
+    pub worker_id: Option<Uuid>,
+    pub route_id: Option<Uuid>,
+    pub channel: Option<SalesChannel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
+pub struct SalesReportRow {
+    /// Primer día del periodo (lunes en semanas, día 1 en meses).
+    pub period_start: NaiveDate,
+    /// ID de la dimensión (o código del canal); nulo sin desglose o cuando
+    /// la venta no tiene trabajador/ruta.
+    pub key: Option<String>,
+    pub label: Option<String>,
+    pub units: i32,
+    /// A precio de venta al público histórico (`price_route` o `price_local`).
+    pub revenue: Decimal,
+    /// Ingreso del negocio a `price_base` histórico.
+    pub income: Decimal,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SalesFigures {
+    pub units: i32,
+    pub revenue: Decimal,
+    pub income: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SalesComparison {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub rows: Vec<SalesReportRow>,
+    pub totals: SalesFigures,
+    /// Variación porcentual frente a este periodo; nula si aquí no hubo ventas.
+    pub units_change_pct: Option<Decimal>,
+    pub revenue_change_pct: Option<Decimal>,
+    pub income_change_pct: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SalesReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub period: SalesPeriod,
+    pub breakdown: Option<SalesDimension>,
+    pub rows: Vec<SalesReportRow>,
+    pub totals: SalesFigures,
+    pub comparison: Option<SalesComparison>,
+}
+
+pub fn sales_figures(rows: &[SalesReportRow]) -> SalesFigures {
+    rows.iter().fold(SalesFigures::default(), |mut t, r| {
+        t.units += r.units;
+        t.revenue += r.revenue;
+        t.income += r.income;
+        t
+    })
+}
+
+/// Variación porcentual de `previous` a `current`, con dos decimales. Se
+/// divide entre el valor absoluto para que una pérdida que se achica cuente
+/// como mejora.
+pub fn change_pct(current: Decimal, previous: Decimal) -> Option<Decimal> {
+    if previous.is_zero() {
+        return None;
+    }
+    Some(((current - previous) * Decimal::ONE_HUNDRED / previous.abs()).round_dp(2))
+}
+
+// ─── Rentabilidad por ruta ──────────────────────────────
+
+/// Viajes retornados de un trabajador en una ruta, ya sumados por la base.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
+pub struct RouteWorkerRow {
+    pub route_id: Uuid,
+    pub route_name: String,
+    /// Contador histórico de la ruta, no del periodo.
+    pub usage_count: i32,
+    pub worker_id: Uuid,
+    pub worker_name: String,
+    pub trips: i32,
+    pub loaded_units: i32,
+    pub returned_units: i32,
+    pub sold_units: i32,
+    /// `amount_due` de los viajes.
+    pub sales: Decimal,
+    /// Vendido × (`price_base` − `cost_price`) del precio vigente al salir.
+    pub business_margin: Decimal,
+    /// Vendido × (`price_route` − precio cobrado al trabajador).
+    pub commission: Decimal,
+    /// Vendido de líneas sin registro en `price_history`: fuera del margen y la comisión.
+    pub unpriced_units: i32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct RouteFigures {
+    pub trips: i32,
+    pub loaded_units: i32,
+    pub returned_units: i32,
+    pub sold_units: i32,
+    /// Devuelto / cargado, de 0 a 1; nulo si no se cargó nada.
+    pub returned_ratio: Option<Decimal>,
+    pub sales: Decimal,
+    pub business_margin: Decimal,
+    pub commission: Decimal,
+    pub unpriced_units: i32,
+    pub average_sale_per_trip: Decimal,
+}
+
+impl RouteFigures {
+    fn add(&mut self, row: &RouteWorkerRow) {
+        self.trips += row.trips;
+        self.loaded_units += row.loaded_units;
+        self.returned_units += row.returned_units;
+        self.sold_units += row.sold_units;
+        self.sales += row.sales;
+        self.business_margin += row.business_margin;
+        self.commission += row.commission;
+        self.unpriced_units += row.unpriced_units;
+    }
+
+    /// Calcula los promedios una vez sumadas las filas.
+    fn finish(mut self) -> Self {
+        self.returned_ratio = (self.loaded_units > 0).then(|| {
+            (Decimal::from(self.returned_units) / Decimal::from(self.loaded_units)).round_dp(4)
+        });
+        if self.trips > 0 {
+            self.average_sale_per_trip = (self.sales / Decimal::from(self.trips)).round_dp(2);
+        }
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct RouteWorkerProfitability {
+    pub worker_id: Uuid,
+    pub worker_name: String,
+    pub figures: RouteFigures,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct RouteProfitability {
+    pub route_id: Uuid,
+    pub route_name: String,
+    pub usage_count: i32,
+    pub figures: RouteFigures,
+    /// Trabajadores que recorrieron la ruta, del de mayor margen al de menor.
+    pub workers: Vec<RouteWorkerProfitability>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct RouteProfitabilityReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub routes: Vec<RouteProfitability>,
+    pub totals: RouteFigures,
+}
+
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct RouteProfitabilityQuery {
+    /// Por defecto, 29 días antes de `to`.
+    pub from: Option<NaiveDate>,
+    /// Por defecto, hoy.
+    pub to: Option<NaiveDate>,
+    pub route_id: Option<Uuid>,
+    pub worker_id: Option<Uuid>,
+}
+
+/// Agrupa las filas por ruta con su desglose por trabajador; las rutas van
+/// de mayor a menor margen.
+pub fn route_profitability(rows: &[RouteWorkerRow]) -> Vec<RouteProfitability> {
+    let mut routes: Vec<(RouteProfitability, RouteFigures)> = Vec::new();
+    for row in rows {
+        let idx = match routes.iter().position(|(r, _)| r.route_id == row.route_id) {
+            Some(idx) => idx,
+            None => {
+                routes.push((
+                    RouteProfitability {
+                        route_id: row.route_id,
+                        route_name: row.route_name.clone(),
+                        usage_count: row.usage_count,
+                        figures: RouteFigures::default(),
+                        workers: Vec::new(),
+                    },
+                    RouteFigures::default(),
+                ));
+                routes.len() - 1
+            }
+        };
+        let (route, figures) = &mut routes[idx];
+        figures.add(row);
+        let mut worker = RouteFigures::default();
+        worker.add(row);
+        route.workers.push(RouteWorkerProfitability {
+            worker_id: row.worker_id,
+            worker_name: row.worker_name.clone(),
+            figures: worker.finish(),
+        });
+    }
+
+    let mut routes: Vec<RouteProfitability> = routes
+        .into_iter()
+        .map(|(mut route, figures)| {
+            route.figures = figures.finish();
+            route
+                .workers
+                .sort_by_key(|w| std::cmp::Reverse(w.figures.business_margin));
+            route
+        })
+        .collect();
+    routes.sort_by_key(|r| std::cmp::Reverse(r.figures.business_margin));
+    routes
+}
+
+/// Totales del periodo sobre todas las filas.
+pub fn route_totals(rows: &[RouteWorkerRow]) -> RouteFigures {
+    rows.iter()
+        .fold(RouteFigures::default(), |mut t, r| {
+            t.add(r);
+            t
+        })
+        .finish()
+}
+
+// ─── Estado de resultados mensual ───────────────────────
+
+/// Costo de lo vendido en un mes, a partir de los hechos de venta.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
+pub struct MonthlyCost {
+    /// Primer día del mes.
+    pub month: NaiveDate,
+    pub cost: Decimal,
+    /// Unidades vendidas sin precio de costo ni compra previa; no suman costo.
+    pub uncosted_units: i32,
+}
+
+/// Salida de caja no revertida de un mes, en positivo.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
+pub struct MonthlyOutflow {
+    pub month: NaiveDate,
+    /// `expense` u `owner_withdrawal`.
+    pub kind: String,
+    pub category: Option<String>,
+    pub amount: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ExpenseByCategory {
+    pub category: String,
+    pub amount: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct MonthlyProfitLoss {
+    pub month: NaiveDate,
+    /// Ventas por canal, con los mismos importes del tablero.
+    pub revenue: SalesTotals,
+    pub cost_of_goods_sold: Decimal,
+    pub uncosted_units: i32,
+    pub gross_margin: Decimal,
+    /// Margen bruto sobre ventas, en porcentaje; nulo sin ventas.
+    pub gross_margin_pct: Option<Decimal>,
+    pub expenses: Vec<ExpenseByCategory>,
+    pub operating_expenses: Decimal,
+    pub owner_withdrawals: Decimal,
+    /// Margen bruto − gastos operativos − retiros del dueño.
+    pub net_profit: Decimal,
+    /// Variación de la utilidad neta frente al mes anterior del reporte.
+    pub net_profit_change_pct: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ProfitLossReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub months: Vec<MonthlyProfitLoss>,
+}
+
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct ProfitLossQuery {
+    /// Cualquier día del primer mes; por defecto, 5 meses antes de `to`.
+    pub from: Option<NaiveDate>,
+    /// Cualquier día del último mes; por defecto, el mes en curso.
+    pub to: Option<NaiveDate>,
+}
+
+/// Arma un estado de resultados por mes de `months` (primeros días, en
+/// orden). Las ventas diarias se suman al mes que les corresponde.
+pub fn profit_and_loss(
+    months: &[NaiveDate],
+    daily: &[DailySales],
+    costs: &[MonthlyCost],
+    outflows: &[MonthlyOutflow],
+) -> Vec<MonthlyProfitLoss> {
+    let mut result: Vec<MonthlyProfitLoss> = Vec::with_capacity(months.len());
+    for &month in months {
+        let days: Vec<DailySales> = daily
+            .iter()
+            .filter(|d| month_start(d.date) == month)
+            .cloned()
+            .collect();
+        let revenue = sales_totals(&days);
+        let (cost_of_goods_sold, uncosted_units) = costs
+            .iter()
+            .filter(|c| c.month == month)
+            .fold((Decimal::ZERO, 0), |(cost, units), c| {
+                (cost + c.cost, units + c.uncosted_units)
+            });
+
+        let mut expenses: Vec<ExpenseByCategory> = Vec::new();
+        let mut owner_withdrawals = Decimal::ZERO;
+        for outflow in outflows.iter().filter(|o| o.month == month) {
+            if outflow.kind == "owner_withdrawal" {
+                owner_withdrawals += outflow.amount;
+                continue;
+            }
+            let category = outflow.category.clone().unwrap_or_else(|| "otros".into());
+            match expenses.iter_mut().find(|e| e.category == category) {
+                Some(e) => e.amount += outflow.amount,
+                None => expenses.push(ExpenseByCategory {
+                    category,
+                    amount: outflow.amount,
+                }),
+            }
+        }
+        expenses.sort_by(|a, b| a.category.cmp(&b.category));
+        let operating_expenses = expenses.iter().map(|e| e.amount).sum();
+
+        let gross_margin = revenue.total - cost_of_goods_sold;
+        let gross_margin_pct = (!revenue.total.is_zero())
+            .then(|| (gross_margin * Decimal::ONE_HUNDRED / revenue.total).round_dp(2));
+        let net_profit = gross_margin - operating_expenses - owner_withdrawals;
+        let net_profit_change_pct = result
+            .last()
+            .and_then(|prev| change_pct(net_profit, prev.net_profit));
+
+        result.push(MonthlyProfitLoss {
+            month,
+            revenue,
+            cost_of_goods_sold,
+            uncosted_units,
+            gross_margin,
+            gross_margin_pct,
+            expenses,
+            operating_expenses,
+            owner_withdrawals,
+            net_profit,
+            net_profit_change_pct,
+        });
+    }
+    result
+}
+
+/// Primer día del mes de `date`.
+pub fn month_start(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).expect("todo mes tiene día 1")
+}
+
+/// Primer día del mes siguiente a `month`; None al final del calendario.
+pub fn next_month(month: NaiveDate) -> Option<NaiveDate> {
+    month_start(month).checked_add_months(chrono::Months::new(1))
+}
+
+// ─── Valorización de inventario ─────────────────────────
+
+/// Lote con stock y el precio vigente de su producto/sabor/proveedor.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
+pub struct InventoryValuationRow {
+    pub inventory_id: Uuid,
+    pub freezer_id: Uuid,
+    pub freezer_number: i32,
+    pub product_id: Uuid,
+    pub product_name: String,
+    pub flavor_id: Uuid,
+    pub flavor_name: String,
+    pub provider_id: Uuid,
+    pub provider_name: String,
+    pub is_deformed: bool,
+    pub quantity: i32,
+    /// Nulos si la combinación no tiene precio vigente.
+    pub cost_price: Option<Decimal>,
+    pub price_base: Option<Decimal>,
+    pub price_route: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ValuationFigures {
+    pub units: i32,
+    /// Unidades sin precio vigente; cuentan en `units` pero no en los importes.
+    pub unpriced_units: i32,
+    pub at_cost: Decimal,
+    pub at_base: Decimal,
+    pub at_route: Decimal,
+}
+
+impl ValuationFigures {
+    fn add(&mut self, row: &InventoryValuationRow) {
+        self.units += row.quantity;
+        match (row.cost_price, row.price_base, row.price_route) {
+            (Some(cost), Some(base), Some(route)) => {
+                let quantity = Decimal::from(row.quantity);
+                self.at_cost += quantity * cost;
+                self.at_base += quantity * base;
+                self.at_route += quantity * route;
+            }
+            _ => self.unpriced_units += row.quantity,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct FreezerValuation {
+    pub freezer_id: Uuid,
+    pub freezer_number: i32,
+    pub figures: ValuationFigures,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ProductValuation {
+    pub product_id: Uuid,
+    pub product_name: String,
+    pub figures: ValuationFigures,
+}
+
+/// Combinación con stock y sin registro en `price_history`.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct UnpricedStock {
+    pub product_id: Uuid,
+    pub product_name: String,
+    pub flavor_id: Uuid,
+    pub flavor_name: String,
+    pub provider_id: Uuid,
+    pub provider_name: String,
+    pub quantity: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct InventoryValuation {
+    pub generated_at: DateTime<Utc>,
+    pub totals: ValuationFigures,
+    pub normal: ValuationFigures,
+    pub deformed: ValuationFigures,
+    /// Por número de congelador.
+    pub by_freezer: Vec<FreezerValuation>,
+    /// Del producto de mayor valor a costo al de menor.
+    pub by_product: Vec<ProductValuation>,
+    pub unpriced: Vec<UnpricedStock>,
+}
+
+/// Suma los lotes por congelador, producto y estado (normal o deformado).
+pub fn value_inventory(
+    rows: &[InventoryValuationRow],
+    generated_at: DateTime<Utc>,
+) -> InventoryValuation {
+    let mut valuation = InventoryValuation {
+        generated_at,
+        totals: ValuationFigures::default(),
+        normal: ValuationFigures::default(),
+        deformed: ValuationFigures::default(),
+        by_freezer: Vec::new(),
+        by_product: Vec::new(),
+        unpriced: Vec::new(),
+    };
+    for row in rows {
+        valuation.totals.add(row);
+        if row.is_deformed {
+            valuation.deformed.add(row);
+        } else {
+            valuation.normal.add(row);
+        }
+
+        match valuation
+            .by_freezer
+            .iter_mut()
+            .find(|f| f.freezer_id == row.freezer_id)
+        {
+            Some(freezer) => freezer.figures.add(row),
+            None => {
+                let mut figures = ValuationFigures::default();
+                figures.add(row);
+                valuation.by_freezer.push(FreezerValuation {
+                    freezer_id: row.freezer_id,
+                    freezer_number: row.freezer_number,
+                    figures,
+                });
+            }
+        }
+
+        match valuation
+            .by_product
+            .iter_mut()
+            .find(|p| p.product_id == row.product_id)
+        {
+            Some(product) => product.figures.add(row),
+            None => {
+                let mut figures = ValuationFigures::default();
+                figures.add(row);
+                valuation.by_product.push(ProductValuation {
+                    product_id: row.product_id,
+                    product_name: row.product_name.clone(),
+                    figures,
+                });
+            }
+        }
+
+        if row.cost_price.is_some() && row.price_base.is_some() && row.price_route.is_some() {
+            continue;
+        }
+        match valuation.unpriced.iter_mut().find(|u| {
+            u.product_id == row.product_id
+                && u.flavor_id == row.flavor_id
+                && u.provider_id == row.provider_id
+        }) {
+            Some(unpriced) => unpriced.quantity += row.quantity,
+            None => valuation.unpriced.push(UnpricedStock {
+                product_id: row.product_id,
+                product_name: row.product_name.clone(),
+                flavor_id: row.flavor_id,
+                flavor_name: row.flavor_name.clone(),
+                provider_id: row.provider_id,
+                provider_name: row.provider_name.clone(),
+                quantity: row.quantity,
+            }),
+        }
+    }
+    valuation.by_freezer.sort_by_key(|f| f.freezer_number);
+    valuation
+        .by_product
+        .sort_by_key(|p| std::cmp::Reverse(p.figures.at_cost));
+    valuation
+}
@@ -0,0 +1,60 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use super::entities::{
+    DailySales, DashboardAlerts, InventoryValuationRow, MonthlyCost, MonthlyOutflow, RouteWorkerRow, SalesFilter,
+    SalesReportRow, TopProduct, TopWorker,
+};
+use crate::shared::errors::AppError;
+
+/// Consultas de solo lectura para reportes. Los rangos de fechas son inclusivos.
+#[async_trait]
+pub trait ReportsRepository: Send + Sync {
+    /// Saldo del último movimiento de caja.
+    async fn cash_available(&self) -> Result<Decimal, AppError>;
+    /// Un registro por día del rango, aunque no haya ventas.
+    async fn daily_sales(&self, from: NaiveDate, to: NaiveDate)
+        -> Result<Vec<DailySales>, AppError>;
+    async fn top_workers(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        limit: i64,
+    ) -> Result<Vec<TopWorker>, AppError>;
+    async fn top_products(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        limit: i64,
+    ) -> Result<Vec<TopProduct>, AppError>;
+    /// Pendientes a la fecha `today`; los días sin cierre se buscan desde `from`.
+    async fn alerts(&self, from: NaiveDate, today: NaiveDate)
+        -> Result<DashboardAlerts, AppError>;
+    /// Hechos de venta agrupados por periodo y, si se pide, por una dimensión.
+    async fn sales_report(&self, filter: &SalesFilter) -> Result<Vec<SalesReportRow>, AppError>;
+    /// Viajes retornados en el rango, sumados por ruta y trabajador. Los viajes
+    /// sin ruta no se incluyen.
+    async fn route_profitability(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        route_id: Option<Uuid>,
+        worker_id: Option<Uuid>,
+    ) -> Result<Vec<RouteWorkerRow>, AppError>;
+    /// Costo de lo vendido por mes entre `from` y `to`.
+    async fn monthly_cost_of_goods(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<MonthlyCost>, AppError>;
+    /// Gastos por categoría y retiros del dueño por mes, sin los revertidos.
+    async fn monthly_outflows(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<MonthlyOutflow>, AppError>;
+    /// Lotes con stock y el precio vigente hoy de cada uno.
+    async fn inventory_valuation(&self) -> Result<Vec<InventoryValuationRow>, AppError>;
+}
@@ -0,0 +1,204 @@
+use axum::{
+    extract::{Query, State},
+    response::Response,
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+use utoipa::OpenApi;
+
+use crate::modules::reports::application::{
+    dashboard, inventory_valuation, profit_loss, route_profitability, sales_report,
+};
+use crate::modules::reports::domain::entities::*;
+use crate::modules::reports::domain::repositories::ReportsRepository;
+use crate::shared::auth::{AppState, AuthUser};
+use crate::shared::csv::csv_response;
+use crate::shared::errors::AppError;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_dashboard,
+        get_sales_report,
+        get_route_profitability,
+        get_profit_loss,
+        export_profit_loss,
+        get_inventory_valuation
+    ),
+    components(schemas(
+        crate::modules::reports::domain::entities::Dashboard,
+        crate::modules::reports::domain::entities::DailySales,
+        crate::modules::reports::domain::entities::SalesTotals,
+        crate::modules::reports::domain::entities::TopWorker,
+        crate::modules::reports::domain::entities::TopProduct,
+        crate::modules::reports::domain::entities::DashboardAlerts,
+        crate::modules::reports::domain::entities::LowStockAlert,
+        crate::modules::reports::domain::entities::OpenTripAlert,
+        crate::modules::reports::domain::entities::SalesReport,
+        crate::modules::reports::domain::entities::SalesReportRow,
+        crate::modules::reports::domain::entities::SalesFigures,
+        crate::modules::reports::domain::entities::SalesComparison,
+        crate::modules::reports::domain::entities::SalesPeriod,
+        crate::modules::reports::domain::entities::SalesDimension,
+        crate::modules::reports::domain::entities::SalesChannel,
+        crate::modules::reports::domain::entities::RouteProfitabilityReport,
+        crate::modules::reports::domain::entities::RouteProfitability,
+        crate::modules::reports::domain::entities::RouteWorkerProfitability,
+        crate::modules::reports::domain::entities::RouteFigures,
+        crate::modules::reports::domain::entities::ProfitLossReport,
+        crate::modules::reports::domain::entities::MonthlyProfitLoss,
+        crate::modules::reports::domain::entities::ExpenseByCategory,
+        crate::modules::reports::domain::entities::InventoryValuation,
+        crate::modules::reports::domain::entities::ValuationFigures,
+        crate::modules::reports::domain::entities::FreezerValuation,
+        crate::modules::reports::domain::entities::ProductValuation,
+        crate::modules::reports::domain::entities::UnpricedStock,
+    ))
+)]
+pub struct ReportsApiDoc;
+
+#[derive(Clone)]
+pub struct ReportsState {
+    pub app: AppState,
+    pub repo: Arc<dyn ReportsRepository>,
+}
+
+impl axum::extract::FromRef<ReportsState> for AppState {
+    fn from_ref(s: &ReportsState) -> AppState {
+        s.app.clone()
+    }
+}
+
+pub fn router(app: AppState, repo: Arc<dyn ReportsRepository>) -> Router {
+    let state = ReportsState { app, repo };
+    Router::new()
+        .route("/dashboard", get(get_dashboard))
+        .route("/sales", get(get_sales_report))
+        .route("/routes", get(get_route_profitability))
+        .route("/profit-loss", get(get_profit_loss))
+        .route("/profit-loss/export", get(export_profit_loss))
+        .route("/inventory-valuation", get(get_inventory_valuation))
+        .with_state(state)
+}
+
+#[utoipa::path(
+    get, path = "/dashboard", tag = "Reportes",
+    params(DashboardQuery),
+    responses(
+        (status = 200, description = "Tablero del dueño", body = Dashboard),
+        (status = 400, description = "Ventana o ranking fuera de rango"),
+        (status = 403, description = "Solo el dueño")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_dashboard(
+    State(state): State<ReportsState>,
+    auth: AuthUser,
+    Query(q): Query<DashboardQuery>,
+) -> Result<Json<Dashboard>, AppError> {
+    auth.require_owner()?;
+    let dashboard = dashboard::get_dashboard(state.repo.as_ref(), &q).await?;
+    Ok(Json(dashboard))
+}
+
+#[utoipa::path(
+    get, path = "/sales", tag = "Reportes",
+    params(SalesReportQuery),
+    responses(
+        (status = 200, description = "Ventas por periodo, con desglose y comparación opcionales", body = SalesReport),
+        (status = 400, description = "Rango de fechas inválido"),
+        (status = 403, description = "Solo el dueño")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_sales_report(
+    State(state): State<ReportsState>,
+    auth: AuthUser,
+    Query(q): Query<SalesReportQuery>,
+) -> Result<Json<SalesReport>, AppError> {
+    auth.require_owner()?;
+    let report = sales_report::get_sales_report(state.repo.as_ref(), &q).await?;
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    get, path = "/routes", tag = "Reportes",
+    params(RouteProfitabilityQuery),
+    responses(
+        (status = 200, description = "Rentabilidad por ruta con desglose por trabajador", body = RouteProfitabilityReport),
+        (status = 400, description = "Rango de fechas inválido"),
+        (status = 403, description = "Solo el dueño")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_route_profitability(
+    State(state): State<ReportsState>,
+    auth: AuthUser,
+    Query(q): Query<RouteProfitabilityQuery>,
+) -> Result<Json<RouteProfitabilityReport>, AppError> {
+    auth.require_owner()?;
+    let report =
+        route_profitability::get_route_profitability(state.repo.as_ref(), &q).await?;
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    get, path = "/profit-loss", tag = "Reportes",
+    params(ProfitLossQuery),
+    responses(
+        (status = 200, description = "Estado de resultados por mes", body = ProfitLossReport),
+        (status = 400, description = "Rango de meses inválido"),
+        (status = 403, description = "Solo el dueño")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_profit_loss(
+    State(state): State<ReportsState>,
+    auth: AuthUser,
+    Query(q): Query<ProfitLossQuery>,
+) -> Result<Json<ProfitLossReport>, AppError> {
+    auth.require_owner()?;
+    let report = profit_loss::get_profit_loss(state.repo.as_ref(), &q).await?;
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    get, path = "/profit-loss/export", tag = "Reportes",
+    params(ProfitLossQuery),
+    responses(
+        (status = 200, description = "Estado de resultados en CSV, un mes por fila", content_type = "text/csv", body = String),
+        (status = 400, description = "Rango de meses inválido"),
+        (status = 403, description = "Solo el dueño")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn export_profit_loss(
+    State(state): State<ReportsState>,
+    auth: AuthUser,
+    Query(q): Query<ProfitLossQuery>,
+) -> Result<Response, AppError> {
+    auth.require_owner()?;
+    let report = profit_loss::get_profit_loss(state.repo.as_ref(), &q).await?;
+    Ok(csv_response(
+        "estado_resultados.csv",
+        profit_loss::render_csv(&report),
+    ))
+}
+
+#[utoipa::path(
+    get, path = "/inventory-valuation", tag = "Reportes",
+    responses(
+        (status = 200, description = "Stock actual valorizado a costo, precio base y precio ruta", body = InventoryValuation),
+        (status = 403, description = "Solo el dueño")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn get_inventory_valuation(
+    State(state): State<ReportsState>,
+    auth: AuthUser,
+) -> Result<Json<InventoryValuation>, AppError> {
+    auth.require_owner()?;
+    let valuation = inventory_valuation::get_inventory_valuation(state.repo.as_ref()).await?;
+    Ok(Json(valuation))
+}
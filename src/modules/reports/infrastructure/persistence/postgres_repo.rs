@@ -0,0 +1,557 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::modules::reports::domain::entities::*;
+use crate::modules::reports::domain::repositories::ReportsRepository;
+use crate::shared::errors::AppError;
+
+pub struct PgReportsRepository {
+    pool: PgPool,
+}
+
+impl PgReportsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+/// Hecho de venta: una fila por línea vendida de viajes retornados, ventas
+/// del dueño completadas y ventas en local, sin documentos anulados. En los
+/// viajes lo devuelto está imputado a su línea; en las ventas del dueño, lo
+/// devuelto por producto y sabor se reparte una sola vez entre las líneas,
+/// llenándolas en orden de id. El costo es
+/// el `cost_price` registrado o, sin él, el de la última compra no anulada
+/// del mismo proveedor antes de la venta; queda nulo si no hay ninguno.
+/// Espera el rango de fechas en `$1`/`$2`.
+const SALES_FACT: &str = r#"
+sales_fact AS (
+    SELECT t.return_time AS sold_at, 'worker_trip' AS channel,
+           li.product_id, li.flavor_id,
+           COALESCE(ph.provider_id, inv.provider_id) AS provider_id,
+           t.worker_id, t.route_id,
+           GREATEST(li.quantity - COALESCE(r.quantity, 0), 0) AS units,
+           li.unit_price AS charged_price,
+           COALESCE(ph.price_route, li.unit_price) AS revenue_price,
+           COALESCE(ph.price_base, li.unit_price) AS income_price,
+           COALESCE(ph.cost_price, pc.unit_cost) AS cost_price
+    FROM worker_trip_loaded_items li
+    JOIN worker_trips t ON t.id = li.trip_id
+    LEFT JOIN price_history ph ON ph.id = li.price_history_id
+    LEFT JOIN inventory inv ON inv.id = li.inventory_id
+    LEFT JOIN LATERAL (
+        SELECT SUM(ri.quantity) AS quantity
+        FROM worker_trip_returned_items ri
+        WHERE ri.loaded_item_id = li.id
+    ) r ON TRUE
+    LEFT JOIN LATERAL (
+        SELECT pi.unit_price AS unit_cost
+        FROM purchase_items pi
+        JOIN purchases pu ON pu.id = pi.purchase_id
+        WHERE pi.product_id = li.product_id AND pi.flavor_id = li.flavor_id
+          AND pu.provider_id = COALESCE(ph.provider_id, inv.provider_id)
+          AND pu.voided_at IS NULL AND pu.created_at <= t.return_time
+        ORDER BY pu.created_at DESC
+        LIMIT 1
+    ) pc ON TRUE
+    WHERE t.status = 'returned' AND t.return_time::date BETWEEN $1 AND $2
+
+    UNION ALL
+
+    SELECT s.return_time, 'owner_sale',
+           li.product_id, li.flavor_id,
+           COALESCE(ph.provider_id, inv.provider_id),
+           NULL::uuid, s.route_id,
+           GREATEST(li.quantity - COALESCE(r.quantity, 0), 0),
+           li.unit_price,
+           COALESCE(ph.price_route, li.unit_price),
+           COALESCE(ph.price_base, li.unit_price),
+           COALESCE(ph.cost_price, pc.unit_cost)
+    FROM owner_sale_loaded_items li
+    JOIN owner_sales s ON s.id = li.sale_id
+    LEFT JOIN price_history ph ON ph.id = li.price_history_id
+    LEFT JOIN inventory inv ON inv.id = li.inventory_id
+    LEFT JOIN LATERAL (
+        SELECT LEAST(li.quantity, GREATEST(
+                   COALESCE(SUM(ri.quantity), 0)
+                   - COALESCE((
+                       SELECT SUM(x.quantity) FROM owner_sale_loaded_items x
+                       WHERE x.sale_id = li.sale_id
+                         AND x.product_id = li.product_id
+                         AND x.flavor_id = li.flavor_id
+                         AND x.id < li.id
+                   ), 0), 0)) AS quantity
+        FROM owner_sale_returned_items ri
+        WHERE ri.sale_id = li.sale_id
+          AND ri.product_id = li.product_id
+          AND ri.flavor_id = li.flavor_id
+    ) r ON TRUE
+    LEFT JOIN LATERAL (
+        SELECT pi.unit_price AS unit_cost
+        FROM purchase_items pi
+        JOIN purchases pu ON pu.id = pi.purchase_id
+        WHERE pi.product_id = li.product_id AND pi.flavor_id = li.flavor_id
+          AND pu.provider_id = COALESCE(ph.provider_id, inv.provider_id)
+          AND pu.voided_at IS NULL AND pu.created_at <= s.return_time
+        ORDER BY pu.created_at DESC
+        LIMIT 1
+    ) pc ON TRUE
+    WHERE s.return_time IS NOT NULL AND s.voided_at IS NULL
+      AND s.return_time::date BETWEEN $1 AND $2
+
+    UNION ALL
+
+    SELECT s.created_at, 'local_sale',
+           i.product_id, i.flavor_id,
+           COALESCE(ph.provider_id, inv.provider_id),
+           NULL::uuid, NULL::uuid,
+           i.quantity,
+           i.unit_price,
+           COALESCE(ph.price_local, i.unit_price),
+           COALESCE(ph.price_base, i.unit_price),
+           COALESCE(ph.cost_price, pc.unit_cost)
+    FROM local_sale_items i
+    JOIN local_sales s ON s.id = i.sale_id
+    LEFT JOIN price_history ph ON ph.id = i.price_history_id
+    LEFT JOIN inventory inv ON inv.id = i.inventory_id
+    LEFT JOIN LATERAL (
+        SELECT pi.unit_price AS unit_cost
+        FROM purchase_items pi
+        JOIN purchases pu ON pu.id = pi.purchase_id
+        WHERE pi.product_id = i.product_id AND pi.flavor_id = i.flavor_id
+          AND pu.provider_id = COALESCE(ph.provider_id, inv.provider_id)
+          AND pu.voided_at IS NULL AND pu.created_at <= s.created_at
+        ORDER BY pu.created_at DESC
+        LIMIT 1
+    ) pc ON TRUE
+    WHERE s.voided_at IS NULL AND s.created_at::date BETWEEN $1 AND $2
+)
+"#;
+
+#[derive(sqlx::FromRow)]
+struct DebtAndPayables {
+    workers_with_debt: i64,
+    worker_debt_total: Decimal,
+    provider_payables_total: Decimal,
+}
+
+#[async_trait]
+impl ReportsRepository for PgReportsRepository {
+    async fn cash_available(&self) -> Result<Decimal, AppError> {
+        Ok(sqlx::query_scalar::<_, Decimal>(
+            "SELECT COALESCE((SELECT balance FROM cash_register ORDER BY seq DESC LIMIT 1), 0)",
+        )
+        .fetch_one(&self.pool)
+        .await?)
+    }
+
+    async fn daily_sales(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<DailySales>, AppError> {
+        Ok(sqlx::query_as::<_, DailySales>(
+            r#"
+            WITH days AS (
+                SELECT d::date AS date FROM generate_series($1::date, $2::date, INTERVAL '1 day') d
+            ),
+            trips AS (
+                SELECT return_time::date AS date, SUM(amount_due) AS amount, SUM(sold_quantity) AS units
+                FROM worker_trips
+                WHERE status = 'returned' AND return_time::date BETWEEN $1 AND $2
+                GROUP BY 1
+            ),
+            owner AS (
+                SELECT return_time::date AS date, SUM(total_amount) AS amount, SUM(sold_quantity) AS units
+                FROM owner_sales
+                WHERE return_time IS NOT NULL AND voided_at IS NULL
+                  AND return_time::date BETWEEN $1 AND $2
+                GROUP BY 1
+            ),
+            local AS (
+                SELECT s.created_at::date AS date, SUM(s.total) AS amount, SUM(i.units) AS units
+                FROM local_sales s
+                LEFT JOIN (
+                    SELECT sale_id, SUM(quantity) AS units FROM local_sale_items GROUP BY sale_id
+                ) i ON i.sale_id = s.id
+                WHERE s.voided_at IS NULL AND s.created_at::date BETWEEN $1 AND $2
+                GROUP BY 1
+            )
+            SELECT d.date,
+                   COALESCE(t.amount, 0) AS worker_trips,
+                   COALESCE(o.amount, 0) AS owner_sales,
+                   COALESCE(l.amount, 0) AS local_sales,
+                   COALESCE(t.amount, 0) + COALESCE(o.amount, 0) + COALESCE(l.amount, 0) AS total,
+                   (COALESCE(t.units, 0) + COALESCE(o.units, 0) + COALESCE(l.units, 0))::int AS units
+            FROM days d
+            LEFT JOIN trips t ON t.date = d.date
+            LEFT JOIN owner o ON o.date = d.date
+            LEFT JOIN local l ON l.date = d.date
+            ORDER BY d.date
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn top_workers(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        limit: i64,
+    ) -> Result<Vec<TopWorker>, AppError> {
+        Ok(sqlx::query_as::<_, TopWorker>(
+            r#"
+            SELECT w.id AS worker_id, w.name::text AS name,
+                   COUNT(*)::int AS trips,
+                   SUM(t.sold_quantity)::int AS sold_quantity,
+                   SUM(t.amount_due) AS amount,
+                   w.current_debt
+            FROM worker_trips t
+            JOIN workers w ON w.id = t.worker_id
+            WHERE t.status = 'returned' AND t.return_time::date BETWEEN $1 AND $2
+            GROUP BY w.id
+            ORDER BY amount DESC, sold_quantity DESC, w.name
+            LIMIT $3
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn top_products(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        limit: i64,
+    ) -> Result<Vec<TopProduct>, AppError> {
+        Ok(sqlx::query_as::<_, TopProduct>(&format!(
+            r#"
+            WITH {SALES_FACT}
+            SELECT p.id AS product_id, p.name::text AS product_name,
+                   f.id AS flavor_id, f.name::text AS flavor_name,
+                   SUM(sf.units)::int AS units,
+                   SUM(sf.units * sf.charged_price) AS amount
+            FROM sales_fact sf
+            JOIN products p ON p.id = sf.product_id
+            JOIN flavors f ON f.id = sf.flavor_id
+            GROUP BY p.id, f.id
+            HAVING SUM(sf.units) > 0
+            ORDER BY units DESC, amount DESC, p.name, f.name
+            LIMIT $3
+            "#
+        ))
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn alerts(
+        &self,
+        from: NaiveDate,
+        today: NaiveDate,
+    ) -> Result<DashboardAlerts, AppError> {
+        let low_stock = sqlx::query_as::<_, LowStockAlert>(
+            r#"
+            SELECT i.id AS inventory_id, i.freezer_id, fz.number AS freezer_number,
+                   p.name::text AS product_name, f.name::text AS flavor_name,
+                   i.quantity, i.min_stock_alert
+            FROM inventory i
+            JOIN freezers fz ON fz.id = i.freezer_id
+            JOIN products p ON p.id = i.product_id
+            JOIN flavors f ON f.id = i.flavor_id
+            WHERE i.is_deformed = FALSE AND i.quantity <= i.min_stock_alert
+            ORDER BY i.quantity, fz.number, p.name, f.name
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let open_trips = sqlx::query_as::<_, OpenTripAlert>(
+            r#"
+            SELECT t.id AS trip_id, t.worker_id, w.name::text AS worker_name, t.departure_time
+            FROM worker_trips t
+            JOIN workers w ON w.id = t.worker_id
+            WHERE t.status = 'in_progress' AND t.departure_time::date < $1
+            ORDER BY t.departure_time
+            "#,
+        )
+        .bind(today)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let unclosed_cash_days = sqlx::query_scalar::<_, NaiveDate>(
+            r#"
+            SELECT DISTINCT c.created_at::date AS day
+            FROM cash_register c
+            WHERE c.created_at::date BETWEEN $1 AND $2 - 1
+              AND NOT EXISTS (
+                  SELECT 1 FROM cash_closings cl
+                  WHERE cl.business_date = c.created_at::date AND cl.status = 'closed'
+              )
+            ORDER BY day
+            "#,
+        )
+        .bind(from)
+        .bind(today)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let totals = sqlx::query_as::<_, DebtAndPayables>(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM workers WHERE current_debt > 0) AS workers_with_debt,
+                (SELECT COALESCE(SUM(current_debt), 0) FROM workers WHERE current_debt > 0)
+                    AS worker_debt_total,
+                (SELECT COALESCE(SUM(total - amount_paid), 0) FROM purchases
+                 WHERE voided_at IS NULL AND amount_paid < total) AS provider_payables_total
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(DashboardAlerts {
+            low_stock,
+            open_trips,
+            unclosed_cash_days,
+            workers_with_debt: totals.workers_with_debt,
+            worker_debt_total: totals.worker_debt_total,
+            provider_payables_total: totals.provider_payables_total,
+        })
+    }
+
+    async fn sales_report(&self, filter: &SalesFilter) -> Result<Vec<SalesReportRow>, AppError> {
+        // La dimensión llega como texto y elige la clave y la etiqueta con CASE;
+        // sin desglose ambas quedan nulas y se agrupa solo por periodo.
+        Ok(sqlx::query_as::<_, SalesReportRow>(&format!(
+            r#"
+            WITH {SALES_FACT},
+            keyed AS (
+                SELECT date_trunc($3, sf.sold_at)::date AS period_start,
+                       CASE $4
+                           WHEN 'product' THEN sf.product_id::text
+                           WHEN 'flavor' THEN sf.flavor_id::text
+                           WHEN 'provider' THEN sf.provider_id::text
+                           WHEN 'worker' THEN sf.worker_id::text
+                           WHEN 'route' THEN sf.route_id::text
+                           WHEN 'channel' THEN sf.channel
+                       END AS key,
+                       CASE $4
+                           WHEN 'product' THEN p.name::text
+                           WHEN 'flavor' THEN p.name || ' ' || f.name
+                           WHEN 'provider' THEN pr.name::text
+                           WHEN 'worker' THEN w.name::text
+                           WHEN 'route' THEN ro.name::text
+                           WHEN 'channel' THEN sf.channel
+                       END AS label,
+                       sf.units,
+                       sf.units * sf.revenue_price AS revenue,
+                       sf.units * sf.income_price AS income
+                FROM sales_fact sf
+                JOIN products p ON p.id = sf.product_id
+                JOIN flavors f ON f.id = sf.flavor_id
+                LEFT JOIN providers pr ON pr.id = sf.provider_id
+                LEFT JOIN workers w ON w.id = sf.worker_id
+                LEFT JOIN routes ro ON ro.id = sf.route_id
+                WHERE ($5::uuid IS NULL OR sf.product_id = $5)
+                  AND ($6::uuid IS NULL OR sf.flavor_id = $6)
+                  AND ($7::uuid IS NULL OR sf.provider_id = $7)
+                  AND ($8::uuid IS NULL OR sf.worker_id = $8)
+                  AND ($9::uuid IS NULL OR sf.route_id = $9)
+                  AND ($10::text IS NULL OR sf.channel = $10)
+            )
+            SELECT period_start, key, MAX(label) AS label,
+                   SUM(units)::int AS units,
+                   SUM(revenue) AS revenue,
+                   SUM(income) AS income
+            FROM keyed
+            GROUP BY period_start, key
+            HAVING SUM(units) > 0
+            ORDER BY period_start, revenue DESC, label
+            "#
+        ))
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(filter.period.as_str())
+        .bind(filter.breakdown.map(|d| d.as_str()))
+        .bind(filter.product_id)
+        .bind(filter.flavor_id)
+        .bind(filter.provider_id)
+        .bind(filter.worker_id)
+        .bind(filter.route_id)
+        .bind(filter.channel.map(|c| c.as_str()))
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn route_profitability(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        route_id: Option<Uuid>,
+        worker_id: Option<Uuid>,
+    ) -> Result<Vec<RouteWorkerRow>, AppError> {
+        // El precio de cada línea es el registrado al cargar; si se cargó sin
+        // registro, el vigente para el proveedor del lote a la fecha de salida.
+        Ok(sqlx::query_as::<_, RouteWorkerRow>(
+            r#"
+            WITH trips AS (
+                SELECT t.id, t.route_id, t.worker_id, t.amount_due
+                FROM worker_trips t
+                WHERE t.status = 'returned' AND t.route_id IS NOT NULL
+                  AND t.return_time::date BETWEEN $1 AND $2
+                  AND ($3::uuid IS NULL OR t.route_id = $3)
+                  AND ($4::uuid IS NULL OR t.worker_id = $4)
+            ),
+            lines AS (
+                SELECT li.trip_id,
+                       SUM(li.quantity) AS loaded,
+                       SUM(COALESCE(r.quantity, 0)) AS returned,
+                       SUM(GREATEST(li.quantity - COALESCE(r.quantity, 0), 0)) AS sold,
+                       SUM(GREATEST(li.quantity - COALESCE(r.quantity, 0), 0)
+                           * (ph.price_base - ph.cost_price)) AS margin,
+                       SUM(GREATEST(li.quantity - COALESCE(r.quantity, 0), 0)
+                           * (ph.price_route - li.unit_price)) AS commission,
+                       SUM(GREATEST(li.quantity - COALESCE(r.quantity, 0), 0))
+                           FILTER (WHERE ph.price_base IS NULL) AS unpriced
+                FROM worker_trip_loaded_items li
+                JOIN worker_trips t ON t.id = li.trip_id
+                JOIN trips tr ON tr.id = t.id
+                LEFT JOIN inventory inv ON inv.id = li.inventory_id
+                LEFT JOIN LATERAL (
+                    SELECT SUM(ri.quantity) AS quantity
+                    FROM worker_trip_returned_items ri
+                    WHERE ri.loaded_item_id = li.id
+                ) r ON TRUE
+                LEFT JOIN LATERAL (
+                    SELECT p.price_base, p.cost_price, p.price_route
+                    FROM price_history p
+                    WHERE p.id = li.price_history_id
+                       OR (li.price_history_id IS NULL
+                           AND p.product_id = li.product_id
+                           AND p.flavor_id = li.flavor_id
+                           AND p.provider_id = inv.provider_id
+                           AND p.effective_date <= t.departure_time)
+                    ORDER BY p.effective_date DESC
+                    LIMIT 1
+                ) ph ON TRUE
+                GROUP BY li.trip_id
+            )
+            SELECT ro.id AS route_id, ro.name::text AS route_name, ro.usage_count,
+                   w.id AS worker_id, w.name::text AS worker_name,
+                   COUNT(*)::int AS trips,
+                   COALESCE(SUM(l.loaded), 0)::int AS loaded_units,
+                   COALESCE(SUM(l.returned), 0)::int AS returned_units,
+                   COALESCE(SUM(l.sold), 0)::int AS sold_units,
+                   SUM(tr.amount_due) AS sales,
+                   COALESCE(SUM(l.margin), 0) AS business_margin,
+                   COALESCE(SUM(l.commission), 0) AS commission,
+                   COALESCE(SUM(l.unpriced), 0)::int AS unpriced_units
+            FROM trips tr
+            JOIN routes ro ON ro.id = tr.route_id
+            JOIN workers w ON w.id = tr.worker_id
+            LEFT JOIN lines l ON l.trip_id = tr.id
+            GROUP BY ro.id, w.id
+            ORDER BY ro.name, w.name
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(route_id)
+        .bind(worker_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn monthly_cost_of_goods(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<MonthlyCost>, AppError> {
+        Ok(sqlx::query_as::<_, MonthlyCost>(&format!(
+            r#"
+            WITH {SALES_FACT}
+            SELECT date_trunc('month', sf.sold_at)::date AS month,
+                   COALESCE(SUM(sf.units * sf.cost_price), 0) AS cost,
+                   COALESCE(SUM(sf.units) FILTER (WHERE sf.cost_price IS NULL), 0)::int
+                       AS uncosted_units
+            FROM sales_fact sf
+            GROUP BY 1
+            ORDER BY 1
+            "#
+        ))
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn monthly_outflows(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<MonthlyOutflow>, AppError> {
+        Ok(sqlx::query_as::<_, MonthlyOutflow>(
+            r#"
+            SELECT date_trunc('month', c.created_at)::date AS month,
+                   c.type::text AS kind, c.category::text AS category,
+                   -SUM(c.amount) AS amount
+            FROM cash_register c
+            WHERE c.type IN ('expense', 'owner_withdrawal')
+              AND c.created_at::date BETWEEN $1 AND $2
+              AND NOT EXISTS (
+                  SELECT 1 FROM cash_register r
+                  WHERE r.type = 'reversal' AND r.related_doc_id = c.id
+              )
+            GROUP BY 1, 2, 3
+            ORDER BY 1, 2, 3
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn inventory_valuation(&self) -> Result<Vec<InventoryValuationRow>, AppError> {
+        Ok(sqlx::query_as::<_, InventoryValuationRow>(
+            r#"
+            WITH current_prices AS (
+                SELECT DISTINCT ON (product_id, flavor_id, provider_id)
+                       product_id, flavor_id, provider_id, cost_price, price_base, price_route
+                FROM price_history
+                WHERE effective_date <= NOW()
+                ORDER BY product_id, flavor_id, provider_id, effective_date DESC
+            )
+            SELECT i.id AS inventory_id, i.freezer_id, fz.number AS freezer_number,
+                   p.id AS product_id, p.name::text AS product_name,
+                   f.id AS flavor_id, f.name::text AS flavor_name,
+                   pr.id AS provider_id, pr.name::text AS provider_name,
+                   i.is_deformed, i.quantity,
+                   cp.cost_price, cp.price_base, cp.price_route
+            FROM inventory i
+            JOIN freezers fz ON fz.id = i.freezer_id
+            JOIN products p ON p.id = i.product_id
+            JOIN flavors f ON f.id = i.flavor_id
+            JOIN providers pr ON pr.id = i.provider_id
+            LEFT JOIN current_prices cp
+                   ON cp.product_id = i.product_id
+                  AND cp.flavor_id = i.flavor_id
+                  AND cp.provider_id = i.provider_id
+            WHERE i.quantity > 0
+            ORDER BY fz.number, p.name, f.name, pr.name
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+}
@@ -11,6 +11,7 @@ pub async fn execute(
     repo: &Arc<dyn UserRepository>,
     id: Uuid,
     dto: UpdateUserDto,
+    updated_by: Uuid,
 ) -> Result<User, AppError> {
     // Verificar que el usuario existe
     let _existing = repo
@@ -18,6 +19,6 @@ pub async fn execute(
         .await?
         .ok_or_else(|| AppError::NotFound(format!("Usuario con id {id} no encontrado")))?;
 
-    let user = repo.update(id, &dto).await?;
+    let user = repo.update(id, &dto, updated_by).await?;
     Ok(user)
 }
@@ -26,9 +26,14 @@ pub trait UserRepository: Send + Sync {
         -> Result<User, AppError>;
 
     /// Actualizar un usuario existente.
-    async fn update(&self, id: Uuid, dto: &UpdateUserDto) -> Result<User, AppError>;
-
-    /// Actualizar la fecha de último login.
+    async fn update(
+        &self,
+        id: Uuid,
+        dto: &UpdateUserDto,
+        updated_by: Uuid,
+    ) -> Result<User, AppError>;
+
+    /// Actualizar la fecha de último login (no se audita: no es un cambio de datos).
     async fn update_last_login(&self, id: Uuid) -> Result<(), AppError>;
 
     /// Verificar si un usuario tiene un rol específico o superior.
@@ -142,7 +142,7 @@ async fn update_handler(
     Json(dto): Json<UpdateUserDto>,
 ) -> Result<Json<UserResponse>, AppError> {
     auth.require_owner()?;
-    let user = update_user::execute(&state.repo, id, dto).await?;
+    let user = update_user::execute(&state.repo, id, dto, auth.user_id()).await?;
     Ok(Json(UserResponse::from(user)))
 }
 
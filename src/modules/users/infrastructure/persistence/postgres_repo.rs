@@ -1,7 +1,10 @@
 use async_trait::async_trait;
 use sqlx::PgPool;
+use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::modules::audit_log::domain::entities::CreateAuditLogDto;
+use crate::modules::audit_log::domain::repositories::AuditLogRepository;
 use crate::modules::users::domain::entities::{CreateUserDto, UpdateUserDto, User};
 use crate::modules::users::domain::repositories::UserRepository;
 use crate::shared::auth::Role;
@@ -10,11 +13,12 @@ use crate::shared::errors::AppError;
 /// Implementación PostgreSQL del repositorio de usuarios.
 pub struct PgUserRepository {
     pool: PgPool,
+    audit: Arc<dyn AuditLogRepository>,
 }
 
 impl PgUserRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, audit: Arc<dyn AuditLogRepository>) -> Self {
+        Self { pool, audit }
     }
 }
 
@@ -57,6 +61,7 @@ impl UserRepository for PgUserRepository {
         dto: &CreateUserDto,
         created_by: Option<Uuid>,
     ) -> Result<User, AppError> {
+        let mut tx = self.pool.begin().await?;
         let user = sqlx::query_as::<_, User>(
             r#"
             INSERT INTO users (email, display_name, photo_url, role, notes, created_by)
@@ -70,12 +75,32 @@ impl UserRepository for PgUserRepository {
         .bind(dto.role.as_str())
         .bind(&dto.notes)
         .bind(created_by)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
         .await?;
+
+        // Sin creador (primer login) el propio usuario figura como autor
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::created("users", user.id, &user, created_by.unwrap_or(user.id))?,
+            )
+            .await?;
+        tx.commit().await?;
         Ok(user)
     }
 
-    async fn update(&self, id: Uuid, dto: &UpdateUserDto) -> Result<User, AppError> {
+    async fn update(
+        &self,
+        id: Uuid,
+        dto: &UpdateUserDto,
+        updated_by: Uuid,
+    ) -> Result<User, AppError> {
+        let mut tx = self.pool.begin().await?;
+        let before = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1 FOR UPDATE")
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+
         let user = sqlx::query_as::<_, User>(
             r#"
             UPDATE users SET
@@ -94,8 +119,16 @@ impl UserRepository for PgUserRepository {
         .bind(dto.active)
         .bind(&dto.notes)
         .bind(id)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
         .await?;
+
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::updated("users", id, &before, &user, updated_by)?,
+            )
+            .await?;
+        tx.commit().await?;
         Ok(user)
     }
 
@@ -0,0 +1,165 @@
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use std::fmt::Write;
+use uuid::Uuid;
+
+use crate::modules::worker_payments::domain::entities::{
+    build_statement, StatementEntry, StatementEntryKind, StatementQuery, WorkerStatement,
+};
+use crate::modules::worker_payments::domain::repositories::WorkerPaymentRepository;
+use crate::shared::errors::AppError;
+
+pub async fn get_statement(
+    repo: &dyn WorkerPaymentRepository,
+    worker_id: Uuid,
+    query: &StatementQuery,
+) -> Result<WorkerStatement, AppError> {
+    let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = query
+        .from
+        .unwrap_or_else(|| to.with_day(1).unwrap_or(to));
+    if from > to {
+        return Err(AppError::BadRequest(
+            "La fecha inicial no puede ser posterior a la final".into(),
+        ));
+    }
+
+    let until = (to + Duration::days(1)).and_time(chrono::NaiveTime::MIN).and_utc();
+    let source = repo.statement_source(worker_id, until).await?;
+    Ok(build_statement(source, from, to))
+}
+
+fn money(amount: Decimal) -> String {
+    if amount < Decimal::ZERO {
+        format!("-${:.2}", -amount)
+    } else {
+        format!("${:.2}", amount)
+    }
+}
+
+fn date(d: NaiveDate) -> String {
+    d.format("%d/%m/%Y").to_string()
+}
+
+/// Concepto legible de un movimiento, común al texto y al HTML.
+fn concept(entry: &StatementEntry) -> String {
+    let detail = entry
+        .description
+        .as_deref()
+        .filter(|d| !d.trim().is_empty());
+    match (entry.kind, detail) {
+        (StatementEntryKind::Trip, Some(route)) => {
+            format!("Viaje {route} ({} vendidos)", entry.sold_quantity)
+        }
+        (StatementEntryKind::Trip, None) => format!("Viaje ({} vendidos)", entry.sold_quantity),
+        (StatementEntryKind::Correction, Some(reason)) => format!("Corrección: {reason}"),
+        (StatementEntryKind::Correction, None) => "Corrección de viaje".into(),
+        (StatementEntryKind::Payment, Some(notes)) => format!("Abono: {notes}"),
+        (StatementEntryKind::Payment, None) => "Abono".into(),
+    }
+}
+
+fn signed_amount(entry: &StatementEntry) -> String {
+    if entry.credit > Decimal::ZERO {
+        format!("-{}", money(entry.credit))
+    } else {
+        format!("+{}", money(entry.debit))
+    }
+}
+
+/// Texto plano pensado para pegarse en WhatsApp (`*negrita*`).
+pub fn render_text(s: &WorkerStatement) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "*Estado de cuenta — {}*", s.worker_name);
+    let _ = writeln!(out, "Del {} al {}", date(s.from), date(s.to));
+    let _ = writeln!(out);
+    let _ = writeln!(out, "Saldo inicial: {}", money(s.opening_balance));
+    if s.entries.is_empty() {
+        let _ = writeln!(out, "Sin movimientos en el periodo");
+    }
+    for e in &s.entries {
+        let _ = writeln!(
+            out,
+            "{} {} {} → {}",
+            date(e.occurred_at.date_naive()),
+            concept(e),
+            signed_amount(e),
+            money(e.balance)
+        );
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "Cargos: {}", money(s.total_debits));
+    let _ = writeln!(out, "Abonos: {}", money(s.total_credits));
+    let _ = writeln!(out, "*Saldo final: {}*", money(s.closing_balance));
+    let _ = writeln!(
+        out,
+        "Vendidos: {} · Comisión ganada: {}",
+        s.sold_quantity,
+        money(s.commission_earned)
+    );
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Página HTML imprimible, sin recursos externos.
+pub fn render_html(s: &WorkerStatement) -> String {
+    let name = escape_html(&s.worker_name);
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"es\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Estado de cuenta — {name}</title>\n\
+         <style>body{{font-family:sans-serif}}table{{border-collapse:collapse}}\
+         td,th{{border:1px solid #999;padding:4px 8px}}td.n{{text-align:right}}</style>\n\
+         </head>\n<body>\n<h1>Estado de cuenta — {name}</h1>\n<p>Del {} al {}</p>\n",
+        date(s.from),
+        date(s.to)
+    );
+    let _ = writeln!(
+        out,
+        "<table>\n<tr><th>Fecha</th><th>Concepto</th><th>Cargo</th><th>Abono</th><th>Saldo</th></tr>"
+    );
+    let _ = writeln!(
+        out,
+        "<tr><td></td><td>Saldo inicial</td><td></td><td></td><td class=\"n\">{}</td></tr>",
+        money(s.opening_balance)
+    );
+    let cell = |d: Decimal| {
+        if d.is_zero() {
+            String::new()
+        } else {
+            money(d)
+        }
+    };
+    for e in &s.entries {
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td>{}</td><td class=\"n\">{}</td><td class=\"n\">{}</td><td class=\"n\">{}</td></tr>",
+            date(e.occurred_at.date_naive()),
+            escape_html(&concept(e)),
+            cell(e.debit),
+            cell(e.credit),
+            money(e.balance)
+        );
+    }
+    let _ = writeln!(
+        out,
+        "<tr><th></th><th>Totales</th><th class=\"n\">{}</th><th class=\"n\">{}</th><th class=\"n\">{}</th></tr>\n</table>",
+        money(s.total_debits),
+        money(s.total_credits),
+        money(s.closing_balance)
+    );
+    let _ = writeln!(
+        out,
+        "<p>Vendidos: {} · Comisión ganada: {}</p>\n</body>\n</html>",
+        s.sold_quantity,
+        money(s.commission_earned)
+    );
+    out
+}
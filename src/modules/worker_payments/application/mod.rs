@@ -1 +1,2 @@
+pub mod account_statement;
 pub mod pay_worker;
@@ -1,6 +1,7 @@
-use crate::modules::worker_payments::domain::entities::WorkerPayment;
+use crate::modules::worker_payments::domain::entities::{CreateWorkerPaymentDto, WorkerPayment};
 use crate::modules::worker_payments::domain::repositories::WorkerPaymentRepository;
 use crate::shared::errors::AppError;
+use rust_decimal::Decimal;
 use uuid::Uuid;
 
 pub async fn list_by_worker(
@@ -10,13 +11,11 @@ pub async fn list_by_worker(
     repo.find_by_worker(worker_id).await
 }
 
-pub async fn get_by_trip(
+pub async fn list_by_trip(
     repo: &dyn WorkerPaymentRepository,
     trip_id: Uuid,
-) -> Result<WorkerPayment, AppError> {
-    repo.find_by_trip(trip_id)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Pago no encontrado para este viaje".into()))
+) -> Result<Vec<WorkerPayment>, AppError> {
+    repo.find_by_trip(trip_id).await
 }
 
 pub async fn create_payment(
@@ -24,11 +23,25 @@ pub async fn create_payment(
     trip_id: Uuid,
     created_by: Uuid,
 ) -> Result<WorkerPayment, AppError> {
-    // Verificar que no exista pago para este viaje
-    if repo.find_by_trip(trip_id).await?.is_some() {
-        return Err(AppError::Conflict(
-            "Ya existe un pago para este viaje".into(),
+    // El repositorio descuenta los abonos previos y rechaza viajes ya pagados
+    repo.create_payment(trip_id, created_by).await
+}
+
+/// Abono parcial (o total) a la deuda de un trabajador.
+/// La validación contra la deuda actual se hace en el repositorio, con el trabajador bloqueado.
+pub async fn create_worker_payment(
+    repo: &dyn WorkerPaymentRepository,
+    worker_id: Uuid,
+    mut dto: CreateWorkerPaymentDto,
+    created_by: Uuid,
+) -> Result<WorkerPayment, AppError> {
+    if dto.amount <= Decimal::ZERO {
+        return Err(AppError::BadRequest(
+            "El monto del abono debe ser positivo".into(),
         ));
     }
-    repo.create_payment(trip_id, created_by).await
+    dto.trip_ids.sort();
+    dto.trip_ids.dedup();
+    repo.create_worker_payment(worker_id, &dto, created_by)
+        .await
 }
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -8,15 +8,183 @@ use uuid::Uuid;
 pub struct WorkerPayment {
     pub id: Uuid,
     pub worker_id: Uuid,
-    pub trip_id: Uuid,
+    /// Viaje pagado. `None` en abonos a la deuda general o que cubren varios viajes.
+    pub trip_id: Option<Uuid>,
     pub amount: Decimal,
     pub previous_debt: Decimal,
     pub new_debt: Decimal,
     pub created_at: DateTime<Utc>,
     pub created_by: Uuid,
+    pub notes: Option<String>,
 }
 
+/// Pago completo del `amount_due` de un viaje.
 #[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct CreatePaymentDto {
     pub trip_id: Uuid,
 }
+
+/// Abono de un monto arbitrario a la deuda del trabajador.
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct CreateWorkerPaymentDto {
+    pub amount: Decimal,
+    /// Viajes a los que se relaciona el abono (opcional).
+    #[serde(default)]
+    pub trip_ids: Vec<Uuid>,
+    /// Permite pagar más que la deuda actual, dejando saldo a favor del trabajador.
+    #[serde(default)]
+    pub allow_credit: bool,
+    pub notes: Option<String>,
+}
+
+// ─── Estado de cuenta ───────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementEntryKind {
+    /// Viaje retornado: cargo por el `amount_due` con que se cerró.
+    Trip,
+    /// Corrección de devoluciones: cargo o descuento por la diferencia.
+    Correction,
+    /// Pago o abono del trabajador.
+    Payment,
+}
+
+/// Movimiento de la deuda de un trabajador, tal como sale de la base.
+/// `amount` positivo aumenta la deuda; negativo la reduce.
+#[derive(Debug, Clone)]
+pub struct StatementMovement {
+    pub occurred_at: DateTime<Utc>,
+    pub kind: StatementEntryKind,
+    /// Viaje (cargos y correcciones) o pago.
+    pub reference_id: Uuid,
+    /// Ruta del viaje, motivo de la corrección o nota del pago.
+    pub description: Option<String>,
+    pub amount: Decimal,
+    pub sold_quantity: i32,
+    /// Ganancia del trabajador: (precio de ruta − precio cobrado) × vendidos.
+    pub commission: Decimal,
+}
+
+/// Trabajador y todos sus movimientos hasta el fin del periodo.
+#[derive(Debug, Clone)]
+pub struct StatementSource {
+    pub worker_id: Uuid,
+    pub worker_name: String,
+    pub current_debt: Decimal,
+    pub movements: Vec<StatementMovement>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct StatementEntry {
+    pub occurred_at: DateTime<Utc>,
+    pub kind: StatementEntryKind,
+    pub reference_id: Uuid,
+    pub description: Option<String>,
+    pub sold_quantity: i32,
+    pub debit: Decimal,
+    pub credit: Decimal,
+    /// Saldo después del movimiento.
+    pub balance: Decimal,
+}
+
+/// Estado de cuenta de un trabajador en un rango de fechas (UTC, inclusivo).
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct WorkerStatement {
+    pub worker_id: Uuid,
+    pub worker_name: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub opening_balance: Decimal,
+    pub entries: Vec<StatementEntry>,
+    pub total_debits: Decimal,
+    pub total_credits: Decimal,
+    pub closing_balance: Decimal,
+    pub sold_quantity: i32,
+    pub commission_earned: Decimal,
+    /// Deuda registrada hoy en el trabajador (coincide con el saldo final
+    /// cuando el periodo llega hasta hoy).
+    pub current_debt: Decimal,
+}
+
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct StatementQuery {
+    /// Por defecto, el primer día del mes de `to`.
+    pub from: Option<NaiveDate>,
+    /// Por defecto, hoy.
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementFormat {
+    /// Texto plano para WhatsApp.
+    #[default]
+    Text,
+    Html,
+}
+
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct PrintStatementQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    #[serde(default)]
+    pub format: StatementFormat,
+}
+
+/// Arma el estado de cuenta: lo anterior a `from` forma el saldo inicial y
+/// cada movimiento del periodo acumula el saldo corrido. Los movimientos
+/// deben venir ordenados y no pasar de `to`.
+pub fn build_statement(
+    source: StatementSource,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> WorkerStatement {
+    let mut opening_balance = Decimal::ZERO;
+    let mut balance = Decimal::ZERO;
+    let mut entries = Vec::new();
+    let (mut total_debits, mut total_credits) = (Decimal::ZERO, Decimal::ZERO);
+    let (mut sold_quantity, mut commission_earned) = (0, Decimal::ZERO);
+
+    for m in source.movements {
+        balance += m.amount;
+        if m.occurred_at.date_naive() < from {
+            opening_balance = balance;
+            continue;
+        }
+        let (debit, credit) = if m.amount >= Decimal::ZERO {
+            (m.amount, Decimal::ZERO)
+        } else {
+            (Decimal::ZERO, -m.amount)
+        };
+        total_debits += debit;
+        total_credits += credit;
+        sold_quantity += m.sold_quantity;
+        commission_earned += m.commission;
+        entries.push(StatementEntry {
+            occurred_at: m.occurred_at,
+            kind: m.kind,
+            reference_id: m.reference_id,
+            description: m.description,
+            sold_quantity: m.sold_quantity,
+            debit,
+            credit,
+            balance,
+        });
+    }
+
+    WorkerStatement {
+        worker_id: source.worker_id,
+        worker_name: source.worker_name,
+        from,
+        to,
+        opening_balance,
+        entries,
+        total_debits,
+        total_credits,
+        closing_balance: balance,
+        sold_quantity,
+        commission_earned,
+        current_debt: source.current_debt,
+    }
+}
@@ -1,17 +1,35 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
-use super::entities::WorkerPayment;
+use super::entities::{CreateWorkerPaymentDto, StatementSource, WorkerPayment};
 use crate::shared::errors::AppError;
 
 #[async_trait]
 pub trait WorkerPaymentRepository: Send + Sync {
     async fn find_by_worker(&self, worker_id: Uuid) -> Result<Vec<WorkerPayment>, AppError>;
-    async fn find_by_trip(&self, trip_id: Uuid) -> Result<Option<WorkerPayment>, AppError>;
-    /// Crea pago, actualiza deuda a 0, registra en caja. Todo en una transacción.
+    /// Pagos relacionados con un viaje (completos o abonos).
+    async fn find_by_trip(&self, trip_id: Uuid) -> Result<Vec<WorkerPayment>, AppError>;
+    /// Crea pago por lo pendiente del viaje (`amount_due` menos abonos ligados a él),
+    /// descuenta deuda, registra en caja. Todo en una transacción; `Conflict` si ya está pagado.
     async fn create_payment(
         &self,
         trip_id: Uuid,
         created_by: Uuid,
     ) -> Result<WorkerPayment, AppError>;
+    /// Registra un abono por un monto arbitrario contra la deuda del trabajador,
+    /// opcionalmente relacionado con viajes. Deuda, pago y caja en una transacción.
+    async fn create_worker_payment(
+        &self,
+        worker_id: Uuid,
+        dto: &CreateWorkerPaymentDto,
+        created_by: Uuid,
+    ) -> Result<WorkerPayment, AppError>;
+    /// Movimientos de la deuda del trabajador (viajes, correcciones y pagos)
+    /// anteriores a `until`, en orden cronológico.
+    async fn statement_source(
+        &self,
+        worker_id: Uuid,
+        until: DateTime<Utc>,
+    ) -> Result<StatementSource, AppError>;
 }
@@ -1,5 +1,7 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
@@ -7,7 +9,7 @@ use std::sync::Arc;
 use utoipa::OpenApi;
 use uuid::Uuid;
 
-use crate::modules::worker_payments::application::pay_worker;
+use crate::modules::worker_payments::application::{account_statement, pay_worker};
 use crate::modules::worker_payments::domain::entities::*;
 use crate::modules::worker_payments::domain::repositories::WorkerPaymentRepository;
 use crate::shared::auth::{AppState, AuthUser};
@@ -15,10 +17,22 @@ use crate::shared::errors::AppError;
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(list_by_worker, get_by_trip, create_payment),
+    paths(
+        list_by_worker,
+        list_by_trip,
+        create_payment,
+        create_worker_payment,
+        worker_statement,
+        print_worker_statement
+    ),
     components(schemas(
         crate::modules::worker_payments::domain::entities::WorkerPayment,
         crate::modules::worker_payments::domain::entities::CreatePaymentDto,
+        crate::modules::worker_payments::domain::entities::CreateWorkerPaymentDto,
+        crate::modules::worker_payments::domain::entities::WorkerStatement,
+        crate::modules::worker_payments::domain::entities::StatementEntry,
+        crate::modules::worker_payments::domain::entities::StatementEntryKind,
+        crate::modules::worker_payments::domain::entities::StatementFormat,
     ))
 )]
 pub struct PaymentsApiDoc;
@@ -38,8 +52,16 @@ impl axum::extract::FromRef<PaymentsState> for AppState {
 pub fn router(app: AppState, repo: Arc<dyn WorkerPaymentRepository>) -> Router {
     let state = PaymentsState { app, repo };
     Router::new()
-        .route("/worker/{worker_id}", get(list_by_worker))
-        .route("/trip/{trip_id}", get(get_by_trip))
+        .route(
+            "/worker/{worker_id}",
+            get(list_by_worker).post(create_worker_payment),
+        )
+        .route("/worker/{worker_id}/statement", get(worker_statement))
+        .route(
+            "/worker/{worker_id}/statement/print",
+            get(print_worker_statement),
+        )
+        .route("/trip/{trip_id}", get(list_by_trip))
         .route("/", post(create_payment))
         .with_state(state)
 }
@@ -63,20 +85,17 @@ async fn list_by_worker(
 #[utoipa::path(
     get, path = "/trip/{trip_id}", tag = "Pagos a Trabajadores",
     params(("trip_id" = Uuid, Path, description = "ID del viaje")),
-    responses(
-        (status = 200, description = "Pago del viaje", body = WorkerPayment),
-        (status = 404, description = "No encontrado")
-    ),
+    responses((status = 200, description = "Pagos relacionados con el viaje", body = Vec<WorkerPayment>)),
     security(("bearer_auth" = []))
 )]
-async fn get_by_trip(
+async fn list_by_trip(
     State(state): State<PaymentsState>,
     auth: AuthUser,
     Path(trip_id): Path<Uuid>,
-) -> Result<Json<WorkerPayment>, AppError> {
+) -> Result<Json<Vec<WorkerPayment>>, AppError> {
     auth.require_role(crate::shared::auth::Role::Admin)?;
-    let payment = pay_worker::get_by_trip(state.repo.as_ref(), trip_id).await?;
-    Ok(Json(payment))
+    let payments = pay_worker::list_by_trip(state.repo.as_ref(), trip_id).await?;
+    Ok(Json(payments))
 }
 
 #[utoipa::path(
@@ -95,3 +114,88 @@ async fn create_payment(
         pay_worker::create_payment(state.repo.as_ref(), dto.trip_id, auth.user_id()).await?;
     Ok(Json(payment))
 }
+
+#[utoipa::path(
+    post, path = "/worker/{worker_id}", tag = "Pagos a Trabajadores",
+    params(("worker_id" = Uuid, Path, description = "ID del trabajador")),
+    request_body = CreateWorkerPaymentDto,
+    responses(
+        (status = 200, description = "Abono registrado", body = WorkerPayment),
+        (status = 400, description = "Monto inválido o mayor a la deuda"),
+        (status = 404, description = "Trabajador no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn create_worker_payment(
+    State(state): State<PaymentsState>,
+    auth: AuthUser,
+    Path(worker_id): Path<Uuid>,
+    Json(dto): Json<CreateWorkerPaymentDto>,
+) -> Result<Json<WorkerPayment>, AppError> {
+    auth.require_role(crate::shared::auth::Role::Admin)?;
+    let payment = pay_worker::create_worker_payment(
+        state.repo.as_ref(),
+        worker_id,
+        dto,
+        auth.user_id(),
+    )
+    .await?;
+    Ok(Json(payment))
+}
+
+#[utoipa::path(
+    get, path = "/worker/{worker_id}/statement", tag = "Pagos a Trabajadores",
+    params(("worker_id" = Uuid, Path, description = "ID del trabajador"), StatementQuery),
+    responses(
+        (status = 200, description = "Estado de cuenta con saldo corrido", body = WorkerStatement),
+        (status = 400, description = "Rango de fechas inválido"),
+        (status = 404, description = "Trabajador no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn worker_statement(
+    State(state): State<PaymentsState>,
+    auth: AuthUser,
+    Path(worker_id): Path<Uuid>,
+    Query(q): Query<StatementQuery>,
+) -> Result<Json<WorkerStatement>, AppError> {
+    auth.require_role(crate::shared::auth::Role::Admin)?;
+    let statement = account_statement::get_statement(state.repo.as_ref(), worker_id, &q).await?;
+    Ok(Json(statement))
+}
+
+#[utoipa::path(
+    get, path = "/worker/{worker_id}/statement/print", tag = "Pagos a Trabajadores",
+    params(("worker_id" = Uuid, Path, description = "ID del trabajador"), PrintStatementQuery),
+    responses(
+        (status = 200, description = "Estado de cuenta para WhatsApp", content_type = "text/plain", body = String),
+        (status = 200, description = "Estado de cuenta imprimible", content_type = "text/html", body = String),
+        (status = 404, description = "Trabajador no encontrado")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn print_worker_statement(
+    State(state): State<PaymentsState>,
+    auth: AuthUser,
+    Path(worker_id): Path<Uuid>,
+    Query(q): Query<PrintStatementQuery>,
+) -> Result<Response, AppError> {
+    auth.require_role(crate::shared::auth::Role::Admin)?;
+    let range = StatementQuery {
+        from: q.from,
+        to: q.to,
+    };
+    let statement =
+        account_statement::get_statement(state.repo.as_ref(), worker_id, &range).await?;
+    let (content_type, body) = match q.format {
+        StatementFormat::Text => (
+            "text/plain; charset=utf-8",
+            account_statement::render_text(&statement),
+        ),
+        StatementFormat::Html => (
+            "text/html; charset=utf-8",
+            account_statement::render_html(&statement),
+        ),
+    };
+    Ok(([(header::CONTENT_TYPE, content_type)], body).into_response())
+}
@@ -1,19 +1,28 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use sqlx::PgPool;
+use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::modules::worker_payments::domain::entities::WorkerPayment;
+use crate::modules::audit_log::domain::entities::CreateAuditLogDto;
+use crate::modules::audit_log::domain::repositories::AuditLogRepository;
+use crate::modules::cash_register::domain::entities::{CashEntry, CashTransactionType};
+use crate::modules::cash_register::infrastructure::persistence::ledger::append_cash_tx;
+use crate::modules::worker_payments::domain::entities::{
+    CreateWorkerPaymentDto, StatementEntryKind, StatementMovement, StatementSource, WorkerPayment,
+};
 use crate::modules::worker_payments::domain::repositories::WorkerPaymentRepository;
 use crate::shared::errors::AppError;
 
 pub struct PgWorkerPaymentRepository {
     pool: PgPool,
+    audit: Arc<dyn AuditLogRepository>,
 }
 
 impl PgWorkerPaymentRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, audit: Arc<dyn AuditLogRepository>) -> Self {
+        Self { pool, audit }
     }
 }
 
@@ -29,6 +38,173 @@ struct TripAmountDue {
     status: String,
 }
 
+#[derive(sqlx::FromRow)]
+struct StatementWorker {
+    name: String,
+    current_debt: Decimal,
+}
+
+#[derive(sqlx::FromRow)]
+struct StatementRow {
+    occurred_at: DateTime<Utc>,
+    kind: String,
+    reference_id: Uuid,
+    description: Option<String>,
+    amount: Decimal,
+    sold_quantity: i32,
+    commission: Decimal,
+}
+
+impl From<StatementRow> for StatementMovement {
+    fn from(r: StatementRow) -> Self {
+        let kind = match r.kind.as_str() {
+            "trip" => StatementEntryKind::Trip,
+            "correction" => StatementEntryKind::Correction,
+            _ => StatementEntryKind::Payment,
+        };
+        StatementMovement {
+            occurred_at: r.occurred_at,
+            kind,
+            reference_id: r.reference_id,
+            description: r.description,
+            amount: r.amount,
+            sold_quantity: r.sold_quantity,
+            commission: r.commission,
+        }
+    }
+}
+
+/// Datos de un pago a registrar dentro de una transacción.
+struct NewPayment<'a> {
+    worker_id: Uuid,
+    amount: Decimal,
+    trip_ids: &'a [Uuid],
+    allow_credit: bool,
+    notes: Option<&'a str>,
+    created_by: Uuid,
+}
+
+/// Registra el pago, actualiza la deuda del trabajador, la caja y la auditoría.
+/// El trabajador se bloquea (`FOR UPDATE`) para que `previous_debt`/`new_debt`
+/// sean consistentes con `workers.current_debt` ante pagos concurrentes.
+async fn insert_payment_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    audit: &dyn AuditLogRepository,
+    p: NewPayment<'_>,
+) -> Result<WorkerPayment, AppError> {
+    // 1. Obtener deuda actual del trabajador (con lock)
+    let worker = sqlx::query_as::<_, WorkerDebt>(
+        "SELECT current_debt FROM workers WHERE id = $1 FOR UPDATE",
+    )
+    .bind(p.worker_id)
+    .fetch_optional(&mut **tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Trabajador no encontrado".into()))?;
+
+    if !p.allow_credit && p.amount > worker.current_debt {
+        return Err(AppError::BadRequest(format!(
+            "El abono ({}) supera la deuda actual del trabajador ({})",
+            p.amount, worker.current_debt
+        )));
+    }
+
+    let new_debt = worker.current_debt - p.amount;
+
+    // 2. Registrar pago (trip_id solo si el pago corresponde a un único viaje)
+    let single_trip = match p.trip_ids {
+        [trip_id] => Some(*trip_id),
+        _ => None,
+    };
+
+    let payment = sqlx::query_as::<_, WorkerPayment>(
+        r#"
+        INSERT INTO worker_payments
+        (worker_id, trip_id, amount, previous_debt, new_debt, notes, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#,
+    )
+    .bind(p.worker_id)
+    .bind(single_trip)
+    .bind(p.amount)
+    .bind(worker.current_debt)
+    .bind(new_debt)
+    .bind(p.notes)
+    .bind(p.created_by)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    for trip_id in p.trip_ids {
+        sqlx::query("INSERT INTO worker_payment_trips (payment_id, trip_id) VALUES ($1, $2)")
+            .bind(payment.id)
+            .bind(trip_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    // 3. Actualizar deuda del trabajador
+    sqlx::query("UPDATE workers SET current_debt = $1 WHERE id = $2")
+        .bind(new_debt)
+        .bind(p.worker_id)
+        .execute(&mut **tx)
+        .await?;
+
+    // 4. Registrar en caja (Event Sourcing)
+    append_cash_tx(
+        tx,
+        CashEntry::doc(
+            CashTransactionType::WorkerPayment,
+            p.amount,
+            "worker_payments",
+            payment.id,
+        )
+        .with_description(p.notes.map(str::to_string)),
+        p.created_by,
+    )
+    .await?;
+
+    // 5. Auditoría
+    audit
+        .create_with_tx(
+            tx,
+            CreateAuditLogDto::created("worker_payments", payment.id, &payment, p.created_by)?,
+        )
+        .await?;
+
+    Ok(payment)
+}
+
+/// Lo pagado por un viaje: los pagos y abonos ligados solo a él cuentan
+/// completos; un abono ligado a varios viajes se reparte en proporción a su
+/// `amount_due` (en partes iguales si ninguno tiene monto).
+pub async fn paid_for_trip_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    trip_id: Uuid,
+) -> Result<Decimal, AppError> {
+    Ok(sqlx::query_scalar::<_, Decimal>(
+        r#"
+        SELECT COALESCE(SUM(ROUND(
+            CASE WHEN s.total_due > 0
+                 THEN wp.amount * COALESCE(t.amount_due, 0) / s.total_due
+                 ELSE wp.amount / s.trips
+            END, 2)), 0)
+        FROM worker_payment_trips wpt
+        JOIN worker_payments wp ON wp.id = wpt.payment_id
+        JOIN worker_trips t ON t.id = wpt.trip_id
+        JOIN LATERAL (
+            SELECT SUM(COALESCE(t2.amount_due, 0)) AS total_due, COUNT(*) AS trips
+            FROM worker_payment_trips x
+            JOIN worker_trips t2 ON t2.id = x.trip_id
+            WHERE x.payment_id = wp.id
+        ) s ON TRUE
+        WHERE wpt.trip_id = $1
+        "#,
+    )
+    .bind(trip_id)
+    .fetch_one(&mut **tx)
+    .await?)
+}
+
 #[async_trait]
 impl WorkerPaymentRepository for PgWorkerPaymentRepository {
     async fn find_by_worker(&self, worker_id: Uuid) -> Result<Vec<WorkerPayment>, AppError> {
@@ -40,13 +216,18 @@ impl WorkerPaymentRepository for PgWorkerPaymentRepository {
         .await?)
     }
 
-    async fn find_by_trip(&self, trip_id: Uuid) -> Result<Option<WorkerPayment>, AppError> {
-        Ok(
-            sqlx::query_as::<_, WorkerPayment>("SELECT * FROM worker_payments WHERE trip_id = $1")
-                .bind(trip_id)
-                .fetch_optional(&self.pool)
-                .await?,
+    async fn find_by_trip(&self, trip_id: Uuid) -> Result<Vec<WorkerPayment>, AppError> {
+        Ok(sqlx::query_as::<_, WorkerPayment>(
+            r#"
+            SELECT wp.* FROM worker_payments wp
+            JOIN worker_payment_trips wpt ON wpt.payment_id = wp.id
+            WHERE wpt.trip_id = $1
+            ORDER BY wp.created_at DESC
+            "#,
         )
+        .bind(trip_id)
+        .fetch_all(&self.pool)
+        .await?)
     }
 
     async fn create_payment(
@@ -56,9 +237,9 @@ impl WorkerPaymentRepository for PgWorkerPaymentRepository {
     ) -> Result<WorkerPayment, AppError> {
         let mut tx = self.pool.begin().await?;
 
-        // 1. Obtener el viaje y verificar estado
+        // Obtener el viaje (con lock, para no pagarlo dos veces) y verificar estado
         let trip = sqlx::query_as::<_, TripAmountDue>(
-            "SELECT worker_id, amount_due, status FROM worker_trips WHERE id = $1",
+            "SELECT worker_id, amount_due, status FROM worker_trips WHERE id = $1 FOR UPDATE",
         )
         .bind(trip_id)
         .fetch_optional(&mut *tx)
@@ -71,81 +252,173 @@ impl WorkerPaymentRepository for PgWorkerPaymentRepository {
             ));
         }
 
-        let amount_due = trip.amount_due.unwrap_or(Decimal::ZERO);
-
-        // 2. Obtener deuda actual del trabajador
-        let worker = sqlx::query_as::<_, WorkerDebt>(
-            "SELECT current_debt FROM workers WHERE id = $1",
+        // Solo el saldo pendiente: los abonos ligados al viaje ya se descontaron
+        let paid = paid_for_trip_tx(&mut tx, trip_id).await?;
+        let remaining = trip.amount_due.unwrap_or(Decimal::ZERO) - paid;
+        let has_payments = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM worker_payment_trips WHERE trip_id = $1)",
         )
-        .bind(trip.worker_id)
+        .bind(trip_id)
         .fetch_one(&mut *tx)
         .await?;
+        if has_payments && remaining <= Decimal::ZERO {
+            return Err(AppError::Conflict(
+                "Ya existe un pago para este viaje".into(),
+            ));
+        }
 
-        let new_debt = worker.current_debt - amount_due;
-
-        // 3. Registrar pago
-        let payment = sqlx::query_as::<_, WorkerPayment>(
-            r#"
-            INSERT INTO worker_payments 
-            (worker_id, trip_id, amount, previous_debt, new_debt, created_by)
-            VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING *
-            "#,
+        let payment = insert_payment_tx(
+            &mut tx,
+            self.audit.as_ref(),
+            NewPayment {
+                worker_id: trip.worker_id,
+                amount: remaining.max(Decimal::ZERO),
+                trip_ids: &[trip_id],
+                allow_credit: true,
+                notes: None,
+                created_by,
+            },
         )
-        .bind(trip.worker_id)
-        .bind(trip_id)
-        .bind(amount_due)
-        .bind(worker.current_debt)
-        .bind(new_debt)
-        .bind(created_by)
-        .fetch_one(&mut *tx)
         .await?;
 
-        // 4. Actualizar deuda del trabajador
-        sqlx::query("UPDATE workers SET current_debt = $1 WHERE id = $2")
-            .bind(new_debt)
-            .execute(&mut *tx)
+        tx.commit().await?;
+        Ok(payment)
+    }
+
+    async fn create_worker_payment(
+        &self,
+        worker_id: Uuid,
+        dto: &CreateWorkerPaymentDto,
+        created_by: Uuid,
+    ) -> Result<WorkerPayment, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // Los viajes relacionados deben ser del trabajador y estar completados
+        if !dto.trip_ids.is_empty() {
+            let valid = sqlx::query_scalar::<_, i64>(
+                r#"
+                SELECT COUNT(*) FROM worker_trips
+                WHERE id = ANY($1) AND worker_id = $2 AND status = 'returned'
+                "#,
+            )
+            .bind(&dto.trip_ids)
+            .bind(worker_id)
+            .fetch_one(&mut *tx)
             .await?;
 
-        // 5. Registrar en caja (Event Sourcing)
-        let current_balance = sqlx::query_scalar::<_, Option<Decimal>>(
-            "SELECT balance FROM cash_register ORDER BY created_at DESC LIMIT 1 FOR UPDATE",
+            if valid != dto.trip_ids.len() as i64 {
+                return Err(AppError::BadRequest(
+                    "Los viajes deben pertenecer al trabajador y estar completados".into(),
+                ));
+            }
+        }
+
+        let payment = insert_payment_tx(
+            &mut tx,
+            self.audit.as_ref(),
+            NewPayment {
+                worker_id,
+                amount: dto.amount,
+                trip_ids: &dto.trip_ids,
+                allow_credit: dto.allow_credit,
+                notes: dto.notes.as_deref(),
+                created_by,
+            },
         )
-        .fetch_optional(&mut *tx)
-        .await?
-        .flatten()
-        .unwrap_or(Decimal::ZERO);
+        .await?;
 
-        let new_balance = current_balance + amount_due;
+        tx.commit().await?;
+        Ok(payment)
+    }
 
-        sqlx::query(
-            r#"
-            INSERT INTO cash_register 
-            (type, amount, balance, related_doc_type, related_doc_id, created_by)
-            VALUES ('worker_payment', $1, $2, 'worker_payments', $3, $4)
-            "#,
+    async fn statement_source(
+        &self,
+        worker_id: Uuid,
+        until: DateTime<Utc>,
+    ) -> Result<StatementSource, AppError> {
+        let worker = sqlx::query_as::<_, StatementWorker>(
+            "SELECT name, current_debt FROM workers WHERE id = $1",
         )
-        .bind(amount_due)
-        .bind(new_balance)
-        .bind(payment.id)
-        .bind(created_by)
-        .execute(&mut *tx)
-        .await?;
+        .bind(worker_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Trabajador no encontrado".into()))?;
 
-        // 6. Auditoría
-        sqlx::query(
+        // El viaje solo guarda el último conteo: cada corrección queda en
+        // audit_log (antes/después) y el cargo original es el vigente menos
+        // la suma de correcciones.
+        let rows = sqlx::query_as::<_, StatementRow>(
             r#"
-            INSERT INTO audit_log (action, table_name, record_id, changes_after, created_by)
-            VALUES ('create', 'worker_payments', $1, $2, $3)
+            WITH corrections AS (
+                SELECT a.record_id AS trip_id,
+                       a.created_at AS occurred_at,
+                       (a.changes_after->>'amount_due')::numeric
+                           - (a.changes_before->>'amount_due')::numeric AS amount,
+                       (a.changes_after->>'sold_quantity')::int
+                           - (a.changes_before->>'sold_quantity')::int AS sold_quantity,
+                       a.changes_after->>'correction_reason' AS description
+                FROM audit_log a
+                JOIN worker_trips t ON t.id = a.record_id
+                WHERE a.table_name = 'worker_trips'
+                  AND a.action = 'update'
+                  AND a.changes_before->>'status' = 'returned'
+                  AND t.worker_id = $1
+            ),
+            commissions AS (
+                SELECT li.trip_id,
+                       SUM(GREATEST(li.quantity - COALESCE(r.quantity, 0), 0)
+                           * (ph.price_route - li.unit_price)) AS commission
+                FROM worker_trip_loaded_items li
+                JOIN worker_trips t ON t.id = li.trip_id
+                JOIN price_history ph ON ph.id = li.price_history_id
+                LEFT JOIN LATERAL (
+                    SELECT SUM(ri.quantity) AS quantity
+                    FROM worker_trip_returned_items ri
+                    WHERE ri.loaded_item_id = li.id
+                ) r ON TRUE
+                WHERE t.worker_id = $1 AND t.status = 'returned'
+                GROUP BY li.trip_id
+            )
+            SELECT * FROM (
+                SELECT t.return_time AS occurred_at,
+                       'trip' AS kind,
+                       t.id AS reference_id,
+                       ro.name::text AS description,
+                       t.amount_due - COALESCE(c.amount, 0) AS amount,
+                       (t.sold_quantity - COALESCE(c.sold_quantity, 0))::int AS sold_quantity,
+                       COALESCE(cm.commission, 0) AS commission
+                FROM worker_trips t
+                LEFT JOIN routes ro ON ro.id = t.route_id
+                LEFT JOIN commissions cm ON cm.trip_id = t.id
+                LEFT JOIN (
+                    SELECT trip_id, SUM(amount) AS amount, SUM(sold_quantity) AS sold_quantity
+                    FROM corrections GROUP BY trip_id
+                ) c ON c.trip_id = t.id
+                WHERE t.worker_id = $1 AND t.status = 'returned'
+                UNION ALL
+                SELECT occurred_at, 'correction', trip_id, description, amount, sold_quantity, 0
+                FROM corrections
+                UNION ALL
+                SELECT created_at, 'payment', id, notes, -amount, 0, 0
+                FROM worker_payments
+                WHERE worker_id = $1
+            ) m
+            WHERE m.occurred_at < $2
+            ORDER BY m.occurred_at,
+                     CASE m.kind WHEN 'trip' THEN 0 WHEN 'correction' THEN 1 ELSE 2 END,
+                     m.reference_id
             "#,
         )
-        .bind(payment.id)
-        .bind(serde_json::to_value(&payment)?)
-        .bind(created_by)
-        .execute(&mut *tx)
+        .bind(worker_id)
+        .bind(until)
+        .fetch_all(&self.pool)
         .await?;
 
-        tx.commit().await?;
-        Ok(payment)
+        Ok(StatementSource {
+            worker_id,
+            worker_name: worker.name,
+            current_debt: worker.current_debt,
+            movements: rows.into_iter().map(StatementMovement::from).collect(),
+        })
     }
 }
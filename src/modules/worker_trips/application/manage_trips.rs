@@ -1,8 +1,13 @@
 use uuid::Uuid;
 
+use crate::modules::inventory::domain::repositories::InventoryRepository;
+use crate::modules::pricing::application::resolve_prices;
+use crate::modules::pricing::domain::entities::PriceTier;
+use crate::modules::pricing::domain::repositories::PriceRepository;
 use crate::modules::worker_trips::domain::entities::*;
 use crate::modules::worker_trips::domain::repositories::WorkerTripRepository;
 use crate::shared::errors::AppError;
+use crate::shared::void::VoidDto;
 
 pub async fn list_active(repo: &dyn WorkerTripRepository) -> Result<Vec<WorkerTrip>, AppError> {
     repo.find_active().await
@@ -25,17 +30,40 @@ pub async fn get_trip(
         .ok_or_else(|| AppError::NotFound(format!("Viaje {id} no encontrado")))
 }
 
+/// Crea el viaje cobrando al trabajador el `price_base` vigente a `departure_time`
+/// y avisa de los deformados que le quedan sin cargar.
 pub async fn create_trip(
     repo: &dyn WorkerTripRepository,
+    prices: &dyn PriceRepository,
+    inventory: &dyn InventoryRepository,
     dto: &CreateTripDto,
     created_by: Uuid,
-) -> Result<WorkerTrip, AppError> {
+) -> Result<TripCreated, AppError> {
     if dto.loaded_items.is_empty() {
         return Err(AppError::BadRequest(
             "El viaje debe tener al menos un item cargado".into(),
         ));
     }
-    repo.create_trip(dto, created_by).await
+    let items: Vec<_> = dto.loaded_items.iter().map(LoadedItemDto::priced).collect();
+    let resolved = resolve_prices::resolve_item_prices(
+        prices,
+        inventory,
+        &items,
+        dto.departure_time,
+        PriceTier::Base,
+    )
+    .await?;
+    let trip = repo.create_trip(dto, &resolved, created_by).await?;
+    let pending_deformed = inventory
+        .find_worker_deformed(dto.worker_id)
+        .await?
+        .into_iter()
+        .filter(|item| item.quantity > 0)
+        .collect();
+    Ok(TripCreated {
+        trip,
+        pending_deformed,
+    })
 }
 
 pub async fn complete_trip(
@@ -47,6 +75,28 @@ pub async fn complete_trip(
     repo.complete_trip(trip_id, dto, created_by).await
 }
 
+/// Corrección de devoluciones mal contadas (solo dueño, con motivo).
+pub async fn correct_trip(
+    repo: &dyn WorkerTripRepository,
+    trip_id: Uuid,
+    dto: &CorrectTripDto,
+    corrected_by: Uuid,
+) -> Result<TripCorrection, AppError> {
+    let reason = dto.reason()?;
+    repo.correct_trip(trip_id, &dto.returned_items, reason, corrected_by)
+        .await
+}
+
+pub async fn cancel_trip(
+    repo: &dyn WorkerTripRepository,
+    trip_id: Uuid,
+    dto: &VoidDto,
+    cancelled_by: Uuid,
+) -> Result<WorkerTrip, AppError> {
+    let reason = dto.reason()?;
+    repo.cancel_trip(trip_id, reason, cancelled_by).await
+}
+
 pub async fn todays_returned(repo: &dyn WorkerTripRepository) -> Result<Vec<WorkerTrip>, AppError> {
     repo.find_todays_returned().await
 }
@@ -1,8 +1,13 @@
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::modules::inventory::domain::entities::InventoryItem;
+use crate::modules::pricing::domain::entities::PricedItem;
+use crate::shared::errors::AppError;
+
 // ─── Entidades ──────────────────────────────────────────
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
@@ -17,6 +22,14 @@ pub struct WorkerTrip {
     pub amount_due: Decimal,
     pub created_at: DateTime<Utc>,
     pub created_by: Uuid,
+    /// Última corrección de devoluciones (el historial está en `audit_log`).
+    pub corrected_at: Option<DateTime<Utc>>,
+    pub corrected_by: Option<Uuid>,
+    pub correction_reason: Option<String>,
+    /// Cancelado en progreso: toda la carga volvió al inventario.
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub cancelled_by: Option<Uuid>,
+    pub cancel_reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
@@ -30,12 +43,16 @@ pub struct LoadedItem {
     pub quantity: i32,
     pub unit_price: Decimal,
     pub is_deformed: bool,
+    pub price_history_id: Option<Uuid>,
+    pub price_override_reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
 pub struct ReturnedItem {
     pub id: Uuid,
     pub trip_id: Uuid,
+    /// Línea cargada de la que vuelve (nulo en viajes antiguos ambiguos).
+    pub loaded_item_id: Option<Uuid>,
     pub product_id: Uuid,
     pub flavor_id: Uuid,
     pub quantity: i32,
@@ -60,10 +77,28 @@ pub struct LoadedItemDto {
     pub flavor_id: Uuid,
     pub freezer_id: Uuid,
     pub quantity: i32,
-    pub unit_price: Decimal,
+    /// Precio manual (solo dueño). Si se omite, se usa `price_base` vigente a `departure_time`.
+    #[serde(default)]
+    pub unit_price: Option<Decimal>,
+    /// Motivo obligatorio cuando se envía `unit_price`.
+    pub price_override_reason: Option<String>,
     pub is_deformed: bool,
 }
 
+impl LoadedItemDto {
+    pub fn priced(&self) -> PricedItem<'_> {
+        PricedItem {
+            inventory_id: self.inventory_id,
+            product_id: self.product_id,
+            flavor_id: self.flavor_id,
+            freezer_id: self.freezer_id,
+            is_deformed: Some(self.is_deformed),
+            unit_price: self.unit_price,
+            override_reason: self.price_override_reason.as_deref(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct CompleteTripDto {
     pub returned_items: Vec<ReturnedItemDto>,
@@ -71,6 +106,10 @@ pub struct CompleteTripDto {
 
 #[derive(Debug, Deserialize, Clone, utoipa::ToSchema)]
 pub struct ReturnedItemDto {
+    /// Línea cargada de la que vuelve. Si se omite, se asigna con
+    /// [`allocate_returns`] entre las líneas del mismo producto y sabor.
+    #[serde(default)]
+    pub loaded_item_id: Option<Uuid>,
     pub product_id: Uuid,
     pub flavor_id: Uuid,
     pub quantity: i32,
@@ -78,6 +117,25 @@ pub struct ReturnedItemDto {
     pub destination_freezer_id: Uuid,
 }
 
+/// Reemplaza las devoluciones de un viaje ya retornado (solo dueño).
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct CorrectTripDto {
+    pub returned_items: Vec<ReturnedItemDto>,
+    pub reason: String,
+}
+
+impl CorrectTripDto {
+    pub fn reason(&self) -> Result<&str, AppError> {
+        let reason = self.reason.trim();
+        if reason.is_empty() {
+            return Err(AppError::BadRequest(
+                "Se requiere el motivo de la corrección".into(),
+            ));
+        }
+        Ok(reason)
+    }
+}
+
 // ─── Respuesta compuesta ────────────────────────────────
 
 #[derive(Debug, Serialize, utoipa::ToSchema)]
@@ -87,3 +145,145 @@ pub struct TripWithItems {
     pub loaded_items: Vec<LoadedItem>,
     pub returned_items: Vec<ReturnedItem>,
 }
+
+/// Viaje creado y los deformados que el trabajador aún tiene pendientes de
+/// vender (filas asignadas con stock que no salieron completas en este viaje).
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct TripCreated {
+    #[serde(flatten)]
+    pub trip: WorkerTrip,
+    pub pending_deformed: Vec<InventoryItem>,
+}
+
+/// Resultado de corregir un viaje: el viaje actualizado y cómo quedó la
+/// deuda. Los pagos existentes no se modifican; la diferencia se carga (o
+/// abona) a `workers.current_debt`.
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct TripCorrection {
+    pub trip: TripWithItems,
+    pub previous_sold_quantity: i32,
+    pub previous_amount_due: Decimal,
+    /// `amount_due` nuevo menos el anterior, aplicado a la deuda del trabajador.
+    pub debt_delta: Decimal,
+    /// Pagos y abonos ligados al viaje; un abono ligado a varios viajes cuenta
+    /// en proporción a su `amount_due`.
+    pub paid_for_trip: Decimal,
+    /// `amount_due - paid_for_trip`: positivo falta cobrar, negativo se pagó de más.
+    pub pending_for_trip: Decimal,
+}
+
+// ─── Devoluciones por línea ─────────────────────────────
+
+/// Parte de una devolución imputada a una línea cargada.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReturnAllocation {
+    pub loaded_item_id: Uuid,
+    pub quantity: i32,
+    pub is_deformed: bool,
+    pub destination_freezer_id: Uuid,
+}
+
+/// Imputa cada devolución a las líneas cargadas del viaje.
+///
+/// Las que indican `loaded_item_id` van a esa línea; el resto se reparte,
+/// en orden, entre las líneas del mismo producto y sabor: primero las de
+/// igual condición (deformado o no), luego las del congelador destino y
+/// por último por id. Un helado cargado deformado no puede volver como
+/// bueno, y ninguna línea puede recibir más de lo que se cargó.
+pub fn allocate_returns(
+    loaded: &[LoadedItem],
+    returned: &[ReturnedItemDto],
+) -> Result<Vec<ReturnAllocation>, String> {
+    let mut remaining: HashMap<Uuid, i32> = loaded.iter().map(|l| (l.id, l.quantity)).collect();
+    let mut allocations = Vec::new();
+
+    let (explicit, implicit): (Vec<_>, Vec<_>) =
+        returned.iter().partition(|r| r.loaded_item_id.is_some());
+
+    for r in explicit.iter().chain(implicit.iter()) {
+        if r.quantity <= 0 {
+            return Err("La cantidad devuelta debe ser positiva".into());
+        }
+        let candidates: Vec<&LoadedItem> = match r.loaded_item_id {
+            Some(id) => {
+                let line = loaded
+                    .iter()
+                    .find(|l| l.id == id)
+                    .ok_or_else(|| format!("La línea {id} no pertenece al viaje"))?;
+                if line.product_id != r.product_id || line.flavor_id != r.flavor_id {
+                    return Err(format!(
+                        "La devolución no coincide con el producto y sabor de la línea {id}"
+                    ));
+                }
+                if line.is_deformed && !r.is_deformed {
+                    return Err(format!(
+                        "La línea {id} salió deformada; no puede volver como buena"
+                    ));
+                }
+                vec![line]
+            }
+            None => {
+                let mut lines: Vec<&LoadedItem> = loaded
+                    .iter()
+                    .filter(|l| l.product_id == r.product_id && l.flavor_id == r.flavor_id)
+                    .filter(|l| r.is_deformed || !l.is_deformed)
+                    .collect();
+                lines.sort_by_key(|l| {
+                    (
+                        l.is_deformed != r.is_deformed,
+                        l.freezer_id != r.destination_freezer_id,
+                        l.id,
+                    )
+                });
+                lines
+            }
+        };
+
+        let mut pending = r.quantity;
+        for line in candidates {
+            let Some(left) = remaining.get_mut(&line.id) else {
+                continue;
+            };
+            let take = pending.min(*left);
+            if take == 0 {
+                continue;
+            }
+            *left -= take;
+            pending -= take;
+            allocations.push(ReturnAllocation {
+                loaded_item_id: line.id,
+                quantity: take,
+                is_deformed: r.is_deformed,
+                destination_freezer_id: r.destination_freezer_id,
+            });
+            if pending == 0 {
+                break;
+            }
+        }
+        if pending > 0 {
+            return Err(format!(
+                "Se devuelven {pending} unidades más de las cargadas para el producto {} sabor {}",
+                r.product_id, r.flavor_id
+            ));
+        }
+    }
+    Ok(allocations)
+}
+
+/// Cantidad vendida y monto adeudado: lo cargado menos lo devuelto, línea
+/// por línea y al precio de cada línea.
+pub fn calculate_sales(loaded: &[LoadedItem], allocations: &[ReturnAllocation]) -> (i32, Decimal) {
+    let mut returned: HashMap<Uuid, i32> = HashMap::new();
+    for a in allocations {
+        *returned.entry(a.loaded_item_id).or_insert(0) += a.quantity;
+    }
+
+    let mut total_sold = 0i32;
+    let mut total_amount = Decimal::ZERO;
+    for item in loaded {
+        let sold = item.quantity - returned.get(&item.id).copied().unwrap_or(0);
+        total_sold += sold;
+        total_amount += item.unit_price * Decimal::from(sold);
+    }
+    (total_sold, total_amount)
+}
@@ -2,6 +2,7 @@ use async_trait::async_trait;
 use uuid::Uuid;
 
 use super::entities::*;
+use crate::modules::pricing::domain::entities::ResolvedPrice;
 use crate::shared::errors::AppError;
 
 #[async_trait]
@@ -20,9 +21,11 @@ pub trait WorkerTripRepository: Send + Sync {
     async fn find_by_id_with_items(&self, id: Uuid) -> Result<Option<TripWithItems>, AppError>;
 
     /// Crear un nuevo viaje con items cargados (TRANSACCIÓN: resta inventario).
+    /// `prices` va alineado con `dto.loaded_items`.
     async fn create_trip(
         &self,
         dto: &CreateTripDto,
+        prices: &[ResolvedPrice],
         created_by: Uuid,
     ) -> Result<WorkerTrip, AppError>;
 
@@ -34,6 +37,24 @@ pub trait WorkerTripRepository: Send + Sync {
         created_by: Uuid,
     ) -> Result<WorkerTrip, AppError>;
 
+    /// Corregir las devoluciones de un viaje retornado (TRANSACCIÓN: revierte
+    /// y reaplica inventario, recalcula ventas y ajusta la deuda por la diferencia).
+    async fn correct_trip(
+        &self,
+        trip_id: Uuid,
+        returned_items: &[ReturnedItemDto],
+        reason: &str,
+        corrected_by: Uuid,
+    ) -> Result<TripCorrection, AppError>;
+
+    /// Cancelar un viaje en progreso (TRANSACCIÓN: devuelve toda la carga).
+    async fn cancel_trip(
+        &self,
+        trip_id: Uuid,
+        reason: &str,
+        cancelled_by: Uuid,
+    ) -> Result<WorkerTrip, AppError>;
+
     /// Viajes retornados de hoy.
     async fn find_todays_returned(&self) -> Result<Vec<WorkerTrip>, AppError>;
 }
@@ -8,11 +8,14 @@ use std::sync::Arc;
 use utoipa::OpenApi;
 use uuid::Uuid;
 
+use crate::modules::inventory::domain::repositories::InventoryRepository;
+use crate::modules::pricing::domain::repositories::PriceRepository;
 use crate::modules::worker_trips::application::manage_trips;
 use crate::modules::worker_trips::domain::entities::*;
 use crate::modules::worker_trips::domain::repositories::WorkerTripRepository;
 use crate::shared::auth::{AppState, AuthUser};
 use crate::shared::errors::AppError;
+use crate::shared::void::VoidDto;
 
 #[derive(OpenApi)]
 #[openapi(
@@ -22,7 +25,9 @@ use crate::shared::errors::AppError;
         todays_returned,
         create_trip,
         get_trip,
-        complete_trip
+        complete_trip,
+        correct_trip,
+        cancel_trip
     ),
     components(schemas(
         crate::modules::worker_trips::domain::entities::WorkerTrip,
@@ -33,6 +38,11 @@ use crate::shared::errors::AppError;
         crate::modules::worker_trips::domain::entities::CompleteTripDto,
         crate::modules::worker_trips::domain::entities::ReturnedItemDto,
         crate::modules::worker_trips::domain::entities::TripWithItems,
+        crate::modules::worker_trips::domain::entities::TripCreated,
+        crate::modules::inventory::domain::entities::InventoryItem,
+        crate::modules::worker_trips::domain::entities::CorrectTripDto,
+        crate::modules::worker_trips::domain::entities::TripCorrection,
+        crate::shared::void::VoidDto,
     ))
 )]
 pub struct TripsApiDoc;
@@ -41,6 +51,8 @@ pub struct TripsApiDoc;
 pub struct TripsState {
     pub app: AppState,
     pub repo: Arc<dyn WorkerTripRepository>,
+    pub prices: Arc<dyn PriceRepository>,
+    pub inventory: Arc<dyn InventoryRepository>,
 }
 
 impl axum::extract::FromRef<TripsState> for AppState {
@@ -54,8 +66,18 @@ pub struct WorkerQuery {
     pub limit: Option<i64>,
 }
 
-pub fn router(app: AppState, repo: Arc<dyn WorkerTripRepository>) -> Router {
-    let state = TripsState { app, repo };
+pub fn router(
+    app: AppState,
+    repo: Arc<dyn WorkerTripRepository>,
+    prices: Arc<dyn PriceRepository>,
+    inventory: Arc<dyn InventoryRepository>,
+) -> Router {
+    let state = TripsState {
+        app,
+        repo,
+        prices,
+        inventory,
+    };
     Router::new()
         .route("/active", get(list_active))
         .route("/worker/{worker_id}", get(list_by_worker))
@@ -63,6 +85,8 @@ pub fn router(app: AppState, repo: Arc<dyn WorkerTripRepository>) -> Router {
         .route("/", post(create_trip))
         .route("/{id}", get(get_trip))
         .route("/{id}/complete", post(complete_trip))
+        .route("/{id}/correct", post(correct_trip))
+        .route("/{id}/cancel", post(cancel_trip))
         .with_state(state)
 }
 
@@ -123,16 +147,31 @@ async fn get_trip(
 #[utoipa::path(
     post, path = "/", tag = "Viajes de Trabajadores",
     request_body = CreateTripDto,
-    responses((status = 200, description = "Viaje creado", body = WorkerTrip)),
+    responses(
+        (status = 200, description = "Viaje creado, con los deformados pendientes del trabajador", body = TripCreated),
+        (status = 400, description = "Sin precio vigente o precio manual sin motivo"),
+        (status = 403, description = "Precio manual sin ser dueño")
+    ),
     security(("bearer_auth" = []))
 )]
 async fn create_trip(
     State(state): State<TripsState>,
     auth: AuthUser,
     Json(dto): Json<CreateTripDto>,
-) -> Result<Json<WorkerTrip>, AppError> {
+) -> Result<Json<TripCreated>, AppError> {
     auth.require_role(crate::shared::auth::Role::Admin)?;
-    let trip = manage_trips::create_trip(state.repo.as_ref(), &dto, auth.user_id()).await?;
+    // Solo el dueño puede fijar precios manualmente
+    if dto.loaded_items.iter().any(|i| i.unit_price.is_some()) {
+        auth.require_owner()?;
+    }
+    let trip = manage_trips::create_trip(
+        state.repo.as_ref(),
+        state.prices.as_ref(),
+        state.inventory.as_ref(),
+        &dto,
+        auth.user_id(),
+    )
+    .await?;
     Ok(Json(trip))
 }
 
@@ -154,6 +193,50 @@ async fn complete_trip(
     Ok(Json(trip))
 }
 
+#[utoipa::path(
+    post, path = "/{id}/correct", tag = "Viajes de Trabajadores",
+    params(("id" = Uuid, Path, description = "ID del viaje")),
+    request_body = CorrectTripDto,
+    responses(
+        (status = 200, description = "Devoluciones corregidas y deuda ajustada por la diferencia", body = TripCorrection),
+        (status = 400, description = "Sin motivo"),
+        (status = 409, description = "Viaje no retornado o stock devuelto ya consumido")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn correct_trip(
+    State(state): State<TripsState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(dto): Json<CorrectTripDto>,
+) -> Result<Json<TripCorrection>, AppError> {
+    auth.require_owner()?;
+    let correction =
+        manage_trips::correct_trip(state.repo.as_ref(), id, &dto, auth.user_id()).await?;
+    Ok(Json(correction))
+}
+
+#[utoipa::path(
+    post, path = "/{id}/cancel", tag = "Viajes de Trabajadores",
+    params(("id" = Uuid, Path, description = "ID del viaje")),
+    request_body = VoidDto,
+    responses(
+        (status = 200, description = "Viaje cancelado y carga devuelta al inventario", body = WorkerTrip),
+        (status = 409, description = "El viaje no está en progreso")
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn cancel_trip(
+    State(state): State<TripsState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(dto): Json<VoidDto>,
+) -> Result<Json<WorkerTrip>, AppError> {
+    auth.require_owner()?;
+    let trip = manage_trips::cancel_trip(state.repo.as_ref(), id, &dto, auth.user_id()).await?;
+    Ok(Json(trip))
+}
+
 #[utoipa::path(
     get, path = "/today", tag = "Viajes de Trabajadores",
     responses((status = 200, description = "Viajes retornados hoy", body = Vec<WorkerTrip>)),
@@ -1,20 +1,28 @@
 use async_trait::async_trait;
-use rust_decimal::Decimal;
 use sqlx::PgPool;
-use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::modules::audit_log::domain::entities::CreateAuditLogDto;
+use crate::modules::audit_log::domain::repositories::AuditLogRepository;
+use crate::modules::inventory::domain::entities::{MovementCause, MovementType};
+use crate::modules::inventory::infrastructure::persistence::movements::{
+    lock_stock_line_tx, record_movement_tx, reverse_doc_movement_types_tx, reverse_doc_movements_tx,
+};
+use crate::modules::pricing::domain::entities::ResolvedPrice;
+use crate::modules::worker_payments::infrastructure::persistence::postgres_repo::paid_for_trip_tx;
 use crate::modules::worker_trips::domain::entities::*;
 use crate::modules::worker_trips::domain::repositories::WorkerTripRepository;
 use crate::shared::errors::AppError;
 
 pub struct PgWorkerTripRepository {
     pool: PgPool,
+    audit: Arc<dyn AuditLogRepository>,
 }
 
 impl PgWorkerTripRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, audit: Arc<dyn AuditLogRepository>) -> Self {
+        Self { pool, audit }
     }
 }
 
@@ -77,8 +85,15 @@ impl WorkerTripRepository for PgWorkerTripRepository {
     async fn create_trip(
         &self,
         dto: &CreateTripDto,
+        prices: &[ResolvedPrice],
         created_by: Uuid,
     ) -> Result<WorkerTrip, AppError> {
+        if prices.len() != dto.loaded_items.len() {
+            return Err(AppError::Internal(
+                "Precios resueltos no coinciden con los items cargados".into(),
+            ));
+        }
+
         let mut tx = self.pool.begin().await?;
 
         // 1. Crear el viaje
@@ -105,12 +120,23 @@ impl WorkerTripRepository for PgWorkerTripRepository {
         }
 
         // 3. Insertar items cargados y restar inventario
-        for item in &dto.loaded_items {
+        for (item, price) in dto.loaded_items.iter().zip(prices) {
+            lock_stock_line_tx(
+                &mut tx,
+                item.inventory_id,
+                item.product_id,
+                item.flavor_id,
+                item.freezer_id,
+                Some(item.is_deformed),
+            )
+            .await?;
+
             sqlx::query(
                 r#"
                 INSERT INTO worker_trip_loaded_items 
-                (trip_id, inventory_id, product_id, flavor_id, freezer_id, quantity, unit_price, is_deformed)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                (trip_id, inventory_id, product_id, flavor_id, freezer_id, quantity, unit_price, is_deformed,
+                 price_history_id, price_override_reason)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
                 "#,
             )
             .bind(trip.id)
@@ -119,8 +145,10 @@ impl WorkerTripRepository for PgWorkerTripRepository {
             .bind(item.flavor_id)
             .bind(item.freezer_id)
             .bind(item.quantity)
-            .bind(item.unit_price)
+            .bind(price.unit_price)
             .bind(item.is_deformed)
+            .bind(price.price_history_id)
+            .bind(&price.override_reason)
             .execute(&mut *tx)
             .await?;
 
@@ -143,6 +171,15 @@ impl WorkerTripRepository for PgWorkerTripRepository {
                 return Err(AppError::InsufficientStock(item.inventory_id));
             }
 
+            record_movement_tx(
+                &mut tx,
+                item.inventory_id,
+                -item.quantity,
+                MovementCause::doc(MovementType::TripLoad, "worker_trips", trip.id),
+                created_by,
+            )
+            .await?;
+
             // Si era deformado y quedó en 0, eliminar
             sqlx::query(
                 "DELETE FROM inventory WHERE id = $1 AND quantity = 0 AND is_deformed = TRUE",
@@ -153,17 +190,12 @@ impl WorkerTripRepository for PgWorkerTripRepository {
         }
 
         // 4. Auditoría
-        sqlx::query(
-            r#"
-            INSERT INTO audit_log (action, table_name, record_id, changes_after, created_by)
-            VALUES ('create', 'worker_trips', $1, $2, $3)
-            "#,
-        )
-        .bind(trip.id)
-        .bind(serde_json::to_value(&trip)?)
-        .bind(created_by)
-        .execute(&mut *tx)
-        .await?;
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::created("worker_trips", trip.id, &trip, created_by)?,
+            )
+            .await?;
 
         tx.commit().await?;
         Ok(trip)
@@ -179,7 +211,7 @@ impl WorkerTripRepository for PgWorkerTripRepository {
 
         // 1. Verificar que el viaje existe y está in_progress
         let existing = sqlx::query_as::<_, WorkerTrip>(
-            "SELECT * FROM worker_trips WHERE id = $1 AND status = 'in_progress'",
+            "SELECT * FROM worker_trips WHERE id = $1 AND status = 'in_progress' FOR UPDATE",
         )
         .bind(trip_id)
         .fetch_optional(&mut *tx)
@@ -194,104 +226,21 @@ impl WorkerTripRepository for PgWorkerTripRepository {
         .fetch_all(&mut *tx)
         .await?;
 
-        // 3. Insertar returned_items y procesar inventario
-        for returned in &dto.returned_items {
-            sqlx::query(
-                r#"
-                INSERT INTO worker_trip_returned_items 
-                (trip_id, product_id, flavor_id, quantity, is_deformed, destination_freezer_id)
-                VALUES ($1, $2, $3, $4, $5, $6)
-                "#,
-            )
-            .bind(trip_id)
-            .bind(returned.product_id)
-            .bind(returned.flavor_id)
-            .bind(returned.quantity)
-            .bind(returned.is_deformed)
-            .bind(returned.destination_freezer_id)
-            .execute(&mut *tx)
-            .await?;
-
-            // Obtener provider_id del item original
-            let provider_id = sqlx::query_scalar::<_, Uuid>(
-                r#"
-                SELECT i.provider_id FROM inventory i
-                JOIN worker_trip_loaded_items li ON i.id = li.inventory_id
-                WHERE li.trip_id = $1 AND li.product_id = $2 AND li.flavor_id = $3
-                LIMIT 1
-                "#,
-            )
-            .bind(trip_id)
-            .bind(returned.product_id)
-            .bind(returned.flavor_id)
-            .fetch_optional(&mut *tx)
-            .await?;
-
-            // Si no encontramos provider_id (inventario ya eliminado), usar primer loaded_item match
-            let provider_id = match provider_id {
-                Some(pid) => pid,
-                None => {
-                    // Fallback: buscar en inventory global
-                    sqlx::query_scalar::<_, Uuid>(
-                        r#"
-                        SELECT provider_id FROM inventory 
-                        WHERE product_id = $1 AND flavor_id = $2
-                        LIMIT 1
-                        "#,
-                    )
-                    .bind(returned.product_id)
-                    .bind(returned.flavor_id)
-                    .fetch_one(&mut *tx)
-                    .await?
-                }
-            };
-
-            if returned.is_deformed {
-                // Deformados → nuevo item asignado al trabajador
-                sqlx::query(
-                    r#"
-                    INSERT INTO inventory 
-                    (freezer_id, product_id, flavor_id, provider_id, quantity, 
-                     is_deformed, assigned_worker_id, min_stock_alert, updated_by)
-                    VALUES ($1, $2, $3, $4, $5, TRUE, $6, 0, $7)
-                    "#,
-                )
-                .bind(returned.destination_freezer_id)
-                .bind(returned.product_id)
-                .bind(returned.flavor_id)
-                .bind(provider_id)
-                .bind(returned.quantity)
-                .bind(existing.worker_id)
-                .bind(created_by)
-                .execute(&mut *tx)
-                .await?;
-            } else {
-                // Buenos → UPSERT al inventario normal
-                sqlx::query(
-                    r#"
-                    INSERT INTO inventory 
-                    (freezer_id, product_id, flavor_id, provider_id, quantity, is_deformed, min_stock_alert, updated_by)
-                    VALUES ($1, $2, $3, $4, $5, FALSE, 20, $6)
-                    ON CONFLICT (freezer_id, product_id, flavor_id, provider_id, is_deformed, assigned_worker_id)
-                    DO UPDATE SET 
-                        quantity = inventory.quantity + EXCLUDED.quantity,
-                        last_updated = NOW(),
-                        updated_by = EXCLUDED.updated_by
-                    "#,
-                )
-                .bind(returned.destination_freezer_id)
-                .bind(returned.product_id)
-                .bind(returned.flavor_id)
-                .bind(provider_id)
-                .bind(returned.quantity)
-                .bind(created_by)
-                .execute(&mut *tx)
-                .await?;
-            }
-        }
+        // 3. Imputar devoluciones por línea, insertarlas y procesar inventario
+        let allocations =
+            allocate_returns(&loaded_items, &dto.returned_items).map_err(AppError::BadRequest)?;
+        apply_returns_tx(
+            &mut tx,
+            &existing,
+            &loaded_items,
+            &allocations,
+            MovementType::TripReturn,
+            created_by,
+        )
+        .await?;
 
         // 4. Calcular sold_quantity y amount_due
-        let (sold_quantity, amount_due) = calculate_sales(&loaded_items, &dto.returned_items);
+        let (sold_quantity, amount_due) = calculate_sales(&loaded_items, &allocations);
 
         // 5. Actualizar el viaje
         let trip = sqlx::query_as::<_, WorkerTrip>(
@@ -326,19 +275,189 @@ impl WorkerTripRepository for PgWorkerTripRepository {
         .await?;
 
         // 7. Auditoría
-        sqlx::query(
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::updated("worker_trips", trip_id, &existing, &trip, created_by)?,
+            )
+            .await?;
+
+        tx.commit().await?;
+        Ok(trip)
+    }
+
+    async fn correct_trip(
+        &self,
+        trip_id: Uuid,
+        returned_items: &[ReturnedItemDto],
+        reason: &str,
+        corrected_by: Uuid,
+    ) -> Result<TripCorrection, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // 1. Viaje retornado (con lock) y su estado previo con items
+        let existing =
+            sqlx::query_as::<_, WorkerTrip>("SELECT * FROM worker_trips WHERE id = $1 FOR UPDATE")
+                .bind(trip_id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or_else(|| AppError::NotFound("Viaje no encontrado".into()))?;
+        if existing.status != "returned" {
+            return Err(AppError::Conflict(
+                "Solo se pueden corregir viajes ya retornados".into(),
+            ));
+        }
+        let before = load_items_tx(&mut tx, existing.clone()).await?;
+        let allocations =
+            allocate_returns(&before.loaded_items, returned_items).map_err(AppError::BadRequest)?;
+
+        // 2. Inventario: retirar lo devuelto (original y correcciones previas)
+        reverse_doc_movement_types_tx(
+            &mut tx,
+            "worker_trips",
+            trip_id,
+            &[MovementType::TripReturn, MovementType::TripCorrection],
+            MovementType::TripCorrection,
+            corrected_by,
+        )
+        .await?;
+
+        // 3. Reemplazar devoluciones y aplicar el nuevo conteo
+        sqlx::query("DELETE FROM worker_trip_returned_items WHERE trip_id = $1")
+            .bind(trip_id)
+            .execute(&mut *tx)
+            .await?;
+        apply_returns_tx(
+            &mut tx,
+            &existing,
+            &before.loaded_items,
+            &allocations,
+            MovementType::TripCorrection,
+            corrected_by,
+        )
+        .await?;
+
+        // 4. Recalcular ventas
+        let (sold_quantity, amount_due) = calculate_sales(&before.loaded_items, &allocations);
+        let trip = sqlx::query_as::<_, WorkerTrip>(
             r#"
-            INSERT INTO audit_log (action, table_name, record_id, changes_before, changes_after, created_by)
-            VALUES ('update', 'worker_trips', $1, $2, $3, $4)
+            UPDATE worker_trips
+            SET sold_quantity = $1, amount_due = $2,
+                corrected_at = NOW(), corrected_by = $3, correction_reason = $4
+            WHERE id = $5
+            RETURNING *
             "#,
         )
+        .bind(sold_quantity)
+        .bind(amount_due)
+        .bind(corrected_by)
+        .bind(reason)
         .bind(trip_id)
-        .bind(serde_json::to_value(&existing)?)
-        .bind(serde_json::to_value(&trip)?)
-        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // 5. Deuda y ventas del trabajador: solo la diferencia. Los pagos ya
+        //    registrados no se tocan; quedan descontados de la deuda.
+        let debt_delta = amount_due - existing.amount_due;
+        sqlx::query(
+            r#"
+            UPDATE workers
+            SET current_debt = current_debt + $1,
+                total_sales = total_sales + $2
+            WHERE id = $3
+            "#,
+        )
+        .bind(debt_delta)
+        .bind(sold_quantity - existing.sold_quantity)
+        .bind(existing.worker_id)
         .execute(&mut *tx)
         .await?;
 
+        let paid_for_trip = paid_for_trip_tx(&mut tx, trip_id).await?;
+
+        // 6. Auditoría con devoluciones antes/después
+        let after = load_items_tx(&mut tx, trip).await?;
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::updated("worker_trips", trip_id, &before, &after, corrected_by)?,
+            )
+            .await?;
+
+        tx.commit().await?;
+        Ok(TripCorrection {
+            previous_sold_quantity: existing.sold_quantity,
+            previous_amount_due: existing.amount_due,
+            debt_delta,
+            paid_for_trip,
+            pending_for_trip: after.trip.amount_due - paid_for_trip,
+            trip: after,
+        })
+    }
+
+    async fn cancel_trip(
+        &self,
+        trip_id: Uuid,
+        reason: &str,
+        cancelled_by: Uuid,
+    ) -> Result<WorkerTrip, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // 1. Viaje en progreso (con lock)
+        let existing =
+            sqlx::query_as::<_, WorkerTrip>("SELECT * FROM worker_trips WHERE id = $1 FOR UPDATE")
+                .bind(trip_id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or_else(|| AppError::NotFound("Viaje no encontrado".into()))?;
+        if existing.status != "in_progress" {
+            return Err(AppError::Conflict(
+                "Solo se pueden cancelar viajes en progreso".into(),
+            ));
+        }
+
+        // 2. Inventario: toda la carga vuelve a su fila
+        reverse_doc_movements_tx(&mut tx, "worker_trips", trip_id, cancelled_by).await?;
+
+        // 3. Ruta usage
+        if let Some(route_id) = existing.route_id {
+            sqlx::query(
+                "UPDATE routes SET usage_count = GREATEST(usage_count - 1, 0) WHERE id = $1",
+            )
+            .bind(route_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // 4. Marcar como cancelado
+        let trip = sqlx::query_as::<_, WorkerTrip>(
+            r#"
+            UPDATE worker_trips
+            SET status = 'cancelled', cancelled_at = NOW(), cancelled_by = $1, cancel_reason = $2
+            WHERE id = $3
+            RETURNING *
+            "#,
+        )
+        .bind(cancelled_by)
+        .bind(reason)
+        .bind(trip_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // 5. Auditoría
+        self.audit
+            .create_with_tx(
+                &mut tx,
+                CreateAuditLogDto::updated(
+                    "worker_trips",
+                    trip_id,
+                    &existing,
+                    &trip,
+                    cancelled_by,
+                )?,
+            )
+            .await?;
+
         tx.commit().await?;
         Ok(trip)
     }
@@ -358,27 +477,134 @@ impl WorkerTripRepository for PgWorkerTripRepository {
     }
 }
 
-/// Calcula la cantidad vendida y el monto adeudado.
-fn calculate_sales(loaded: &[LoadedItem], returned: &[ReturnedItemDto]) -> (i32, Decimal) {
-    // Agrupar devoluciones por (product_id, flavor_id)
-    let mut returned_map: HashMap<(Uuid, Uuid), i32> = HashMap::new();
-    for r in returned {
-        *returned_map.entry((r.product_id, r.flavor_id)).or_insert(0) += r.quantity;
-    }
+/// Registra las devoluciones ya imputadas y las suma al inventario con el
+/// proveedor de su línea cargada: los deformados se acumulan en la fila del
+/// trabajador y los buenos en la fila normal del congelador destino.
+async fn apply_returns_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    trip: &WorkerTrip,
+    loaded_items: &[LoadedItem],
+    allocations: &[ReturnAllocation],
+    movement_type: MovementType,
+    created_by: Uuid,
+) -> Result<(), AppError> {
+    for returned in allocations {
+        let line = loaded_items
+            .iter()
+            .find(|l| l.id == returned.loaded_item_id)
+            .ok_or_else(|| AppError::Internal("Devolución sin línea cargada".into()))?;
 
-    let mut total_sold = 0i32;
-    let mut total_amount = Decimal::ZERO;
+        sqlx::query(
+            r#"
+            INSERT INTO worker_trip_returned_items
+            (trip_id, loaded_item_id, product_id, flavor_id, quantity, is_deformed, destination_freezer_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(trip.id)
+        .bind(line.id)
+        .bind(line.product_id)
+        .bind(line.flavor_id)
+        .bind(returned.quantity)
+        .bind(returned.is_deformed)
+        .bind(returned.destination_freezer_id)
+        .execute(&mut **tx)
+        .await?;
 
-    for item in loaded {
-        let returned_qty = returned_map
-            .get(&(item.product_id, item.flavor_id))
-            .copied()
-            .unwrap_or(0);
+        // Proveedor de la fila cargada; si ya no existe, el del kardex
+        let provider_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT provider_id FROM inventory WHERE id = $1
+            UNION ALL
+            (SELECT provider_id FROM inventory_movements WHERE inventory_id = $1 ORDER BY seq DESC LIMIT 1)
+            LIMIT 1
+            "#,
+        )
+        .bind(line.inventory_id)
+        .fetch_one(&mut **tx)
+        .await?;
 
-        let sold = (item.quantity - returned_qty).max(0);
-        total_sold += sold;
-        total_amount += item.unit_price * Decimal::from(sold);
+        let inventory_id = if returned.is_deformed {
+            // Deformados → fila del trabajador (UPSERT)
+            sqlx::query_scalar::<_, Uuid>(
+                r#"
+                INSERT INTO inventory 
+                (freezer_id, product_id, flavor_id, provider_id, quantity, 
+                 is_deformed, assigned_worker_id, min_stock_alert, updated_by)
+                VALUES ($1, $2, $3, $4, $5, TRUE, $6, 0, $7)
+                ON CONFLICT (freezer_id, product_id, flavor_id, provider_id, is_deformed, assigned_worker_id)
+                DO UPDATE SET 
+                    quantity = inventory.quantity + EXCLUDED.quantity,
+                    last_updated = NOW(),
+                    updated_by = EXCLUDED.updated_by
+                RETURNING id
+                "#,
+            )
+            .bind(returned.destination_freezer_id)
+            .bind(line.product_id)
+            .bind(line.flavor_id)
+            .bind(provider_id)
+            .bind(returned.quantity)
+            .bind(trip.worker_id)
+            .bind(created_by)
+            .fetch_one(&mut **tx)
+            .await?
+        } else {
+            // Buenos → UPSERT al inventario normal
+            sqlx::query_scalar::<_, Uuid>(
+                r#"
+                INSERT INTO inventory 
+                (freezer_id, product_id, flavor_id, provider_id, quantity, is_deformed, min_stock_alert, updated_by)
+                VALUES ($1, $2, $3, $4, $5, FALSE, 20, $6)
+                ON CONFLICT (freezer_id, product_id, flavor_id, provider_id, is_deformed, assigned_worker_id)
+                DO UPDATE SET 
+                    quantity = inventory.quantity + EXCLUDED.quantity,
+                    last_updated = NOW(),
+                    updated_by = EXCLUDED.updated_by
+                RETURNING id
+                "#,
+            )
+            .bind(returned.destination_freezer_id)
+            .bind(line.product_id)
+            .bind(line.flavor_id)
+            .bind(provider_id)
+            .bind(returned.quantity)
+            .bind(created_by)
+            .fetch_one(&mut **tx)
+            .await?
+        };
+
+        record_movement_tx(
+            tx,
+            inventory_id,
+            returned.quantity,
+            MovementCause::doc(movement_type, "worker_trips", trip.id),
+            created_by,
+        )
+        .await?;
     }
+    Ok(())
+}
 
-    (total_sold, total_amount)
+async fn load_items_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    trip: WorkerTrip,
+) -> Result<TripWithItems, AppError> {
+    let loaded_items = sqlx::query_as::<_, LoadedItem>(
+        "SELECT * FROM worker_trip_loaded_items WHERE trip_id = $1",
+    )
+    .bind(trip.id)
+    .fetch_all(&mut **tx)
+    .await?;
+    let returned_items = sqlx::query_as::<_, ReturnedItem>(
+        "SELECT * FROM worker_trip_returned_items WHERE trip_id = $1",
+    )
+    .bind(trip.id)
+    .fetch_all(&mut **tx)
+    .await?;
+    Ok(TripWithItems {
+        trip,
+        loaded_items,
+        returned_items,
+    })
 }
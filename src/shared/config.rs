@@ -6,6 +6,8 @@ pub struct AppConfig {
     pub google_client_id: String,
     pub server_host: String,
     pub server_port: u16,
+    /// Cada cuántos minutos se revisan los contadores desnormalizados (0 = nunca).
+    pub reconcile_interval_minutes: u64,
 }
 
 impl AppConfig {
@@ -19,6 +21,10 @@ impl AppConfig {
                 .unwrap_or_else(|_| "3000".into())
                 .parse()
                 .expect("SERVER_PORT must be a number"),
+            reconcile_interval_minutes: std::env::var("RECONCILE_INTERVAL_MINUTES")
+                .unwrap_or_else(|_| "60".into())
+                .parse()
+                .expect("RECONCILE_INTERVAL_MINUTES must be a number"),
         }
     }
 }
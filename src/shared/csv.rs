@@ -0,0 +1,60 @@
+use std::borrow::Cow;
+
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+
+/// Escritor CSV mínimo (RFC 4180): separador coma y fin de línea CRLF.
+pub struct CsvWriter {
+    buf: String,
+}
+
+impl CsvWriter {
+    /// Crea el documento con la fila de encabezados.
+    pub fn new(header: &[&str]) -> Self {
+        let mut writer = Self { buf: String::new() };
+        writer.row(header);
+        writer
+    }
+
+    pub fn row<I, S>(&mut self, fields: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for (i, field) in fields.into_iter().enumerate() {
+            if i > 0 {
+                self.buf.push(',');
+            }
+            self.buf.push_str(&escape(field.as_ref()));
+        }
+        self.buf.push_str("\r\n");
+    }
+
+    pub fn finish(self) -> String {
+        self.buf
+    }
+}
+
+/// Entrecomilla el campo si contiene separadores, comillas o saltos de línea.
+fn escape(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+/// Respuesta HTTP de descarga para un CSV ya generado.
+pub fn csv_response(filename: &str, body: String) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::shared::errors::AppError;
+
+/// Tablas encadenadas por hash (ver migración 012).
+#[derive(Debug, Clone, Copy)]
+pub enum ChainedTable {
+    CashRegister,
+    AuditLog,
+}
+
+impl ChainedTable {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CashRegister => "cash_register",
+            Self::AuditLog => "audit_log",
+        }
+    }
+
+    /// Función SQL que recalcula el hash de una fila.
+    fn hash_fn(&self) -> &'static str {
+        match self {
+            Self::CashRegister => "cash_register_row_hash",
+            Self::AuditLog => "audit_log_row_hash",
+        }
+    }
+}
+
+/// Primer eslabón roto de la cadena.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct BrokenLink {
+    pub seq: i64,
+    pub id: Uuid,
+    pub reason: String,
+}
+
+/// Resultado de recorrer la cadena completa.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ChainVerification {
+    pub table_name: String,
+    pub rows_checked: i64,
+    pub is_valid: bool,
+    pub first_broken: Option<BrokenLink>,
+}
+
+/// Recorre la tabla en orden de `seq` y reporta el primer eslabón roto:
+/// un hueco en la secuencia (filas borradas), un `prev_hash` que no coincide
+/// con la fila anterior o un hash que no coincide con el contenido.
+/// Borrar las últimas filas no rompe la cadena; para la caja eso lo detecta
+/// la comparación de balances.
+pub async fn verify_chain(
+    pool: &PgPool,
+    table: ChainedTable,
+) -> Result<ChainVerification, AppError> {
+    let rows_checked =
+        sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) FROM {}", table.as_str()))
+            .fetch_one(pool)
+            .await?;
+
+    let sql = format!(
+        r#"
+        SELECT seq, id, reason FROM (
+            SELECT seq, id,
+                CASE
+                    WHEN seq <> COALESCE(prev_seq, 0) + 1
+                        THEN 'Faltan filas antes de esta secuencia'
+                    WHEN prev_hash IS DISTINCT FROM prev_actual
+                        THEN 'prev_hash no coincide con la fila anterior'
+                    WHEN hash <> expected
+                        THEN 'El hash no coincide con el contenido'
+                END AS reason
+            FROM (
+                SELECT t.seq, t.id, t.prev_hash, t.hash, {hash_fn}(t) AS expected,
+                       LAG(t.seq) OVER w AS prev_seq,
+                       LAG(t.hash) OVER w AS prev_actual
+                FROM {table} t
+                WINDOW w AS (ORDER BY t.seq)
+            ) chain
+        ) checked
+        WHERE reason IS NOT NULL
+        ORDER BY seq
+        LIMIT 1
+        "#,
+        hash_fn = table.hash_fn(),
+        table = table.as_str(),
+    );
+    let first_broken = sqlx::query_as::<_, BrokenLink>(&sql)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(ChainVerification {
+        table_name: table.as_str().to_string(),
+        rows_checked,
+        is_valid: first_broken.is_none(),
+        first_broken,
+    })
+}
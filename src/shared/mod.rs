@@ -1,4 +1,7 @@
 pub mod auth;
 pub mod config;
+pub mod csv;
 pub mod db;
 pub mod errors;
+pub mod hash_chain;
+pub mod void;
@@ -0,0 +1,23 @@
+use serde::Deserialize;
+
+use crate::shared::errors::AppError;
+
+/// Anulación de un documento (solo dueño). El original queda marcado como
+/// anulado y sus efectos se revierten con registros compensatorios.
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct VoidDto {
+    pub reason: String,
+}
+
+impl VoidDto {
+    /// Motivo sin espacios en los extremos; vacío es un error.
+    pub fn reason(&self) -> Result<&str, AppError> {
+        let reason = self.reason.trim();
+        if reason.is_empty() {
+            return Err(AppError::BadRequest(
+                "Se requiere el motivo de la anulación".into(),
+            ));
+        }
+        Ok(reason)
+    }
+}
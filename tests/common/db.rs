@@ -93,6 +93,7 @@ pub fn test_app_state(pool: sqlx::PgPool) -> helados_sofis_core::shared::auth::A
             google_client_id: String::new(),
             server_host: "127.0.0.1".into(),
             server_port: 0,
+            reconcile_interval_minutes: 0,
         },
     }
 }
@@ -111,3 +112,15 @@ pub fn test_jwt(
     )
     .expect("Error creando JWT de test")
 }
+
+/// Helper: repositorio de auditoría real, requerido por los repositorios Pg.
+pub fn test_audit_repo(
+    pool: &sqlx::PgPool,
+) -> std::sync::Arc<dyn helados_sofis_core::modules::audit_log::domain::repositories::AuditLogRepository>
+{
+    std::sync::Arc::new(
+        helados_sofis_core::modules::audit_log::infrastructure::persistence::postgres_repo::PgAuditLogRepository::new(
+            pool.clone(),
+        ),
+    )
+}
@@ -7,16 +7,29 @@ use rust_decimal::Decimal;
 use std::sync::Mutex;
 use uuid::Uuid;
 
+use helados_sofis_core::modules::audit_log::domain::entities::*;
+use helados_sofis_core::modules::audit_log::domain::repositories::AuditLogRepository;
 use helados_sofis_core::modules::catalog::domain::entities::*;
 use helados_sofis_core::modules::catalog::domain::repositories::*;
 use helados_sofis_core::modules::inventory::domain::entities::*;
-use helados_sofis_core::modules::inventory::domain::repositories::InventoryRepository;
+use helados_sofis_core::modules::inventory::domain::repositories::{
+    InventoryRepository, StockAdjustmentRepository,
+};
 use helados_sofis_core::modules::pricing::domain::entities::*;
 use helados_sofis_core::modules::pricing::domain::repositories::PriceRepository;
+use helados_sofis_core::modules::purchases::domain::entities::*;
+use helados_sofis_core::modules::purchases::domain::repositories::{
+    ProviderPayableRepository, PurchaseRepository,
+};
+use helados_sofis_core::modules::reports::domain::entities::*;
+use helados_sofis_core::modules::reports::domain::repositories::ReportsRepository;
 use helados_sofis_core::modules::users::domain::entities::*;
 use helados_sofis_core::modules::users::domain::repositories::UserRepository;
+use helados_sofis_core::modules::worker_payments::domain::entities::*;
+use helados_sofis_core::modules::worker_payments::domain::repositories::WorkerPaymentRepository;
 use helados_sofis_core::shared::auth::Role;
 use helados_sofis_core::shared::errors::AppError;
+use helados_sofis_core::shared::hash_chain::ChainVerification;
 
 // ═══════════════════════════════════════════════════════════
 // Mocks de repositorios usando mockall
@@ -32,7 +45,7 @@ mock! {
         async fn find_all(&self) -> Result<Vec<User>, AppError>;
         async fn find_active(&self) -> Result<Vec<User>, AppError>;
         async fn create(&self, dto: &CreateUserDto, created_by: Option<Uuid>) -> Result<User, AppError>;
-        async fn update(&self, id: Uuid, dto: &UpdateUserDto) -> Result<User, AppError>;
+        async fn update(&self, id: Uuid, dto: &UpdateUserDto, updated_by: Uuid) -> Result<User, AppError>;
         async fn update_last_login(&self, id: Uuid) -> Result<(), AppError>;
         async fn has_role(&self, id: Uuid, required: Role) -> Result<bool, AppError>;
         async fn count_by_role(&self, role: Role) -> Result<i64, AppError>;
@@ -61,7 +74,7 @@ mock! {
         async fn find_by_product(&self, product_id: Uuid) -> Result<Vec<Flavor>, AppError>;
         async fn find_by_id(&self, id: Uuid) -> Result<Option<Flavor>, AppError>;
         async fn create(&self, dto: &CreateFlavorDto, created_by: Uuid) -> Result<Flavor, AppError>;
-        async fn update(&self, id: Uuid, dto: &UpdateFlavorDto) -> Result<Flavor, AppError>;
+        async fn update(&self, id: Uuid, dto: &UpdateFlavorDto, updated_by: Uuid) -> Result<Flavor, AppError>;
     }
 }
 
@@ -74,7 +87,7 @@ mock! {
         async fn find_active(&self) -> Result<Vec<Provider>, AppError>;
         async fn find_by_id(&self, id: Uuid) -> Result<Option<Provider>, AppError>;
         async fn create(&self, dto: &CreateProviderDto, created_by: Uuid) -> Result<Provider, AppError>;
-        async fn update(&self, id: Uuid, dto: &UpdateProviderDto) -> Result<Provider, AppError>;
+        async fn update(&self, id: Uuid, dto: &UpdateProviderDto, updated_by: Uuid) -> Result<Provider, AppError>;
     }
 }
 
@@ -87,7 +100,7 @@ mock! {
         async fn find_active(&self) -> Result<Vec<Worker>, AppError>;
         async fn find_by_id(&self, id: Uuid) -> Result<Option<Worker>, AppError>;
         async fn create(&self, dto: &CreateWorkerDto, created_by: Uuid) -> Result<Worker, AppError>;
-        async fn update(&self, id: Uuid, dto: &UpdateWorkerDto) -> Result<Worker, AppError>;
+        async fn update(&self, id: Uuid, dto: &UpdateWorkerDto, updated_by: Uuid) -> Result<Worker, AppError>;
     }
 }
 
@@ -110,8 +123,27 @@ mock! {
         async fn find_all(&self) -> Result<Vec<Freezer>, AppError>;
         async fn find_by_id(&self, id: Uuid) -> Result<Option<Freezer>, AppError>;
         async fn create(&self, dto: &CreateFreezerDto, created_by: Uuid) -> Result<Freezer, AppError>;
-        async fn update(&self, id: Uuid, dto: &UpdateFreezerDto) -> Result<Freezer, AppError>;
-        async fn toggle_power(&self, id: Uuid) -> Result<Freezer, AppError>;
+        async fn update(
+            &self,
+            id: Uuid,
+            dto: &UpdateFreezerDto,
+            updated_by: Uuid,
+        ) -> Result<Freezer, AppError>;
+        async fn toggle_power(&self, id: Uuid, toggled_by: Uuid) -> Result<Freezer, AppError>;
+        async fn find_power_events(
+            &self,
+            freezer_id: Uuid,
+            limit: i64,
+        ) -> Result<Vec<FreezerPowerEvent>, AppError>;
+        async fn power_events_between(
+            &self,
+            from: chrono::DateTime<Utc>,
+            to: chrono::DateTime<Utc>,
+        ) -> Result<Vec<FreezerPowerEvent>, AppError>;
+        async fn stock_by_product(
+            &self,
+            freezer_id: Option<Uuid>,
+        ) -> Result<Vec<FreezerProductStock>, AppError>;
     }
 }
 
@@ -149,6 +181,165 @@ mock! {
         ) -> Result<Vec<PriceHistory>, AppError>;
 
         async fn list_current_prices(&self) -> Result<Vec<PriceHistory>, AppError>;
+
+        async fn list_markup_rules(&self) -> Result<Vec<MarkupRule>, AppError>;
+
+        async fn upsert_markup_rule(
+            &self,
+            dto: &UpsertMarkupRuleDto,
+            updated_by: Uuid,
+        ) -> Result<MarkupRule, AppError>;
+    }
+}
+
+mock! {
+    pub WorkerPaymentRepo {}
+
+    #[async_trait]
+    impl WorkerPaymentRepository for WorkerPaymentRepo {
+        async fn find_by_worker(&self, worker_id: Uuid) -> Result<Vec<WorkerPayment>, AppError>;
+        async fn find_by_trip(&self, trip_id: Uuid) -> Result<Vec<WorkerPayment>, AppError>;
+        async fn create_payment(&self, trip_id: Uuid, created_by: Uuid) -> Result<WorkerPayment, AppError>;
+        async fn statement_source(
+            &self,
+            worker_id: Uuid,
+            until: chrono::DateTime<chrono::Utc>,
+        ) -> Result<StatementSource, AppError>;
+        async fn create_worker_payment(
+            &self,
+            worker_id: Uuid,
+            dto: &CreateWorkerPaymentDto,
+            created_by: Uuid,
+        ) -> Result<WorkerPayment, AppError>;
+    }
+}
+
+mock! {
+    pub StockAdjustmentRepo {}
+
+    #[async_trait]
+    impl StockAdjustmentRepository for StockAdjustmentRepo {
+        async fn create_adjustment(
+            &self,
+            dto: &CreateAdjustmentDto,
+            created_by: Uuid,
+        ) -> Result<InventoryAdjustment, AppError>;
+        async fn find_adjustments(&self, query: &AdjustmentQuery) -> Result<Vec<InventoryAdjustment>, AppError>;
+        async fn shrinkage(&self, query: &AdjustmentQuery) -> Result<Vec<ShrinkageRow>, AppError>;
+        async fn open_count(
+            &self,
+            dto: &OpenStockCountDto,
+            opened_by: Uuid,
+        ) -> Result<StockCountWithLines, AppError>;
+        async fn find_count(&self, id: Uuid) -> Result<Option<StockCountWithLines>, AppError>;
+        async fn find_counts(&self, freezer_id: Option<Uuid>) -> Result<Vec<StockCountSession>, AppError>;
+        async fn submit_counts(
+            &self,
+            session_id: Uuid,
+            dto: &SubmitCountsDto,
+            submitted_by: Uuid,
+        ) -> Result<StockCountWithLines, AppError>;
+        async fn commit_count(
+            &self,
+            session_id: Uuid,
+            committed_by: Uuid,
+        ) -> Result<StockCountWithLines, AppError>;
+        async fn cancel_count(
+            &self,
+            session_id: Uuid,
+            cancelled_by: Uuid,
+        ) -> Result<StockCountSession, AppError>;
+    }
+}
+
+mock! {
+    pub PurchaseRepo {}
+
+    #[async_trait]
+    impl PurchaseRepository for PurchaseRepo {
+        async fn find_all(&self) -> Result<Vec<Purchase>, AppError>;
+        async fn find_by_id_with_items(&self, id: Uuid) -> Result<Option<PurchaseWithItems>, AppError>;
+        async fn create(
+            &self,
+            dto: &CreatePurchaseDto,
+            created_by: Uuid,
+        ) -> Result<PurchaseCreated, AppError>;
+        async fn void_purchase(
+            &self,
+            id: Uuid,
+            reason: &str,
+            voided_by: Uuid,
+        ) -> Result<Purchase, AppError>;
+    }
+}
+
+mock! {
+    pub ProviderPayableRepo {}
+
+    #[async_trait]
+    impl ProviderPayableRepository for ProviderPayableRepo {
+        async fn balances(&self) -> Result<Vec<ProviderBalance>, AppError>;
+        async fn open_purchases(&self, provider_id: Uuid) -> Result<Vec<Purchase>, AppError>;
+        async fn find_payments(
+            &self,
+            provider_id: Uuid,
+        ) -> Result<Vec<ProviderPaymentWithAllocations>, AppError>;
+        async fn pay(
+            &self,
+            provider_id: Uuid,
+            dto: &CreateProviderPaymentDto,
+            created_by: Uuid,
+        ) -> Result<ProviderPaymentWithAllocations, AppError>;
+    }
+}
+
+mock! {
+    pub ReportsRepo {}
+
+    #[async_trait]
+    impl ReportsRepository for ReportsRepo {
+        async fn cash_available(&self) -> Result<Decimal, AppError>;
+        async fn daily_sales(
+            &self,
+            from: chrono::NaiveDate,
+            to: chrono::NaiveDate,
+        ) -> Result<Vec<DailySales>, AppError>;
+        async fn top_workers(
+            &self,
+            from: chrono::NaiveDate,
+            to: chrono::NaiveDate,
+            limit: i64,
+        ) -> Result<Vec<TopWorker>, AppError>;
+        async fn top_products(
+            &self,
+            from: chrono::NaiveDate,
+            to: chrono::NaiveDate,
+            limit: i64,
+        ) -> Result<Vec<TopProduct>, AppError>;
+        async fn alerts(
+            &self,
+            from: chrono::NaiveDate,
+            today: chrono::NaiveDate,
+        ) -> Result<DashboardAlerts, AppError>;
+        async fn sales_report(&self, filter: &SalesFilter) -> Result<Vec<SalesReportRow>, AppError>;
+        async fn route_profitability(
+            &self,
+            from: chrono::NaiveDate,
+            to: chrono::NaiveDate,
+            route_id: Option<Uuid>,
+            worker_id: Option<Uuid>,
+        ) -> Result<Vec<RouteWorkerRow>, AppError>;
+        async fn monthly_cost_of_goods(
+            &self,
+            from: chrono::NaiveDate,
+            to: chrono::NaiveDate,
+        ) -> Result<Vec<MonthlyCost>, AppError>;
+        async fn monthly_outflows(
+            &self,
+            from: chrono::NaiveDate,
+            to: chrono::NaiveDate,
+        ) -> Result<Vec<MonthlyOutflow>, AppError>;
+        async fn inventory_valuation(&self) -> Result<Vec<InventoryValuationRow>, AppError>;
     }
 }
 
@@ -163,6 +354,9 @@ pub struct MockInventoryRepo {
     pub find_worker_deformed_result: Mutex<Option<Result<Vec<InventoryItem>, AppError>>>,
     pub add_stock_result: Mutex<Option<Result<InventoryItem, AppError>>>,
     pub update_alert_result: Mutex<Option<Result<InventoryItem, AppError>>>,
+    pub find_movements_result: Mutex<Option<Result<Vec<InventoryMovement>, AppError>>>,
+    pub stock_at_result: Mutex<Option<Result<Vec<StockAtTime>, AppError>>>,
+    pub transfer_deformed_result: Mutex<Option<Result<DeformedTransfer, AppError>>>,
 }
 
 impl MockInventoryRepo {
@@ -176,6 +370,9 @@ impl MockInventoryRepo {
             find_worker_deformed_result: Mutex::new(None),
             add_stock_result: Mutex::new(None),
             update_alert_result: Mutex::new(None),
+            find_movements_result: Mutex::new(None),
+            stock_at_result: Mutex::new(None),
+            transfer_deformed_result: Mutex::new(None),
         }
     }
 }
@@ -217,6 +414,7 @@ impl InventoryRepository for MockInventoryRepo {
         _tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
         _inventory_id: Uuid,
         _quantity: i32,
+        _cause: MovementCause,
         _updated_by: Uuid,
     ) -> Result<(), AppError> {
         Ok(())
@@ -230,6 +428,7 @@ impl InventoryRepository for MockInventoryRepo {
         _provider_id: Uuid,
         _quantity: i32,
         _worker_id: Uuid,
+        _cause: MovementCause,
         _updated_by: Uuid,
     ) -> Result<InventoryItem, AppError> {
         Err(AppError::Internal("Mock no configurado para _tx".into()))
@@ -242,14 +441,105 @@ impl InventoryRepository for MockInventoryRepo {
         _flavor_id: Uuid,
         _provider_id: Uuid,
         _quantity: i32,
+        _cause: MovementCause,
         _updated_by: Uuid,
     ) -> Result<(), AppError> {
         Ok(())
     }
-    async fn update_alert(&self, _id: Uuid, _min_stock: i32) -> Result<InventoryItem, AppError> {
+    async fn transfer_deformed(
+        &self,
+        _inventory_id: Uuid,
+        _to_worker_id: Option<Uuid>,
+        _quantity: Option<i32>,
+        _notes: Option<&str>,
+        _created_by: Uuid,
+    ) -> Result<DeformedTransfer, AppError> {
+        self.transfer_deformed_result.lock().unwrap().take()
+            .unwrap_or(Err(AppError::Internal("Mock no configurado".into())))
+    }
+    async fn update_alert(
+        &self,
+        _id: Uuid,
+        _min_stock: i32,
+        _updated_by: Uuid,
+    ) -> Result<InventoryItem, AppError> {
         self.update_alert_result.lock().unwrap().take()
             .unwrap_or(Err(AppError::Internal("Mock no configurado".into())))
     }
+    async fn find_movements(
+        &self,
+        _filter: &MovementFilter,
+    ) -> Result<Vec<InventoryMovement>, AppError> {
+        self.find_movements_result.lock().unwrap().take().unwrap_or(Ok(vec![]))
+    }
+    async fn stock_at(
+        &self,
+        _at: chrono::DateTime<Utc>,
+        _freezer_id: Option<Uuid>,
+        _product_id: Option<Uuid>,
+    ) -> Result<Vec<StockAtTime>, AppError> {
+        self.stock_at_result.lock().unwrap().take().unwrap_or(Ok(vec![]))
+    }
+}
+
+/// Mock manual de AuditLogRepository (create_with_tx recibe una transacción).
+/// `search` devuelve `entries` y registra el (limit, offset) recibido.
+pub struct MockAuditLogRepo {
+    pub entries: Vec<AuditLogEntry>,
+    pub total: Option<i64>,
+    pub search_calls: Mutex<Vec<(i64, i64)>>,
+}
+
+impl MockAuditLogRepo {
+    pub fn new(entries: Vec<AuditLogEntry>) -> Self {
+        Self {
+            entries,
+            total: None,
+            search_calls: Mutex::new(vec![]),
+        }
+    }
+}
+
+#[async_trait]
+impl AuditLogRepository for MockAuditLogRepo {
+    async fn create(&self, _dto: CreateAuditLogDto) -> Result<AuditLogEntry, AppError> {
+        Err(AppError::Internal("Mock no configurado".into()))
+    }
+    async fn create_with_tx(
+        &self,
+        _tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        _dto: CreateAuditLogDto,
+    ) -> Result<AuditLogEntry, AppError> {
+        Err(AppError::Internal("Mock no configurado".into()))
+    }
+    async fn find_by_record(
+        &self,
+        _table_name: &str,
+        _record_id: Uuid,
+    ) -> Result<Vec<AuditLogEntry>, AppError> {
+        Ok(self.entries.clone())
+    }
+    async fn find_by_user(&self, _user_id: Uuid, _limit: i64) -> Result<Vec<AuditLogEntry>, AppError> {
+        Ok(self.entries.clone())
+    }
+    async fn search(
+        &self,
+        _filter: &AuditLogFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AuditLogEntry>, AppError> {
+        self.search_calls.lock().unwrap().push((limit, offset));
+        Ok(self.entries.clone())
+    }
+    async fn count(&self, _filter: &AuditLogFilter) -> Result<i64, AppError> {
+        Ok(self.total.unwrap_or(self.entries.len() as i64))
+    }
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<AuditLogEntry>, AppError> {
+        Ok(self.entries.iter().find(|e| e.id == id).cloned())
+    }
+    async fn verify_chain(&self) -> Result<ChainVerification, AppError> {
+        Err(AppError::Internal("Mock no configurado".into()))
+    }
 }
 
 // ═══════════════════════════════════════════════════════════
@@ -330,11 +620,13 @@ pub fn fake_freezer(number: i32) -> Freezer {
     Freezer {
         id: Uuid::new_v4(),
         number,
-        max_capacity: serde_json::json!({"paletas": 500}),
+        max_capacity: FreezerCapacity::default(),
         is_on: true,
         last_toggle: None,
         created_at: Utc::now(),
         created_by: Uuid::new_v4(),
+        power_watts: None,
+        kwh_per_day: None,
     }
 }
 
@@ -377,3 +669,57 @@ pub fn fake_inventory_item(
         updated_by: Uuid::new_v4(),
     }
 }
+
+/// Crea un WorkerPayment de prueba (abono sin viaje).
+pub fn fake_worker_payment(worker_id: Uuid, amount: Decimal) -> WorkerPayment {
+    WorkerPayment {
+        id: Uuid::new_v4(),
+        worker_id,
+        trip_id: None,
+        amount,
+        previous_debt: Decimal::new(10000, 2),
+        new_debt: Decimal::new(10000, 2) - amount,
+        created_at: Utc::now(),
+        created_by: Uuid::new_v4(),
+        notes: None,
+    }
+}
+
+/// Crea una compra a crédito de prueba con un abono parcial.
+pub fn fake_credit_purchase(provider_id: Uuid, total: Decimal, amount_paid: Decimal) -> Purchase {
+    Purchase {
+        id: Uuid::new_v4(),
+        provider_id,
+        total,
+        payment_status: "credit".into(),
+        paid_at: None,
+        created_at: Utc::now(),
+        created_by: Uuid::new_v4(),
+        amount_paid,
+        paid_from: None,
+        voided_at: None,
+        voided_by: None,
+        void_reason: None,
+    }
+}
+
+/// Crea un registro de auditoría de prueba.
+pub fn fake_audit_entry(
+    action: &str,
+    before: Option<serde_json::Value>,
+    after: Option<serde_json::Value>,
+) -> AuditLogEntry {
+    AuditLogEntry {
+        id: Uuid::new_v4(),
+        action: action.into(),
+        table_name: "products".into(),
+        record_id: Uuid::new_v4(),
+        changes_before: before,
+        changes_after: after,
+        created_by: Uuid::new_v4(),
+        created_at: Utc::now(),
+        seq: 1,
+        prev_hash: None,
+        hash: String::new(),
+    }
+}
@@ -14,6 +14,7 @@ pub struct SeedData {
     pub worker_id: Uuid,
     pub route_id: Uuid,
     pub freezer_id: Uuid,
+    pub inventory_id: Uuid,
 }
 
 /// Inserta datos semilla en la BD de test.
@@ -108,14 +109,25 @@ pub async fn seed_test_data(pool: &PgPool) -> SeedData {
     let freezer_id = Uuid::new_v4();
     sqlx::query(
         r#"INSERT INTO freezers (id, number, max_capacity, is_on, created_by)
-           VALUES ($1, 1, '{"paletas": 500}', TRUE, $2)"#,
+           VALUES ($1, 1, jsonb_build_object($3::text, 500), TRUE, $2)"#,
     )
     .bind(freezer_id)
     .bind(owner_id)
+    .bind(product_id)
     .execute(pool)
     .await
     .expect("Error sembrando congelador");
 
+    sqlx::query(
+        r#"INSERT INTO freezer_power_events (freezer_id, is_on, created_at, created_by)
+           VALUES ($1, TRUE, NOW() - INTERVAL '2 hours', $2)"#,
+    )
+    .bind(freezer_id)
+    .bind(owner_id)
+    .execute(pool)
+    .await
+    .expect("Error sembrando encendido del congelador");
+
     // ─── Precio ─────────────────────────────────
     sqlx::query(
         r#"INSERT INTO price_history 
@@ -132,12 +144,14 @@ pub async fn seed_test_data(pool: &PgPool) -> SeedData {
     .expect("Error sembrando precio");
 
     // ─── Inventario ─────────────────────────────
+    let inventory_id = Uuid::new_v4();
     sqlx::query(
         r#"INSERT INTO inventory 
-           (freezer_id, product_id, flavor_id, provider_id, quantity, 
+           (id, freezer_id, product_id, flavor_id, provider_id, quantity, 
             min_stock_alert, is_deformed, updated_by)
-           VALUES ($1, $2, $3, $4, 100, 20, FALSE, $5)"#,
+           VALUES ($1, $2, $3, $4, $5, 100, 20, FALSE, $6)"#,
     )
+    .bind(inventory_id)
     .bind(freezer_id)
     .bind(product_id)
     .bind(flavor_id)
@@ -147,6 +161,23 @@ pub async fn seed_test_data(pool: &PgPool) -> SeedData {
     .await
     .expect("Error sembrando inventario");
 
+    // Saldo inicial en el kardex
+    sqlx::query(
+        r#"INSERT INTO inventory_movements
+           (inventory_id, freezer_id, product_id, flavor_id, provider_id, is_deformed,
+            delta, quantity_after, movement_type, created_by)
+           VALUES ($1, $2, $3, $4, $5, FALSE, 100, 100, 'opening', $6)"#,
+    )
+    .bind(inventory_id)
+    .bind(freezer_id)
+    .bind(product_id)
+    .bind(flavor_id)
+    .bind(provider_id)
+    .bind(owner_id)
+    .execute(pool)
+    .await
+    .expect("Error sembrando kardex");
+
     SeedData {
         owner_id,
         admin_id,
@@ -156,5 +187,6 @@ pub async fn seed_test_data(pool: &PgPool) -> SeedData {
         worker_id,
         route_id,
         freezer_id,
+        inventory_id,
     }
 }
@@ -0,0 +1,241 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use http_body_util::BodyExt;
+use tower::ServiceExt;
+
+use common::db::{setup_test_db, teardown_test_db, test_app_state, test_audit_repo, test_jwt};
+use common::seed::{seed_test_data, SeedData};
+use helados_sofis_core::modules::audit_log::domain::entities::AuditLogPage;
+use helados_sofis_core::modules::audit_log::infrastructure::controllers::http_router;
+use helados_sofis_core::modules::catalog::domain::entities::UpdateFlavorDto;
+use helados_sofis_core::modules::catalog::domain::repositories::FlavorRepository;
+use helados_sofis_core::modules::catalog::infrastructure::persistence::postgres_repo::PgFlavorRepository;
+use helados_sofis_core::shared::auth::Role;
+use helados_sofis_core::shared::hash_chain::ChainVerification;
+
+// ═══════════════════════════════════════════════════════════
+// Tests de Integración — Búsqueda y exportación de auditoría
+// BD real exclusiva por test · Semilla · Patrón AAA
+// ═══════════════════════════════════════════════════════════
+
+fn build_audit_router(pool: sqlx::PgPool) -> axum::Router {
+    http_router::router(test_app_state(pool.clone()), test_audit_repo(&pool))
+}
+
+/// Renombra el sabor de la semilla como admin (genera un registro 'update').
+async fn rename_flavor(pool: &sqlx::PgPool, seed: &SeedData, name: &str) {
+    PgFlavorRepository::new(pool.clone(), test_audit_repo(pool))
+        .update(
+            seed.flavor_id,
+            &UpdateFlavorDto {
+                name: Some(name.into()),
+                active: None,
+            },
+            seed.admin_id,
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn busqueda_filtra_por_tabla_accion_y_usuario_con_diff() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    rename_flavor(&pool, &seed, "Fresa Premium").await;
+    rename_flavor(&pool, &seed, "Fresa Clásica").await;
+    let app = build_audit_router(pool.clone());
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!(
+            "/?table_name=flavors&action=update&user_id={}&per_page=1",
+            seed.admin_id
+        ))
+        .header("Authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap();
+
+    // Act
+    let response = app.oneshot(request).await.unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let page: AuditLogPage = serde_json::from_slice(&body).unwrap();
+    assert_eq!(page.total, 2);
+    assert_eq!(page.items.len(), 1);
+    let change = &page.items[0].changes[0];
+    assert_eq!(change.field, "name");
+    assert_eq!(change.after, Some(serde_json::json!("Fresa Clásica")));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn exportacion_csv_devuelve_adjunto() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    rename_flavor(&pool, &seed, "Fresa Premium").await;
+    let app = build_audit_router(pool.clone());
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/export?table_name=flavors")
+        .header("Authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap();
+
+    // Act
+    let response = app.oneshot(request).await.unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()[header::CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .starts_with("text/csv"));
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let csv = String::from_utf8(body.to_vec()).unwrap();
+    assert!(csv.starts_with("fecha,usuario,accion,tabla,registro,campo,antes,despues\r\n"));
+    assert!(csv.contains(",name,Chocolate,Fresa Premium\r\n"));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn busqueda_como_admin_retorna_403() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_audit_router(pool.clone());
+    let token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/")
+        .header("Authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap();
+
+    // Act
+    let response = app.oneshot(request).await.unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn cadena_de_hashes_es_valida_tras_escrituras() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    rename_flavor(&pool, &seed, "Fresa Premium").await;
+    rename_flavor(&pool, &seed, "Fresa Clásica").await;
+    let app = build_audit_router(pool.clone());
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/verify-chain")
+        .header("Authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap();
+
+    // Act
+    let response = app.oneshot(request).await.unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let result: ChainVerification = serde_json::from_slice(&body).unwrap();
+    assert!(result.is_valid);
+    assert!(result.rows_checked >= 2);
+    assert!(result.first_broken.is_none());
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn cadena_de_hashes_detecta_fila_alterada() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    rename_flavor(&pool, &seed, "Fresa Premium").await;
+    rename_flavor(&pool, &seed, "Fresa Clásica").await;
+    let tampered: i64 = sqlx::query_scalar("SELECT MIN(seq) FROM audit_log")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    sqlx::query("ALTER TABLE audit_log DISABLE TRIGGER audit_log_append_only")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE audit_log SET changes_after = '{\"name\": \"Vainilla\"}' WHERE seq = $1")
+        .bind(tampered)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let app = build_audit_router(pool.clone());
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/verify-chain")
+        .header("Authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap();
+
+    // Act
+    let response = app.oneshot(request).await.unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let result: ChainVerification = serde_json::from_slice(&body).unwrap();
+    assert!(!result.is_valid);
+    let broken = result.first_broken.unwrap();
+    assert_eq!(broken.seq, tampered);
+    assert_eq!(broken.reason, "El hash no coincide con el contenido");
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn log_de_auditoria_rechaza_modificaciones_directas() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    rename_flavor(&pool, &seed, "Fresa Premium").await;
+
+    // Act
+    let result = sqlx::query("DELETE FROM audit_log").execute(&pool).await;
+
+    // Assert
+    assert!(result.is_err());
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
@@ -0,0 +1,548 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use http_body_util::BodyExt;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use common::db::{setup_test_db, teardown_test_db, test_app_state, test_audit_repo, test_jwt};
+use common::seed::seed_test_data;
+use helados_sofis_core::modules::cash_register::domain::entities::*;
+use helados_sofis_core::modules::cash_register::domain::repositories::CashRegisterRepository;
+use helados_sofis_core::modules::cash_register::infrastructure::controllers::http_router;
+use helados_sofis_core::modules::cash_register::infrastructure::persistence::postgres_repo::{
+    PgCashClosingRepository, PgCashRegisterRepository,
+};
+use helados_sofis_core::shared::auth::Role;
+use helados_sofis_core::shared::errors::AppError;
+
+// ═══════════════════════════════════════════════════════════
+// Tests de Integración — Libro de Caja
+// BD real exclusiva por test · Semilla · Patrón AAA
+// ═══════════════════════════════════════════════════════════
+
+fn cash_repo(pool: &sqlx::PgPool) -> Arc<dyn CashRegisterRepository> {
+    Arc::new(PgCashRegisterRepository::new(
+        pool.clone(),
+        test_audit_repo(pool),
+    ))
+}
+
+fn build_cash_router(pool: sqlx::PgPool) -> axum::Router {
+    http_router::router(
+        test_app_state(pool.clone()),
+        cash_repo(&pool),
+        Arc::new(PgCashClosingRepository::new(
+            pool.clone(),
+            test_audit_repo(&pool),
+        )),
+    )
+}
+
+fn entry(tx_type: CashTransactionType, amount: Decimal) -> CashEntry {
+    CashEntry {
+        tx_type,
+        amount,
+        description: None,
+        category: None,
+        related_doc_type: None,
+        related_doc_id: None,
+    }
+}
+
+async fn get_json(app: axum::Router, token: &str, uri: &str) -> (StatusCode, serde_json::Value) {
+    let request = Request::builder()
+        .method("GET")
+        .uri(uri)
+        .header("Authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap_or_default())
+}
+
+async fn post_json(
+    app: axum::Router,
+    token: &str,
+    uri: &str,
+    body: serde_json::Value,
+) -> (StatusCode, serde_json::Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("Authorization", format!("Bearer {token}"))
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap_or_default())
+}
+
+#[tokio::test]
+async fn asientos_concurrentes_encadenan_el_balance_sin_perder_movimientos() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let repo = cash_repo(&pool);
+
+    // Act
+    let handles: Vec<_> = (1..=20)
+        .map(|i| {
+            let repo = repo.clone();
+            let admin_id = seed.admin_id;
+            tokio::spawn(async move {
+                repo.add_transaction(
+                    entry(CashTransactionType::LocalSale, Decimal::from(i)),
+                    admin_id,
+                )
+                .await
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.await.unwrap().unwrap();
+    }
+
+    // Assert
+    let rows = sqlx::query_as::<_, (i64, Decimal, Decimal)>(
+        "SELECT seq, amount, balance FROM cash_register ORDER BY seq",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(rows.len(), 20);
+    let mut running = Decimal::ZERO;
+    for (i, (seq, amount, balance)) in rows.iter().enumerate() {
+        running += amount;
+        assert_eq!(*seq, i as i64 + 1);
+        assert_eq!(*balance, running);
+    }
+    assert_eq!(
+        repo.get_current_balance().await.unwrap(),
+        Decimal::from(210)
+    );
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn balance_en_secuencia_e_instante() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let repo = cash_repo(&pool);
+    repo.add_transaction(
+        entry(CashTransactionType::LocalSale, Decimal::from(100)),
+        seed.admin_id,
+    )
+    .await
+    .unwrap();
+    let second = repo
+        .add_transaction(
+            entry(CashTransactionType::Expense, Decimal::from(-30)),
+            seed.admin_id,
+        )
+        .await
+        .unwrap();
+    repo.add_transaction(
+        entry(CashTransactionType::LocalSale, Decimal::from(50)),
+        seed.admin_id,
+    )
+    .await
+    .unwrap();
+    let token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+
+    // Act
+    let (by_seq_status, by_seq) = get_json(
+        build_cash_router(pool.clone()),
+        &token,
+        &format!("/balance-at?seq={}", second.seq),
+    )
+    .await;
+    let (before_status, before) = get_json(
+        build_cash_router(pool.clone()),
+        &token,
+        "/balance-at?at=2000-01-01T00:00:00Z",
+    )
+    .await;
+
+    // Assert
+    assert_eq!(by_seq_status, StatusCode::OK);
+    let by_seq: BalanceAt = serde_json::from_value(by_seq).unwrap();
+    assert_eq!(by_seq.seq, second.seq);
+    assert_eq!(by_seq.balance, Decimal::from(70));
+
+    assert_eq!(before_status, StatusCode::OK);
+    let before: BalanceAt = serde_json::from_value(before).unwrap();
+    assert_eq!(before.seq, 0);
+    assert_eq!(before.balance, Decimal::ZERO);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn balance_con_secuencia_e_instante_a_la_vez_retorna_400() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_cash_router(pool.clone());
+    let token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+
+    // Act
+    let (status, _) = get_json(app, &token, "/balance-at?seq=1&at=2030-01-01T00:00:00Z").await;
+
+    // Assert
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+// ─── Cierre de caja ─────────────────────────────────────
+
+#[tokio::test]
+async fn cierre_con_faltante_asienta_ajuste_y_bloquea_el_dia() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let repo = cash_repo(&pool);
+    repo.add_transaction(
+        entry(CashTransactionType::LocalSale, Decimal::from(250)),
+        seed.admin_id,
+    )
+    .await
+    .unwrap();
+    repo.add_transaction(
+        entry(CashTransactionType::Expense, Decimal::from(-40)),
+        seed.admin_id,
+    )
+    .await
+    .unwrap();
+    let token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+
+    // Act
+    let (status, body) = post_json(
+        build_cash_router(pool.clone()),
+        &token,
+        "/closings",
+        serde_json::json!({
+            "denominations": [
+                { "value": "100.00", "count": 2 },
+                { "value": "5.00", "count": 1 }
+            ],
+            "explanation": "Vuelto mal entregado"
+        }),
+    )
+    .await;
+    let blocked = repo
+        .add_transaction(
+            entry(CashTransactionType::LocalSale, Decimal::from(10)),
+            seed.admin_id,
+        )
+        .await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    let closing: CashClosing = serde_json::from_value(body).unwrap();
+    assert_eq!(closing.status, "closed");
+    assert_eq!(closing.expected_balance, Decimal::from(210));
+    assert_eq!(closing.counted_total, Decimal::from(205));
+    assert_eq!(closing.difference, Decimal::from(-5));
+    assert!(closing.adjustment_tx_id.is_some());
+    assert_eq!(
+        repo.get_current_balance().await.unwrap(),
+        Decimal::from(205)
+    );
+
+    let shortage = closing
+        .report
+        .by_type
+        .iter()
+        .find(|t| t.tx_type == "cash_shortage")
+        .unwrap();
+    assert_eq!(shortage.total, Decimal::from(-5));
+    assert_eq!(closing.report.income, Decimal::from(250));
+    assert_eq!(closing.report.outflow, Decimal::from(45));
+    assert_eq!(closing.report.by_user.len(), 1);
+    assert_eq!(closing.report.by_user[0].count, 3);
+
+    assert!(matches!(blocked, Err(AppError::Conflict(_))));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn cierre_de_dia_anterior_fecha_el_ajuste_en_ese_dia() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let repo = cash_repo(&pool);
+    // Venta de $100.00 asentada ayer y otra de $10.00 anteayer
+    for (amount, days_ago) in [(10, 2), (100, 1)] {
+        sqlx::query(
+            r#"INSERT INTO cash_register (type, amount, balance, created_by, created_at)
+               VALUES ('local_sale', $1, $1, $2, NOW() - make_interval(days => $3))"#,
+        )
+        .bind(Decimal::from(amount))
+        .bind(seed.admin_id)
+        .bind(days_ago)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    let yesterday: chrono::NaiveDate = sqlx::query_scalar("SELECT CURRENT_DATE - 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+    let close = |date: chrono::NaiveDate, counted: &str| {
+        serde_json::json!({
+            "business_date": date,
+            "denominations": [{ "value": counted, "count": 1 }],
+            "explanation": "Billete faltante"
+        })
+    };
+
+    // Act
+    let (status, body) = post_json(
+        build_cash_router(pool.clone()),
+        &token,
+        "/closings",
+        close(yesterday, "100.00"),
+    )
+    .await;
+    let today_write = repo
+        .add_transaction(
+            entry(CashTransactionType::LocalSale, Decimal::from(5)),
+            seed.admin_id,
+        )
+        .await;
+    let (earlier_status, _) = post_json(
+        build_cash_router(pool.clone()),
+        &token,
+        "/closings",
+        close(yesterday - chrono::Duration::days(1), "1.00"),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    let closing: CashClosing = serde_json::from_value(body).unwrap();
+    assert_eq!(closing.expected_balance, Decimal::from(110));
+    assert_eq!(closing.difference, Decimal::from(-10));
+    let adjustment_day: chrono::NaiveDate =
+        sqlx::query_scalar("SELECT created_at::date FROM cash_register WHERE id = $1")
+            .bind(closing.adjustment_tx_id.unwrap())
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(adjustment_day, yesterday);
+    assert!(closing
+        .report
+        .by_type
+        .iter()
+        .any(|t| t.tx_type == "cash_shortage" && t.total == Decimal::from(-10)));
+    assert!(today_write.is_ok());
+    // Un ajuste anteayer cambiaría el saldo ya cerrado de ayer
+    assert_eq!(earlier_status, StatusCode::CONFLICT);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn cierre_con_diferencia_sin_explicacion_retorna_400() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    cash_repo(&pool)
+        .add_transaction(
+            entry(CashTransactionType::LocalSale, Decimal::from(100)),
+            seed.admin_id,
+        )
+        .await
+        .unwrap();
+    let token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+
+    // Act
+    let (status, _) = post_json(
+        build_cash_router(pool.clone()),
+        &token,
+        "/closings",
+        serde_json::json!({ "denominations": [{ "value": "50.00", "count": 1 }] }),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let closings: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM cash_closings")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(closings, 0);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn reabrir_cierre_solo_dueno_y_desbloquea_el_dia() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let repo = cash_repo(&pool);
+    let admin_token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+    let owner_token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+    let (_, body) = post_json(
+        build_cash_router(pool.clone()),
+        &admin_token,
+        "/closings",
+        serde_json::json!({ "denominations": [{ "value": "1.00", "count": 0 }] }),
+    )
+    .await;
+    let closing: CashClosing = serde_json::from_value(body).unwrap();
+    let reopen_uri = format!("/closings/{}/reopen", closing.id);
+
+    // Act
+    let (admin_status, _) = post_json(
+        build_cash_router(pool.clone()),
+        &admin_token,
+        &reopen_uri,
+        serde_json::json!({ "reason": "Venta olvidada" }),
+    )
+    .await;
+    let (owner_status, body) = post_json(
+        build_cash_router(pool.clone()),
+        &owner_token,
+        &reopen_uri,
+        serde_json::json!({ "reason": "Venta olvidada" }),
+    )
+    .await;
+    let after_reopen = repo
+        .add_transaction(
+            entry(CashTransactionType::LocalSale, Decimal::from(10)),
+            seed.admin_id,
+        )
+        .await;
+
+    // Assert
+    assert_eq!(admin_status, StatusCode::FORBIDDEN);
+    assert_eq!(owner_status, StatusCode::OK);
+    let reopened: CashClosing = serde_json::from_value(body).unwrap();
+    assert_eq!(reopened.status, "reopened");
+    assert_eq!(reopened.reopened_by, Some(seed.owner_id));
+    assert!(after_reopen.is_ok());
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+// ─── Anulación ──────────────────────────────────────────
+
+#[tokio::test]
+async fn anular_gasto_asienta_reversa_una_sola_vez() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let repo = cash_repo(&pool);
+    repo.add_transaction(
+        entry(CashTransactionType::LocalSale, Decimal::from(100)),
+        seed.admin_id,
+    )
+    .await
+    .unwrap();
+    let expense = repo
+        .add_transaction(
+            entry(CashTransactionType::Expense, Decimal::from(-30)),
+            seed.owner_id,
+        )
+        .await
+        .unwrap();
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+    let uri = format!("/transactions/{}/void", expense.id);
+
+    // Act
+    let (status, body) = post_json(
+        build_cash_router(pool.clone()),
+        &token,
+        &uri,
+        serde_json::json!({ "reason": "Gasto cargado dos veces" }),
+    )
+    .await;
+    let (again_status, _) = post_json(
+        build_cash_router(pool.clone()),
+        &token,
+        &uri,
+        serde_json::json!({ "reason": "Gasto cargado dos veces" }),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    let reversal: CashTransaction = serde_json::from_value(body).unwrap();
+    assert_eq!(reversal.tx_type, "reversal");
+    assert_eq!(reversal.amount, Decimal::from(30));
+    assert_eq!(reversal.related_doc_type.as_deref(), Some("cash_register"));
+    assert_eq!(reversal.related_doc_id, Some(expense.id));
+    assert_eq!(
+        repo.get_current_balance().await.unwrap(),
+        Decimal::from(100)
+    );
+    assert_eq!(again_status, StatusCode::CONFLICT);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn anular_movimiento_de_documento_retorna_400() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let sale = cash_repo(&pool)
+        .add_transaction(
+            CashEntry::doc(
+                CashTransactionType::LocalSale,
+                Decimal::from(40),
+                "local_sales",
+                uuid::Uuid::new_v4(),
+            ),
+            seed.admin_id,
+        )
+        .await
+        .unwrap();
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+
+    // Act
+    let (status, _) = post_json(
+        build_cash_router(pool.clone()),
+        &token,
+        &format!("/transactions/{}/void", sale.id),
+        serde_json::json!({ "reason": "Error" }),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
@@ -6,7 +6,7 @@ use http_body_util::BodyExt;
 use std::sync::Arc;
 use tower::ServiceExt;
 
-use common::db::{setup_test_db, teardown_test_db, test_app_state, test_jwt};
+use common::db::{setup_test_db, teardown_test_db, test_app_state, test_audit_repo, test_jwt};
 use common::seed::seed_test_data;
 use helados_sofis_core::modules::catalog::domain::entities::*;
 use helados_sofis_core::modules::catalog::infrastructure::controllers::http_router::{
@@ -25,12 +25,12 @@ fn build_catalog_router(pool: sqlx::PgPool) -> axum::Router {
 
     let catalog_state = CatalogState {
         app: app_state,
-        products: Arc::new(PgProductRepository::new(pool.clone())),
-        flavors: Arc::new(PgFlavorRepository::new(pool.clone())),
-        providers: Arc::new(PgProviderRepository::new(pool.clone())),
-        workers: Arc::new(PgWorkerRepository::new(pool.clone())),
-        routes: Arc::new(PgRouteRepository::new(pool.clone())),
-        freezers: Arc::new(PgFreezerRepository::new(pool)),
+        products: Arc::new(PgProductRepository::new(pool.clone(), test_audit_repo(&pool))),
+        flavors: Arc::new(PgFlavorRepository::new(pool.clone(), test_audit_repo(&pool))),
+        providers: Arc::new(PgProviderRepository::new(pool.clone(), test_audit_repo(&pool))),
+        workers: Arc::new(PgWorkerRepository::new(pool.clone(), test_audit_repo(&pool))),
+        routes: Arc::new(PgRouteRepository::new(pool.clone(), test_audit_repo(&pool))),
+        freezers: Arc::new(PgFreezerRepository::new(pool.clone(), test_audit_repo(&pool))),
     };
 
     http_router::router(catalog_state)
@@ -195,6 +195,48 @@ async fn actualizar_producto_como_admin_retorna_403() {
     teardown_test_db(&db_name).await;
 }
 
+#[tokio::test]
+async fn actualizar_producto_registra_auditoria_antes_y_despues() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_catalog_router(pool.clone());
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+
+    let body = serde_json::json!({ "name": "Paleta Auditada" });
+
+    let request = Request::builder()
+        .method("PUT")
+        .uri(format!("/products/{}", seed.product_id))
+        .header("Authorization", format!("Bearer {token}"))
+        .header("Content-Type", "application/json")
+        .body(Body::from(serde_json::to_vec(&body).unwrap()))
+        .unwrap();
+
+    // Act
+    let response = app.oneshot(request).await.unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (action, before, after, created_by): (String, serde_json::Value, serde_json::Value, uuid::Uuid) =
+        sqlx::query_as(
+            "SELECT action, changes_before, changes_after, created_by FROM audit_log WHERE table_name = 'products' AND record_id = $1",
+        )
+        .bind(seed.product_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(action, "update");
+    assert_ne!(before["name"], "Paleta Auditada");
+    assert_eq!(after["name"], "Paleta Auditada");
+    assert_eq!(created_by, seed.owner_id);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
 // ─── Flavors ────────────────────────────────────────────
 
 #[tokio::test]
@@ -491,6 +533,140 @@ async fn toggle_congelador_retorna_200() {
     teardown_test_db(&db_name).await;
 }
 
+#[tokio::test]
+async fn toggle_registra_historial_y_reporte_de_consumo() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_catalog_router(pool.clone());
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+
+    sqlx::query("UPDATE freezers SET power_watts = 1000 WHERE id = $1")
+        .bind(seed.freezer_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let from = (chrono::Utc::now() - chrono::Duration::hours(3))
+        .to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+
+    let toggle = Request::builder()
+        .method("POST")
+        .uri(format!("/freezers/{}/toggle", seed.freezer_id))
+        .header("Authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap();
+
+    // Act
+    let toggle_response = app.clone().oneshot(toggle).await.unwrap();
+    let events_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/freezers/{}/power-events", seed.freezer_id))
+                .header("Authorization", format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let report_response = app
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/freezers/power-report?from={from}&price_per_kwh=800"))
+                .header("Authorization", format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(toggle_response.status(), StatusCode::OK);
+    assert_eq!(events_response.status(), StatusCode::OK);
+    let body = events_response.into_body().collect().await.unwrap().to_bytes();
+    let events: Vec<FreezerPowerEvent> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(events.len(), 2);
+    assert!(!events[0].is_on);
+
+    assert_eq!(report_response.status(), StatusCode::OK);
+    let body = report_response.into_body().collect().await.unwrap().to_bytes();
+    let report: PowerConsumptionReport = serde_json::from_slice(&body).unwrap();
+    let row = &report.freezers[0];
+    // Encendido desde hace 2 horas (semilla) hasta el toggle
+    assert_eq!(row.hours_on.round(), rust_decimal::Decimal::new(2, 0));
+    assert_eq!(row.power_events, 2);
+    assert!(row.cost.is_some());
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn ocupacion_de_congelador_usa_capacidad_por_producto() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_catalog_router(pool.clone());
+    let token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!("/freezers/{}/occupancy", seed.freezer_id))
+        .header("Authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap();
+
+    // Act
+    let response = app.oneshot(request).await.unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let occupancy: FreezerOccupancy = serde_json::from_slice(&body).unwrap();
+    assert_eq!(occupancy.total_capacity, 500);
+    assert_eq!(occupancy.total_quantity, 100);
+    assert_eq!(occupancy.products[0].product_id, seed.product_id);
+    assert_eq!(occupancy.occupancy_pct, Some(rust_decimal::Decimal::new(20, 0)));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn capacidad_con_producto_inexistente_retorna_400() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_catalog_router(pool.clone());
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+
+    let mut capacity = serde_json::Map::new();
+    capacity.insert(uuid::Uuid::new_v4().to_string(), 100.into());
+    let body = serde_json::json!({ "max_capacity": capacity });
+    let request = Request::builder()
+        .method("PUT")
+        .uri(format!("/freezers/{}", seed.freezer_id))
+        .header("Authorization", format!("Bearer {token}"))
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+
+    // Act
+    let response = app.oneshot(request).await.unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
 #[tokio::test]
 async fn todos_los_endpoints_sin_auth_retornan_401() {
     // Arrange
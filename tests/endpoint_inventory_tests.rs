@@ -0,0 +1,487 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use chrono::{SecondsFormat, Utc};
+use http_body_util::BodyExt;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use common::db::{setup_test_db, teardown_test_db, test_app_state, test_audit_repo, test_jwt};
+use common::seed::seed_test_data;
+use helados_sofis_core::modules::inventory::domain::entities::*;
+use helados_sofis_core::modules::inventory::domain::repositories::InventoryRepository;
+use helados_sofis_core::modules::inventory::infrastructure::controllers::http_router;
+use helados_sofis_core::modules::inventory::infrastructure::persistence::postgres_repo::{
+    PgInventoryRepository, PgStockAdjustmentRepository,
+};
+use helados_sofis_core::modules::pricing::domain::entities::ResolvedPrice;
+use helados_sofis_core::modules::worker_trips::domain::entities::{CreateTripDto, LoadedItemDto};
+use helados_sofis_core::modules::worker_trips::domain::repositories::WorkerTripRepository;
+use helados_sofis_core::modules::worker_trips::infrastructure::persistence::postgres_repo::PgWorkerTripRepository;
+use helados_sofis_core::shared::auth::Role;
+
+// ═══════════════════════════════════════════════════════════
+// Tests de Integración — Inventario y Kardex
+// BD real exclusiva por test · Semilla · Patrón AAA
+// ═══════════════════════════════════════════════════════════
+
+fn build_inventory_router(pool: sqlx::PgPool) -> axum::Router {
+    let app_state = test_app_state(pool.clone());
+    http_router::router(
+        app_state,
+        Arc::new(PgInventoryRepository::new(pool.clone(), test_audit_repo(&pool))),
+        Arc::new(PgStockAdjustmentRepository::new(pool.clone(), test_audit_repo(&pool))),
+    )
+}
+
+async fn send_json<T: serde::de::DeserializeOwned>(
+    app: axum::Router,
+    method: &str,
+    uri: String,
+    token: &str,
+    body: serde_json::Value,
+) -> T {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Authorization", format!("Bearer {token}"))
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&body).unwrap()
+}
+
+async fn get_json<T: serde::de::DeserializeOwned>(
+    app: axum::Router,
+    uri: String,
+    token: &str,
+) -> T {
+    let request = Request::builder()
+        .method("GET")
+        .uri(uri)
+        .header("Authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn agregar_stock_registra_movimiento_en_kardex() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_inventory_router(pool.clone());
+    let token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+
+    let body = serde_json::json!({
+        "freezer_id": seed.freezer_id,
+        "product_id": seed.product_id,
+        "flavor_id": seed.flavor_id,
+        "provider_id": seed.provider_id,
+        "quantity": 10
+    });
+    let request = Request::builder()
+        .method("POST")
+        .uri("/")
+        .header("Authorization", format!("Bearer {token}"))
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+
+    // Act
+    let response = app.clone().oneshot(request).await.unwrap();
+    let movements: Vec<InventoryMovement> = get_json(
+        app,
+        format!("/movements?inventory_id={}", seed.inventory_id),
+        &token,
+    )
+    .await;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(movements.len(), 2);
+    assert_eq!(movements[0].movement_type, "manual_add");
+    assert_eq!(movements[0].delta, 10);
+    assert_eq!(movements[0].quantity_after, 110);
+    assert_eq!(movements[1].movement_type, "opening");
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn stock_en_fecha_pasada_se_reconstruye_desde_kardex() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let repo = PgInventoryRepository::new(pool.clone(), test_audit_repo(&pool));
+    let app = build_inventory_router(pool.clone());
+    let token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+
+    let before = Utc::now();
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    repo.add_stock(
+        seed.freezer_id,
+        seed.product_id,
+        seed.flavor_id,
+        seed.provider_id,
+        25,
+        seed.admin_id,
+    )
+    .await
+    .unwrap();
+
+    // Act
+    let past: Vec<StockAtTime> = get_json(
+        app.clone(),
+        format!(
+            "/stock-at?at={}&freezer_id={}",
+            before.to_rfc3339_opts(SecondsFormat::Micros, true),
+            seed.freezer_id
+        ),
+        &token,
+    )
+    .await;
+    let now: Vec<StockAtTime> = get_json(
+        app,
+        format!(
+            "/stock-at?at={}",
+            Utc::now().to_rfc3339_opts(SecondsFormat::Micros, true)
+        ),
+        &token,
+    )
+    .await;
+
+    // Assert
+    assert_eq!(past.len(), 1);
+    assert_eq!(past[0].quantity, 100);
+    assert_eq!(now.len(), 1);
+    assert_eq!(now[0].quantity, 125);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn crear_viaje_registra_salida_con_documento() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let inventory = PgInventoryRepository::new(pool.clone(), test_audit_repo(&pool));
+
+    let dto = CreateTripDto {
+        worker_id: seed.worker_id,
+        departure_time: Utc::now(),
+        route_id: None,
+        loaded_items: vec![LoadedItemDto {
+            inventory_id: seed.inventory_id,
+            product_id: seed.product_id,
+            flavor_id: seed.flavor_id,
+            freezer_id: seed.freezer_id,
+            quantity: 30,
+            unit_price: None,
+            price_override_reason: None,
+            is_deformed: false,
+        }],
+    };
+    let prices = vec![ResolvedPrice {
+        unit_price: Decimal::new(1000, 2),
+        price_history_id: None,
+        override_reason: None,
+    }];
+
+    // Act
+    let trip = trips.create_trip(&dto, &prices, seed.admin_id).await.unwrap();
+    let movements = inventory
+        .find_movements(&MovementFilter {
+            inventory_id: Some(seed.inventory_id),
+            limit: Some(10),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    // Assert
+    let load = &movements[0];
+    assert_eq!(load.movement_type, "trip_load");
+    assert_eq!(load.delta, -30);
+    assert_eq!(load.quantity_after, 70);
+    assert_eq!(load.related_doc_type.as_deref(), Some("worker_trips"));
+    assert_eq!(load.related_doc_id, Some(trip.id));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn conteo_fisico_confirmado_genera_ajuste_valorizado() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_inventory_router(pool.clone());
+    let token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+
+    let count: StockCountWithLines = send_json(
+        app.clone(),
+        "POST",
+        "/counts".into(),
+        &token,
+        serde_json::json!({ "freezer_id": seed.freezer_id }),
+    )
+    .await;
+
+    // Act
+    let counted: StockCountWithLines = send_json(
+        app.clone(),
+        "PUT",
+        format!("/counts/{}/lines", count.session.id),
+        &token,
+        serde_json::json!({ "lines": [
+            { "inventory_id": seed.inventory_id, "counted_quantity": 96, "reason": "melted" }
+        ]}),
+    )
+    .await;
+    let committed: StockCountWithLines = send_json(
+        app.clone(),
+        "POST",
+        format!("/counts/{}/commit", count.session.id),
+        &token,
+        serde_json::json!({}),
+    )
+    .await;
+    let report: ShrinkageReport = get_json(app.clone(), "/shrinkage".into(), &token).await;
+    let movements: Vec<InventoryMovement> = get_json(
+        app,
+        format!("/movements?inventory_id={}", seed.inventory_id),
+        &token,
+    )
+    .await;
+
+    // Assert
+    assert_eq!(count.lines.len(), 1);
+    assert_eq!(count.lines[0].expected_quantity, 100);
+    assert_eq!(counted.lines[0].variance, Some(-4));
+    assert_eq!(committed.session.status, "committed");
+    assert_eq!(committed.lines[0].system_quantity, 96);
+    assert_eq!(committed.lines[0].variance, Some(-4));
+    assert_eq!(report.total_units_lost, 4);
+    assert_eq!(report.total_value_lost, Decimal::new(2000, 2));
+    assert_eq!(report.rows[0].reason, "melted");
+    assert_eq!(movements[0].movement_type, "adjustment");
+    assert_eq!(movements[0].delta, -4);
+    assert_eq!(movements[0].related_doc_type.as_deref(), Some("inventory_adjustments"));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn conteo_ajusta_contra_lo_esperado_y_sin_precio_queda_sin_costo() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_inventory_router(pool.clone());
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+    sqlx::query("DELETE FROM price_history")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let count: StockCountWithLines = send_json(
+        app.clone(),
+        "POST",
+        "/counts".into(),
+        &token,
+        serde_json::json!({ "freezer_id": seed.freezer_id }),
+    )
+    .await;
+    // Carga de 30 mientras el conteo está abierto
+    let dto = CreateTripDto {
+        worker_id: seed.worker_id,
+        departure_time: Utc::now(),
+        route_id: None,
+        loaded_items: vec![LoadedItemDto {
+            inventory_id: seed.inventory_id,
+            product_id: seed.product_id,
+            flavor_id: seed.flavor_id,
+            freezer_id: seed.freezer_id,
+            quantity: 30,
+            unit_price: None,
+            price_override_reason: None,
+            is_deformed: false,
+        }],
+    };
+    let prices = vec![ResolvedPrice {
+        unit_price: Decimal::new(1000, 2),
+        price_history_id: None,
+        override_reason: None,
+    }];
+    trips.create_trip(&dto, &prices, seed.admin_id).await.unwrap();
+
+    // Act
+    let _: StockCountWithLines = send_json(
+        app.clone(),
+        "PUT",
+        format!("/counts/{}/lines", count.session.id),
+        &token,
+        serde_json::json!({ "lines": [
+            { "inventory_id": seed.inventory_id, "counted_quantity": 97, "reason": "lost" }
+        ]}),
+    )
+    .await;
+    let committed: StockCountWithLines = send_json(
+        app.clone(),
+        "POST",
+        format!("/counts/{}/commit", count.session.id),
+        &token,
+        serde_json::json!({}),
+    )
+    .await;
+    let report: ShrinkageReport = get_json(app, "/shrinkage".into(), &token).await;
+
+    // Assert
+    assert_eq!(committed.lines[0].variance, Some(-3));
+    assert_eq!(committed.lines[0].system_quantity, 67);
+    assert_eq!(report.total_units_lost, 3);
+    assert_eq!(report.total_value_lost, Decimal::ZERO);
+    assert_eq!(report.total_uncosted_units, 3);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn baja_mayor_al_stock_es_rechazada() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_inventory_router(pool.clone());
+    let token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+
+    let body = serde_json::json!({
+        "inventory_id": seed.inventory_id,
+        "quantity_delta": -101,
+        "reason": "lost"
+    });
+    let request = Request::builder()
+        .method("POST")
+        .uri("/adjustments")
+        .header("Authorization", format!("Bearer {token}"))
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+
+    // Act
+    let response = app.oneshot(request).await.unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let quantity: i32 = sqlx::query_scalar("SELECT quantity FROM inventory WHERE id = $1")
+        .bind(seed.inventory_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(quantity, 100);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn reasignar_y_liberar_deformados_deja_rastro_en_kardex() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_inventory_router(pool.clone());
+    let token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+
+    let new_worker = uuid::Uuid::new_v4();
+    sqlx::query("INSERT INTO workers (id, name, active, created_by) VALUES ($1, 'Ana', TRUE, $2)")
+        .bind(new_worker)
+        .bind(seed.owner_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let deformed_id = sqlx::query_scalar::<_, uuid::Uuid>(
+        r#"
+        INSERT INTO inventory
+        (freezer_id, product_id, flavor_id, provider_id, quantity,
+         is_deformed, assigned_worker_id, min_stock_alert, updated_by)
+        VALUES ($1, $2, $3, $4, 5, TRUE, $5, 0, $6)
+        RETURNING id
+        "#,
+    )
+    .bind(seed.freezer_id)
+    .bind(seed.product_id)
+    .bind(seed.flavor_id)
+    .bind(seed.provider_id)
+    .bind(seed.worker_id)
+    .bind(seed.admin_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    // Act
+    let reassigned: DeformedTransfer = send_json(
+        app.clone(),
+        "POST",
+        format!("/deformed/{deformed_id}/reassign"),
+        &token,
+        serde_json::json!({ "worker_id": new_worker, "quantity": 3, "notes": "Juan renunció" }),
+    )
+    .await;
+    let released: DeformedTransfer = send_json(
+        app.clone(),
+        "POST",
+        format!("/deformed/{deformed_id}/release"),
+        &token,
+        serde_json::json!({}),
+    )
+    .await;
+    let old_worker: Vec<InventoryItem> = get_json(
+        app.clone(),
+        format!("/worker/{}/deformed", seed.worker_id),
+        &token,
+    )
+    .await;
+    let new_worker_rows: Vec<InventoryItem> =
+        get_json(app.clone(), format!("/worker/{new_worker}/deformed"), &token).await;
+    let movements: Vec<InventoryMovement> = get_json(
+        app,
+        format!("/movements?inventory_id={}", released.to_inventory_id),
+        &token,
+    )
+    .await;
+
+    // Assert
+    assert_eq!(reassigned.quantity, 3);
+    assert_eq!(reassigned.to_worker_id, Some(new_worker));
+    assert_eq!(released.quantity, 2);
+    assert_eq!(released.to_worker_id, None);
+    assert!(old_worker.is_empty());
+    assert_eq!(new_worker_rows.len(), 1);
+    assert_eq!(new_worker_rows[0].quantity, 3);
+    assert_eq!(movements[0].movement_type, "deformed_transfer");
+    assert_eq!(movements[0].quantity_after, 2);
+    assert_eq!(movements[0].assigned_worker_id, None);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
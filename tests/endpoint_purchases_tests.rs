@@ -0,0 +1,519 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use http_body_util::BodyExt;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use common::db::{setup_test_db, teardown_test_db, test_app_state, test_audit_repo, test_jwt};
+use common::seed::{seed_test_data, SeedData};
+use helados_sofis_core::modules::purchases::domain::entities::*;
+use helados_sofis_core::modules::purchases::domain::repositories::{
+    ProviderPayableRepository, PurchaseRepository,
+};
+use helados_sofis_core::modules::purchases::infrastructure::controllers::http_router;
+use helados_sofis_core::modules::purchases::infrastructure::persistence::postgres_repo::{
+    PgProviderPayableRepository, PgPurchaseRepository,
+};
+use helados_sofis_core::shared::auth::Role;
+
+// ═══════════════════════════════════════════════════════════
+// Tests de Integración — Compras y Cuentas por Pagar
+// BD real exclusiva por test · Semilla · Patrón AAA
+// ═══════════════════════════════════════════════════════════
+
+fn build_purchases_router(pool: sqlx::PgPool) -> axum::Router {
+    let app_state = test_app_state(pool.clone());
+    http_router::router(
+        app_state,
+        Arc::new(PgPurchaseRepository::new(pool.clone(), test_audit_repo(&pool))),
+        Arc::new(PgProviderPayableRepository::new(pool.clone(), test_audit_repo(&pool))),
+    )
+}
+
+async fn credit_purchase(pool: &sqlx::PgPool, seed: &SeedData, quantity: i32) -> Purchase {
+    let dto = CreatePurchaseDto {
+        provider_id: seed.provider_id,
+        payment_status: "credit".into(),
+        paid_from: None,
+        items: vec![CreatePurchaseItemDto {
+            product_id: seed.product_id,
+            flavor_id: seed.flavor_id,
+            quantity,
+            unit_price: Decimal::new(500, 2),
+            freezer_id: seed.freezer_id,
+        }],
+    };
+    PgPurchaseRepository::new(pool.clone(), test_audit_repo(pool))
+        .create(&dto, seed.admin_id)
+        .await
+        .unwrap()
+        .purchase
+        .purchase
+}
+
+async fn post_purchase(app: &axum::Router, token: &str, seed: &SeedData, body: serde_json::Value) -> Purchase {
+    let created = post_purchase_json(app, token, seed, body, "5.00").await;
+    serde_json::from_value(created).unwrap()
+}
+
+async fn post_purchase_json(
+    app: &axum::Router,
+    token: &str,
+    seed: &SeedData,
+    body: serde_json::Value,
+    unit_price: &str,
+) -> serde_json::Value {
+    let mut body = body;
+    body["provider_id"] = serde_json::json!(seed.provider_id);
+    body["items"] = serde_json::json!([{
+        "product_id": seed.product_id,
+        "flavor_id": seed.flavor_id,
+        "quantity": 10,
+        "unit_price": unit_price,
+        "freezer_id": seed.freezer_id,
+    }]);
+    let request = Request::builder()
+        .method("POST")
+        .uri("/")
+        .header("Authorization", format!("Bearer {token}"))
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn costo_distinto_sin_regla_devuelve_advertencia() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_purchases_router(pool.clone());
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+
+    // Act — el precio base vigente es 10.00; el nuevo costo lo supera
+    let created = post_purchase_json(
+        &app,
+        &token,
+        &seed,
+        serde_json::json!({ "payment_status": "credit" }),
+        "11.00",
+    )
+    .await;
+
+    // Assert
+    assert_eq!(created["price_updates"].as_array().unwrap().len(), 0);
+    let warnings = created["price_warnings"].as_array().unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0]["previous_cost"], "5.00");
+    assert_eq!(warnings[0]["new_cost"], "11.00");
+    assert_eq!(warnings[0]["below_cost"], true);
+    let prices: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM price_history")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(prices, 1);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn costo_distinto_con_regla_crea_precio_nuevo() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_purchases_router(pool.clone());
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+    sqlx::query(
+        r#"INSERT INTO price_markup_rules
+           (product_id, base_markup_pct, route_markup_pct, local_markup_pct, updated_by)
+           VALUES ($1, 100, 140, 200, $2)"#,
+    )
+    .bind(seed.product_id)
+    .bind(seed.owner_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    // Act
+    let created = post_purchase_json(
+        &app,
+        &token,
+        &seed,
+        serde_json::json!({ "payment_status": "credit" }),
+        "6.00",
+    )
+    .await;
+
+    // Assert
+    assert_eq!(created["price_warnings"].as_array().unwrap().len(), 0);
+    assert_eq!(created["price_updates"].as_array().unwrap().len(), 1);
+    let (cost, base, route, local, commission): (Decimal, Decimal, Decimal, Decimal, Decimal) =
+        sqlx::query_as(
+            r#"SELECT cost_price, price_base, price_route, price_local, commission
+               FROM price_history ORDER BY created_at DESC LIMIT 1"#,
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(cost, Decimal::new(600, 2));
+    assert_eq!(base, Decimal::new(1200, 2));
+    assert_eq!(route, Decimal::new(1440, 2));
+    assert_eq!(local, Decimal::new(1800, 2));
+    assert_eq!(commission, Decimal::new(240, 2));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn precio_futuro_no_cuenta_como_costo_vigente() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_purchases_router(pool.clone());
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+    sqlx::query(
+        r#"INSERT INTO price_history
+           (product_id, flavor_id, provider_id, cost_price, price_base,
+            price_route, price_local, commission, effective_date, created_by)
+           VALUES ($1, $2, $3, 8.00, 16.00, 18.00, 20.00, 2.00, NOW() + INTERVAL '7 days', $4)"#,
+    )
+    .bind(seed.product_id)
+    .bind(seed.flavor_id)
+    .bind(seed.provider_id)
+    .bind(seed.owner_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    // Act — mismo costo que el precio vigente (5.00)
+    let created = post_purchase_json(
+        &app,
+        &token,
+        &seed,
+        serde_json::json!({ "payment_status": "credit" }),
+        "5.00",
+    )
+    .await;
+
+    // Assert
+    assert_eq!(created["price_warnings"].as_array().unwrap().len(), 0);
+    assert_eq!(created["price_updates"].as_array().unwrap().len(), 0);
+    let prices: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM price_history")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(prices, 2);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn compra_pagada_registra_salida_de_caja() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_purchases_router(pool.clone());
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+
+    // Act
+    let purchase = post_purchase(&app, &token, &seed, serde_json::json!({ "payment_status": "paid" })).await;
+
+    // Assert
+    assert_eq!(purchase.paid_from.as_deref(), Some("cash_register"));
+    let (cash_type, cash_amount): (String, Decimal) = sqlx::query_as(
+        "SELECT type, amount FROM cash_register WHERE related_doc_id = $1",
+    )
+    .bind(purchase.id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(cash_type, "purchase_payment");
+    assert_eq!(cash_amount, Decimal::new(-5000, 2));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn compra_pagada_fuera_de_caja_no_afecta_saldo() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_purchases_router(pool.clone());
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+    let cash_rows_before: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM cash_register")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    // Act
+    let purchase = post_purchase(
+        &app,
+        &token,
+        &seed,
+        serde_json::json!({ "payment_status": "paid", "paid_from": "external" }),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(purchase.payment_status, "paid");
+    assert_eq!(purchase.paid_from.as_deref(), Some("external"));
+    let cash_rows_after: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM cash_register")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(cash_rows_after, cash_rows_before);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn pago_parcial_salda_compras_mas_antiguas_y_sale_de_caja() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_purchases_router(pool.clone());
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+
+    let first = credit_purchase(&pool, &seed, 10).await; // 50.00
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    let second = credit_purchase(&pool, &seed, 20).await; // 100.00
+
+    let body = serde_json::json!({ "amount": "80.00", "notes": "Abono semanal" });
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/payables/{}/payments", seed.provider_id))
+        .header("Authorization", format!("Bearer {token}"))
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+
+    // Act
+    let response = app.clone().oneshot(request).await.unwrap();
+    let summary_response = app
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/payables")
+                .header("Authorization", format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let payment: ProviderPaymentWithAllocations = serde_json::from_slice(&body).unwrap();
+    assert_eq!(payment.allocations.len(), 2);
+
+    let statuses: Vec<(uuid::Uuid, String, Decimal)> = sqlx::query_as(
+        "SELECT id, payment_status, amount_paid FROM purchases ORDER BY created_at",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(statuses[0], (first.id, "paid".into(), Decimal::new(5000, 2)));
+    assert_eq!(statuses[1], (second.id, "credit".into(), Decimal::new(3000, 2)));
+
+    let (cash_type, cash_amount): (String, Decimal) = sqlx::query_as(
+        "SELECT type, amount FROM cash_register WHERE related_doc_id = $1",
+    )
+    .bind(payment.payment.id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(cash_type, "provider_payment");
+    assert_eq!(cash_amount, Decimal::new(-8000, 2));
+
+    let body = summary_response.into_body().collect().await.unwrap().to_bytes();
+    let summary: PayablesSummary = serde_json::from_slice(&body).unwrap();
+    assert_eq!(summary.total_debt, Decimal::new(7000, 2));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn pago_mayor_al_saldo_es_rechazado() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_purchases_router(pool.clone());
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+    credit_purchase(&pool, &seed, 10).await; // 50.00
+
+    let body = serde_json::json!({ "amount": "50.01" });
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/payables/{}/payments", seed.provider_id))
+        .header("Authorization", format!("Bearer {token}"))
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+
+    // Act
+    let response = app.oneshot(request).await.unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let payments: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM provider_payments")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(payments, 0);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+async fn post_void(app: &axum::Router, token: &str, purchase_id: uuid::Uuid) -> (StatusCode, serde_json::Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/{purchase_id}/void"))
+        .header("Authorization", format!("Bearer {token}"))
+        .header("Content-Type", "application/json")
+        .body(Body::from(serde_json::json!({ "reason": "Compra duplicada" }).to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap_or_default())
+}
+
+#[tokio::test]
+async fn anular_compra_pagada_retira_stock_y_devuelve_a_caja() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_purchases_router(pool.clone());
+    let owner_token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+    let admin_token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+    let purchase = post_purchase(&app, &owner_token, &seed, serde_json::json!({ "payment_status": "paid" })).await;
+
+    // Act
+    let (admin_status, _) = post_void(&app, &admin_token, purchase.id).await;
+    let (status, body) = post_void(&app, &owner_token, purchase.id).await;
+    let (again_status, _) = post_void(&app, &owner_token, purchase.id).await;
+
+    // Assert
+    assert_eq!(admin_status, StatusCode::FORBIDDEN);
+    assert_eq!(status, StatusCode::OK);
+    let voided: Purchase = serde_json::from_value(body).unwrap();
+    assert!(voided.voided_at.is_some());
+    assert_eq!(voided.voided_by, Some(seed.owner_id));
+    assert_eq!(voided.void_reason.as_deref(), Some("Compra duplicada"));
+    assert_eq!(again_status, StatusCode::CONFLICT);
+
+    let quantity: i32 = sqlx::query_scalar("SELECT quantity FROM inventory WHERE id = $1")
+        .bind(seed.inventory_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(quantity, 100);
+
+    let (reversal_amount, balance): (Decimal, Decimal) = sqlx::query_as(
+        "SELECT amount, balance FROM cash_register WHERE type = 'reversal'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(reversal_amount, Decimal::new(5000, 2));
+    assert_eq!(balance, Decimal::ZERO);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn anular_compra_con_abonos_retorna_409() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_purchases_router(pool.clone());
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+    let purchase = credit_purchase(&pool, &seed, 10).await;
+    PgProviderPayableRepository::new(pool.clone(), test_audit_repo(&pool))
+        .pay(
+            seed.provider_id,
+            &CreateProviderPaymentDto {
+                amount: Decimal::new(1000, 2),
+                purchase_ids: vec![purchase.id],
+                notes: None,
+            },
+            seed.owner_id,
+        )
+        .await
+        .unwrap();
+
+    // Act
+    let (status, _) = post_void(&app, &token, purchase.id).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::CONFLICT);
+    let voided: bool = sqlx::query_scalar("SELECT voided_at IS NOT NULL FROM purchases WHERE id = $1")
+        .bind(purchase.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(!voided);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn anular_compra_anterior_al_kardex_retorna_409() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let app = build_purchases_router(pool.clone());
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+    let purchase = post_purchase(&app, &token, &seed, serde_json::json!({ "payment_status": "paid" })).await;
+    sqlx::query("DELETE FROM inventory_movements WHERE related_doc_id = $1")
+        .bind(purchase.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // Act
+    let (status, _) = post_void(&app, &token, purchase.id).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::CONFLICT);
+    let voided: bool = sqlx::query_scalar("SELECT voided_at IS NOT NULL FROM purchases WHERE id = $1")
+        .bind(purchase.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(!voided);
+    let reversals: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM cash_register WHERE type = 'reversal'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(reversals, 0);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
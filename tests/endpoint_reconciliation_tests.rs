@@ -0,0 +1,209 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use chrono::Utc;
+use http_body_util::BodyExt;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use common::db::{setup_test_db, teardown_test_db, test_app_state, test_audit_repo, test_jwt};
+use common::seed::{seed_test_data, SeedData};
+use helados_sofis_core::modules::pricing::domain::entities::ResolvedPrice;
+use helados_sofis_core::modules::reconciliation::domain::entities::ReconciliationReport;
+use helados_sofis_core::modules::reconciliation::infrastructure::controllers::http_router;
+use helados_sofis_core::modules::reconciliation::infrastructure::persistence::postgres_repo::PgReconciliationRepository;
+use helados_sofis_core::modules::worker_payments::domain::entities::CreateWorkerPaymentDto;
+use helados_sofis_core::modules::worker_payments::domain::repositories::WorkerPaymentRepository;
+use helados_sofis_core::modules::worker_payments::infrastructure::persistence::postgres_repo::PgWorkerPaymentRepository;
+use helados_sofis_core::modules::worker_trips::domain::entities::*;
+use helados_sofis_core::modules::worker_trips::domain::repositories::WorkerTripRepository;
+use helados_sofis_core::modules::worker_trips::infrastructure::persistence::postgres_repo::PgWorkerTripRepository;
+use helados_sofis_core::shared::auth::Role;
+
+// ═══════════════════════════════════════════════════════════
+// Tests de Integración — Conciliación de contadores
+// BD real exclusiva por test · Semilla · Patrón AAA
+// ═══════════════════════════════════════════════════════════
+
+fn build_reconciliation_router(pool: sqlx::PgPool) -> axum::Router {
+    http_router::router(
+        test_app_state(pool.clone()),
+        Arc::new(PgReconciliationRepository::new(pool.clone(), test_audit_repo(&pool))),
+    )
+}
+
+async fn send(
+    pool: &sqlx::PgPool,
+    method: &str,
+    uri: &str,
+    token: &str,
+) -> (StatusCode, Option<ReconciliationReport>) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap();
+    let response = build_reconciliation_router(pool.clone())
+        .oneshot(request)
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).ok())
+}
+
+/// Viaje de 30 unidades a $10.00 por la ruta semilla, sin completar.
+async fn start_trip(repo: &PgWorkerTripRepository, seed: &SeedData) -> WorkerTrip {
+    let dto = CreateTripDto {
+        worker_id: seed.worker_id,
+        departure_time: Utc::now(),
+        route_id: Some(seed.route_id),
+        loaded_items: vec![LoadedItemDto {
+            inventory_id: seed.inventory_id,
+            product_id: seed.product_id,
+            flavor_id: seed.flavor_id,
+            freezer_id: seed.freezer_id,
+            quantity: 30,
+            unit_price: None,
+            price_override_reason: None,
+            is_deformed: false,
+        }],
+    };
+    let prices = vec![ResolvedPrice {
+        unit_price: Decimal::new(1000, 2),
+        price_history_id: None,
+        override_reason: None,
+    }];
+    repo.create_trip(&dto, &prices, seed.admin_id).await.unwrap()
+}
+
+fn returned(seed: &SeedData, quantity: i32) -> Vec<ReturnedItemDto> {
+    vec![ReturnedItemDto {
+        loaded_item_id: None,
+        product_id: seed.product_id,
+        flavor_id: seed.flavor_id,
+        quantity,
+        is_deformed: false,
+        destination_freezer_id: seed.freezer_id,
+    }]
+}
+
+#[tokio::test]
+async fn operaciones_normales_dejan_los_contadores_consistentes() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let payments = PgWorkerPaymentRepository::new(pool.clone(), test_audit_repo(&pool));
+
+    let trip = start_trip(&trips, &seed).await;
+    let dto = CompleteTripDto {
+        returned_items: returned(&seed, 10),
+    };
+    trips.complete_trip(trip.id, &dto, seed.admin_id).await.unwrap();
+    trips
+        .correct_trip(trip.id, &returned(&seed, 12), "Conteo", seed.owner_id)
+        .await
+        .unwrap();
+    let abono = CreateWorkerPaymentDto {
+        amount: Decimal::new(5000, 2),
+        trip_ids: vec![],
+        allow_credit: false,
+        notes: None,
+    };
+    payments
+        .create_worker_payment(seed.worker_id, &abono, seed.admin_id)
+        .await
+        .unwrap();
+    let cancelled = start_trip(&trips, &seed).await;
+    trips
+        .cancel_trip(cancelled.id, "Se averió la moto", seed.owner_id)
+        .await
+        .unwrap();
+    let token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+
+    // Act
+    let (status, report) = send(&pool, "GET", "/counters", &token).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    let report = report.unwrap();
+    assert!(report.is_consistent, "{report:?}");
+    assert_eq!(report.workers_checked, 1);
+    assert_eq!(report.routes_checked, 1);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn contadores_alterados_se_reportan_y_el_dueno_los_repara() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let trip = start_trip(&trips, &seed).await;
+    let dto = CompleteTripDto {
+        returned_items: returned(&seed, 10),
+    };
+    trips.complete_trip(trip.id, &dto, seed.admin_id).await.unwrap();
+    sqlx::query("UPDATE workers SET current_debt = 999, total_sales = 1 WHERE id = $1")
+        .bind(seed.worker_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE routes SET usage_count = 7 WHERE id = $1")
+        .bind(seed.route_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let admin = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+    let owner = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+
+    // Act
+    let (_, check) = send(&pool, "GET", "/counters", &admin).await;
+    let (forbidden, _) = send(&pool, "POST", "/counters/repair", &admin).await;
+    let (status, repair) = send(&pool, "POST", "/counters/repair", &owner).await;
+    let (_, after) = send(&pool, "GET", "/counters", &admin).await;
+
+    // Assert
+    let check = check.unwrap();
+    assert!(!check.is_consistent);
+    assert!(!check.repaired);
+    assert_eq!(check.workers[0].current_debt, Decimal::new(99900, 2));
+    assert_eq!(check.workers[0].expected_current_debt, Decimal::new(20000, 2));
+    assert_eq!(check.workers[0].expected_total_sales, 20);
+    assert_eq!(check.routes[0].usage_count, 7);
+    assert_eq!(check.routes[0].expected_usage_count, 1);
+
+    assert_eq!(forbidden, StatusCode::FORBIDDEN);
+    assert_eq!(status, StatusCode::OK);
+    assert!(repair.unwrap().repaired);
+    assert!(after.unwrap().is_consistent);
+
+    let debt = sqlx::query_scalar::<_, Decimal>("SELECT current_debt FROM workers WHERE id = $1")
+        .bind(seed.worker_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(debt, Decimal::new(20000, 2));
+
+    let audited = sqlx::query_scalar::<_, i64>(
+        r#"SELECT COUNT(*) FROM audit_log
+           WHERE action = 'update' AND created_by = $1
+             AND table_name IN ('workers', 'routes')"#,
+    )
+    .bind(seed.owner_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(audited, 2);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
@@ -0,0 +1,733 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use chrono::Utc;
+use http_body_util::BodyExt;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use common::db::{setup_test_db, teardown_test_db, test_app_state, test_audit_repo, test_jwt};
+use common::seed::seed_test_data;
+use helados_sofis_core::modules::cash_register::application::manage_cash;
+use helados_sofis_core::modules::cash_register::domain::entities::{
+    CreateExpenseDto, CreateWithdrawalDto,
+};
+use helados_sofis_core::modules::cash_register::domain::repositories::CashRegisterRepository;
+use helados_sofis_core::modules::cash_register::infrastructure::persistence::postgres_repo::PgCashRegisterRepository;
+use helados_sofis_core::modules::local_sales::domain::entities::*;
+use helados_sofis_core::modules::local_sales::domain::repositories::LocalSaleRepository;
+use helados_sofis_core::modules::local_sales::infrastructure::persistence::postgres_repo::PgLocalSaleRepository;
+use helados_sofis_core::modules::owner_sales::domain::entities::*;
+use helados_sofis_core::modules::owner_sales::domain::repositories::OwnerSaleRepository;
+use helados_sofis_core::modules::owner_sales::infrastructure::persistence::postgres_repo::PgOwnerSaleRepository;
+use helados_sofis_core::modules::pricing::domain::entities::ResolvedPrice;
+use helados_sofis_core::modules::reports::domain::entities::{
+    Dashboard, InventoryValuation, ProfitLossReport, RouteProfitabilityReport, SalesReport,
+};
+use helados_sofis_core::modules::reports::infrastructure::controllers::http_router;
+use helados_sofis_core::modules::reports::infrastructure::persistence::postgres_repo::PgReportsRepository;
+use helados_sofis_core::modules::worker_trips::domain::entities::*;
+use helados_sofis_core::modules::worker_trips::domain::repositories::WorkerTripRepository;
+use helados_sofis_core::modules::worker_trips::infrastructure::persistence::postgres_repo::PgWorkerTripRepository;
+use helados_sofis_core::shared::auth::Role;
+
+// ═══════════════════════════════════════════════════════════
+// Tests de Integración — Reportes (tablero del dueño)
+// BD real exclusiva por test · Semilla · Patrón AAA
+// ═══════════════════════════════════════════════════════════
+
+fn build_reports_router(pool: sqlx::PgPool) -> axum::Router {
+    http_router::router(
+        test_app_state(pool.clone()),
+        Arc::new(PgReportsRepository::new(pool.clone())),
+    )
+}
+
+async fn get<T: serde::de::DeserializeOwned>(
+    pool: &sqlx::PgPool,
+    token: &str,
+    uri: &str,
+) -> (StatusCode, Option<T>) {
+    let request = Request::builder()
+        .method("GET")
+        .uri(uri)
+        .header("Authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap();
+    let response = build_reports_router(pool.clone())
+        .oneshot(request)
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).ok())
+}
+
+#[tokio::test]
+async fn tablero_agrega_viajes_ventas_locales_y_alertas() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let local_sales = PgLocalSaleRepository::new(pool.clone(), test_audit_repo(&pool));
+
+    // Viaje: 30 cargados, 10 devueltos → 20 vendidos a $10.00
+    let trip_dto = CreateTripDto {
+        worker_id: seed.worker_id,
+        departure_time: Utc::now(),
+        route_id: None,
+        loaded_items: vec![LoadedItemDto {
+            inventory_id: seed.inventory_id,
+            product_id: seed.product_id,
+            flavor_id: seed.flavor_id,
+            freezer_id: seed.freezer_id,
+            quantity: 30,
+            unit_price: None,
+            price_override_reason: None,
+            is_deformed: false,
+        }],
+    };
+    let price = ResolvedPrice {
+        unit_price: Decimal::new(1000, 2),
+        price_history_id: None,
+        override_reason: None,
+    };
+    let trip = trips
+        .create_trip(&trip_dto, std::slice::from_ref(&price), seed.admin_id)
+        .await
+        .unwrap();
+    let complete = CompleteTripDto {
+        returned_items: vec![ReturnedItemDto {
+            loaded_item_id: None,
+            product_id: seed.product_id,
+            flavor_id: seed.flavor_id,
+            quantity: 10,
+            is_deformed: false,
+            destination_freezer_id: seed.freezer_id,
+        }],
+    };
+    trips.complete_trip(trip.id, &complete, seed.admin_id).await.unwrap();
+
+    // Venta local: 5 a $15.00 (el stock queda en 75, sobre el mínimo de 20)
+    let sale_dto = CreateLocalSaleDto {
+        sale_type: "local".into(),
+        notes: None,
+        items: vec![LocalSaleItemDto {
+            inventory_id: seed.inventory_id,
+            product_id: seed.product_id,
+            flavor_id: seed.flavor_id,
+            freezer_id: seed.freezer_id,
+            quantity: 5,
+            unit_price: None,
+            price_override_reason: None,
+        }],
+    };
+    let local_price = ResolvedPrice {
+        unit_price: Decimal::new(1500, 2),
+        ..price.clone()
+    };
+    local_sales
+        .create_sale(&sale_dto, &[local_price], seed.admin_id)
+        .await
+        .unwrap();
+
+    // Viaje de hace dos días que nunca retornó
+    let mut stale_dto = trip_dto;
+    stale_dto.departure_time = Utc::now() - chrono::Duration::days(2);
+    stale_dto.loaded_items[0].quantity = 60;
+    trips
+        .create_trip(&stale_dto, std::slice::from_ref(&price), seed.admin_id)
+        .await
+        .unwrap();
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+
+    // Act
+    let (status, dashboard) = get::<Dashboard>(&pool, &token, "/dashboard?days=7").await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    let dashboard = dashboard.unwrap();
+    assert_eq!(dashboard.sales.len(), 7);
+    assert_eq!(dashboard.sales.last().unwrap().date, dashboard.to);
+    assert_eq!(dashboard.totals.worker_trips, Decimal::new(20000, 2));
+    assert_eq!(dashboard.totals.local_sales, Decimal::new(7500, 2));
+    assert_eq!(dashboard.totals.units, 25);
+    assert_eq!(dashboard.cash_available, Decimal::new(7500, 2));
+
+    assert_eq!(dashboard.top_workers.len(), 1);
+    assert_eq!(dashboard.top_workers[0].sold_quantity, 20);
+    assert_eq!(dashboard.top_products[0].units, 25);
+    assert_eq!(dashboard.top_products[0].amount, Decimal::new(27500, 2));
+
+    // 100 − 30 + 10 − 5 − 60 = 15 ≤ 20
+    assert_eq!(dashboard.alerts.low_stock.len(), 1);
+    assert_eq!(dashboard.alerts.low_stock[0].quantity, 15);
+    assert_eq!(dashboard.alerts.open_trips.len(), 1);
+    assert_eq!(dashboard.alerts.workers_with_debt, 1);
+    assert_eq!(dashboard.alerts.worker_debt_total, Decimal::new(20000, 2));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn tablero_es_solo_para_el_dueno() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let admin = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+    let owner = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+
+    // Act
+    let (forbidden, _) = get::<Dashboard>(&pool, &admin, "/dashboard").await;
+    let (bad_window, _) = get::<Dashboard>(&pool, &owner, "/dashboard?days=0").await;
+    let (status, dashboard) = get::<Dashboard>(&pool, &owner, "/dashboard").await;
+
+    // Assert
+    assert_eq!(forbidden, StatusCode::FORBIDDEN);
+    assert_eq!(bad_window, StatusCode::BAD_REQUEST);
+    assert_eq!(status, StatusCode::OK);
+    let dashboard = dashboard.unwrap();
+    assert_eq!(dashboard.sales.len(), 30);
+    assert!(dashboard.top_workers.is_empty());
+    assert_eq!(dashboard.totals.total, Decimal::ZERO);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn ventas_separan_precio_de_venta_e_ingreso_por_canal() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let local_sales = PgLocalSaleRepository::new(pool.clone(), test_audit_repo(&pool));
+    let price_history_id: uuid::Uuid = sqlx::query_scalar("SELECT id FROM price_history LIMIT 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    // Viaje: 30 cargados, 10 devueltos → 20 vendidos (ruta $12.00, base $10.00)
+    let trip_dto = CreateTripDto {
+        worker_id: seed.worker_id,
+        departure_time: Utc::now(),
+        route_id: Some(seed.route_id),
+        loaded_items: vec![LoadedItemDto {
+            inventory_id: seed.inventory_id,
+            product_id: seed.product_id,
+            flavor_id: seed.flavor_id,
+            freezer_id: seed.freezer_id,
+            quantity: 30,
+            unit_price: None,
+            price_override_reason: None,
+            is_deformed: false,
+        }],
+    };
+    let route_price = ResolvedPrice {
+        unit_price: Decimal::new(1200, 2),
+        price_history_id: Some(price_history_id),
+        override_reason: None,
+    };
+    let trip = trips
+        .create_trip(&trip_dto, &[route_price], seed.admin_id)
+        .await
+        .unwrap();
+    let complete = CompleteTripDto {
+        returned_items: vec![ReturnedItemDto {
+            loaded_item_id: None,
+            product_id: seed.product_id,
+            flavor_id: seed.flavor_id,
+            quantity: 10,
+            is_deformed: false,
+            destination_freezer_id: seed.freezer_id,
+        }],
+    };
+    trips.complete_trip(trip.id, &complete, seed.admin_id).await.unwrap();
+
+    // Venta local: 5 a $15.00
+    let sale_dto = CreateLocalSaleDto {
+        sale_type: "local".into(),
+        notes: None,
+        items: vec![LocalSaleItemDto {
+            inventory_id: seed.inventory_id,
+            product_id: seed.product_id,
+            flavor_id: seed.flavor_id,
+            freezer_id: seed.freezer_id,
+            quantity: 5,
+            unit_price: None,
+            price_override_reason: None,
+        }],
+    };
+    let local_price = ResolvedPrice {
+        unit_price: Decimal::new(1500, 2),
+        price_history_id: Some(price_history_id),
+        override_reason: None,
+    };
+    local_sales
+        .create_sale(&sale_dto, &[local_price], seed.admin_id)
+        .await
+        .unwrap();
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+
+    // Act
+    let (status, by_channel) =
+        get::<SalesReport>(&pool, &token, "/sales?breakdown=channel&compare=true").await;
+    let (_, local_only) = get::<SalesReport>(&pool, &token, "/sales?channel=local_sale").await;
+    let (_, by_worker) = get::<SalesReport>(
+        &pool,
+        &token,
+        &format!("/sales?period=week&breakdown=worker&route_id={}", seed.route_id),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    let by_channel = by_channel.unwrap();
+    assert_eq!(by_channel.totals.units, 25);
+    assert_eq!(by_channel.totals.revenue, Decimal::new(31500, 2));
+    assert_eq!(by_channel.totals.income, Decimal::new(25000, 2));
+    let trip_row = by_channel
+        .rows
+        .iter()
+        .find(|r| r.key.as_deref() == Some("worker_trip"))
+        .unwrap();
+    assert_eq!(trip_row.units, 20);
+    assert_eq!(trip_row.revenue, Decimal::new(24000, 2));
+    assert_eq!(trip_row.income, Decimal::new(20000, 2));
+    let comparison = by_channel.comparison.unwrap();
+    assert!(comparison.rows.is_empty());
+    assert_eq!(comparison.revenue_change_pct, None);
+
+    let local_only = local_only.unwrap();
+    assert_eq!(local_only.totals.units, 5);
+    assert_eq!(local_only.totals.revenue, Decimal::new(7500, 2));
+    assert!(local_only.rows.iter().all(|r| r.key.is_none()));
+
+    let by_worker = by_worker.unwrap();
+    assert_eq!(by_worker.rows.len(), 1);
+    assert_eq!(by_worker.rows[0].key, Some(seed.worker_id.to_string()));
+    assert_eq!(by_worker.rows[0].units, 20);
+    assert_eq!(
+        chrono::Datelike::weekday(&by_worker.rows[0].period_start),
+        chrono::Weekday::Mon
+    );
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn venta_del_dueno_descuenta_la_devolucion_una_sola_vez() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let sales = PgOwnerSaleRepository::new(pool.clone(), test_audit_repo(&pool));
+    let line = || OwnerLoadedItemDto {
+        inventory_id: seed.inventory_id,
+        product_id: seed.product_id,
+        flavor_id: seed.flavor_id,
+        freezer_id: seed.freezer_id,
+        quantity: 10,
+        unit_price: None,
+        price_override_reason: None,
+        is_deformed: false,
+    };
+    let dto = CreateOwnerSaleDto {
+        departure_time: Utc::now(),
+        route_id: None,
+        loaded_items: vec![line(), line()],
+    };
+    let price = ResolvedPrice {
+        unit_price: Decimal::new(1200, 2),
+        price_history_id: None,
+        override_reason: None,
+    };
+    let sale = sales
+        .create_sale(&dto, &[price.clone(), price], seed.owner_id)
+        .await
+        .unwrap();
+    let returned = CompleteOwnerSaleDto {
+        returned_items: vec![OwnerReturnedItemDto {
+            product_id: seed.product_id,
+            flavor_id: seed.flavor_id,
+            quantity: 5,
+            is_deformed: false,
+            destination_freezer_id: seed.freezer_id,
+        }],
+    };
+    sales
+        .complete_sale(sale.id, &returned, seed.owner_id)
+        .await
+        .unwrap();
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+
+    // Act
+    let (status, report) = get::<SalesReport>(&pool, &token, "/sales?channel=owner_sale").await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    let totals = report.unwrap().totals;
+    assert_eq!(totals.units, 15);
+    assert_eq!(totals.revenue, Decimal::new(18000, 2));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn rentabilidad_por_ruta_desglosa_por_trabajador() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let other_worker = uuid::Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO workers (id, name, phone, active, created_by) VALUES ($1, 'Ana Ruiz', '555-9876', TRUE, $2)",
+    )
+    .bind(other_worker)
+    .bind(seed.owner_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+    let price = ResolvedPrice {
+        unit_price: Decimal::new(1000, 2),
+        price_history_id: None,
+        override_reason: None,
+    };
+
+    // Juan: 30 cargados, 10 devueltos; Ana: 10 cargados, ninguno devuelto
+    for (worker_id, quantity, returned) in [(seed.worker_id, 30, 10), (other_worker, 10, 0)] {
+        let dto = CreateTripDto {
+            worker_id,
+            departure_time: Utc::now(),
+            route_id: Some(seed.route_id),
+            loaded_items: vec![LoadedItemDto {
+                inventory_id: seed.inventory_id,
+                product_id: seed.product_id,
+                flavor_id: seed.flavor_id,
+                freezer_id: seed.freezer_id,
+                quantity,
+                unit_price: None,
+                price_override_reason: None,
+                is_deformed: false,
+            }],
+        };
+        let trip = trips
+            .create_trip(&dto, std::slice::from_ref(&price), seed.admin_id)
+            .await
+            .unwrap();
+        let returned_items = if returned > 0 {
+            vec![ReturnedItemDto {
+                loaded_item_id: None,
+                product_id: seed.product_id,
+                flavor_id: seed.flavor_id,
+                quantity: returned,
+                is_deformed: false,
+                destination_freezer_id: seed.freezer_id,
+            }]
+        } else {
+            vec![]
+        };
+        trips
+            .complete_trip(trip.id, &CompleteTripDto { returned_items }, seed.admin_id)
+            .await
+            .unwrap();
+    }
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+
+    // Act
+    let (status, report) = get::<RouteProfitabilityReport>(&pool, &token, "/routes").await;
+    let (_, only_ana) = get::<RouteProfitabilityReport>(
+        &pool,
+        &token,
+        &format!("/routes?worker_id={other_worker}"),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    let report = report.unwrap();
+    assert_eq!(report.routes.len(), 1);
+    let route = &report.routes[0];
+    assert_eq!(route.route_id, seed.route_id);
+    assert_eq!(route.figures.trips, 2);
+    assert_eq!(route.figures.sold_units, 30);
+    assert_eq!(route.figures.returned_ratio, Some(Decimal::new(2500, 4)));
+    assert_eq!(route.figures.sales, Decimal::new(30000, 2));
+    assert_eq!(route.figures.average_sale_per_trip, Decimal::new(15000, 2));
+    // Margen (10 − 5) y comisión (12 − 10) por unidad vendida
+    assert_eq!(route.figures.business_margin, Decimal::new(15000, 2));
+    assert_eq!(route.figures.commission, Decimal::new(6000, 2));
+    assert_eq!(route.figures.unpriced_units, 0);
+    assert_eq!(route.workers.len(), 2);
+    assert_eq!(route.workers[0].worker_id, seed.worker_id);
+    assert_eq!(route.workers[0].figures.business_margin, Decimal::new(10000, 2));
+    assert_eq!(report.totals, route.figures);
+
+    let only_ana = only_ana.unwrap();
+    assert_eq!(only_ana.routes[0].workers.len(), 1);
+    assert_eq!(only_ana.totals.returned_ratio, Some(Decimal::ZERO));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn rentabilidad_por_ruta_informa_lo_vendido_sin_precio() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    sqlx::query("DELETE FROM price_history")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let dto = CreateTripDto {
+        worker_id: seed.worker_id,
+        departure_time: Utc::now(),
+        route_id: Some(seed.route_id),
+        loaded_items: vec![LoadedItemDto {
+            inventory_id: seed.inventory_id,
+            product_id: seed.product_id,
+            flavor_id: seed.flavor_id,
+            freezer_id: seed.freezer_id,
+            quantity: 20,
+            unit_price: None,
+            price_override_reason: None,
+            is_deformed: false,
+        }],
+    };
+    let price = ResolvedPrice {
+        unit_price: Decimal::new(1000, 2),
+        price_history_id: None,
+        override_reason: None,
+    };
+    let trip = trips.create_trip(&dto, &[price], seed.admin_id).await.unwrap();
+    trips
+        .complete_trip(trip.id, &CompleteTripDto { returned_items: vec![] }, seed.admin_id)
+        .await
+        .unwrap();
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+
+    // Act
+    let (status, report) = get::<RouteProfitabilityReport>(&pool, &token, "/routes").await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    let totals = report.unwrap().totals;
+    assert_eq!(totals.sold_units, 20);
+    assert_eq!(totals.unpriced_units, 20);
+    assert_eq!(totals.business_margin, Decimal::ZERO);
+    assert_eq!(totals.sales, Decimal::new(20000, 2));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn estado_de_resultados_resta_costo_gastos_y_retiros() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let local_sales = PgLocalSaleRepository::new(pool.clone(), test_audit_repo(&pool));
+    let cash = PgCashRegisterRepository::new(pool.clone(), test_audit_repo(&pool));
+    let price_history_id: uuid::Uuid = sqlx::query_scalar("SELECT id FROM price_history LIMIT 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let price = |cents| ResolvedPrice {
+        unit_price: Decimal::new(cents, 2),
+        price_history_id: Some(price_history_id),
+        override_reason: None,
+    };
+
+    // Viaje: 20 vendidos a $10.00; venta local: 5 a $15.00. Costo $5.00
+    let trip_dto = CreateTripDto {
+        worker_id: seed.worker_id,
+        departure_time: Utc::now(),
+        route_id: None,
+        loaded_items: vec![LoadedItemDto {
+            inventory_id: seed.inventory_id,
+            product_id: seed.product_id,
+            flavor_id: seed.flavor_id,
+            freezer_id: seed.freezer_id,
+            quantity: 30,
+            unit_price: None,
+            price_override_reason: None,
+            is_deformed: false,
+        }],
+    };
+    let trip = trips
+        .create_trip(&trip_dto, &[price(1000)], seed.admin_id)
+        .await
+        .unwrap();
+    let complete = CompleteTripDto {
+        returned_items: vec![ReturnedItemDto {
+            loaded_item_id: None,
+            product_id: seed.product_id,
+            flavor_id: seed.flavor_id,
+            quantity: 10,
+            is_deformed: false,
+            destination_freezer_id: seed.freezer_id,
+        }],
+    };
+    trips.complete_trip(trip.id, &complete, seed.admin_id).await.unwrap();
+    let sale_dto = CreateLocalSaleDto {
+        sale_type: "local".into(),
+        notes: None,
+        items: vec![LocalSaleItemDto {
+            inventory_id: seed.inventory_id,
+            product_id: seed.product_id,
+            flavor_id: seed.flavor_id,
+            freezer_id: seed.freezer_id,
+            quantity: 5,
+            unit_price: None,
+            price_override_reason: None,
+        }],
+    };
+    local_sales
+        .create_sale(&sale_dto, &[price(1500)], seed.admin_id)
+        .await
+        .unwrap();
+
+    // Gasto de luz, un gasto anulado y un retiro
+    let expense = |amount, category: &str| CreateExpenseDto {
+        amount: Decimal::from(amount),
+        description: None,
+        category: category.into(),
+    };
+    manage_cash::add_expense(&cash, &expense(30, "luz"), seed.owner_id)
+        .await
+        .unwrap();
+    let voided = manage_cash::add_expense(&cash, &expense(10, "agua"), seed.owner_id)
+        .await
+        .unwrap();
+    cash.void_transaction(voided.id, "Registrado dos veces", seed.owner_id)
+        .await
+        .unwrap();
+    let withdrawal = CreateWithdrawalDto {
+        amount: Decimal::from(20),
+        description: None,
+    };
+    manage_cash::add_withdrawal(&cash, &withdrawal, seed.owner_id)
+        .await
+        .unwrap();
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+
+    // Act
+    let (status, report) = get::<ProfitLossReport>(&pool, &token, "/profit-loss").await;
+    let request = Request::builder()
+        .uri("/profit-loss/export")
+        .header("Authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap();
+    let response = build_reports_router(pool.clone())
+        .oneshot(request)
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    let report = report.unwrap();
+    assert_eq!(report.months.len(), 6);
+    let month = report.months.last().unwrap();
+    assert_eq!(month.revenue.total, Decimal::new(27500, 2));
+    assert_eq!(month.cost_of_goods_sold, Decimal::new(12500, 2));
+    assert_eq!(month.gross_margin, Decimal::new(15000, 2));
+    assert_eq!(month.expenses.len(), 1);
+    assert_eq!(month.expenses[0].category, "luz");
+    assert_eq!(month.operating_expenses, Decimal::from(30));
+    assert_eq!(month.owner_withdrawals, Decimal::from(20));
+    assert_eq!(month.net_profit, Decimal::new(10000, 2));
+    assert_eq!(month.net_profit_change_pct, None);
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let csv = String::from_utf8(body.to_vec()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 7);
+    assert!(lines[0].contains("gasto_luz"));
+    assert!(!lines[0].contains("gasto_agua"));
+    assert!(lines[6].ends_with(",30.00,30.00,20.00,100.00,"));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn valorizacion_separa_deformados_y_marca_lotes_sin_precio() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let other_provider = uuid::Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO providers (id, name, contact_info, active, created_by) VALUES ($1, 'Proveedor Nuevo', NULL, TRUE, $2)",
+    )
+    .bind(other_provider)
+    .bind(seed.owner_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+    // 4 deformados del proveedor con precio y 7 de un proveedor sin precio
+    for (provider_id, quantity, is_deformed) in [
+        (seed.provider_id, 4, true),
+        (other_provider, 7, false),
+    ] {
+        sqlx::query(
+            r#"INSERT INTO inventory
+               (freezer_id, product_id, flavor_id, provider_id, quantity, is_deformed, updated_by)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+        )
+        .bind(seed.freezer_id)
+        .bind(seed.product_id)
+        .bind(seed.flavor_id)
+        .bind(provider_id)
+        .bind(quantity)
+        .bind(is_deformed)
+        .bind(seed.owner_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    let owner = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+    let admin = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+
+    // Act
+    let (forbidden, _) = get::<InventoryValuation>(&pool, &admin, "/inventory-valuation").await;
+    let (status, valuation) = get::<InventoryValuation>(&pool, &owner, "/inventory-valuation").await;
+
+    // Assert
+    assert_eq!(forbidden, StatusCode::FORBIDDEN);
+    assert_eq!(status, StatusCode::OK);
+    let valuation = valuation.unwrap();
+    // Costo 5, base 10, ruta 12 por unidad con precio
+    assert_eq!(valuation.totals.units, 111);
+    assert_eq!(valuation.totals.unpriced_units, 7);
+    assert_eq!(valuation.totals.at_cost, Decimal::from(520));
+    assert_eq!(valuation.totals.at_base, Decimal::from(1040));
+    assert_eq!(valuation.totals.at_route, Decimal::from(1248));
+    assert_eq!(valuation.deformed.units, 4);
+    assert_eq!(valuation.deformed.at_cost, Decimal::from(20));
+    assert_eq!(valuation.normal.at_cost, Decimal::from(500));
+    assert_eq!(valuation.by_freezer.len(), 1);
+    assert_eq!(valuation.by_freezer[0].figures, valuation.totals);
+    assert_eq!(valuation.by_product.len(), 1);
+    assert_eq!(valuation.unpriced.len(), 1);
+    assert_eq!(valuation.unpriced[0].provider_id, other_provider);
+    assert_eq!(valuation.unpriced[0].quantity, 7);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
@@ -6,7 +6,7 @@ use http_body_util::BodyExt;
 use std::sync::Arc;
 use tower::ServiceExt;
 
-use common::db::{setup_test_db, teardown_test_db, test_app_state, test_jwt};
+use common::db::{setup_test_db, teardown_test_db, test_app_state, test_audit_repo, test_jwt};
 use common::seed::seed_test_data;
 use helados_sofis_core::modules::users::infrastructure::persistence::postgres_repo::PgUserRepository;
 use helados_sofis_core::shared::auth::Role;
@@ -21,7 +21,7 @@ fn build_users_router(
     pool: sqlx::PgPool,
 ) -> axum::Router {
     let app_state = test_app_state(pool.clone());
-    let repo = Arc::new(PgUserRepository::new(pool))
+    let repo = Arc::new(PgUserRepository::new(pool.clone(), test_audit_repo(&pool)))
         as Arc<dyn helados_sofis_core::modules::users::domain::repositories::UserRepository>;
 
     helados_sofis_core::modules::users::infrastructure::controllers::http_router::router(
@@ -0,0 +1,259 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use chrono::Utc;
+use http_body_util::BodyExt;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use common::db::{setup_test_db, teardown_test_db, test_app_state, test_audit_repo, test_jwt};
+use common::seed::{seed_test_data, SeedData};
+use helados_sofis_core::modules::pricing::domain::entities::ResolvedPrice;
+use helados_sofis_core::modules::worker_payments::domain::entities::*;
+use helados_sofis_core::modules::worker_payments::domain::repositories::WorkerPaymentRepository;
+use helados_sofis_core::modules::worker_payments::infrastructure::controllers::http_router;
+use helados_sofis_core::modules::worker_payments::infrastructure::persistence::postgres_repo::PgWorkerPaymentRepository;
+use helados_sofis_core::modules::worker_trips::domain::entities::*;
+use helados_sofis_core::modules::worker_trips::domain::repositories::WorkerTripRepository;
+use helados_sofis_core::modules::worker_trips::infrastructure::persistence::postgres_repo::PgWorkerTripRepository;
+use helados_sofis_core::shared::auth::Role;
+use helados_sofis_core::shared::errors::AppError;
+
+// ═══════════════════════════════════════════════════════════
+// Tests de Integración — Estado de cuenta de trabajadores
+// BD real exclusiva por test · Semilla · Patrón AAA
+// ═══════════════════════════════════════════════════════════
+
+fn build_payments_router(pool: sqlx::PgPool) -> axum::Router {
+    http_router::router(
+        test_app_state(pool.clone()),
+        Arc::new(PgWorkerPaymentRepository::new(pool.clone(), test_audit_repo(&pool))),
+    )
+}
+
+async fn get(app: axum::Router, token: &str, uri: &str) -> (StatusCode, String, String) {
+    let request = Request::builder()
+        .method("GET")
+        .uri(uri)
+        .header("Authorization", format!("Bearer {token}"))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .map(|v| v.to_str().unwrap().to_string())
+        .unwrap_or_default();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, content_type, String::from_utf8(body.to_vec()).unwrap())
+}
+
+/// Viaje de 30 unidades a precio base ($10.00, ruta $12.00) completado
+/// con `returned` unidades de vuelta.
+async fn returned_trip(
+    pool: &sqlx::PgPool,
+    repo: &PgWorkerTripRepository,
+    seed: &SeedData,
+    returned: i32,
+) -> WorkerTrip {
+    let price_history_id = sqlx::query_scalar("SELECT id FROM price_history LIMIT 1")
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    let dto = CreateTripDto {
+        worker_id: seed.worker_id,
+        departure_time: Utc::now(),
+        route_id: Some(seed.route_id),
+        loaded_items: vec![LoadedItemDto {
+            inventory_id: seed.inventory_id,
+            product_id: seed.product_id,
+            flavor_id: seed.flavor_id,
+            freezer_id: seed.freezer_id,
+            quantity: 30,
+            unit_price: None,
+            price_override_reason: None,
+            is_deformed: false,
+        }],
+    };
+    let prices = vec![ResolvedPrice {
+        unit_price: Decimal::new(1000, 2),
+        price_history_id: Some(price_history_id),
+        override_reason: None,
+    }];
+    let trip = repo.create_trip(&dto, &prices, seed.admin_id).await.unwrap();
+    let complete = CompleteTripDto {
+        returned_items: vec![returned_item(seed, returned)],
+    };
+    repo.complete_trip(trip.id, &complete, seed.admin_id)
+        .await
+        .unwrap()
+}
+
+fn returned_item(seed: &SeedData, quantity: i32) -> ReturnedItemDto {
+    ReturnedItemDto {
+        loaded_item_id: None,
+        product_id: seed.product_id,
+        flavor_id: seed.flavor_id,
+        quantity,
+        is_deformed: false,
+        destination_freezer_id: seed.freezer_id,
+    }
+}
+
+#[tokio::test]
+async fn estado_de_cuenta_intercala_viaje_abono_y_correccion() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let payments = PgWorkerPaymentRepository::new(pool.clone(), test_audit_repo(&pool));
+    let trip = returned_trip(&pool, &trips, &seed, 10).await;
+    let abono = CreateWorkerPaymentDto {
+        amount: Decimal::new(5000, 2),
+        trip_ids: vec![trip.id],
+        allow_credit: false,
+        notes: Some("Efectivo".into()),
+    };
+    payments
+        .create_worker_payment(seed.worker_id, &abono, seed.admin_id)
+        .await
+        .unwrap();
+    trips
+        .correct_trip(trip.id, &[returned_item(&seed, 15)], "Conteo", seed.owner_id)
+        .await
+        .unwrap();
+    let token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+
+    // Act
+    let (status, _, body) = get(
+        build_payments_router(pool.clone()),
+        &token,
+        &format!("/worker/{}/statement", seed.worker_id),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    let statement: WorkerStatement = serde_json::from_str(&body).unwrap();
+    let kinds: Vec<_> = statement.entries.iter().map(|e| e.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            StatementEntryKind::Trip,
+            StatementEntryKind::Payment,
+            StatementEntryKind::Correction
+        ]
+    );
+    // Cargo original de 20 vendidos, aunque el viaje hoy diga 15
+    assert_eq!(statement.entries[0].debit, Decimal::new(20000, 2));
+    assert_eq!(statement.entries[1].balance, Decimal::new(15000, 2));
+    assert_eq!(statement.entries[2].credit, Decimal::new(5000, 2));
+    assert_eq!(statement.opening_balance, Decimal::ZERO);
+    assert_eq!(statement.closing_balance, Decimal::new(10000, 2));
+    assert_eq!(statement.closing_balance, statement.current_debt);
+    assert_eq!(statement.sold_quantity, 15);
+    // 15 vendidos × ($12.00 ruta − $10.00 cobrado)
+    assert_eq!(statement.commission_earned, Decimal::new(3000, 2));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn pago_completo_descuenta_abonos_previos_del_viaje() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let payments = PgWorkerPaymentRepository::new(pool.clone(), test_audit_repo(&pool));
+    let trip = returned_trip(&pool, &trips, &seed, 10).await;
+    let abono = CreateWorkerPaymentDto {
+        amount: Decimal::new(5000, 2),
+        trip_ids: vec![trip.id],
+        allow_credit: false,
+        notes: None,
+    };
+    payments
+        .create_worker_payment(seed.worker_id, &abono, seed.admin_id)
+        .await
+        .unwrap();
+
+    // Act
+    let payment = payments.create_payment(trip.id, seed.admin_id).await.unwrap();
+    let again = payments.create_payment(trip.id, seed.admin_id).await;
+
+    // Assert
+    // 20 vendidos × $10.00 menos el abono de $50.00
+    assert_eq!(payment.amount, Decimal::new(15000, 2));
+    assert_eq!(payment.new_debt, Decimal::ZERO);
+    assert!(matches!(again, Err(AppError::Conflict(_))));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn estado_de_cuenta_imprimible_en_texto_y_html() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    returned_trip(&pool, &trips, &seed, 10).await;
+    let token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+    let uri = format!("/worker/{}/statement/print", seed.worker_id);
+
+    // Act
+    let (text_status, text_type, text) =
+        get(build_payments_router(pool.clone()), &token, &uri).await;
+    let (html_status, html_type, html) = get(
+        build_payments_router(pool.clone()),
+        &token,
+        &format!("{uri}?format=html"),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(text_status, StatusCode::OK);
+    assert!(text_type.starts_with("text/plain"));
+    assert!(text.contains("*Saldo final: $200.00*"));
+    assert!(text.contains("Comisión ganada: $40.00"));
+    assert_eq!(html_status, StatusCode::OK);
+    assert!(html_type.starts_with("text/html"));
+    assert!(html.contains("<table>"));
+    assert!(html.contains("$200.00"));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn estado_de_cuenta_con_rango_invertido_retorna_400() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+
+    // Act
+    let (status, _, _) = get(
+        build_payments_router(pool.clone()),
+        &token,
+        &format!(
+            "/worker/{}/statement?from=2026-02-01&to=2026-01-01",
+            seed.worker_id
+        ),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
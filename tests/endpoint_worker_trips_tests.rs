@@ -0,0 +1,664 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use chrono::Utc;
+use http_body_util::BodyExt;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use common::db::{setup_test_db, teardown_test_db, test_app_state, test_audit_repo, test_jwt};
+use common::seed::{seed_test_data, SeedData};
+use helados_sofis_core::modules::inventory::infrastructure::persistence::postgres_repo::PgInventoryRepository;
+use helados_sofis_core::modules::pricing::domain::entities::ResolvedPrice;
+use helados_sofis_core::modules::pricing::infrastructure::persistence::postgres_repo::PgPriceRepository;
+use helados_sofis_core::modules::worker_payments::domain::entities::CreateWorkerPaymentDto;
+use helados_sofis_core::modules::worker_payments::domain::repositories::WorkerPaymentRepository;
+use helados_sofis_core::modules::worker_payments::infrastructure::persistence::postgres_repo::PgWorkerPaymentRepository;
+use helados_sofis_core::modules::worker_trips::domain::entities::*;
+use helados_sofis_core::modules::worker_trips::domain::repositories::WorkerTripRepository;
+use helados_sofis_core::modules::worker_trips::infrastructure::controllers::http_router;
+use helados_sofis_core::modules::worker_trips::infrastructure::persistence::postgres_repo::PgWorkerTripRepository;
+use helados_sofis_core::shared::auth::Role;
+
+// ═══════════════════════════════════════════════════════════
+// Tests de Integración — Viajes de Trabajadores
+// Corrección de devoluciones y cancelación
+// BD real exclusiva por test · Semilla · Patrón AAA
+// ═══════════════════════════════════════════════════════════
+
+fn build_trips_router(pool: sqlx::PgPool) -> axum::Router {
+    let app_state = test_app_state(pool.clone());
+    http_router::router(
+        app_state,
+        Arc::new(PgWorkerTripRepository::new(
+            pool.clone(),
+            test_audit_repo(&pool),
+        )),
+        Arc::new(PgPriceRepository::new(pool.clone(), test_audit_repo(&pool))),
+        Arc::new(PgInventoryRepository::new(
+            pool.clone(),
+            test_audit_repo(&pool),
+        )),
+    )
+}
+
+async fn post_json(
+    app: axum::Router,
+    token: &str,
+    uri: &str,
+    body: serde_json::Value,
+) -> (StatusCode, serde_json::Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("Authorization", format!("Bearer {token}"))
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap_or_default())
+}
+
+/// Viaje de 30 unidades a $10.00 cargadas desde la fila semilla.
+async fn start_trip(repo: &PgWorkerTripRepository, seed: &SeedData) -> WorkerTrip {
+    let dto = CreateTripDto {
+        worker_id: seed.worker_id,
+        departure_time: Utc::now(),
+        route_id: None,
+        loaded_items: vec![LoadedItemDto {
+            inventory_id: seed.inventory_id,
+            product_id: seed.product_id,
+            flavor_id: seed.flavor_id,
+            freezer_id: seed.freezer_id,
+            quantity: 30,
+            unit_price: None,
+            price_override_reason: None,
+            is_deformed: false,
+        }],
+    };
+    let prices = vec![ResolvedPrice {
+        unit_price: Decimal::new(1000, 2),
+        price_history_id: None,
+        override_reason: None,
+    }];
+    repo.create_trip(&dto, &prices, seed.admin_id)
+        .await
+        .unwrap()
+}
+
+fn returned(seed: &SeedData, quantity: i32) -> ReturnedItemDto {
+    ReturnedItemDto {
+        loaded_item_id: None,
+        product_id: seed.product_id,
+        flavor_id: seed.flavor_id,
+        quantity,
+        is_deformed: false,
+        destination_freezer_id: seed.freezer_id,
+    }
+}
+
+async fn stock_and_debt(pool: &sqlx::PgPool, seed: &SeedData) -> (i32, Decimal) {
+    let stock = sqlx::query_scalar::<_, i32>("SELECT quantity FROM inventory WHERE id = $1")
+        .bind(seed.inventory_id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    let debt = sqlx::query_scalar::<_, Decimal>("SELECT current_debt FROM workers WHERE id = $1")
+        .bind(seed.worker_id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    (stock, debt)
+}
+
+#[tokio::test]
+async fn corregir_viaje_pagado_ajusta_stock_y_deuda_por_la_diferencia() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let payments = PgWorkerPaymentRepository::new(pool.clone(), test_audit_repo(&pool));
+    let trip = start_trip(&trips, &seed).await;
+    let dto = CompleteTripDto {
+        returned_items: vec![returned(&seed, 10)],
+    };
+    trips
+        .complete_trip(trip.id, &dto, seed.admin_id)
+        .await
+        .unwrap();
+    payments
+        .create_payment(trip.id, seed.admin_id)
+        .await
+        .unwrap();
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+
+    // Act
+    let (status, body) = post_json(
+        build_trips_router(pool.clone()),
+        &token,
+        &format!("/{}/correct", trip.id),
+        serde_json::json!({
+            "returned_items": [{
+                "product_id": seed.product_id,
+                "flavor_id": seed.flavor_id,
+                "quantity": 15,
+                "is_deformed": false,
+                "destination_freezer_id": seed.freezer_id
+            }],
+            "reason": "Se contaron mal las devoluciones"
+        }),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["trip"]["sold_quantity"], 15);
+    assert_eq!(body["previous_sold_quantity"], 20);
+    assert_eq!(body["debt_delta"], "-50.00");
+    assert_eq!(body["paid_for_trip"], "200.00");
+    assert_eq!(body["pending_for_trip"], "-50.00");
+    assert_eq!(body["trip"]["returned_items"][0]["quantity"], 15);
+    let (stock, debt) = stock_and_debt(&pool, &seed).await;
+    assert_eq!(stock, 85);
+    assert_eq!(debt, Decimal::new(-5000, 2));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn corregir_viaje_con_abono_ligado_lo_cuenta_como_pagado() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let payments = PgWorkerPaymentRepository::new(pool.clone(), test_audit_repo(&pool));
+    let trip = start_trip(&trips, &seed).await;
+    let dto = CompleteTripDto {
+        returned_items: vec![returned(&seed, 10)],
+    };
+    trips
+        .complete_trip(trip.id, &dto, seed.admin_id)
+        .await
+        .unwrap();
+    let abono = CreateWorkerPaymentDto {
+        amount: Decimal::new(5000, 2),
+        trip_ids: vec![trip.id],
+        allow_credit: false,
+        notes: None,
+    };
+    payments
+        .create_worker_payment(seed.worker_id, &abono, seed.admin_id)
+        .await
+        .unwrap();
+
+    // Act
+    let correction = trips
+        .correct_trip(trip.id, &[returned(&seed, 15)], "Conteo", seed.owner_id)
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(correction.paid_for_trip, Decimal::new(5000, 2));
+    // 15 vendidos × $10.00 menos el abono
+    assert_eq!(correction.pending_for_trip, Decimal::new(10000, 2));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn segunda_correccion_parte_del_conteo_corregido() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let trip = start_trip(&trips, &seed).await;
+    let dto = CompleteTripDto {
+        returned_items: vec![returned(&seed, 10)],
+    };
+    trips
+        .complete_trip(trip.id, &dto, seed.admin_id)
+        .await
+        .unwrap();
+    trips
+        .correct_trip(
+            trip.id,
+            &[returned(&seed, 5)],
+            "primer conteo",
+            seed.owner_id,
+        )
+        .await
+        .unwrap();
+
+    // Act
+    let correction = trips
+        .correct_trip(
+            trip.id,
+            &[returned(&seed, 12)],
+            "segundo conteo",
+            seed.owner_id,
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(correction.previous_sold_quantity, 25);
+    assert_eq!(correction.trip.trip.sold_quantity, 18);
+    assert_eq!(
+        correction.trip.trip.correction_reason.as_deref(),
+        Some("segundo conteo")
+    );
+    let (stock, debt) = stock_and_debt(&pool, &seed).await;
+    assert_eq!(stock, 82);
+    assert_eq!(debt, Decimal::new(18000, 2));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn corregir_viaje_requiere_dueno_y_viaje_retornado() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let trip = start_trip(&trips, &seed).await;
+    let app = build_trips_router(pool.clone());
+    let body = serde_json::json!({ "returned_items": [], "reason": "recuento" });
+    let uri = format!("/{}/correct", trip.id);
+
+    // Act
+    let (as_admin, _) = post_json(
+        app.clone(),
+        &test_jwt(seed.admin_id, "admin@test.com", Role::Admin),
+        &uri,
+        body.clone(),
+    )
+    .await;
+    let (in_progress, _) = post_json(
+        app.clone(),
+        &test_jwt(seed.owner_id, "owner@test.com", Role::Owner),
+        &uri,
+        body,
+    )
+    .await;
+
+    // Assert
+    assert_eq!(as_admin, StatusCode::FORBIDDEN);
+    assert_eq!(in_progress, StatusCode::CONFLICT);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn cancelar_viaje_en_progreso_devuelve_toda_la_carga() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let trip = start_trip(&trips, &seed).await;
+    let app = build_trips_router(pool.clone());
+    let token = test_jwt(seed.owner_id, "owner@test.com", Role::Owner);
+    let uri = format!("/{}/cancel", trip.id);
+
+    // Act
+    let (status, body) = post_json(
+        app.clone(),
+        &token,
+        &uri,
+        serde_json::json!({ "reason": "El trabajador no salió" }),
+    )
+    .await;
+    let (again, _) = post_json(
+        app,
+        &token,
+        &uri,
+        serde_json::json!({ "reason": "otra vez" }),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "cancelled");
+    assert_eq!(body["cancel_reason"], "El trabajador no salió");
+    assert_eq!(again, StatusCode::CONFLICT);
+    let (stock, debt) = stock_and_debt(&pool, &seed).await;
+    assert_eq!(stock, 100);
+    assert_eq!(debt, Decimal::ZERO);
+    let active = trips.find_active().await.unwrap();
+    assert!(active.iter().all(|t| t.id != trip.id));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn cancelar_viaje_que_cargo_todos_los_deformados_recrea_la_fila() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let first = start_trip(&trips, &seed).await;
+    let dto = CompleteTripDto {
+        returned_items: vec![ReturnedItemDto {
+            is_deformed: true,
+            ..returned(&seed, 3)
+        }],
+    };
+    trips
+        .complete_trip(first.id, &dto, seed.admin_id)
+        .await
+        .unwrap();
+    let deformed_id: uuid::Uuid =
+        sqlx::query_scalar("SELECT id FROM inventory WHERE is_deformed = TRUE")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    let load = CreateTripDto {
+        worker_id: seed.worker_id,
+        departure_time: Utc::now(),
+        route_id: None,
+        loaded_items: vec![LoadedItemDto {
+            inventory_id: deformed_id,
+            product_id: seed.product_id,
+            flavor_id: seed.flavor_id,
+            freezer_id: seed.freezer_id,
+            quantity: 3,
+            unit_price: None,
+            price_override_reason: None,
+            is_deformed: true,
+        }],
+    };
+    let prices = vec![ResolvedPrice {
+        unit_price: Decimal::new(500, 2),
+        price_history_id: None,
+        override_reason: None,
+    }];
+    let second = trips
+        .create_trip(&load, &prices, seed.admin_id)
+        .await
+        .unwrap();
+
+    // Act
+    let result = trips
+        .cancel_trip(second.id, "El trabajador no salió", seed.owner_id)
+        .await;
+
+    // Assert
+    assert!(result.is_ok());
+    let (quantity, worker): (i32, Option<uuid::Uuid>) = sqlx::query_as(
+        "SELECT quantity, assigned_worker_id FROM inventory WHERE is_deformed = TRUE",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(quantity, 3);
+    assert_eq!(worker, Some(seed.worker_id));
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn cargar_deformados_como_buenos_retorna_400() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let first = start_trip(&trips, &seed).await;
+    let dto = CompleteTripDto {
+        returned_items: vec![ReturnedItemDto {
+            is_deformed: true,
+            ..returned(&seed, 3)
+        }],
+    };
+    trips
+        .complete_trip(first.id, &dto, seed.admin_id)
+        .await
+        .unwrap();
+    let deformed_id: uuid::Uuid =
+        sqlx::query_scalar("SELECT id FROM inventory WHERE is_deformed = TRUE")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    let token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+
+    // Act
+    let (status, _) = post_json(
+        build_trips_router(pool.clone()),
+        &token,
+        "/",
+        serde_json::json!({
+            "worker_id": seed.worker_id,
+            "departure_time": Utc::now(),
+            "loaded_items": [{
+                "inventory_id": deformed_id,
+                "product_id": seed.product_id,
+                "flavor_id": seed.flavor_id,
+                "freezer_id": seed.freezer_id,
+                "quantity": 3,
+                "is_deformed": false
+            }]
+        }),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let deformed: i32 = sqlx::query_scalar("SELECT quantity FROM inventory WHERE id = $1")
+        .bind(deformed_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(deformed, 3);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn completar_con_mas_devoluciones_que_carga_retorna_400() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let trip = start_trip(&trips, &seed).await;
+    let token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+
+    // Act
+    let (status, _) = post_json(
+        build_trips_router(pool.clone()),
+        &token,
+        &format!("/{}/complete", trip.id),
+        serde_json::json!({
+            "returned_items": [{
+                "product_id": seed.product_id,
+                "flavor_id": seed.flavor_id,
+                "quantity": 31,
+                "is_deformed": false,
+                "destination_freezer_id": seed.freezer_id
+            }]
+        }),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (stock, debt) = stock_and_debt(&pool, &seed).await;
+    assert_eq!(stock, 70);
+    assert_eq!(debt, Decimal::ZERO);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn devolucion_queda_enlazada_a_su_linea_cargada() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let trip = start_trip(&trips, &seed).await;
+    let dto = CompleteTripDto {
+        returned_items: vec![returned(&seed, 4)],
+    };
+
+    // Act
+    trips
+        .complete_trip(trip.id, &dto, seed.admin_id)
+        .await
+        .unwrap();
+    let detail = trips.find_by_id_with_items(trip.id).await.unwrap().unwrap();
+
+    // Assert
+    assert_eq!(detail.returned_items.len(), 1);
+    assert_eq!(
+        detail.returned_items[0].loaded_item_id,
+        Some(detail.loaded_items[0].id)
+    );
+    assert_eq!(detail.trip.sold_quantity, 26);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn devolucion_heredada_ambigua_se_reparte_entre_las_lineas() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let trip = start_trip(&trips, &seed).await;
+    // Segunda línea del mismo sabor y devolución sin línea, como antes de 016
+    sqlx::query(
+        r#"
+        INSERT INTO worker_trip_loaded_items
+            (trip_id, inventory_id, product_id, flavor_id, freezer_id, quantity, unit_price)
+        VALUES ($1, $2, $3, $4, $5, 10, 10.00)
+        "#,
+    )
+    .bind(trip.id)
+    .bind(seed.inventory_id)
+    .bind(seed.product_id)
+    .bind(seed.flavor_id)
+    .bind(seed.freezer_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        r#"
+        INSERT INTO worker_trip_returned_items
+            (trip_id, product_id, flavor_id, quantity, is_deformed, destination_freezer_id)
+        VALUES ($1, $2, $3, 35, FALSE, $4)
+        "#,
+    )
+    .bind(trip.id)
+    .bind(seed.product_id)
+    .bind(seed.flavor_id)
+    .bind(seed.freezer_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    // Act
+    sqlx::raw_sql(include_str!(
+        "../migrations/019_backfill_returned_lines.sql"
+    ))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    // Assert
+    let rows = sqlx::query_as::<_, (Option<uuid::Uuid>, i32, i32)>(
+        r#"
+        SELECT ri.loaded_item_id, ri.quantity, li.quantity
+        FROM worker_trip_returned_items ri
+        LEFT JOIN worker_trip_loaded_items li ON li.id = ri.loaded_item_id
+        WHERE ri.trip_id = $1
+        ORDER BY li.id
+        "#,
+    )
+    .bind(trip.id)
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(rows.len(), 2);
+    assert!(rows.iter().all(|(line, _, _)| line.is_some()));
+    assert!(rows.iter().all(|(_, returned, loaded)| returned <= loaded));
+    assert_eq!(rows.iter().map(|(_, q, _)| q).sum::<i32>(), 35);
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
+
+#[tokio::test]
+async fn deformados_devueltos_en_dos_viajes_se_acumulan_y_se_avisan_al_salir() {
+    // Arrange
+    let (pool, db_name) = setup_test_db().await;
+    let seed = seed_test_data(&pool).await;
+    let trips = PgWorkerTripRepository::new(pool.clone(), test_audit_repo(&pool));
+    let deformed = |quantity| CompleteTripDto {
+        returned_items: vec![ReturnedItemDto {
+            is_deformed: true,
+            ..returned(&seed, quantity)
+        }],
+    };
+    let first = start_trip(&trips, &seed).await;
+    trips
+        .complete_trip(first.id, &deformed(2), seed.admin_id)
+        .await
+        .unwrap();
+    let second = start_trip(&trips, &seed).await;
+    trips
+        .complete_trip(second.id, &deformed(3), seed.admin_id)
+        .await
+        .unwrap();
+    let token = test_jwt(seed.admin_id, "admin@test.com", Role::Admin);
+
+    // Act
+    let (status, body) = post_json(
+        build_trips_router(pool.clone()),
+        &token,
+        "/",
+        serde_json::json!({
+            "worker_id": seed.worker_id,
+            "departure_time": Utc::now(),
+            "loaded_items": [{
+                "inventory_id": seed.inventory_id,
+                "product_id": seed.product_id,
+                "flavor_id": seed.flavor_id,
+                "freezer_id": seed.freezer_id,
+                "quantity": 5,
+                "is_deformed": false
+            }]
+        }),
+    )
+    .await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    let pending = body["pending_deformed"].as_array().unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0]["quantity"], 5);
+    assert_eq!(pending[0]["assigned_worker_id"], seed.worker_id.to_string());
+
+    // Cleanup
+    pool.close().await;
+    teardown_test_db(&db_name).await;
+}
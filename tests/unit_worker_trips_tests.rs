@@ -0,0 +1,149 @@
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use helados_sofis_core::modules::worker_trips::domain::entities::*;
+
+// ═══════════════════════════════════════════════════════════
+// Tests Unitarios — Devoluciones por línea de viaje
+// Patrón AAA: Arrange → Act → Assert
+// ═══════════════════════════════════════════════════════════
+
+fn line(
+    product_id: Uuid,
+    flavor_id: Uuid,
+    freezer_id: Uuid,
+    quantity: i32,
+    price: i64,
+    is_deformed: bool,
+) -> LoadedItem {
+    LoadedItem {
+        id: Uuid::new_v4(),
+        trip_id: Uuid::nil(),
+        inventory_id: Uuid::new_v4(),
+        product_id,
+        flavor_id,
+        freezer_id,
+        quantity,
+        unit_price: Decimal::new(price, 2),
+        is_deformed,
+        price_history_id: None,
+        price_override_reason: None,
+    }
+}
+
+fn ret(
+    product_id: Uuid,
+    flavor_id: Uuid,
+    freezer_id: Uuid,
+    quantity: i32,
+    is_deformed: bool,
+) -> ReturnedItemDto {
+    ReturnedItemDto {
+        loaded_item_id: None,
+        product_id,
+        flavor_id,
+        quantity,
+        is_deformed,
+        destination_freezer_id: freezer_id,
+    }
+}
+
+#[cfg(test)]
+mod allocate_returns_tests {
+    use super::*;
+
+    #[test]
+    fn mismo_sabor_en_dos_congeladores_no_descuenta_dos_veces() {
+        // Arrange
+        let (product, flavor) = (Uuid::new_v4(), Uuid::new_v4());
+        let (freezer_a, freezer_b) = (Uuid::new_v4(), Uuid::new_v4());
+        let loaded = vec![
+            line(product, flavor, freezer_a, 10, 1000, false),
+            line(product, flavor, freezer_b, 10, 1000, false),
+        ];
+        let returned = vec![ret(product, flavor, freezer_b, 4, false)];
+
+        // Act
+        let allocations = allocate_returns(&loaded, &returned).unwrap();
+        let (sold, amount) = calculate_sales(&loaded, &allocations);
+
+        // Assert
+        assert_eq!(allocations.len(), 1);
+        assert_eq!(allocations[0].loaded_item_id, loaded[1].id);
+        assert_eq!(sold, 16);
+        assert_eq!(amount, Decimal::new(16000, 2));
+    }
+
+    #[test]
+    fn devolucion_deformada_prefiere_la_linea_deformada() {
+        // Arrange
+        let (product, flavor, freezer) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let loaded = vec![
+            line(product, flavor, freezer, 10, 1000, false),
+            line(product, flavor, freezer, 5, 500, true),
+        ];
+        let returned = vec![ret(product, flavor, freezer, 7, true)];
+
+        // Act
+        let allocations = allocate_returns(&loaded, &returned).unwrap();
+        let (sold, amount) = calculate_sales(&loaded, &allocations);
+
+        // Assert
+        assert_eq!(allocations[0].loaded_item_id, loaded[1].id);
+        assert_eq!(allocations[0].quantity, 5);
+        assert_eq!(allocations[1].loaded_item_id, loaded[0].id);
+        assert_eq!(allocations[1].quantity, 2);
+        assert_eq!(sold, 8);
+        assert_eq!(amount, Decimal::new(8000, 2));
+    }
+
+    #[test]
+    fn devolver_mas_de_lo_cargado_falla() {
+        // Arrange
+        let (product, flavor, freezer) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let loaded = vec![line(product, flavor, freezer, 10, 1000, false)];
+        let returned = vec![
+            ret(product, flavor, freezer, 6, false),
+            ret(product, flavor, freezer, 5, false),
+        ];
+
+        // Act
+        let result = allocate_returns(&loaded, &returned);
+
+        // Assert
+        assert!(result.unwrap_err().contains("1 unidades más"));
+    }
+
+    #[test]
+    fn linea_deformada_no_vuelve_como_buena() {
+        // Arrange
+        let (product, flavor, freezer) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let loaded = vec![line(product, flavor, freezer, 5, 500, true)];
+        let mut returned = ret(product, flavor, freezer, 1, false);
+        returned.loaded_item_id = Some(loaded[0].id);
+
+        // Act
+        let explicit = allocate_returns(&loaded, std::slice::from_ref(&returned));
+        returned.loaded_item_id = None;
+        let implicit = allocate_returns(&loaded, &[returned]);
+
+        // Assert
+        assert!(explicit.is_err());
+        assert!(implicit.is_err());
+    }
+
+    #[test]
+    fn linea_de_otro_viaje_es_rechazada() {
+        // Arrange
+        let (product, flavor, freezer) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let loaded = vec![line(product, flavor, freezer, 5, 500, false)];
+        let mut returned = ret(product, flavor, freezer, 1, false);
+        returned.loaded_item_id = Some(Uuid::new_v4());
+
+        // Act
+        let result = allocate_returns(&loaded, &[returned]);
+
+        // Assert
+        assert!(result.unwrap_err().contains("no pertenece al viaje"));
+    }
+}
@@ -0,0 +1,172 @@
+mod common;
+
+use serde_json::json;
+use std::sync::Arc;
+
+use common::mocks::*;
+use helados_sofis_core::modules::audit_log::application::search_audit;
+use helados_sofis_core::modules::audit_log::domain::entities::*;
+use helados_sofis_core::modules::audit_log::domain::repositories::AuditLogRepository;
+use helados_sofis_core::shared::errors::AppError;
+
+// ═══════════════════════════════════════════════════════════
+// Tests de Casos de Uso — Auditoría (búsqueda, diff y CSV)
+// Patrón AAA: Arrange → Act → Assert
+// ═══════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod diff_tests {
+    use super::*;
+
+    #[test]
+    fn diff_solo_incluye_campos_modificados() {
+        // Arrange
+        let before = json!({ "name": "Paleta", "price": "10.00", "is_active": true });
+        let after = json!({ "name": "Paleta Grande", "price": "10.00", "is_active": true });
+
+        // Act
+        let changes = field_diff(Some(&before), Some(&after));
+
+        // Assert
+        assert_eq!(
+            changes,
+            vec![FieldChange {
+                field: "name".into(),
+                before: Some(json!("Paleta")),
+                after: Some(json!("Paleta Grande")),
+            }]
+        );
+    }
+
+    #[test]
+    fn diff_aplana_objetos_anidados_y_detecta_altas() {
+        // Arrange
+        let before = json!({ "purchase": { "total": "50.00" } });
+        let after = json!({ "purchase": { "total": "60.00", "paid_from": "external" } });
+
+        // Act
+        let changes = field_diff(Some(&before), Some(&after));
+
+        // Assert
+        let fields: Vec<&str> = changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["purchase.paid_from", "purchase.total"]);
+        assert_eq!(changes[0].before, None);
+    }
+
+    #[test]
+    fn diff_de_creacion_lista_todos_los_campos() {
+        // Arrange
+        let after = json!({ "name": "Fresa", "is_active": true });
+
+        // Act
+        let changes = field_diff(None, Some(&after));
+
+        // Assert
+        assert_eq!(changes.len(), 2);
+        assert!(changes.iter().all(|c| c.before.is_none()));
+    }
+}
+
+#[cfg(test)]
+mod busqueda_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn busqueda_limita_tamano_de_pagina_y_calcula_offset() {
+        // Arrange
+        let mock = Arc::new(MockAuditLogRepo::new(vec![fake_audit_entry(
+            "update",
+            Some(json!({ "name": "A" })),
+            Some(json!({ "name": "B" })),
+        )]));
+        let repo: Arc<dyn AuditLogRepository> = mock.clone();
+        let filter = AuditLogFilter {
+            page: Some(3),
+            per_page: Some(5000),
+            ..Default::default()
+        };
+
+        // Act
+        let page = search_audit::search(&repo, filter).await.unwrap();
+
+        // Assert
+        assert_eq!(page.per_page, 200);
+        assert_eq!(page.page, 3);
+        assert_eq!(*mock.search_calls.lock().unwrap(), vec![(200, 400)]);
+        assert_eq!(page.items[0].changes[0].field, "name");
+    }
+
+    #[tokio::test]
+    async fn accion_invalida_es_rechazada() {
+        // Arrange
+        let mock = Arc::new(MockAuditLogRepo::new(vec![]));
+        let repo: Arc<dyn AuditLogRepository> = mock.clone();
+        let filter = AuditLogFilter {
+            action: Some("borrar".into()),
+            ..Default::default()
+        };
+
+        // Act
+        let result = search_audit::search(&repo, filter).await;
+
+        // Assert
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+        assert!(mock.search_calls.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn registro_inexistente_retorna_not_found() {
+        // Arrange
+        let repo: Arc<dyn AuditLogRepository> = Arc::new(MockAuditLogRepo::new(vec![]));
+
+        // Act
+        let result = search_audit::find_entry(&repo, uuid::Uuid::new_v4()).await;
+
+        // Assert
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+}
+
+#[cfg(test)]
+mod exportacion_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn csv_tiene_una_fila_por_campo_y_escapa_comas() {
+        // Arrange
+        let entry = fake_audit_entry(
+            "update",
+            Some(json!({ "name": "Paleta", "notes": null })),
+            Some(json!({ "name": "Paleta, grande", "notes": "dijo \"ok\"" })),
+        );
+        let repo: Arc<dyn AuditLogRepository> = Arc::new(MockAuditLogRepo::new(vec![entry]));
+
+        // Act
+        let csv = search_audit::export_csv(&repo, AuditLogFilter::default())
+            .await
+            .unwrap();
+
+        // Assert
+        let lines: Vec<&str> = csv.split("\r\n").filter(|l| !l.is_empty()).collect();
+        assert_eq!(lines[0], "fecha,usuario,accion,tabla,registro,campo,antes,despues");
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].ends_with(",name,Paleta,\"Paleta, grande\""));
+        assert!(lines[2].ends_with(",notes,,\"dijo \"\"ok\"\"\""));
+    }
+
+    #[tokio::test]
+    async fn exportacion_demasiado_grande_es_rechazada() {
+        // Arrange
+        let mut mock = MockAuditLogRepo::new(vec![]);
+        mock.total = Some(search_audit::MAX_EXPORT_ROWS + 1);
+        let mock = Arc::new(mock);
+        let repo: Arc<dyn AuditLogRepository> = mock.clone();
+
+        // Act
+        let result = search_audit::export_csv(&repo, AuditLogFilter::default()).await;
+
+        // Assert
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+        assert!(mock.search_calls.lock().unwrap().is_empty());
+    }
+}
@@ -257,7 +257,7 @@ mod sabores_tests {
         };
 
         // Act
-        let result = crud::update_flavor(&repo, Uuid::new_v4(), dto).await;
+        let result = crud::update_flavor(&repo, Uuid::new_v4(), dto, Uuid::new_v4()).await;
 
         // Assert
         assert!(result.is_err());
@@ -330,7 +330,7 @@ mod proveedores_tests {
         };
 
         // Act
-        let result = crud::update_provider(&repo, Uuid::new_v4(), dto).await;
+        let result = crud::update_provider(&repo, Uuid::new_v4(), dto, Uuid::new_v4()).await;
 
         // Assert
         assert!(result.is_err());
@@ -468,7 +468,7 @@ mod congeladores_tests {
         let repo: Arc<dyn FreezerRepository> = Arc::new(mock);
 
         // Act
-        let result = crud::toggle_freezer(&repo, Uuid::new_v4()).await;
+        let result = crud::toggle_freezer(&repo, Uuid::new_v4(), Uuid::new_v4()).await;
 
         // Assert
         assert!(result.is_err());
@@ -490,14 +490,14 @@ mod congeladores_tests {
             .returning(move |_| Ok(Some(freezer_clone.clone())));
 
         mock.expect_toggle_power()
-            .withf(move |id| *id == freezer_id)
+            .withf(move |id, _| *id == freezer_id)
             .times(1)
-            .returning(move |_| Ok(toggled_clone.clone()));
+            .returning(move |_, _| Ok(toggled_clone.clone()));
 
         let repo: Arc<dyn FreezerRepository> = Arc::new(mock);
 
         // Act
-        let result = crud::toggle_freezer(&repo, freezer_id).await;
+        let result = crud::toggle_freezer(&repo, freezer_id, Uuid::new_v4()).await;
 
         // Assert
         assert!(result.is_ok());
@@ -505,6 +505,154 @@ mod congeladores_tests {
     }
 }
 
+#[cfg(test)]
+mod capacidad_congeladores_tests {
+    use super::*;
+    use helados_sofis_core::shared::errors::AppError;
+    use rust_decimal::Decimal;
+    use std::collections::BTreeMap;
+
+    #[tokio::test]
+    async fn crear_congelador_con_capacidad_no_positiva_falla() {
+        // Arrange
+        let mut mock = MockFreezerRepo::new();
+        mock.expect_create().never();
+        let repo: Arc<dyn FreezerRepository> = Arc::new(mock);
+
+        let dto = CreateFreezerDto {
+            number: 8,
+            max_capacity: Some(FreezerCapacity(BTreeMap::from([(Uuid::new_v4(), 0)]))),
+            power_watts: None,
+            kwh_per_day: None,
+        };
+
+        // Act
+        let result = crud::create_freezer(&repo, dto, Uuid::new_v4()).await;
+
+        // Assert
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn ocupacion_por_producto_contra_capacidad() {
+        // Arrange
+        let mut mock = MockFreezerRepo::new();
+        let conos = Uuid::new_v4();
+        let vasos = Uuid::new_v4();
+        let mut freezer = fake_freezer(1);
+        freezer.max_capacity = FreezerCapacity(BTreeMap::from([(conos, 100), (vasos, 50)]));
+        let freezer_id = freezer.id;
+
+        mock.expect_find_by_id()
+            .returning(move |_| Ok(Some(freezer.clone())));
+        mock.expect_stock_by_product()
+            .withf(move |id| *id == Some(freezer_id))
+            .returning(move |_| {
+                Ok(vec![FreezerProductStock {
+                    freezer_id,
+                    product_id: conos,
+                    quantity: 25,
+                }])
+            });
+        let repo: Arc<dyn FreezerRepository> = Arc::new(mock);
+
+        // Act
+        let occupancy = crud::freezer_occupancy(&repo, freezer_id).await.unwrap();
+
+        // Assert
+        assert_eq!(occupancy.total_capacity, 150);
+        assert_eq!(occupancy.total_quantity, 25);
+        let conos_row = occupancy.products.iter().find(|p| p.product_id == conos).unwrap();
+        assert_eq!(conos_row.occupancy_pct, Some(Decimal::new(25, 0)));
+        let vasos_row = occupancy.products.iter().find(|p| p.product_id == vasos).unwrap();
+        assert_eq!(vasos_row.quantity, 0);
+    }
+}
+
+#[cfg(test)]
+mod consumo_congeladores_tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use helados_sofis_core::modules::catalog::application::freezer_power;
+    use rust_decimal::Decimal;
+
+    fn event(freezer_id: Uuid, is_on: bool, at: chrono::DateTime<Utc>) -> FreezerPowerEvent {
+        FreezerPowerEvent {
+            id: Uuid::new_v4(),
+            freezer_id,
+            is_on,
+            created_at: at,
+            created_by: Uuid::new_v4(),
+        }
+    }
+
+    #[test]
+    fn horas_encendido_se_recortan_al_periodo() {
+        // Arrange — encendido antes del período, apagado a las 6h, encendido a las 10h
+        let mut freezer = fake_freezer(1);
+        freezer.power_watts = Some(500);
+        let from = Utc::now() - Duration::hours(24);
+        let to = from + Duration::hours(12);
+        let events = [
+            event(freezer.id, true, from - Duration::days(3)),
+            event(freezer.id, false, from + Duration::hours(6)),
+            event(freezer.id, true, from + Duration::hours(10)),
+        ];
+        let refs: Vec<&FreezerPowerEvent> = events.iter().collect();
+
+        // Act
+        let row = freezer_power::compute_consumption(
+            &freezer,
+            &refs,
+            from,
+            to,
+            Some(Decimal::new(800, 0)),
+        );
+
+        // Assert — 6h + 2h encendido a 0.5 kW
+        assert_eq!(row.hours_on, Decimal::new(8, 0));
+        assert_eq!(row.hours_off, Decimal::new(4, 0));
+        assert_eq!(row.power_events, 2);
+        assert_eq!(row.kwh, Some(Decimal::new(4, 0)));
+        assert_eq!(row.kwh_saved, Some(Decimal::new(2, 0)));
+        assert_eq!(row.cost, Some(Decimal::new(3200, 0)));
+        assert_eq!(row.cost_saved, Some(Decimal::new(1600, 0)));
+    }
+
+    #[test]
+    fn kwh_por_dia_tiene_prioridad_y_sin_consumo_no_hay_estimado() {
+        // Arrange
+        let mut rated = fake_freezer(1);
+        rated.power_watts = Some(1000);
+        rated.kwh_per_day = Some(Decimal::new(24, 1));
+        let unrated = fake_freezer(2);
+
+        // Act / Assert
+        assert_eq!(rated.kwh_per_hour(), Some(Decimal::new(1, 1)));
+        assert_eq!(unrated.kwh_per_hour(), None);
+    }
+
+    #[tokio::test]
+    async fn reporte_rechaza_rango_invertido() {
+        // Arrange
+        let mut mock = MockFreezerRepo::new();
+        mock.expect_power_events_between().never();
+        let repo: Arc<dyn FreezerRepository> = Arc::new(mock);
+
+        let query = PowerReportQuery {
+            from: Utc::now(),
+            to: Some(Utc::now() - Duration::hours(1)),
+            price_per_kwh: None,
+        };
+
+        // Act
+        let result = freezer_power::power_report(&repo, query).await;
+
+        // Assert
+        assert!(result.is_err());
+    }
+}
+
 #[cfg(test)]
 mod rutas_tests {
     use super::*;
@@ -0,0 +1,121 @@
+mod common;
+
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use common::mocks::*;
+use helados_sofis_core::modules::catalog::domain::entities::*;
+use helados_sofis_core::modules::freezer_transfers::application::consolidation;
+use helados_sofis_core::modules::inventory::domain::entities::InventoryItem;
+
+// ═══════════════════════════════════════════════════════════
+// Tests de Casos de Uso — Consolidación de Congeladores
+// Patrón AAA: Arrange → Act → Assert
+// ═══════════════════════════════════════════════════════════
+
+fn freezer(number: i32, capacity: &[(Uuid, i32)]) -> Freezer {
+    let mut f = fake_freezer(number);
+    f.max_capacity = FreezerCapacity(capacity.iter().copied().collect::<BTreeMap<_, _>>());
+    f
+}
+
+fn stock(freezer_id: Uuid, product_id: Uuid, quantity: i32) -> InventoryItem {
+    let mut item = fake_inventory_item(freezer_id, product_id, Uuid::new_v4(), Uuid::new_v4());
+    item.quantity = quantity;
+    item
+}
+
+#[cfg(test)]
+mod consolidacion_tests {
+    use super::*;
+
+    #[test]
+    fn dos_semivacios_caben_en_un_tercero() {
+        // Arrange — ejemplo del plan: 20+30 conos y 10+15 vasos caben en 100 conos / 50 vasos
+        let conos = Uuid::new_v4();
+        let vasos = Uuid::new_v4();
+        let c1 = freezer(1, &[(conos, 100), (vasos, 50)]);
+        let c2 = freezer(2, &[(conos, 100), (vasos, 50)]);
+        let c3 = freezer(3, &[(conos, 200), (vasos, 100)]);
+        let inventory = vec![
+            stock(c1.id, conos, 20),
+            stock(c1.id, vasos, 10),
+            stock(c2.id, conos, 30),
+            stock(c2.id, vasos, 15),
+            stock(c3.id, conos, 150),
+            stock(c3.id, vasos, 25),
+        ];
+
+        // Act
+        let plans = consolidation::plan_consolidation(&[c1.clone(), c2.clone(), c3], &inventory, 50);
+
+        // Assert
+        assert_eq!(plans.len(), 1);
+        let plan = &plans[0];
+        assert_eq!(plan.target_number, 3);
+        assert_eq!(plan.freezers_to_turn_off, vec![c1.id, c2.id]);
+        assert_eq!(plan.units_moved, 75);
+        assert_eq!(plan.transfers.len(), 2);
+        assert!(plan.transfers.iter().all(|t| t.items.len() == 2));
+        assert!(plan.transfers.iter().all(|t| t.items.iter().all(|i| i.provider_id.is_some())));
+    }
+
+    #[test]
+    fn un_semivacio_se_vacia_en_otro_semivacio() {
+        // Arrange
+        let paletas = Uuid::new_v4();
+        let c1 = freezer(1, &[(paletas, 100)]);
+        let c2 = freezer(2, &[(paletas, 100)]);
+        let inventory = vec![stock(c1.id, paletas, 30), stock(c2.id, paletas, 40)];
+
+        // Act
+        let plans = consolidation::plan_consolidation(&[c1.clone(), c2.clone()], &inventory, 50);
+
+        // Assert
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].freezers_to_turn_off.len(), 1);
+        assert_eq!(plans[0].transfers[0].items[0].quantity, 30);
+        assert_eq!(plans[0].target_occupancy_after_pct, rust_decimal::Decimal::new(70, 0));
+    }
+
+    #[test]
+    fn sin_sugerencia_si_no_cabe_por_producto() {
+        // Arrange — el destino no admite vasos
+        let conos = Uuid::new_v4();
+        let vasos = Uuid::new_v4();
+        let c1 = freezer(1, &[(conos, 100), (vasos, 50)]);
+        let c2 = freezer(2, &[(conos, 100)]);
+        let inventory = vec![stock(c1.id, vasos, 10), stock(c2.id, conos, 90)];
+
+        // Act
+        let plans = consolidation::plan_consolidation(&[c1, c2], &inventory, 50);
+
+        // Assert
+        assert!(plans.is_empty());
+    }
+
+    #[test]
+    fn congeladores_apagados_y_con_deformados_no_se_consideran() {
+        // Arrange
+        let paletas = Uuid::new_v4();
+        let c1 = freezer(1, &[(paletas, 100)]);
+        let mut c2 = freezer(2, &[(paletas, 100)]);
+        c2.is_on = false;
+        let c3 = freezer(3, &[(paletas, 100)]);
+        let mut deformed = stock(c3.id, paletas, 5);
+        deformed.is_deformed = true;
+        let inventory = vec![
+            stock(c1.id, paletas, 10),
+            stock(c2.id, paletas, 10),
+            deformed,
+        ];
+
+        // Act
+        let plans = consolidation::plan_consolidation(&[c1.clone(), c2, c3.clone()], &inventory, 50);
+
+        // Assert — solo C1 se puede vaciar, y en C3 (que no se apaga)
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].target_freezer_id, c3.id);
+        assert_eq!(plans[0].freezers_to_turn_off, vec![c1.id]);
+    }
+}
@@ -1,12 +1,18 @@
 mod common;
 
+use rust_decimal::Decimal;
 use std::sync::Arc;
 use uuid::Uuid;
 
 use common::mocks::*;
-use helados_sofis_core::modules::inventory::application::manage_inventory;
+use helados_sofis_core::modules::inventory::application::{
+    deformed_stock, manage_inventory, stock_adjustments,
+};
 use helados_sofis_core::modules::inventory::domain::entities::*;
-use helados_sofis_core::modules::inventory::domain::repositories::InventoryRepository;
+use helados_sofis_core::modules::inventory::domain::repositories::{
+    InventoryRepository, StockAdjustmentRepository,
+};
+use helados_sofis_core::shared::errors::AppError;
 
 // ═══════════════════════════════════════════════════════════
 // Tests de Casos de Uso — Inventario (con Mocks)
@@ -146,7 +152,7 @@ mod inventario_tests {
         let repo: Arc<dyn InventoryRepository> = Arc::new(mock);
 
         // Act
-        let result = manage_inventory::update_alert(&repo, item_id, 50).await;
+        let result = manage_inventory::update_alert(&repo, item_id, 50, Uuid::new_v4()).await;
 
         // Assert
         assert!(result.is_ok());
@@ -181,3 +187,180 @@ mod inventario_tests {
         assert_eq!(items[0].assigned_worker_id, Some(worker_id));
     }
 }
+
+#[cfg(test)]
+mod ajustes_tests {
+    use super::*;
+
+    fn shrinkage_row(reason: &str, units_lost: i64, value_lost: i64, value_found: i64) -> ShrinkageRow {
+        ShrinkageRow {
+            reason: reason.into(),
+            product_id: Uuid::new_v4(),
+            units_lost,
+            value_lost: Decimal::new(value_lost, 2),
+            units_found: 0,
+            value_found: Decimal::new(value_found, 2),
+            uncosted_units: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn ajuste_con_cantidad_cero_es_rechazado() {
+        // Arrange
+        let mut mock = MockStockAdjustmentRepo::new();
+        mock.expect_create_adjustment().never();
+        let repo: Arc<dyn StockAdjustmentRepository> = Arc::new(mock);
+
+        let dto = CreateAdjustmentDto {
+            inventory_id: Uuid::new_v4(),
+            quantity_delta: 0,
+            reason: AdjustmentReason::Melted,
+            notes: None,
+        };
+
+        // Act
+        let result = stock_adjustments::create_adjustment(&repo, dto, Uuid::new_v4()).await;
+
+        // Assert
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn conteo_con_cantidad_negativa_es_rechazado() {
+        // Arrange
+        let mut mock = MockStockAdjustmentRepo::new();
+        mock.expect_submit_counts().never();
+        let repo: Arc<dyn StockAdjustmentRepository> = Arc::new(mock);
+
+        let dto = SubmitCountsDto {
+            lines: vec![CountedLineDto {
+                inventory_id: Uuid::new_v4(),
+                counted_quantity: -1,
+                reason: None,
+            }],
+        };
+
+        // Act
+        let result = stock_adjustments::submit_counts(&repo, Uuid::new_v4(), dto, Uuid::new_v4()).await;
+
+        // Assert
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn reporte_de_merma_calcula_totales() {
+        // Arrange
+        let mut mock = MockStockAdjustmentRepo::new();
+        mock.expect_shrinkage().returning(|_| {
+            Ok(vec![
+                shrinkage_row("melted", 4, 2000, 0),
+                shrinkage_row("counting_error", 1, 500, 1500),
+            ])
+        });
+        let repo: Arc<dyn StockAdjustmentRepository> = Arc::new(mock);
+
+        // Act
+        let report = stock_adjustments::shrinkage_report(&repo, AdjustmentQuery::default())
+            .await
+            .unwrap();
+
+        // Assert
+        assert_eq!(report.rows.len(), 2);
+        assert_eq!(report.total_units_lost, 5);
+        assert_eq!(report.total_value_lost, Decimal::new(2500, 2));
+        assert_eq!(report.total_value_found, Decimal::new(1500, 2));
+        assert_eq!(report.net_value, Decimal::new(-1000, 2));
+    }
+}
+
+#[cfg(test)]
+mod deformados_tests {
+    use super::*;
+
+    fn deformed_item(quantity: i32) -> InventoryItem {
+        let mut item = fake_inventory_item(Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        item.quantity = quantity;
+        item.is_deformed = true;
+        item.assigned_worker_id = Some(Uuid::new_v4());
+        item
+    }
+
+    #[tokio::test]
+    async fn baja_de_fila_no_deformada_es_rechazada() {
+        // Arrange
+        let inventory = MockInventoryRepo::new();
+        let item = fake_inventory_item(Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let id = item.id;
+        *inventory.find_by_id_result.lock().unwrap() = Some(Ok(Some(item)));
+        let inventory: Arc<dyn InventoryRepository> = Arc::new(inventory);
+        let mut adjustments = MockStockAdjustmentRepo::new();
+        adjustments.expect_create_adjustment().never();
+        let adjustments: Arc<dyn StockAdjustmentRepository> = Arc::new(adjustments);
+
+        let dto = WriteOffDeformedDto {
+            quantity: None,
+            reason: None,
+            notes: None,
+        };
+
+        // Act
+        let result =
+            deformed_stock::write_off(&inventory, &adjustments, id, dto, Uuid::new_v4()).await;
+
+        // Assert
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn baja_sin_cantidad_descuenta_toda_la_fila_como_danado() {
+        // Arrange
+        let inventory = MockInventoryRepo::new();
+        let item = deformed_item(7);
+        let id = item.id;
+        *inventory.find_by_id_result.lock().unwrap() = Some(Ok(Some(item.clone())));
+        let inventory: Arc<dyn InventoryRepository> = Arc::new(inventory);
+        let mut adjustments = MockStockAdjustmentRepo::new();
+        adjustments
+            .expect_create_adjustment()
+            .withf(move |dto, _| {
+                dto.inventory_id == id
+                    && dto.quantity_delta == -7
+                    && dto.reason == AdjustmentReason::Damaged
+            })
+            .times(1)
+            .returning(move |dto, by| {
+                Ok(InventoryAdjustment {
+                    id: Uuid::new_v4(),
+                    inventory_id: dto.inventory_id,
+                    freezer_id: item.freezer_id,
+                    product_id: item.product_id,
+                    flavor_id: item.flavor_id,
+                    provider_id: item.provider_id,
+                    quantity_delta: dto.quantity_delta,
+                    reason: dto.reason.as_str().into(),
+                    unit_cost: Some(Decimal::ZERO),
+                    total_cost: Some(Decimal::ZERO),
+                    notes: dto.notes.clone(),
+                    count_session_id: None,
+                    created_at: chrono::Utc::now(),
+                    created_by: by,
+                })
+            });
+        let adjustments: Arc<dyn StockAdjustmentRepository> = Arc::new(adjustments);
+
+        let dto = WriteOffDeformedDto {
+            quantity: None,
+            reason: None,
+            notes: Some("Trabajador dejó la empresa".into()),
+        };
+
+        // Act
+        let result =
+            deformed_stock::write_off(&inventory, &adjustments, id, dto, Uuid::new_v4()).await;
+
+        // Assert
+        let adjustment = result.unwrap();
+        assert_eq!(adjustment.quantity_delta, -7);
+        assert_eq!(adjustment.reason, "damaged");
+    }
+}
@@ -157,3 +157,242 @@ mod precios_tests {
         assert_eq!(result.unwrap().len(), 3);
     }
 }
+
+#[cfg(test)]
+mod resolucion_precios_tests {
+    use super::*;
+    use chrono::Utc;
+    use helados_sofis_core::modules::pricing::application::resolve_prices;
+    use helados_sofis_core::modules::pricing::domain::entities::{PriceTier, PricedItem};
+    use helados_sofis_core::shared::errors::AppError;
+    use rust_decimal::Decimal;
+
+    /// Inventario con un item y el PricedItem que lo referencia.
+    fn inventory_with_item() -> (MockInventoryRepo, PricedItem<'static>, Uuid) {
+        let provider_id = Uuid::new_v4();
+        let item = fake_inventory_item(Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), provider_id);
+        let priced = PricedItem {
+            inventory_id: item.id,
+            product_id: item.product_id,
+            flavor_id: item.flavor_id,
+            freezer_id: item.freezer_id,
+            is_deformed: Some(item.is_deformed),
+            unit_price: None,
+            override_reason: None,
+        };
+        let inventory = MockInventoryRepo::new();
+        *inventory.find_by_id_result.lock().unwrap() = Some(Ok(Some(item)));
+        (inventory, priced, provider_id)
+    }
+
+    #[tokio::test]
+    async fn viaje_usa_price_base_vigente() {
+        // Arrange
+        let (inventory, priced, provider_id) = inventory_with_item();
+        let price = fake_price(priced.product_id, priced.flavor_id, provider_id);
+        let price_id = price.id;
+        let mut prices = MockPriceRepo::new();
+        prices
+            .expect_get_price_at()
+            .withf(move |_, _, prov, _| *prov == provider_id)
+            .times(1)
+            .returning(move |_, _, _, _| Ok(Some(price.clone())));
+
+        // Act
+        let result =
+            resolve_prices::resolve_item_price(&prices, &inventory, priced, Utc::now(), PriceTier::Base)
+                .await;
+
+        // Assert
+        let resolved = result.unwrap();
+        assert_eq!(resolved.unit_price, Decimal::new(1000, 2));
+        assert_eq!(resolved.price_history_id, Some(price_id));
+        assert!(resolved.override_reason.is_none());
+    }
+
+    #[tokio::test]
+    async fn venta_local_usa_price_local() {
+        // Arrange
+        let (inventory, priced, provider_id) = inventory_with_item();
+        let price = fake_price(priced.product_id, priced.flavor_id, provider_id);
+        let mut prices = MockPriceRepo::new();
+        prices
+            .expect_get_price_at()
+            .returning(move |_, _, _, _| Ok(Some(price.clone())));
+
+        // Act
+        let result =
+            resolve_prices::resolve_item_price(&prices, &inventory, priced, Utc::now(), PriceTier::Local)
+                .await;
+
+        // Assert
+        assert_eq!(result.unwrap().unit_price, Decimal::new(1500, 2));
+    }
+
+    #[tokio::test]
+    async fn sin_precio_vigente_falla() {
+        // Arrange
+        let (inventory, priced, _) = inventory_with_item();
+        let mut prices = MockPriceRepo::new();
+        prices.expect_get_price_at().returning(|_, _, _, _| Ok(None));
+
+        // Act
+        let result =
+            resolve_prices::resolve_item_price(&prices, &inventory, priced, Utc::now(), PriceTier::Base)
+                .await;
+
+        // Assert
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn precio_manual_sin_motivo_falla() {
+        // Arrange
+        let (inventory, mut priced, _) = inventory_with_item();
+        priced.unit_price = Some(Decimal::new(800, 2));
+        priced.override_reason = Some("   ");
+        let mut prices = MockPriceRepo::new();
+        prices.expect_get_price_at().returning(|_, _, _, _| Ok(None));
+
+        // Act
+        let result =
+            resolve_prices::resolve_item_price(&prices, &inventory, priced, Utc::now(), PriceTier::Base)
+                .await;
+
+        // Assert
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn precio_manual_con_motivo_se_respeta() {
+        // Arrange
+        let (inventory, mut priced, provider_id) = inventory_with_item();
+        priced.unit_price = Some(Decimal::new(800, 2));
+        priced.override_reason = Some("Promoción de temporada");
+        let price = fake_price(priced.product_id, priced.flavor_id, provider_id);
+        let price_id = price.id;
+        let mut prices = MockPriceRepo::new();
+        prices
+            .expect_get_price_at()
+            .returning(move |_, _, _, _| Ok(Some(price.clone())));
+
+        // Act
+        let result =
+            resolve_prices::resolve_item_price(&prices, &inventory, priced, Utc::now(), PriceTier::Route)
+                .await;
+
+        // Assert
+        let resolved = result.unwrap();
+        assert_eq!(resolved.unit_price, Decimal::new(800, 2));
+        assert_eq!(resolved.price_history_id, Some(price_id));
+        assert_eq!(resolved.override_reason.as_deref(), Some("Promoción de temporada"));
+    }
+
+    #[tokio::test]
+    async fn producto_distinto_al_inventario_falla() {
+        // Arrange
+        let (inventory, mut priced, _) = inventory_with_item();
+        priced.product_id = Uuid::new_v4();
+        let mut prices = MockPriceRepo::new();
+        prices.expect_get_price_at().times(0);
+
+        // Act
+        let result =
+            resolve_prices::resolve_item_price(&prices, &inventory, priced, Utc::now(), PriceTier::Base)
+                .await;
+
+        // Assert
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn condicion_o_congelador_distintos_al_inventario_fallan() {
+        // Arrange
+        let (deformed_inventory, priced, _) = inventory_with_item();
+        let as_deformed = PricedItem {
+            is_deformed: Some(true),
+            ..priced
+        };
+        let (freezer_inventory, priced, _) = inventory_with_item();
+        let other_freezer = PricedItem {
+            freezer_id: Uuid::new_v4(),
+            ..priced
+        };
+        let mut prices = MockPriceRepo::new();
+        prices.expect_get_price_at().times(0);
+
+        // Act
+        let deformed = resolve_prices::resolve_item_price(
+            &prices,
+            &deformed_inventory,
+            as_deformed,
+            Utc::now(),
+            PriceTier::Base,
+        )
+        .await;
+        let freezer = resolve_prices::resolve_item_price(
+            &prices,
+            &freezer_inventory,
+            other_freezer,
+            Utc::now(),
+            PriceTier::Base,
+        )
+        .await;
+
+        // Assert
+        assert!(matches!(deformed, Err(AppError::BadRequest(_))));
+        assert!(matches!(freezer, Err(AppError::BadRequest(_))));
+    }
+}
+
+#[cfg(test)]
+mod reglas_margen_tests {
+    use super::*;
+    use chrono::Utc;
+    use helados_sofis_core::modules::pricing::domain::entities::{MarkupRule, UpsertMarkupRuleDto};
+    use helados_sofis_core::shared::errors::AppError;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn regla_aplica_porcentajes_sobre_el_costo_redondeando_a_centavos() {
+        // Arrange
+        let rule = MarkupRule {
+            id: Uuid::new_v4(),
+            product_id: None,
+            base_markup_pct: Decimal::new(100, 0),
+            route_markup_pct: Decimal::new(140, 0),
+            local_markup_pct: Decimal::new(3333, 2),
+            updated_at: Utc::now(),
+            updated_by: Uuid::new_v4(),
+        };
+
+        // Act
+        let (base, route, local) = rule.prices_for(Decimal::new(550, 2));
+
+        // Assert
+        assert_eq!(base, Decimal::new(1100, 2));
+        assert_eq!(route, Decimal::new(1320, 2));
+        assert_eq!(local, Decimal::new(733, 2)); // 5.50 * 1.3333 = 7.333
+    }
+
+    #[tokio::test]
+    async fn regla_con_margen_negativo_es_rechazada() {
+        // Arrange
+        let mut mock = MockPriceRepo::new();
+        mock.expect_upsert_markup_rule().never();
+        let repo: Arc<dyn PriceRepository> = Arc::new(mock);
+
+        let dto = UpsertMarkupRuleDto {
+            product_id: None,
+            base_markup_pct: Decimal::new(50, 0),
+            route_markup_pct: Decimal::new(-10, 0),
+            local_markup_pct: Decimal::new(80, 0),
+        };
+
+        // Act
+        let result = manage_prices::upsert_markup_rule(&repo, dto, Uuid::new_v4()).await;
+
+        // Assert
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+}
@@ -0,0 +1,186 @@
+mod common;
+
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use common::mocks::*;
+use helados_sofis_core::modules::purchases::application::{manage_purchases, provider_payables};
+use helados_sofis_core::modules::purchases::domain::entities::*;
+use helados_sofis_core::modules::purchases::domain::repositories::{
+    ProviderPayableRepository, PurchaseRepository,
+};
+use helados_sofis_core::shared::errors::AppError;
+use helados_sofis_core::shared::void::VoidDto;
+
+// ═══════════════════════════════════════════════════════════
+// Tests de Casos de Uso — Compras y Cuentas por Pagar (con Mocks)
+// Patrón AAA: Arrange → Act → Assert
+// ═══════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod registrar_compra_tests {
+    use super::*;
+
+    fn dto(payment_status: &str, paid_from: Option<&str>) -> CreatePurchaseDto {
+        CreatePurchaseDto {
+            provider_id: Uuid::new_v4(),
+            payment_status: payment_status.into(),
+            paid_from: paid_from.map(Into::into),
+            items: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn compra_pagada_sale_de_caja_por_defecto() {
+        // Arrange
+        let mut mock = MockPurchaseRepo::new();
+        mock.expect_create()
+            .withf(|dto, _| dto.paid_from.as_deref() == Some("cash_register"))
+            .times(1)
+            .returning(|dto, user| {
+                let mut purchase = fake_credit_purchase(dto.provider_id, Decimal::ZERO, Decimal::ZERO);
+                purchase.payment_status = dto.payment_status.clone();
+                purchase.paid_from = dto.paid_from.clone();
+                purchase.created_by = user;
+                Ok(PurchaseCreated {
+                    purchase: PurchaseWithItems { purchase, items: vec![] },
+                    price_updates: vec![],
+                    price_warnings: vec![],
+                })
+            });
+        let repo: Arc<dyn PurchaseRepository> = Arc::new(mock);
+
+        // Act
+        let result = manage_purchases::create_purchase(&repo, dto("paid", None), Uuid::new_v4()).await;
+
+        // Assert
+        assert_eq!(result.unwrap().purchase.purchase.paid_from.as_deref(), Some("cash_register"));
+    }
+
+    #[tokio::test]
+    async fn origen_de_pago_en_compra_a_credito_es_rechazado() {
+        // Arrange
+        let mut mock = MockPurchaseRepo::new();
+        mock.expect_create().never();
+        let repo: Arc<dyn PurchaseRepository> = Arc::new(mock);
+
+        // Act
+        let result =
+            manage_purchases::create_purchase(&repo, dto("credit", Some("external")), Uuid::new_v4())
+                .await;
+
+        // Assert
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn origen_de_pago_desconocido_es_rechazado() {
+        // Arrange
+        let mut mock = MockPurchaseRepo::new();
+        mock.expect_create().never();
+        let repo: Arc<dyn PurchaseRepository> = Arc::new(mock);
+
+        // Act
+        let result =
+            manage_purchases::create_purchase(&repo, dto("paid", Some("bank")), Uuid::new_v4())
+                .await;
+
+        // Assert
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn anular_sin_motivo_es_rechazado() {
+        // Arrange
+        let mut mock = MockPurchaseRepo::new();
+        mock.expect_void_purchase().never();
+        let repo: Arc<dyn PurchaseRepository> = Arc::new(mock);
+        let dto = VoidDto { reason: "   ".into() };
+
+        // Act
+        let result =
+            manage_purchases::void_purchase(&repo, Uuid::new_v4(), &dto, Uuid::new_v4()).await;
+
+        // Assert
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+}
+
+#[cfg(test)]
+mod cuentas_por_pagar_tests {
+    use super::*;
+
+    fn balance(name: &str, amount: i64) -> ProviderBalance {
+        ProviderBalance {
+            provider_id: Uuid::new_v4(),
+            provider_name: name.into(),
+            open_purchases: 1,
+            total_credit: Decimal::new(amount, 0),
+            total_paid: Decimal::ZERO,
+            balance: Decimal::new(amount, 0),
+            oldest_unpaid_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn resumen_suma_deuda_de_todos_los_proveedores() {
+        // Arrange
+        let mut mock = MockProviderPayableRepo::new();
+        mock.expect_balances()
+            .times(1)
+            .returning(|| Ok(vec![balance("Proveedor A", 300), balance("Proveedor B", 120)]));
+        let repo: Arc<dyn ProviderPayableRepository> = Arc::new(mock);
+
+        // Act
+        let summary = provider_payables::payables_summary(&repo).await.unwrap();
+
+        // Assert
+        assert_eq!(summary.providers.len(), 2);
+        assert_eq!(summary.total_debt, Decimal::new(420, 0));
+    }
+
+    #[tokio::test]
+    async fn saldo_del_proveedor_descuenta_abonos() {
+        // Arrange
+        let mut mock = MockProviderPayableRepo::new();
+        let provider_id = Uuid::new_v4();
+        mock.expect_open_purchases().returning(move |_| {
+            Ok(vec![
+                fake_credit_purchase(provider_id, Decimal::new(100, 0), Decimal::new(40, 0)),
+                fake_credit_purchase(provider_id, Decimal::new(50, 0), Decimal::ZERO),
+            ])
+        });
+        let repo: Arc<dyn ProviderPayableRepository> = Arc::new(mock);
+
+        // Act
+        let payables = provider_payables::provider_payables(&repo, provider_id)
+            .await
+            .unwrap();
+
+        // Assert
+        assert_eq!(payables.open_purchases.len(), 2);
+        assert_eq!(payables.balance, Decimal::new(110, 0));
+    }
+
+    #[tokio::test]
+    async fn pago_no_positivo_es_rechazado() {
+        // Arrange
+        let mut mock = MockProviderPayableRepo::new();
+        mock.expect_pay().never();
+        let repo: Arc<dyn ProviderPayableRepository> = Arc::new(mock);
+
+        let dto = CreateProviderPaymentDto {
+            amount: Decimal::ZERO,
+            purchase_ids: vec![],
+            notes: None,
+        };
+
+        // Act
+        let result =
+            provider_payables::pay_provider(&repo, Uuid::new_v4(), dto, Uuid::new_v4()).await;
+
+        // Assert
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+}
@@ -0,0 +1,531 @@
+mod common;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use common::mocks::*;
+use helados_sofis_core::modules::reports::application::{
+    dashboard, inventory_valuation, profit_loss, route_profitability, sales_report,
+};
+use helados_sofis_core::modules::reports::domain::entities::*;
+use helados_sofis_core::shared::errors::AppError;
+
+// ═══════════════════════════════════════════════════════════
+// Tests de Casos de Uso — Tablero del dueño (con Mocks)
+// Patrón AAA: Arrange → Act → Assert
+// ═══════════════════════════════════════════════════════════
+
+fn day(date: NaiveDate, trips: i64, local: i64, units: i32) -> DailySales {
+    DailySales {
+        date,
+        worker_trips: Decimal::new(trips, 2),
+        owner_sales: Decimal::ZERO,
+        local_sales: Decimal::new(local, 2),
+        total: Decimal::new(trips + local, 2),
+        units,
+    }
+}
+
+fn row(period_start: NaiveDate, units: i32, revenue: i64, income: i64) -> SalesReportRow {
+    SalesReportRow {
+        period_start,
+        key: None,
+        label: None,
+        units,
+        revenue: Decimal::new(revenue, 2),
+        income: Decimal::new(income, 2),
+    }
+}
+
+#[cfg(test)]
+mod dashboard_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn ventana_termina_hoy_y_suma_la_serie() {
+        // Arrange
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let from = NaiveDate::from_ymd_opt(2026, 3, 4).unwrap();
+        let mut mock = MockReportsRepo::new();
+        mock.expect_cash_available()
+            .returning(|| Ok(Decimal::new(50000, 2)));
+        mock.expect_daily_sales()
+            .withf(move |f, t| *f == from && *t == today)
+            .times(1)
+            .returning(move |f, t| {
+                Ok(vec![day(f, 20000, 1500, 21), day(t, 10000, 0, 10)])
+            });
+        mock.expect_top_workers()
+            .withf(|_, _, limit| *limit == 3)
+            .returning(|_, _, _| Ok(vec![]));
+        mock.expect_top_products()
+            .withf(|_, _, limit| *limit == 3)
+            .returning(|_, _, _| Ok(vec![]));
+        mock.expect_alerts()
+            .returning(|_, _| Ok(DashboardAlerts::default()));
+        let query = DashboardQuery {
+            days: Some(7),
+            top: Some(3),
+        };
+
+        // Act
+        let result = dashboard::get_dashboard_at(&mock, &query, today)
+            .await
+            .unwrap();
+
+        // Assert
+        assert_eq!(result.from, from);
+        assert_eq!(result.to, today);
+        assert_eq!(result.cash_available, Decimal::new(50000, 2));
+        assert_eq!(result.totals.worker_trips, Decimal::new(30000, 2));
+        assert_eq!(result.totals.local_sales, Decimal::new(1500, 2));
+        assert_eq!(result.totals.total, Decimal::new(31500, 2));
+        assert_eq!(result.totals.units, 31);
+    }
+
+    #[tokio::test]
+    async fn ventana_fuera_de_rango_falla_sin_consultar() {
+        // Arrange
+        let mut mock = MockReportsRepo::new();
+        mock.expect_daily_sales().times(0);
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+
+        // Act
+        let zero = dashboard::get_dashboard_at(
+            &mock,
+            &DashboardQuery {
+                days: Some(0),
+                top: None,
+            },
+            today,
+        )
+        .await;
+        let too_many = dashboard::get_dashboard_at(
+            &mock,
+            &DashboardQuery {
+                days: None,
+                top: Some(500),
+            },
+            today,
+        )
+        .await;
+
+        // Assert
+        assert!(matches!(zero, Err(AppError::BadRequest(_))));
+        assert!(matches!(too_many, Err(AppError::BadRequest(_))));
+    }
+}
+
+#[cfg(test)]
+mod sales_report_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn comparacion_usa_el_periodo_anterior_de_igual_duracion() {
+        // Arrange
+        let monday = NaiveDate::from_ymd_opt(2026, 3, 9).unwrap();
+        let sunday = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+        let prev_monday = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let prev_sunday = NaiveDate::from_ymd_opt(2026, 3, 8).unwrap();
+        let mut mock = MockReportsRepo::new();
+        mock.expect_sales_report()
+            .withf(move |f| f.from == monday && f.to == sunday)
+            .times(1)
+            .returning(move |f| Ok(vec![row(f.from, 30, 36000, 30000)]));
+        mock.expect_sales_report()
+            .withf(move |f| {
+                f.from == prev_monday
+                    && f.to == prev_sunday
+                    && f.period == SalesPeriod::Week
+                    && f.channel == Some(SalesChannel::WorkerTrip)
+            })
+            .times(1)
+            .returning(move |f| Ok(vec![row(f.from, 20, 24000, 20000)]));
+        let query = SalesReportQuery {
+            from: Some(monday),
+            to: Some(sunday),
+            period: SalesPeriod::Week,
+            channel: Some(SalesChannel::WorkerTrip),
+            compare: true,
+            ..Default::default()
+        };
+
+        // Act
+        let report = sales_report::get_sales_report_at(&mock, &query, sunday)
+            .await
+            .unwrap();
+
+        // Assert
+        assert_eq!(report.totals.units, 30);
+        assert_eq!(report.totals.revenue, Decimal::new(36000, 2));
+        let comparison = report.comparison.unwrap();
+        assert_eq!(comparison.from, prev_monday);
+        assert_eq!(comparison.to, prev_sunday);
+        assert_eq!(comparison.totals.income, Decimal::new(20000, 2));
+        assert_eq!(comparison.units_change_pct, Some(Decimal::new(5000, 2)));
+        assert_eq!(comparison.revenue_change_pct, Some(Decimal::new(5000, 2)));
+    }
+
+    #[tokio::test]
+    async fn sin_ventas_previas_la_variacion_es_nula() {
+        // Arrange
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let mut mock = MockReportsRepo::new();
+        mock.expect_sales_report()
+            .withf(move |f| f.to == today)
+            .returning(move |f| Ok(vec![row(f.to, 5, 7500, 5000)]));
+        mock.expect_sales_report()
+            .withf(move |f| f.to < today)
+            .returning(|_| Ok(vec![]));
+        let query = SalesReportQuery {
+            compare: true,
+            ..Default::default()
+        };
+
+        // Act
+        let report = sales_report::get_sales_report_at(&mock, &query, today)
+            .await
+            .unwrap();
+
+        // Assert
+        assert_eq!(report.from, NaiveDate::from_ymd_opt(2026, 3, 4).unwrap());
+        let comparison = report.comparison.unwrap();
+        assert_eq!(comparison.to, NaiveDate::from_ymd_opt(2026, 3, 3).unwrap());
+        assert_eq!(comparison.totals, SalesFigures::default());
+        assert_eq!(comparison.revenue_change_pct, None);
+    }
+
+    #[tokio::test]
+    async fn rango_invertido_o_demasiado_largo_falla_sin_consultar() {
+        // Arrange
+        let mut mock = MockReportsRepo::new();
+        mock.expect_sales_report().times(0);
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+
+        // Act
+        let inverted = sales_report::get_sales_report_at(
+            &mock,
+            &SalesReportQuery {
+                from: Some(today),
+                to: Some(today - chrono::Duration::days(1)),
+                ..Default::default()
+            },
+            today,
+        )
+        .await;
+        let too_long = sales_report::get_sales_report_at(
+            &mock,
+            &SalesReportQuery {
+                from: Some(today - chrono::Duration::days(1000)),
+                ..Default::default()
+            },
+            today,
+        )
+        .await;
+
+        // Assert
+        assert!(matches!(inverted, Err(AppError::BadRequest(_))));
+        assert!(matches!(too_long, Err(AppError::BadRequest(_))));
+    }
+}
+
+#[cfg(test)]
+mod route_profitability_tests {
+    use super::*;
+
+    fn route_row(
+        route_id: Uuid,
+        worker: &str,
+        trips: i32,
+        loaded: i32,
+        returned: i32,
+        margin: i64,
+    ) -> RouteWorkerRow {
+        RouteWorkerRow {
+            route_id,
+            route_name: format!("Ruta {route_id}"),
+            usage_count: 9,
+            worker_id: Uuid::new_v4(),
+            worker_name: worker.into(),
+            trips,
+            loaded_units: loaded,
+            returned_units: returned,
+            sold_units: loaded - returned,
+            sales: Decimal::from((loaded - returned) * 10),
+            business_margin: Decimal::new(margin, 2),
+            commission: Decimal::ZERO,
+            unpriced_units: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn agrupa_por_ruta_y_ordena_por_margen() {
+        // Arrange
+        let centro = Uuid::new_v4();
+        let norte = Uuid::new_v4();
+        let mut mock = MockReportsRepo::new();
+        mock.expect_route_profitability()
+            .returning(move |_, _, _, _| {
+                Ok(vec![
+                    route_row(centro, "Ana", 1, 40, 10, 5000),
+                    route_row(centro, "Juan", 2, 60, 30, 9000),
+                    RouteWorkerRow {
+                        unpriced_units: 15,
+                        ..route_row(norte, "Ana", 3, 100, 0, 50000)
+                    },
+                ])
+            });
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+
+        // Act
+        let report = route_profitability::get_route_profitability_at(
+            &mock,
+            &RouteProfitabilityQuery::default(),
+            today,
+        )
+        .await
+        .unwrap();
+
+        // Assert
+        assert_eq!(report.from, NaiveDate::from_ymd_opt(2026, 2, 9).unwrap());
+        assert_eq!(report.routes[0].route_id, norte);
+        let centro = &report.routes[1];
+        assert_eq!(centro.figures.trips, 3);
+        assert_eq!(centro.figures.returned_ratio, Some(Decimal::new(4000, 4)));
+        assert_eq!(centro.figures.average_sale_per_trip, Decimal::new(20000, 2));
+        assert_eq!(centro.workers[0].worker_name, "Juan");
+        assert_eq!(centro.workers[0].figures.returned_ratio, Some(Decimal::new(5000, 4)));
+        assert_eq!(report.totals.trips, 6);
+        assert_eq!(report.totals.business_margin, Decimal::new(64000, 2));
+        assert_eq!(centro.figures.unpriced_units, 0);
+        assert_eq!(report.totals.unpriced_units, 15);
+    }
+
+    #[tokio::test]
+    async fn rango_invertido_falla_sin_consultar() {
+        // Arrange
+        let mut mock = MockReportsRepo::new();
+        mock.expect_route_profitability().times(0);
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let query = RouteProfitabilityQuery {
+            from: Some(today + chrono::Duration::days(1)),
+            ..Default::default()
+        };
+
+        // Act
+        let result = route_profitability::get_route_profitability_at(&mock, &query, today).await;
+
+        // Assert
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+}
+
+#[cfg(test)]
+mod profit_loss_tests {
+    use super::*;
+
+    fn outflow(month: NaiveDate, kind: &str, category: Option<&str>, amount: i64) -> MonthlyOutflow {
+        MonthlyOutflow {
+            month,
+            kind: kind.into(),
+            category: category.map(Into::into),
+            amount: Decimal::from(amount),
+        }
+    }
+
+    #[tokio::test]
+    async fn suma_los_dias_por_mes_y_compara_con_el_mes_anterior() {
+        // Arrange
+        let jan = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let feb = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
+        let mut mock = MockReportsRepo::new();
+        mock.expect_daily_sales()
+            .withf(move |f, t| *f == jan && *t == NaiveDate::from_ymd_opt(2026, 2, 28).unwrap())
+            .returning(move |_, _| {
+                Ok(vec![
+                    day(NaiveDate::from_ymd_opt(2026, 1, 5).unwrap(), 20000, 0, 20),
+                    day(NaiveDate::from_ymd_opt(2026, 1, 31).unwrap(), 10000, 0, 10),
+                    day(NaiveDate::from_ymd_opt(2026, 2, 3).unwrap(), 40000, 5000, 45),
+                ])
+            });
+        mock.expect_monthly_cost_of_goods().returning(move |_, _| {
+            Ok(vec![
+                MonthlyCost {
+                    month: jan,
+                    cost: Decimal::from(150),
+                    uncosted_units: 0,
+                },
+                MonthlyCost {
+                    month: feb,
+                    cost: Decimal::from(225),
+                    uncosted_units: 2,
+                },
+            ])
+        });
+        mock.expect_monthly_outflows().returning(move |_, _| {
+            Ok(vec![
+                outflow(jan, "expense", Some("luz"), 50),
+                outflow(jan, "owner_withdrawal", None, 50),
+                outflow(feb, "expense", Some("transporte"), 25),
+                outflow(feb, "expense", Some("luz"), 50),
+                outflow(feb, "expense", None, 5),
+            ])
+        });
+        let query = ProfitLossQuery {
+            from: Some(NaiveDate::from_ymd_opt(2026, 1, 20).unwrap()),
+            to: Some(NaiveDate::from_ymd_opt(2026, 2, 10).unwrap()),
+        };
+
+        // Act
+        let report = profit_loss::get_profit_loss_at(&mock, &query, feb)
+            .await
+            .unwrap();
+
+        // Assert
+        assert_eq!(report.months.len(), 2);
+        let (january, february) = (&report.months[0], &report.months[1]);
+        assert_eq!(january.revenue.total, Decimal::from(300));
+        assert_eq!(january.gross_margin_pct, Some(Decimal::new(5000, 2)));
+        assert_eq!(january.net_profit, Decimal::from(50));
+        assert_eq!(february.uncosted_units, 2);
+        assert_eq!(february.gross_margin, Decimal::from(225));
+        let categories: Vec<&str> = february.expenses.iter().map(|e| e.category.as_str()).collect();
+        assert_eq!(categories, ["luz", "otros", "transporte"]);
+        assert_eq!(february.operating_expenses, Decimal::from(80));
+        assert_eq!(february.net_profit, Decimal::from(145));
+        assert_eq!(february.net_profit_change_pct, Some(Decimal::new(19000, 2)));
+
+        let csv = profit_loss::render_csv(&report);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].contains("gasto_luz,gasto_otros,gasto_transporte"));
+        assert!(lines[1].starts_with("2026-01,"));
+        assert!(lines[1].contains(",50,0,0,50,50,"));
+    }
+
+    #[tokio::test]
+    async fn rango_de_meses_invalido_falla_sin_consultar() {
+        // Arrange
+        let mut mock = MockReportsRepo::new();
+        mock.expect_daily_sales().times(0);
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+
+        // Act
+        let inverted = profit_loss::get_profit_loss_at(
+            &mock,
+            &ProfitLossQuery {
+                from: Some(NaiveDate::from_ymd_opt(2026, 4, 1).unwrap()),
+                to: None,
+            },
+            today,
+        )
+        .await;
+        let too_long = profit_loss::get_profit_loss_at(
+            &mock,
+            &ProfitLossQuery {
+                from: Some(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()),
+                to: None,
+            },
+            today,
+        )
+        .await;
+
+        // Assert
+        assert!(matches!(inverted, Err(AppError::BadRequest(_))));
+        assert!(matches!(too_long, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn fechas_en_los_extremos_del_calendario_fallan_sin_consultar() {
+        // Arrange
+        let mut mock = MockReportsRepo::new();
+        mock.expect_daily_sales().times(0);
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+
+        // Act
+        let past_max = profit_loss::get_profit_loss_at(
+            &mock,
+            &ProfitLossQuery {
+                from: Some(NaiveDate::MAX),
+                to: Some(NaiveDate::MAX),
+            },
+            today,
+        )
+        .await;
+        let before_min = profit_loss::get_profit_loss_at(
+            &mock,
+            &ProfitLossQuery {
+                from: None,
+                to: Some(NaiveDate::MIN),
+            },
+            today,
+        )
+        .await;
+
+        // Assert
+        assert!(matches!(past_max, Err(AppError::BadRequest(_))));
+        assert!(matches!(before_min, Err(AppError::BadRequest(_))));
+    }
+}
+
+#[cfg(test)]
+mod inventory_valuation_tests {
+    use super::*;
+
+    fn lot(
+        freezer_number: i32,
+        product_id: Uuid,
+        quantity: i32,
+        cost: Option<i64>,
+    ) -> InventoryValuationRow {
+        InventoryValuationRow {
+            inventory_id: Uuid::new_v4(),
+            freezer_id: Uuid::from_u128(freezer_number as u128),
+            freezer_number,
+            product_id,
+            product_name: "Paleta".into(),
+            flavor_id: Uuid::nil(),
+            flavor_name: "Fresa".into(),
+            provider_id: Uuid::nil(),
+            provider_name: "Proveedor".into(),
+            is_deformed: false,
+            quantity,
+            cost_price: cost.map(Decimal::from),
+            price_base: cost.map(|c| Decimal::from(c * 2)),
+            price_route: cost.map(|c| Decimal::from(c * 3)),
+        }
+    }
+
+    #[tokio::test]
+    async fn agrupa_por_congelador_y_producto_y_junta_combos_sin_precio() {
+        // Arrange
+        let paleta = Uuid::new_v4();
+        let vaso = Uuid::new_v4();
+        let mut mock = MockReportsRepo::new();
+        mock.expect_inventory_valuation().returning(move || {
+            Ok(vec![
+                lot(2, paleta, 10, Some(4)),
+                lot(1, vaso, 5, Some(20)),
+                lot(1, paleta, 3, None),
+                lot(2, paleta, 2, None),
+            ])
+        });
+
+        // Act
+        let valuation = inventory_valuation::get_inventory_valuation(&mock)
+            .await
+            .unwrap();
+
+        // Assert
+        assert_eq!(valuation.totals.units, 20);
+        assert_eq!(valuation.totals.unpriced_units, 5);
+        assert_eq!(valuation.totals.at_cost, Decimal::from(140));
+        assert_eq!(valuation.totals.at_route, Decimal::from(420));
+        let freezers: Vec<i32> = valuation.by_freezer.iter().map(|f| f.freezer_number).collect();
+        assert_eq!(freezers, [1, 2]);
+        assert_eq!(valuation.by_product[0].product_id, vaso);
+        assert_eq!(valuation.unpriced.len(), 1);
+        assert_eq!(valuation.unpriced[0].quantity, 5);
+        assert_eq!(valuation.deformed, ValuationFigures::default());
+    }
+}
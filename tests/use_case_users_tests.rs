@@ -252,7 +252,7 @@ mod actualizar_usuario_tests {
 
         mock.expect_update()
             .times(1)
-            .returning(move |_, _| Ok(updated_clone.clone()));
+            .returning(move |_, _, _| Ok(updated_clone.clone()));
 
         let repo: Arc<dyn helados_sofis_core::modules::users::domain::repositories::UserRepository> =
             Arc::new(mock);
@@ -266,7 +266,7 @@ mod actualizar_usuario_tests {
         };
 
         // Act
-        let result = update_user::execute(&repo, user_id, dto).await;
+        let result = update_user::execute(&repo, user_id, dto, Uuid::new_v4()).await;
 
         // Assert
         assert!(result.is_ok());
@@ -297,7 +297,7 @@ mod actualizar_usuario_tests {
         };
 
         // Act
-        let result = update_user::execute(&repo, missing_id, dto).await;
+        let result = update_user::execute(&repo, missing_id, dto, Uuid::new_v4()).await;
 
         // Assert
         assert!(result.is_err());
@@ -0,0 +1,214 @@
+mod common;
+
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use common::mocks::*;
+use helados_sofis_core::modules::worker_payments::application::{account_statement, pay_worker};
+use helados_sofis_core::modules::worker_payments::domain::entities::*;
+use helados_sofis_core::shared::errors::AppError;
+
+// ═══════════════════════════════════════════════════════════
+// Tests de Casos de Uso — Pagos a Trabajadores (con Mocks)
+// Patrón AAA: Arrange → Act → Assert
+// ═══════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod abonos_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn abono_parcial_exitoso() {
+        // Arrange
+        let mut mock = MockWorkerPaymentRepo::new();
+        let worker_id = Uuid::new_v4();
+        let amount = Decimal::new(2500, 2);
+        let payment = fake_worker_payment(worker_id, amount);
+
+        mock.expect_create_worker_payment()
+            .times(1)
+            .returning(move |_, _, _| Ok(payment.clone()));
+
+        let dto = CreateWorkerPaymentDto {
+            amount,
+            trip_ids: vec![],
+            allow_credit: false,
+            notes: Some("Abono semanal".into()),
+        };
+
+        // Act
+        let result = pay_worker::create_worker_payment(&mock, worker_id, dto, Uuid::new_v4()).await;
+
+        // Assert
+        let created = result.unwrap();
+        assert_eq!(created.amount, amount);
+        assert!(created.trip_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn abono_con_monto_cero_falla() {
+        // Arrange
+        let mut mock = MockWorkerPaymentRepo::new();
+        mock.expect_create_worker_payment().times(0);
+
+        let dto = CreateWorkerPaymentDto {
+            amount: Decimal::ZERO,
+            trip_ids: vec![],
+            allow_credit: false,
+            notes: None,
+        };
+
+        // Act
+        let result =
+            pay_worker::create_worker_payment(&mock, Uuid::new_v4(), dto, Uuid::new_v4()).await;
+
+        // Assert
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn abono_elimina_viajes_duplicados() {
+        // Arrange
+        let mut mock = MockWorkerPaymentRepo::new();
+        let worker_id = Uuid::new_v4();
+        let trip_id = Uuid::new_v4();
+        let payment = fake_worker_payment(worker_id, Decimal::new(1000, 2));
+
+        mock.expect_create_worker_payment()
+            .withf(move |_, dto, _| dto.trip_ids == vec![trip_id])
+            .times(1)
+            .returning(move |_, _, _| Ok(payment.clone()));
+
+        let dto = CreateWorkerPaymentDto {
+            amount: Decimal::new(1000, 2),
+            trip_ids: vec![trip_id, trip_id],
+            allow_credit: false,
+            notes: None,
+        };
+
+        // Act
+        let result = pay_worker::create_worker_payment(&mock, worker_id, dto, Uuid::new_v4()).await;
+
+        // Assert
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn pago_completo_de_viaje_ya_pagado_falla() {
+        // Arrange
+        let mut mock = MockWorkerPaymentRepo::new();
+        let trip_id = Uuid::new_v4();
+
+        mock.expect_create_payment()
+            .times(1)
+            .returning(|_, _| Err(AppError::Conflict("Ya existe un pago para este viaje".into())));
+
+        // Act
+        let result = pay_worker::create_payment(&mock, trip_id, Uuid::new_v4()).await;
+
+        // Assert
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+}
+
+#[cfg(test)]
+mod estado_de_cuenta_tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    fn movement(day: u32, kind: StatementEntryKind, amount: i64) -> StatementMovement {
+        StatementMovement {
+            occurred_at: Utc.with_ymd_and_hms(2026, 3, day, 15, 0, 0).unwrap(),
+            kind,
+            reference_id: Uuid::new_v4(),
+            description: None,
+            amount: Decimal::new(amount, 2),
+            sold_quantity: 0,
+            commission: Decimal::ZERO,
+        }
+    }
+
+    #[tokio::test]
+    async fn movimientos_previos_forman_el_saldo_inicial() {
+        // Arrange
+        let mut mock = MockWorkerPaymentRepo::new();
+        let worker_id = Uuid::new_v4();
+        mock.expect_statement_source()
+            .withf(|_, until| *until == Utc.with_ymd_and_hms(2026, 3, 31, 0, 0, 0).unwrap())
+            .times(1)
+            .returning(move |_, _| {
+                Ok(StatementSource {
+                    worker_id,
+                    worker_name: "Pedro".into(),
+                    current_debt: Decimal::new(7000, 2),
+                    movements: vec![
+                        movement(1, StatementEntryKind::Trip, 10000),
+                        movement(2, StatementEntryKind::Payment, -4000),
+                        movement(10, StatementEntryKind::Trip, 5000),
+                        movement(12, StatementEntryKind::Payment, -4000),
+                    ],
+                })
+            });
+        let query = StatementQuery {
+            from: NaiveDate::from_ymd_opt(2026, 3, 5),
+            to: NaiveDate::from_ymd_opt(2026, 3, 30),
+        };
+
+        // Act
+        let statement = account_statement::get_statement(&mock, worker_id, &query)
+            .await
+            .unwrap();
+
+        // Assert
+        assert_eq!(statement.opening_balance, Decimal::new(6000, 2));
+        assert_eq!(statement.entries.len(), 2);
+        assert_eq!(statement.entries[0].balance, Decimal::new(11000, 2));
+        assert_eq!(statement.total_debits, Decimal::new(5000, 2));
+        assert_eq!(statement.total_credits, Decimal::new(4000, 2));
+        assert_eq!(statement.closing_balance, Decimal::new(7000, 2));
+    }
+
+    #[tokio::test]
+    async fn rango_invertido_falla_sin_consultar() {
+        // Arrange
+        let mut mock = MockWorkerPaymentRepo::new();
+        mock.expect_statement_source().times(0);
+        let query = StatementQuery {
+            from: NaiveDate::from_ymd_opt(2026, 3, 10),
+            to: NaiveDate::from_ymd_opt(2026, 3, 1),
+        };
+
+        // Act
+        let result = account_statement::get_statement(&mock, Uuid::new_v4(), &query).await;
+
+        // Assert
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn texto_conserva_notas_y_html_las_escapa() {
+        // Arrange
+        let mut payment = movement(3, StatementEntryKind::Payment, -2000);
+        payment.description = Some("<b>efectivo</b>".into());
+        let source = StatementSource {
+            worker_id: Uuid::new_v4(),
+            worker_name: "Ana".into(),
+            current_debt: Decimal::ZERO,
+            movements: vec![movement(2, StatementEntryKind::Trip, 2000), payment],
+        };
+        let statement = build_statement(
+            source,
+            NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 3, 31).unwrap(),
+        );
+
+        // Act
+        let text = account_statement::render_text(&statement);
+        let html = account_statement::render_html(&statement);
+
+        // Assert
+        assert!(text.contains("03/03/2026 Abono: <b>efectivo</b> -$20.00 → $0.00"));
+        assert!(html.contains("Abono: &lt;b&gt;efectivo&lt;/b&gt;"));
+        assert!(!html.contains("<b>"));
+    }
+}